@@ -0,0 +1,146 @@
+use std::fmt::Write as _;
+
+use crate::data_item::DataItem;
+use crate::error::Error;
+use crate::span::{Header, read_header};
+
+/// Annotate a CBOR bytes as a hex dump where each line contains an offset,
+/// hex encoded bytes and a comment describing those bytes
+pub(crate) fn annotate(bytes: &[u8]) -> Result<String, Error> {
+    // decoding first makes sure that bytes are well formed
+    DataItem::decode(bytes)?;
+    let mut annotator = Annotator {
+        bytes,
+        position: 0,
+        lines: vec![],
+    };
+    annotator.annotate_item(0)?;
+    let width = annotator
+        .lines
+        .iter()
+        .map(|line| line.depth * 3 + line.hex.len())
+        .max()
+        .unwrap_or_default();
+    let mut annotation = String::new();
+    for line in annotator.lines {
+        let hex = format!("{}{}", "   ".repeat(line.depth), line.hex);
+        let _ = writeln!(
+            annotation,
+            "{:04x}: {hex:<width$} # {}{}",
+            line.offset,
+            "  ".repeat(line.depth),
+            line.comment
+        );
+    }
+    Ok(annotation)
+}
+
+/// Single line of an annotation
+struct Line {
+    offset: usize,
+    depth: usize,
+    hex: String,
+    comment: String,
+}
+
+/// Annotator which walks through a well formed CBOR bytes
+struct Annotator<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    lines: Vec<Line>,
+}
+
+impl Annotator<'_> {
+    fn take(&mut self, length: usize) -> Result<&[u8], Error> {
+        let end = self.position.checked_add(length).ok_or(Error::Incomplete)?;
+        let bytes = self
+            .bytes
+            .get(self.position..end)
+            .ok_or(Error::Incomplete)?;
+        self.position = end;
+        Ok(bytes)
+    }
+
+    fn push_line(&mut self, offset: usize, depth: usize, comment: String) {
+        let hex = self.bytes[offset..self.position]
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        self.lines.push(Line {
+            offset,
+            depth,
+            hex,
+            comment,
+        });
+    }
+
+    /// Annotate items till a break stop and annotate a break stop itself
+    fn annotate_indefinite(&mut self, depth: usize) -> Result<(), Error> {
+        while self.bytes.get(self.position) != Some(&0xff) {
+            self.annotate_item(depth)?;
+        }
+        let offset = self.position;
+        self.take(1)?;
+        self.push_line(offset, depth, "break".to_string());
+        Ok(())
+    }
+
+    fn annotate_item(&mut self, depth: usize) -> Result<(), Error> {
+        let offset = self.position;
+        let Header {
+            major_type,
+            argument,
+        } = read_header(self.bytes, &mut self.position)?;
+        let length = argument.map_or("*".to_string(), |number| number.to_string());
+        match major_type {
+            0 => {
+                self.push_line(offset, depth, format!("unsigned({length})"));
+            }
+            1 => {
+                let number = -1 - i128::from(argument.unwrap_or_default());
+                self.push_line(offset, depth, format!("negative({number})"));
+            }
+            2 | 3 => {
+                let kind = if major_type == 2 { "bytes" } else { "text" };
+                self.push_line(offset, depth, format!("{kind}({length})"));
+                match argument {
+                    Some(0) => {}
+                    Some(number) => {
+                        let content_offset = self.position;
+                        let content = self.take(usize::try_from(number)?)?.to_vec();
+                        let comment = if major_type == 2 {
+                            format!("{:?}", DataItem::from(content.as_slice()))
+                        } else {
+                            format!("{:?}", String::from_utf8(content)?)
+                        };
+                        self.push_line(content_offset, depth + 1, comment);
+                    }
+                    None => self.annotate_indefinite(depth + 1)?,
+                }
+            }
+            4 | 5 => {
+                let kind = if major_type == 4 { "array" } else { "map" };
+                self.push_line(offset, depth, format!("{kind}({length})"));
+                match argument {
+                    Some(number) => {
+                        let count = if major_type == 4 { number } else { number * 2 };
+                        for _ in 0..count {
+                            self.annotate_item(depth + 1)?;
+                        }
+                    }
+                    None => self.annotate_indefinite(depth + 1)?,
+                }
+            }
+            6 => {
+                self.push_line(offset, depth, format!("tag({length})"));
+                self.annotate_item(depth + 1)?;
+            }
+            _ => {
+                let simple = DataItem::decode(&self.bytes[offset..self.position])?;
+                self.push_line(offset, depth, format!("{simple:?}"));
+            }
+        }
+        Ok(())
+    }
+}
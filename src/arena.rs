@@ -0,0 +1,305 @@
+use bumpalo::Bump;
+use bumpalo::collections::Vec as BumpVec;
+
+use crate::content::{ArrayContent, MapContent, SimpleValue, TagContent};
+use crate::data_item::{DataItem, check_depth, check_length, check_size};
+use crate::decode_options::DecodeOptions;
+use crate::error::{Error, Malformed};
+use crate::float16::f16_bits_to_f64;
+use crate::span::{Header, read_header};
+
+/// Data item whose nested data item, bytes and text are allocated inside a
+/// bump arena
+///
+/// Decoding into an arena avoids a heap allocation for every nested data item
+/// and all of them are freed at once when an arena is dropped or reset. Chunks
+/// of an indefinite length byte string and text string are joined and length
+/// of an array and a map is not recorded, so an arena data item is meant for
+/// inspecting a decoded value. Map entries are kept in an encoded order
+/// including any duplicate key. Use [`ArenaItem::to_data_item`] to get an owned
+/// data item
+///
+/// # Example
+/// ```rust
+/// use bumpalo::Bump;
+/// use cbor_next::DataItem;
+/// use cbor_next::arena::ArenaItem;
+///
+/// let bump = Bump::new();
+/// let bytes = [0xa1, 0x61, 0x61, 0x82, 0x01, 0x42, 0x01, 0x02];
+/// let ArenaItem::Map(entries) = ArenaItem::decode(&bytes, &bump).unwrap() else {
+///     panic!("map should be decoded as a map");
+/// };
+/// assert_eq!(entries[0].0, ArenaItem::Text("a"));
+/// let ArenaItem::Array(items) = &entries[0].1 else {
+///     panic!("value should be decoded as an array");
+/// };
+/// assert_eq!(items, &[ArenaItem::Unsigned(1), ArenaItem::Byte(&[1, 2])]);
+/// assert_eq!(
+///     ArenaItem::Map(entries).to_data_item(),
+///     DataItem::decode(&bytes).unwrap()
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum ArenaItem<'bump> {
+    /// Unsigned integer
+    Unsigned(u64),
+    /// Negative integer stored in a same way as [`DataItem::Signed`]
+    Signed(u64),
+    /// Byte string with all chunks joined
+    Byte(&'bump [u8]),
+    /// Text string with all chunks joined
+    Text(&'bump str),
+    /// Array of data item
+    Array(&'bump [ArenaItem<'bump>]),
+    /// Map entries in an encoded order
+    Map(&'bump [(ArenaItem<'bump>, ArenaItem<'bump>)]),
+    /// Tag number and its content
+    Tag(u64, &'bump ArenaItem<'bump>),
+    /// Boolean value
+    Boolean(bool),
+    /// Null value
+    Null,
+    /// Undefined value
+    Undefined,
+    /// Floating point number
+    Floating(f64),
+    /// Other simple value
+    GenericSimple(SimpleValue),
+}
+
+impl<'bump> ArenaItem<'bump> {
+    /// Decode a CBOR representation into a data item allocated inside provided
+    /// arena
+    ///
+    /// # Errors
+    /// If provided bytes cannot be converted to CBOR
+    pub fn decode(val: &[u8], bump: &'bump Bump) -> Result<Self, Error> {
+        Self::decode_with_options(val, bump, &DecodeOptions::default())
+    }
+
+    /// Decode a CBOR representation into a data item allocated inside provided
+    /// arena while applying resource limits of provided decode options
+    ///
+    /// # Example
+    /// ```rust
+    /// use bumpalo::Bump;
+    /// use cbor_next::DecodeOptions;
+    /// use cbor_next::arena::ArenaItem;
+    ///
+    /// let bump = Bump::new();
+    /// let mut options = DecodeOptions::default();
+    /// options.set_max_depth(1);
+    /// assert!(ArenaItem::decode_with_options(&[0x81, 0x01], &bump, &options).is_ok());
+    /// assert!(ArenaItem::decode_with_options(&[0x81, 0x81, 0x01], &bump, &options).is_err());
+    /// ```
+    ///
+    /// # Errors
+    /// If provided bytes cannot be converted to CBOR or any limit is exceeded
+    pub fn decode_with_options(
+        val: &[u8],
+        bump: &'bump Bump,
+        options: &DecodeOptions,
+    ) -> Result<Self, Error> {
+        check_size(options, val.len())?;
+        ArenaDecoder {
+            bytes: val,
+            position: 0,
+            bump,
+            options,
+        }
+        .decode_item(0)
+    }
+
+    /// Convert into an owned data item
+    #[must_use]
+    pub fn to_data_item(&self) -> DataItem {
+        match self {
+            Self::Unsigned(number) => DataItem::Unsigned(*number),
+            Self::Signed(number) => DataItem::Signed(*number),
+            Self::Byte(bytes) => DataItem::from(*bytes),
+            Self::Text(text) => DataItem::from(*text),
+            Self::Array(items) => {
+                items
+                    .iter()
+                    .map(Self::to_data_item)
+                    .collect::<ArrayContent>()
+                    .into()
+            }
+            Self::Map(entries) => {
+                entries
+                    .iter()
+                    .map(|(key, value)| (key.to_data_item(), value.to_data_item()))
+                    .collect::<MapContent>()
+                    .into()
+            }
+            Self::Tag(number, content) => {
+                TagContent::from((*number, content.to_data_item())).into()
+            }
+            Self::Boolean(bool_val) => DataItem::Boolean(*bool_val),
+            Self::Null => DataItem::Null,
+            Self::Undefined => DataItem::Undefined,
+            Self::Floating(number) => DataItem::Floating(*number),
+            Self::GenericSimple(simple_value) => DataItem::GenericSimple(simple_value.clone()),
+        }
+    }
+}
+
+/// Decoder which walks through a CBOR bytes and allocates decoded data item
+/// inside an arena
+struct ArenaDecoder<'a, 'bump> {
+    bytes: &'a [u8],
+    position: usize,
+    bump: &'bump Bump,
+    options: &'a DecodeOptions,
+}
+
+impl<'a, 'bump> ArenaDecoder<'a, 'bump> {
+    fn take(&mut self, length: u64) -> Result<&'a [u8], Error> {
+        let end = self
+            .position
+            .checked_add(usize::try_from(length)?)
+            .ok_or(Error::Incomplete)?;
+        let bytes = self
+            .bytes
+            .get(self.position..end)
+            .ok_or(Error::Incomplete)?;
+        self.position = end;
+        Ok(bytes)
+    }
+
+    /// Check whether a next byte is a break stop and consume it if it is
+    fn consume_break(&mut self) -> Result<bool, Error> {
+        match self.bytes.get(self.position) {
+            Some(255) => {
+                self.position += 1;
+                Ok(true)
+            }
+            Some(_) => Ok(false),
+            None => Err(Error::IncompleteIndefinite),
+        }
+    }
+
+    fn decode_item(&mut self, depth: usize) -> Result<ArenaItem<'bump>, Error> {
+        let initial = *self.bytes.get(self.position).ok_or(Error::Incomplete)?;
+        if initial == 255 {
+            return Err(Error::InvalidBreakStop);
+        }
+        let Header {
+            major_type,
+            argument,
+        } = read_header(self.bytes, &mut self.position)?;
+        match (major_type, argument) {
+            (0, Some(number)) => Ok(ArenaItem::Unsigned(number)),
+            (1, Some(number)) => Ok(ArenaItem::Signed(number)),
+            (2, length) => Ok(ArenaItem::Byte(self.decode_string(major_type, length)?)),
+            (3, length) => {
+                let bytes = self.decode_string(major_type, length)?;
+                let text = std::str::from_utf8(bytes)
+                    .map_err(|error| Error::NotWellFormed(Malformed::InvalidUtf8(error)))?;
+                Ok(ArenaItem::Text(text))
+            }
+            (4, length) => {
+                check_depth(self.options, depth + 1)?;
+                let mut items = BumpVec::new_in(self.bump);
+                while !self.is_end(length, items.len())? {
+                    check_length(self.options, items.len() as u64 + 1)?;
+                    items.push(self.decode_item(depth + 1)?);
+                }
+                Ok(ArenaItem::Array(items.into_bump_slice()))
+            }
+            (5, length) => {
+                check_depth(self.options, depth + 1)?;
+                let mut entries = BumpVec::new_in(self.bump);
+                while !self.is_end(length, entries.len())? {
+                    check_length(self.options, entries.len() as u64 + 1)?;
+                    let key = self.decode_item(depth + 1)?;
+                    entries.push((key, self.decode_item(depth + 1)?));
+                }
+                Ok(ArenaItem::Map(entries.into_bump_slice()))
+            }
+            (6, Some(number)) => {
+                check_depth(self.options, depth + 1)?;
+                let content = self.decode_item(depth + 1)?;
+                Ok(ArenaItem::Tag(number, self.bump.alloc(content)))
+            }
+            (7, Some(argument)) => decode_simple_or_floating(initial & 0x1f, argument),
+            _ => {
+                Err(Error::NotWellFormed(Malformed::IndefiniteLength {
+                    major_type,
+                }))
+            }
+        }
+    }
+
+    /// Check whether all items of an array or a map are decoded. For an
+    /// indefinite length array or map a break stop is consumed
+    fn is_end(&mut self, length: Option<u64>, decoded: usize) -> Result<bool, Error> {
+        match length {
+            Some(length) => Ok(decoded as u64 >= length),
+            None => self.consume_break(),
+        }
+    }
+
+    /// Decode a byte string or text string content joining all chunks of an
+    /// indefinite length string
+    fn decode_string(&mut self, major_type: u8, length: Option<u64>) -> Result<&'bump [u8], Error> {
+        if let Some(length) = length {
+            check_length(self.options, length)?;
+            let bytes = self.take(length)?;
+            return Ok(self.bump.alloc_slice_copy(bytes));
+        }
+        let mut joined = BumpVec::new_in(self.bump);
+        while !self.consume_break()? {
+            let chunk_header = read_header(self.bytes, &mut self.position)?;
+            let (true, Some(chunk_length)) =
+                (chunk_header.major_type == major_type, chunk_header.argument)
+            else {
+                return Err(Error::NotWellFormed(Malformed::InvalidChunk {
+                    expected: major_type,
+                    found: chunk_header.major_type,
+                }));
+            };
+            let chunk = self.take(chunk_length)?;
+            if major_type == 3 {
+                std::str::from_utf8(chunk)
+                    .map_err(|error| Error::NotWellFormed(Malformed::InvalidUtf8(error)))?;
+            }
+            joined.extend_from_slice(chunk);
+            check_length(self.options, joined.len() as u64)?;
+        }
+        Ok(joined.into_bump_slice())
+    }
+}
+
+fn decode_simple_or_floating<'bump>(
+    additional: u8,
+    argument: u64,
+) -> Result<ArenaItem<'bump>, Error> {
+    match additional {
+        20 => Ok(ArenaItem::Boolean(false)),
+        21 => Ok(ArenaItem::Boolean(true)),
+        22 => Ok(ArenaItem::Null),
+        23 => Ok(ArenaItem::Undefined),
+        0..=19 | 24 => {
+            u8::try_from(argument)
+                .ok()
+                .filter(|value| additional < 24 || *value >= 32)
+                .and_then(SimpleValue::new)
+                .map(ArenaItem::GenericSimple)
+                .ok_or(Error::InvalidSimple)
+        }
+        25 => {
+            Ok(ArenaItem::Floating(f16_bits_to_f64(u16::try_from(
+                argument,
+            )?)))
+        }
+        26 => {
+            Ok(ArenaItem::Floating(f64::from(f32::from_bits(
+                u32::try_from(argument)?,
+            ))))
+        }
+        _ => Ok(ArenaItem::Floating(f64::from_bits(argument))),
+    }
+}
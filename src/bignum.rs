@@ -0,0 +1,42 @@
+use num_bigint::{BigInt, BigUint, Sign};
+
+use crate::content::TagContent;
+use crate::data_item::DataItem;
+use crate::tag;
+
+impl From<BigUint> for DataItem {
+    fn from(value: BigUint) -> Self {
+        match u64::try_from(&value) {
+            Ok(u64_val) => Self::Unsigned(u64_val),
+            Err(_) => TagContent::from((tag::POS_BIGNUM, value.to_bytes_be().as_slice())).into(),
+        }
+    }
+}
+
+impl From<BigInt> for DataItem {
+    fn from(value: BigInt) -> Self {
+        match value.sign() {
+            Sign::Minus => {
+                let (_, magnitude) = (-value - 1u8).into_parts();
+                match u64::try_from(&magnitude) {
+                    Ok(u64_val) => Self::Signed(u64_val),
+                    Err(_) => {
+                        TagContent::from((tag::NEG_BIGNUM, magnitude.to_bytes_be().as_slice()))
+                            .into()
+                    }
+                }
+            }
+            Sign::NoSign | Sign::Plus => value.into_parts().1.into(),
+        }
+    }
+}
+
+/// Parse a big integer from a content of a tag 2 or tag 3
+pub(crate) fn from_tag_content(tag: u64, content: &DataItem) -> Option<BigInt> {
+    let magnitude = BigInt::from_bytes_be(Sign::Plus, &content.as_byte()?);
+    match tag {
+        tag::POS_BIGNUM => Some(magnitude),
+        tag::NEG_BIGNUM => Some(-magnitude - 1u8),
+        _ => None,
+    }
+}
@@ -0,0 +1,157 @@
+use indexmap::IndexMap;
+
+use crate::content::{ArrayContent, MapContent, TagContent};
+use crate::data_item::DataItem;
+
+/// Fluent builder of an array data item
+///
+/// # Example
+/// ```rust
+/// use cbor_next::DataItem;
+///
+/// let value = DataItem::array_builder()
+///     .push(1)
+///     .push_tagged(32, "http://www.example.com")
+///     .extend(["a", "b"])
+///     .build();
+/// assert_eq!(
+///     format!("{value:?}"),
+///     "[1, 32(\"http://www.example.com\"), \"a\", \"b\"]"
+/// );
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+#[must_use]
+pub struct ArrayBuilder {
+    array: Vec<DataItem>,
+    indefinite: bool,
+}
+
+impl ArrayBuilder {
+    /// Create a new empty array builder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether a built array is indefinite length
+    pub fn indefinite(mut self, indefinite: bool) -> Self {
+        self.indefinite = indefinite;
+        self
+    }
+
+    /// Push an item to an array
+    pub fn push<T>(mut self, item: T) -> Self
+    where
+        T: Into<DataItem>,
+    {
+        self.array.push(item.into());
+        self
+    }
+
+    /// Push an item tagged with a provided tag number to an array
+    pub fn push_tagged<T>(self, tag: u64, item: T) -> Self
+    where
+        T: Into<DataItem>,
+    {
+        self.push(TagContent::from((tag, item)))
+    }
+
+    /// Push all items of an iterator to an array
+    pub fn extend<I, T>(mut self, items: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<DataItem>,
+    {
+        self.array.extend(items.into_iter().map(Into::into));
+        self
+    }
+
+    /// Build an array data item
+    #[must_use]
+    pub fn build(self) -> DataItem {
+        ArrayContent::from(self.array)
+            .set_indefinite(self.indefinite)
+            .clone()
+            .into()
+    }
+}
+
+/// Fluent builder of a map data item
+///
+/// Inserting an existing key replaces its value while keeping its position
+///
+/// # Example
+/// ```rust
+/// use cbor_next::DataItem;
+///
+/// let value = DataItem::map_builder()
+///     .insert(1, -7)
+///     .insert(4, b"kid".as_slice())
+///     .insert_tagged("a", 32, "http://www.example.com")
+///     .build();
+/// assert_eq!(
+///     format!("{value:?}"),
+///     "{1: -7, 4: h'6b6964', \"a\": 32(\"http://www.example.com\")}"
+/// );
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+#[must_use]
+pub struct MapBuilder {
+    map: IndexMap<DataItem, DataItem>,
+    indefinite: bool,
+}
+
+impl MapBuilder {
+    /// Create a new empty map builder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether a built map is indefinite length
+    pub fn indefinite(mut self, indefinite: bool) -> Self {
+        self.indefinite = indefinite;
+        self
+    }
+
+    /// Insert a key and value to a map
+    pub fn insert<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: Into<DataItem>,
+        V: Into<DataItem>,
+    {
+        self.map.insert(key.into(), value.into());
+        self
+    }
+
+    /// Insert a key and value tagged with a provided tag number to a map
+    pub fn insert_tagged<K, V>(self, key: K, tag: u64, value: V) -> Self
+    where
+        K: Into<DataItem>,
+        V: Into<DataItem>,
+    {
+        self.insert(key, TagContent::from((tag, value)))
+    }
+
+    /// Insert all key and value pairs of an iterator to a map
+    pub fn extend<I, K, V>(mut self, entries: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<DataItem>,
+        V: Into<DataItem>,
+    {
+        self.map.extend(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key.into(), value.into())),
+        );
+        self
+    }
+
+    /// Build a map data item
+    #[must_use]
+    pub fn build(self) -> DataItem {
+        MapContent::from(self.map)
+            .set_indefinite(self.indefinite)
+            .clone()
+            .into()
+    }
+}
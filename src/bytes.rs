@@ -0,0 +1,200 @@
+use std::fmt::Formatter;
+
+use serde::de::{Error as _, SeqAccess, Unexpected, Visitor};
+use serde::{Deserializer, Serializer};
+
+/// Serialize a bytes like value as a CBOR byte string
+///
+/// # Errors
+/// If serializer fails to serialize bytes
+pub fn serialize<T, S>(bytes: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: AsRef<[u8]> + ?Sized,
+    S: Serializer,
+{
+    serializer.serialize_bytes(bytes.as_ref())
+}
+
+/// Deserialize a bytes like value from a CBOR byte string or from an array
+/// of a integer
+///
+/// # Errors
+/// If deserializer fails to deserialize bytes
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: TryFrom<Vec<u8>>,
+    D: Deserializer<'de>,
+{
+    let bytes = deserializer.deserialize_byte_buf(ByteBufVisitor)?;
+    let length = bytes.len();
+    T::try_from(bytes).map_err(|_| D::Error::invalid_length(length, &"valid length byte string"))
+}
+
+struct ByteBufVisitor;
+
+impl<'de> Visitor<'de> for ByteBufVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str("byte string")
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(byte) = seq.next_element::<u8>()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+/// Serde `with` module which serialize bytes as a hex encoded text string and
+/// deserialize bytes from either a hex encoded text string or a byte string
+///
+/// # Example
+/// ```rust
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// struct Checksum {
+///     #[serde(with = "cbor_next::bytes::hex")]
+///     sha256: Vec<u8>,
+/// }
+/// ```
+pub mod hex {
+    use serde::{Deserializer, Serializer};
+
+    /// Serialize a bytes like value as a lowercase hex encoded text string
+    ///
+    /// # Errors
+    /// If serializer fails to serialize text string
+    pub fn serialize<T, S>(bytes: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: AsRef<[u8]> + ?Sized,
+        S: Serializer,
+    {
+        serializer.serialize_str(&crate::encoding::encode_hex(bytes.as_ref()))
+    }
+
+    /// Deserialize a bytes like value from a hex encoded text string or from a
+    /// byte string
+    ///
+    /// # Errors
+    /// If deserializer fails to deserialize bytes or text is not a valid hex
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: TryFrom<Vec<u8>>,
+        D: Deserializer<'de>,
+    {
+        super::deserialize_text(deserializer, crate::encoding::decode_hex)
+    }
+}
+
+/// Serde `with` module which serialize bytes as a base64 encoded text string
+/// with padding and deserialize bytes from either a base64 encoded text string
+/// or a byte string
+///
+/// # Example
+/// ```rust
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// struct Avatar {
+///     #[serde(with = "cbor_next::bytes::base64")]
+///     image: Vec<u8>,
+/// }
+/// ```
+pub mod base64 {
+    use serde::{Deserializer, Serializer};
+
+    /// Serialize a bytes like value as a base64 encoded text string
+    ///
+    /// # Errors
+    /// If serializer fails to serialize text string
+    pub fn serialize<T, S>(bytes: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: AsRef<[u8]> + ?Sized,
+        S: Serializer,
+    {
+        serializer.serialize_str(&crate::encoding::encode_base64(
+            bytes.as_ref(),
+            crate::encoding::BASE64_ALPHABET,
+            true,
+        ))
+    }
+
+    /// Deserialize a bytes like value from a base64 encoded text string or
+    /// from a byte string. Padding of text string is optional
+    ///
+    /// # Errors
+    /// If deserializer fails to deserialize bytes or text is not a valid
+    /// base64
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: TryFrom<Vec<u8>>,
+        D: Deserializer<'de>,
+    {
+        super::deserialize_text(deserializer, |text| {
+            crate::encoding::decode_base64(text, crate::encoding::BASE64_ALPHABET)
+        })
+    }
+}
+
+/// Deserialize a bytes like value from a text string which is decoded with
+/// provided decoder or from a byte string
+fn deserialize_text<'de, T, D>(
+    deserializer: D,
+    decode: fn(&str) -> Option<Vec<u8>>,
+) -> Result<T, D::Error>
+where
+    T: TryFrom<Vec<u8>>,
+    D: Deserializer<'de>,
+{
+    let bytes = deserializer.deserialize_any(EncodedVisitor(decode))?;
+    let length = bytes.len();
+    T::try_from(bytes).map_err(|_| D::Error::invalid_length(length, &"valid length byte string"))
+}
+
+struct EncodedVisitor(fn(&str) -> Option<Vec<u8>>);
+
+impl Visitor<'_> for EncodedVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str("encoded text string or byte string")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        (self.0)(v).ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v)
+    }
+}
@@ -0,0 +1,89 @@
+use ::ciborium::value::{Integer, Value};
+
+use crate::content::{MapContent, TagContent};
+use crate::data_item::DataItem;
+use crate::error::{Conversion, Error, ItemKind};
+
+impl TryFrom<Value> for DataItem {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        let data_item = match value {
+            Value::Integer(integer) => i128::from(integer).into(),
+            Value::Bytes(bytes) => bytes.as_slice().into(),
+            Value::Float(number) => Self::Floating(number),
+            Value::Text(string) => string.into(),
+            Value::Bool(bool_val) => Self::Boolean(bool_val),
+            Value::Null => Self::Null,
+            Value::Tag(number, content) => {
+                TagContent::from((number, Self::try_from(*content)?)).into()
+            }
+            Value::Array(array) => {
+                array
+                    .into_iter()
+                    .map(Self::try_from)
+                    .collect::<Result<Vec<_>, _>>()?
+                    .into()
+            }
+            Value::Map(map) => {
+                let mut map_content = MapContent::default();
+                for (key, value) in map {
+                    let key = Self::try_from(key)?;
+                    if map_content.map().contains_key(&key) {
+                        return Err(Error::ValueConversion(Conversion::DuplicateKey));
+                    }
+                    map_content.insert_content(key, Self::try_from(value)?);
+                }
+                map_content.into()
+            }
+            _ => return Err(Error::ValueConversion(Conversion::UnsupportedValue)),
+        };
+        Ok(data_item)
+    }
+}
+
+impl TryFrom<DataItem> for Value {
+    type Error = Error;
+
+    fn try_from(value: DataItem) -> Result<Self, Self::Error> {
+        let value = match value {
+            DataItem::Unsigned(number) => Self::Integer(number.into()),
+            DataItem::Signed(number) => {
+                let integer = Integer::try_from(-1 - i128::from(number))
+                    .map_err(|_| Error::ValueConversion(Conversion::IntegerOutOfRange))?;
+                Self::Integer(integer)
+            }
+            DataItem::Floating(number) => Self::Float(number),
+            DataItem::Byte(byte_content) => Self::Bytes(byte_content.into_full()),
+            DataItem::Text(text_content) => Self::Text(text_content.into_full()),
+            DataItem::Array(array_content) => {
+                Self::Array(
+                    array_content
+                        .into_iter()
+                        .map(Self::try_from)
+                        .collect::<Result<_, _>>()?,
+                )
+            }
+            DataItem::Map(map_content) => {
+                Self::Map(
+                    map_content
+                        .into_iter()
+                        .map(|(key, value)| Ok((Self::try_from(key)?, Self::try_from(value)?)))
+                        .collect::<Result<_, Error>>()?,
+                )
+            }
+            DataItem::Tag(tag_content) => {
+                let (number, content) = tag_content.into_parts();
+                Self::Tag(number, Box::new(Self::try_from(content)?))
+            }
+            DataItem::Boolean(bool_val) => Self::Bool(bool_val),
+            DataItem::Null => Self::Null,
+            DataItem::Undefined | DataItem::GenericSimple(_) => {
+                return Err(Error::ValueConversion(Conversion::UnsupportedItem {
+                    kind: ItemKind::of(&value),
+                }));
+            }
+        };
+        Ok(value)
+    }
+}
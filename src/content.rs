@@ -1,11 +1,31 @@
-use std::fmt::Debug;
+use std::borrow::{Borrow, Cow};
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt::{Debug, Display, Formatter};
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::ops::Deref;
 use std::string::FromUtf8Error;
+use std::sync::{Arc, OnceLock};
+#[cfg(not(feature = "smallvec"))]
+use std::vec as chunks;
+use std::{slice, vec};
 
 use indexmap::IndexMap;
+use indexmap::map::{IntoIter, Iter, IterMut, Keys, Values};
+#[cfg(feature = "smallvec")]
+use smallvec::smallvec as chunks;
 
-use crate::DataItem;
+use crate::deterministic::DeterministicMode;
 use crate::error::Error;
+use crate::index::{TextKey, untagged};
+use crate::{DataItem, tag};
+
+/// Chunks of a byte content or a text content. A single chunk is stored
+/// inline without an extra allocation when `smallvec` feature is enabled
+#[cfg(feature = "smallvec")]
+type Chunks<T> = smallvec::SmallVec<[T; 1]>;
+#[cfg(not(feature = "smallvec"))]
+type Chunks<T> = Vec<T>;
 
 /// Struct which holds a byte data
 ///
@@ -21,15 +41,38 @@ use crate::error::Error;
 #[derive(Default, PartialEq, PartialOrd, Clone, Hash)]
 pub struct ByteContent {
     is_indefinite: bool,
-    bytes: Vec<Vec<u8>>,
+    bytes: Chunks<Vec<u8>>,
 }
 
 impl From<Vec<u8>> for ByteContent {
     fn from(value: Vec<u8>) -> Self {
         Self {
             is_indefinite: false,
-            bytes: vec![value],
+            bytes: chunks![value],
+        }
+    }
+}
+
+impl Debug for ByteContent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let write_chunk = |f: &mut Formatter<'_>, chunk: &[u8]| {
+            write!(f, "h'")?;
+            for byte in chunk {
+                write!(f, "{byte:02x}")?;
+            }
+            write!(f, "'")
+        };
+        if !self.is_indefinite {
+            return write_chunk(f, &self.full());
+        }
+        write!(f, "(_ ")?;
+        for (index, chunk) in self.bytes.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write_chunk(f, chunk)?;
         }
+        write!(f, ")")
     }
 }
 
@@ -42,7 +85,13 @@ impl ByteContent {
 
     /// Set value of a content by overriding old data present inside content
     pub fn set_bytes(&mut self, byte: &[u8]) -> &mut Self {
-        self.bytes = vec![byte.to_vec()];
+        self.bytes = chunks![byte.to_vec()];
+        self
+    }
+
+    /// Set value of a content by taking ownership of bytes without copying
+    pub fn set_bytes_owned(&mut self, byte: Vec<u8>) -> &mut Self {
+        self.bytes = chunks![byte];
         self
     }
 
@@ -58,23 +107,288 @@ impl ByteContent {
         self
     }
 
+    /// Push a chunk to byte content by taking its ownership without copying
+    pub fn push_chunk(&mut self, chunk: Vec<u8>) -> &mut Self {
+        self.bytes.push(chunk);
+        self
+    }
+
+    /// Get a total number of bytes of all chunks
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.bytes.iter().map(Vec::len).sum()
+    }
+
+    /// Check whether a byte content has no bytes
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.bytes.iter().all(Vec::is_empty)
+    }
+
+    /// Get a number of chunks of a byte content
+    #[must_use]
+    pub fn chunk_count(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Split bytes into chunks of at most provided number of bytes and mark a
+    /// content as indefinite. Chunk contains at least one byte
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::ByteContent;
+    ///
+    /// let mut content = ByteContent::from(vec![1, 2, 3, 4, 5]);
+    /// content.rechunk(2);
+    /// assert!(content.is_indefinite());
+    /// assert_eq!(content.chunk(), &[vec![1, 2], vec![3, 4], vec![5]]);
+    /// content.coalesce();
+    /// assert!(content.is_indefinite());
+    /// assert_eq!(content.chunk(), &[vec![1, 2, 3, 4, 5]]);
+    /// ```
+    pub fn rechunk(&mut self, max_chunk_len: usize) -> &mut Self {
+        let bytes = self.bytes.concat();
+        self.bytes = bytes
+            .chunks(max_chunk_len.max(1))
+            .map(<[u8]>::to_vec)
+            .collect();
+        self.is_indefinite = true;
+        self
+    }
+
+    /// Join all chunks into a single chunk while preserving whether a content
+    /// is indefinite or not
+    pub fn coalesce(&mut self) -> &mut Self {
+        if self.bytes.len() > 1 {
+            self.bytes = chunks![self.bytes.concat()];
+        }
+        self
+    }
+
     /// Get whether a byte content is indefinite or not
     #[must_use]
     pub fn is_indefinite(&self) -> bool {
         self.is_indefinite
     }
 
-    /// Get full bytes from a byte content
+    /// Get full bytes from a byte content. Bytes are borrowed without
+    /// allocation when a byte content has at most one chunk
+    #[must_use]
+    pub fn full(&self) -> Cow<'_, [u8]> {
+        match self.as_slice() {
+            Some(bytes) => Cow::Borrowed(bytes),
+            None => Cow::Owned(self.bytes.concat()),
+        }
+    }
+
+    /// Get full bytes by taking ownership of a byte content. Bytes are moved
+    /// without copying when a byte content has a single chunk
     #[must_use]
-    pub fn full(&self) -> Vec<u8> {
+    pub fn into_full(mut self) -> Vec<u8> {
+        if self.bytes.len() == 1 {
+            return self.bytes.pop().unwrap_or_default();
+        }
         self.bytes.concat()
     }
 
+    /// Get full bytes as a slice when a byte content has at most one chunk.
+    /// Returns `None` for a byte content with multiple chunks
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::ByteContent;
+    ///
+    /// let mut content = ByteContent::from(vec![1, 2]);
+    /// assert_eq!(content.as_slice(), Some([1, 2].as_slice()));
+    /// content.set_indefinite(true).push_bytes(&[3]);
+    /// assert_eq!(content.as_slice(), None);
+    /// assert_eq!(content.full().as_ref(), &[1, 2, 3]);
+    /// ```
+    #[must_use]
+    pub fn as_slice(&self) -> Option<&[u8]> {
+        match self.bytes.as_slice() {
+            [] => Some(&[]),
+            [chunk] => Some(chunk),
+            _ => None,
+        }
+    }
+
     /// Get chunk of  bytes from a byte content
     #[must_use]
     pub fn chunk(&self) -> &[Vec<u8>] {
         &self.bytes
     }
+
+    /// Get chunk of bytes from a byte content by taking its ownership
+    #[must_use]
+    pub fn into_chunks(self) -> Vec<Vec<u8>> {
+        self.bytes.into_iter().collect()
+    }
+
+    /// Create an empty byte content which can hold at least provided number of
+    /// chunks without reallocating
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            is_indefinite: false,
+            bytes: Chunks::with_capacity(capacity),
+        }
+    }
+
+    /// Get a number of chunks a byte content can hold without reallocating
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.bytes.capacity()
+    }
+
+    /// Reserve capacity for at least provided number of additional chunks
+    pub fn reserve(&mut self, additional: usize) -> &mut Self {
+        self.bytes.reserve(additional);
+        self
+    }
+
+    /// Shrink capacity of a byte content and each of its chunk as much as
+    /// possible
+    pub fn shrink_to_fit(&mut self) -> &mut Self {
+        self.bytes.iter_mut().for_each(Vec::shrink_to_fit);
+        self.bytes.shrink_to_fit();
+        self
+    }
+}
+
+/// Chunk of a text content. A chunk either owns its string, borrows a static
+/// string without allocating or shares a reference counted string with other
+/// chunks, such as a map key interned while decoding. Chunks are compared and
+/// hashed by their string alone
+///
+/// # Example
+/// ```rust
+/// use std::sync::Arc;
+///
+/// use cbor_next::content::TextChunk;
+///
+/// let shared = TextChunk::from(Arc::<str>::from("kid"));
+/// assert_eq!(shared, TextChunk::from("kid"));
+/// assert_eq!(&*shared, "kid");
+/// assert!(matches!(shared, TextChunk::Shared(_)));
+/// ```
+#[derive(Clone)]
+#[non_exhaustive]
+pub enum TextChunk {
+    /// Owned string
+    Owned(String),
+    /// Borrowed static string
+    Static(&'static str),
+    /// Reference counted string shared with other chunks
+    Shared(Arc<str>),
+}
+
+impl Default for TextChunk {
+    fn default() -> Self {
+        Self::Static("")
+    }
+}
+
+impl Deref for TextChunk {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Owned(string) => string,
+            Self::Static(string) => string,
+            Self::Shared(string) => string,
+        }
+    }
+}
+
+impl Borrow<str> for TextChunk {
+    fn borrow(&self) -> &str {
+        self
+    }
+}
+
+impl PartialEq for TextChunk {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for TextChunk {}
+
+impl PartialEq<&str> for TextChunk {
+    fn eq(&self, other: &&str) -> bool {
+        &**self == *other
+    }
+}
+
+impl PartialOrd for TextChunk {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TextChunk {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+impl Hash for TextChunk {
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        (**self).hash(state);
+    }
+}
+
+impl Debug for TextChunk {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+impl Display for TextChunk {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self)
+    }
+}
+
+impl From<String> for TextChunk {
+    fn from(value: String) -> Self {
+        Self::Owned(value)
+    }
+}
+
+impl From<&str> for TextChunk {
+    fn from(value: &str) -> Self {
+        Self::Owned(value.to_string())
+    }
+}
+
+impl From<Arc<str>> for TextChunk {
+    fn from(value: Arc<str>) -> Self {
+        Self::Shared(value)
+    }
+}
+
+impl From<Cow<'static, str>> for TextChunk {
+    fn from(value: Cow<'static, str>) -> Self {
+        match value {
+            Cow::Borrowed(string) => Self::Static(string),
+            Cow::Owned(string) => Self::Owned(string),
+        }
+    }
+}
+
+impl From<TextChunk> for String {
+    fn from(value: TextChunk) -> Self {
+        match value {
+            TextChunk::Owned(string) => string,
+            TextChunk::Static(string) => string.to_string(),
+            TextChunk::Shared(string) => string.to_string(),
+        }
+    }
 }
 
 /// Struct which holds a text content
@@ -88,17 +402,17 @@ impl ByteContent {
 /// content.set_indefinite(true);
 /// assert!(content.is_indefinite());
 /// ```
-#[derive(Default, PartialEq, PartialOrd, Clone, Hash)]
+#[derive(Default, PartialEq, PartialOrd, Clone)]
 pub struct TextContent {
     is_indefinite: bool,
-    strings: Vec<String>,
+    strings: Chunks<TextChunk>,
 }
 
 impl From<String> for TextContent {
     fn from(value: String) -> Self {
         Self {
             is_indefinite: false,
-            strings: vec![value],
+            strings: chunks![TextChunk::Owned(value)],
         }
     }
 }
@@ -107,7 +421,41 @@ impl From<&str> for TextContent {
     fn from(value: &str) -> Self {
         Self {
             is_indefinite: false,
-            strings: vec![value.to_string()],
+            strings: chunks![TextChunk::from(value)],
+        }
+    }
+}
+
+/// Create a definite length text content which keeps a borrowed static string
+/// without allocating
+///
+/// # Example
+/// ```rust
+/// use std::borrow::Cow;
+///
+/// use cbor_next::TextContent;
+/// use cbor_next::content::TextChunk;
+///
+/// let content = TextContent::from(Cow::Borrowed("kid"));
+/// assert!(matches!(content.chunk(), [TextChunk::Static("kid")]));
+/// assert!(content == TextContent::from("kid"));
+/// ```
+impl From<Cow<'static, str>> for TextContent {
+    fn from(value: Cow<'static, str>) -> Self {
+        Self {
+            is_indefinite: false,
+            strings: chunks![value.into()],
+        }
+    }
+}
+
+/// Create a definite length text content which shares a reference counted
+/// string without copying it
+impl From<Arc<str>> for TextContent {
+    fn from(value: Arc<str>) -> Self {
+        Self {
+            is_indefinite: false,
+            strings: chunks![value.into()],
         }
     }
 }
@@ -138,6 +486,62 @@ impl TryFrom<ByteContent> for TextContent {
     }
 }
 
+/// Text string is hashed by its content alone, so a text split into different
+/// chunk or encoded with an indefinite length has a same hash as a definite
+/// length text and a map key can be found using a borrowed text
+impl Hash for TextContent {
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        hash_text(self.strings.iter().map(|string| string.as_bytes()), state);
+    }
+}
+
+/// Hash a text which is split into provided chunks. Bytes are written in block
+/// of same size independent of chunks so a hash only depends on a text
+pub(crate) fn hash_text<'a, I, H>(chunks: I, state: &mut H)
+where
+    I: Iterator<Item = &'a [u8]>,
+    H: Hasher,
+{
+    const BLOCK_SIZE: usize = 64;
+    let mut block = [0; BLOCK_SIZE];
+    let mut filled = 0;
+    let mut length = 0;
+    for mut chunk in chunks {
+        length += chunk.len();
+        while !chunk.is_empty() {
+            let count = (BLOCK_SIZE - filled).min(chunk.len());
+            block[filled..filled + count].copy_from_slice(&chunk[..count]);
+            filled += count;
+            chunk = &chunk[count..];
+            if filled == BLOCK_SIZE {
+                state.write(&block);
+                filled = 0;
+            }
+        }
+    }
+    state.write(&block[..filled]);
+    state.write_usize(length);
+}
+
+impl Debug for TextContent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if !self.is_indefinite {
+            return write!(f, "{:?}", self.full());
+        }
+        write!(f, "(_ ")?;
+        for (index, chunk) in self.strings.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{chunk:?}")?;
+        }
+        write!(f, ")")
+    }
+}
+
 impl TextContent {
     /// Set a content as an indefinite content
     pub fn set_indefinite(&mut self, indefinite: bool) -> &mut Self {
@@ -147,19 +551,33 @@ impl TextContent {
 
     /// Set value of a content by overriding old data present inside content
     pub fn set_string(&mut self, string: &str) -> &mut Self {
-        self.strings = vec![string.to_string()];
+        self.strings = chunks![TextChunk::from(string)];
+        self
+    }
+
+    /// Set value of a content by taking ownership of a string without copying
+    pub fn set_string_owned(&mut self, string: String) -> &mut Self {
+        self.strings = chunks![TextChunk::Owned(string)];
         self
     }
 
     /// push new string to a text content
     pub fn push_string(&mut self, string: &str) -> &mut Self {
-        self.strings.push(string.to_string());
+        self.strings.push(TextChunk::from(string));
+        self
+    }
+
+    /// Push a static string to a text content as a new chunk without
+    /// allocating
+    pub fn push_static(&mut self, string: &'static str) -> &mut Self {
+        self.strings.push(TextChunk::Static(string));
         self
     }
 
     /// Extend text content by string list
     pub fn extend_string(&mut self, strings: &[String]) -> &mut Self {
-        self.strings.extend(strings.to_vec());
+        self.strings
+            .extend(strings.iter().cloned().map(TextChunk::Owned));
         self
     }
 
@@ -169,15 +587,129 @@ impl TextContent {
         self.is_indefinite
     }
 
-    /// Get full strings from a string content
+    /// Get full strings from a string content. String is borrowed without
+    /// allocation when a string content has at most one chunk
+    #[must_use]
+    pub fn full(&self) -> Cow<'_, str> {
+        match self.as_str() {
+            Some(string) => Cow::Borrowed(string),
+            None => Cow::Owned(self.strings.concat()),
+        }
+    }
+
+    /// Get full string by taking ownership of a text content. String is moved
+    /// without copying when a text content has a single owned chunk
+    #[must_use]
+    pub fn into_full(mut self) -> String {
+        if self.strings.len() == 1 {
+            return self.strings.pop().unwrap_or_default().into();
+        }
+        self.strings.concat()
+    }
+
+    /// Get full string as a string slice when a string content has at most
+    /// one chunk. Returns `None` for a string content with multiple chunks
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::TextContent;
+    ///
+    /// let mut content = TextContent::from("ab");
+    /// assert_eq!(content.as_str(), Some("ab"));
+    /// content.set_indefinite(true).push_str("c");
+    /// assert_eq!(content.as_str(), None);
+    /// assert_eq!(content.full(), "abc");
+    /// assert_eq!(content.len(), 3);
+    /// assert!(content.contains("bc"));
+    /// ```
+    #[must_use]
+    pub fn as_str(&self) -> Option<&str> {
+        match self.strings.as_slice() {
+            [] => Some(""),
+            [chunk] => Some(chunk),
+            _ => None,
+        }
+    }
+
+    /// Push a string to a text content as a new chunk by taking its ownership
+    pub fn push_str<S>(&mut self, string: S) -> &mut Self
+    where
+        S: Into<String>,
+    {
+        self.strings.push(TextChunk::Owned(string.into()));
+        self
+    }
+
+    /// Get a total number of bytes of all chunks
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.strings.iter().map(|chunk| chunk.len()).sum()
+    }
+
+    /// Check whether a text content has no character
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.strings.iter().all(|chunk| chunk.is_empty())
+    }
+
+    /// Get an iterator over characters of all chunks
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        self.strings.iter().flat_map(|chunk| chunk.chars())
+    }
+
+    /// Check whether a full string contains provided pattern. Pattern spanning
+    /// multiple chunks is also matched
     #[must_use]
-    pub fn full(&self) -> String {
-        self.strings.join("")
+    pub fn contains(&self, pattern: &str) -> bool {
+        self.full().contains(pattern)
+    }
+
+    /// Split a string into chunks of at most provided number of bytes and mark
+    /// a content as indefinite. Chunks are split at a character boundary so a
+    /// chunk contains at least one character even if it is longer than
+    /// provided number of bytes
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::TextContent;
+    ///
+    /// let mut content = TextContent::from("abécd");
+    /// content.rechunk(3);
+    /// assert!(content.is_indefinite());
+    /// assert_eq!(content.chunk(), &["ab", "éc", "d"]);
+    /// content.coalesce();
+    /// assert_eq!(content.chunk(), &["abécd"]);
+    /// ```
+    pub fn rechunk(&mut self, max_chunk_len: usize) -> &mut Self {
+        let string = self.strings.concat();
+        let mut chunks = chunks![];
+        let mut chunk = String::new();
+        for character in string.chars() {
+            if !chunk.is_empty() && chunk.len() + character.len_utf8() > max_chunk_len {
+                chunks.push(TextChunk::Owned(std::mem::take(&mut chunk)));
+            }
+            chunk.push(character);
+        }
+        if !chunk.is_empty() {
+            chunks.push(TextChunk::Owned(chunk));
+        }
+        self.strings = chunks;
+        self.is_indefinite = true;
+        self
+    }
+
+    /// Join all chunks into a single chunk while preserving whether a content
+    /// is indefinite or not
+    pub fn coalesce(&mut self) -> &mut Self {
+        if self.strings.len() > 1 {
+            self.strings = chunks![TextChunk::Owned(self.strings.concat())];
+        }
+        self
     }
 
     /// Get chunk of  strings from a string content
     #[must_use]
-    pub fn chunk(&self) -> &[String] {
+    pub fn chunk(&self) -> &[TextChunk] {
         &self.strings
     }
 }
@@ -211,6 +743,30 @@ where
     }
 }
 
+impl Drop for ArrayContent {
+    fn drop(&mut self) {
+        if self.array.iter().any(has_nested_content) {
+            drop_iteratively(std::mem::take(&mut self.array));
+        }
+    }
+}
+
+impl Debug for ArrayContent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            return f.write_str(&DataItem::Array(self.clone()).to_diagnostic_pretty(4));
+        }
+        write!(f, "{}", if self.is_indefinite { "[_ " } else { "[" })?;
+        for (index, item) in self.array.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{item:?}")?;
+        }
+        write!(f, "]")
+    }
+}
+
 impl ArrayContent {
     /// Set a content as an indefinite content
     pub fn set_indefinite(&mut self, indefinite: bool) -> &mut Self {
@@ -227,6 +783,13 @@ impl ArrayContent {
         self
     }
 
+    /// Set value to a content by taking ownership of data item without
+    /// cloning
+    pub fn set_content_owned(&mut self, array: Vec<DataItem>) -> &mut Self {
+        self.array = array;
+        self
+    }
+
     /// push a data item to array
     pub fn push_content<T>(&mut self, content: T) -> &mut Self
     where
@@ -246,6 +809,80 @@ impl ArrayContent {
         self
     }
 
+    /// Remove a data item at an index shifting all data item after it. Return
+    /// `None` if an index is out of bounds
+    pub fn remove_content(&mut self, index: usize) -> Option<DataItem> {
+        self.remove(index)
+    }
+
+    /// Append a data item to an end of an array
+    pub fn push<T>(&mut self, item: T)
+    where
+        T: Into<DataItem>,
+    {
+        self.array.push(item.into());
+    }
+
+    /// Remove a last data item of an array and return it
+    pub fn pop(&mut self) -> Option<DataItem> {
+        self.array.pop()
+    }
+
+    /// Insert a data item at an index shifting all data item after it
+    ///
+    /// # Panics
+    /// If an index is greater than a length of an array
+    pub fn insert<T>(&mut self, index: usize, item: T)
+    where
+        T: Into<DataItem>,
+    {
+        self.array.insert(index, item.into());
+    }
+
+    /// Remove a data item at an index shifting all data item after it. Return
+    /// `None` if an index is out of bounds
+    pub fn remove(&mut self, index: usize) -> Option<DataItem> {
+        (index < self.array.len()).then(|| self.array.remove(index))
+    }
+
+    /// Keep first provided number of data item and drop remaining data item
+    pub fn truncate(&mut self, length: usize) {
+        self.array.truncate(length);
+    }
+
+    /// Remove all data item of an array
+    pub fn clear(&mut self) {
+        self.array.clear();
+    }
+
+    /// Get a number of data item in an array
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.array.len()
+    }
+
+    /// Check whether an array has no data item
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.array.is_empty()
+    }
+
+    /// Get an iterator over data item of an array
+    pub fn iter(&self) -> slice::Iter<'_, DataItem> {
+        self.array.iter()
+    }
+
+    /// Get an iterator over mutable data item of an array
+    pub fn iter_mut(&mut self) -> slice::IterMut<'_, DataItem> {
+        self.array.iter_mut()
+    }
+
+    /// Remove a data item at an index replacing it with a last data item.
+    /// Return `None` if an index is out of bounds
+    pub fn swap_remove_content(&mut self, index: usize) -> Option<DataItem> {
+        (index < self.array.len()).then(|| self.array.swap_remove(index))
+    }
+
     /// Get whether a array content is indefinite or not
     #[must_use]
     pub fn is_indefinite(&self) -> bool {
@@ -263,6 +900,82 @@ impl ArrayContent {
     pub fn array_mut(&mut self) -> &mut [DataItem] {
         &mut self.array
     }
+
+    /// Create an empty array content which can hold at least provided number
+    /// of data item without reallocating
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            is_indefinite: false,
+            array: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Get a number of data item an array content can hold without
+    /// reallocating
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.array.capacity()
+    }
+
+    /// Reserve capacity for at least provided number of additional data item
+    pub fn reserve(&mut self, additional: usize) -> &mut Self {
+        self.array.reserve(additional);
+        self
+    }
+
+    /// Shrink capacity of an array content as much as possible
+    pub fn shrink_to_fit(&mut self) -> &mut Self {
+        self.array.shrink_to_fit();
+        self
+    }
+}
+
+impl<'a> IntoIterator for &'a ArrayContent {
+    type IntoIter = slice::Iter<'a, DataItem>;
+    type Item = &'a DataItem;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut ArrayContent {
+    type IntoIter = slice::IterMut<'a, DataItem>;
+    type Item = &'a mut DataItem;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl IntoIterator for ArrayContent {
+    type IntoIter = vec::IntoIter<DataItem>;
+    type Item = DataItem;
+
+    fn into_iter(mut self) -> Self::IntoIter {
+        std::mem::take(&mut self.array).into_iter()
+    }
+}
+
+impl<T> FromIterator<T> for ArrayContent
+where
+    T: Into<DataItem>,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut array_content = Self::default();
+        array_content.extend(iter);
+        array_content
+    }
+}
+
+impl<T> Extend<T> for ArrayContent
+where
+    T: Into<DataItem>,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.array.extend(iter.into_iter().map(Into::into));
+    }
 }
 
 /// Struct which holds a map content
@@ -276,10 +989,168 @@ impl ArrayContent {
 /// content.set_indefinite(true);
 /// assert!(content.is_indefinite());
 /// ```
-#[derive(Default, PartialEq, Clone)]
+#[derive(Default, Clone)]
 pub struct MapContent {
     is_indefinite: bool,
+    state: Box<MapState>,
+}
+
+/// Entries of a map along with data derived from them. It is boxed so a map
+/// content, which is the largest content, doesn't increase a size of every
+/// data item
+#[derive(Default, Clone)]
+struct MapState {
     map: IndexMap<DataItem, DataItem>,
+    duplicates: Vec<(usize, DataItem, DataItem)>,
+    sorted_index: Option<SortedIndex>,
+    digest: OnceLock<u64>,
+}
+
+impl Drop for MapContent {
+    fn drop(&mut self) {
+        let state = &mut *self.state;
+        if state
+            .map
+            .iter()
+            .chain(state.duplicates.iter().map(|(_, key, value)| (key, value)))
+            .any(|(key, value)| has_nested_content(key) || has_nested_content(value))
+        {
+            drop_iteratively(
+                std::mem::take(&mut state.map)
+                    .into_iter()
+                    .chain(
+                        state
+                            .duplicates
+                            .drain(..)
+                            .map(|(_, key, value)| (key, value)),
+                    )
+                    .flat_map(|(key, value)| [key, value])
+                    .collect(),
+            );
+        }
+    }
+}
+
+impl PartialEq for MapContent {
+    fn eq(&self, other: &Self) -> bool {
+        self.is_indefinite == other.is_indefinite
+            && self.state.map == other.state.map
+            && self.state.duplicates == other.state.duplicates
+    }
+}
+
+/// Mix bits of a hash using a finalizer of splitmix64 so every bit of an input
+/// affects every bit of an output
+fn mix_hash(mut hash: u64) -> u64 {
+    hash ^= hash >> 30;
+    hash = hash.wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    hash ^= hash >> 27;
+    hash = hash.wrapping_mul(0x94d0_49bb_1331_11eb);
+    hash ^ (hash >> 31)
+}
+
+/// Position of keys of a map sorted by their deterministic encoding along with
+/// that encoding. Keys with same deterministic encoding are ordered by an order
+/// of a data item. Positions are built lazily on a first sorted access and are
+/// updated in place when a single entry is inserted or removed, while a bulk
+/// change of a map builds them again
+#[derive(Clone)]
+struct SortedIndex {
+    mode: DeterministicMode,
+    positions: OnceLock<Vec<(usize, Vec<u8>)>>,
+}
+
+impl SortedIndex {
+    fn new(mode: DeterministicMode) -> Self {
+        Self {
+            mode,
+            positions: OnceLock::new(),
+        }
+    }
+
+    fn positions(&self, map: &IndexMap<DataItem, DataItem>) -> &[(usize, Vec<u8>)] {
+        self.positions.get_or_init(|| {
+            let mut positions = map
+                .keys()
+                .map(|key| key.encode_deterministic(&self.mode))
+                .enumerate()
+                .collect::<Vec<_>>();
+            positions.sort_by(|(first, first_encoded), (second, second_encoded)| {
+                self.mode
+                    .compare(first_encoded, second_encoded)
+                    .then_with(|| key_at(map, *first).cmp(&key_at(map, *second)))
+            });
+            positions
+        })
+    }
+
+    fn search(&self, map: &IndexMap<DataItem, DataItem>, key: &DataItem) -> Result<usize, usize> {
+        self.search_encoded(map, key, &key.encode_deterministic(&self.mode))
+    }
+
+    fn search_encoded(
+        &self,
+        map: &IndexMap<DataItem, DataItem>,
+        key: &DataItem,
+        encoded: &[u8],
+    ) -> Result<usize, usize> {
+        self.positions(map)
+            .binary_search_by(|(position, entry_encoded)| {
+                self.mode
+                    .compare(entry_encoded, encoded)
+                    .then_with(|| key_at(map, *position).cmp(&Some(key)))
+            })
+    }
+
+    /// Get a sorted position of a key at provided index of a map when
+    /// positions are built
+    fn slot(&self, map: &IndexMap<DataItem, DataItem>, index: usize) -> Option<usize> {
+        self.positions.get()?;
+        self.search(map, key_at(map, index)?).ok()
+    }
+
+    /// Insert a position of a new key at provided index of a map using a
+    /// binary search when positions are built
+    fn insert(&mut self, map: &IndexMap<DataItem, DataItem>, index: usize) {
+        let Some(key) = key_at(map, index).filter(|_| self.positions.get().is_some()) else {
+            return;
+        };
+        let encoded = key.encode_deterministic(&self.mode);
+        let slot = self
+            .search_encoded(map, key, &encoded)
+            .unwrap_or_else(|slot| slot);
+        if let Some(positions) = self.positions.get_mut() {
+            positions.insert(slot, (index, encoded));
+        }
+    }
+
+    /// Remove a sorted position of a key which was at provided index of a map
+    /// before being removed. When `shifted` is true all entries after an index
+    /// moved back by one, otherwise a last entry of a map moved to an index.
+    /// Positions are built again when a sorted position of a removed key is
+    /// not known
+    fn remove(&mut self, slot: Option<usize>, index: usize, len: usize, shifted: bool) {
+        let Some(positions) = self.positions.get_mut() else {
+            return;
+        };
+        let Some(slot) = slot.filter(|slot| *slot < positions.len()) else {
+            self.positions.take();
+            return;
+        };
+        positions.remove(slot);
+        for (position, _) in positions {
+            if shifted && *position > index {
+                *position -= 1;
+            } else if !shifted && *position == len {
+                *position = index;
+            }
+        }
+    }
+}
+
+/// Get a key at a position of a map
+fn key_at(map: &IndexMap<DataItem, DataItem>, position: usize) -> Option<&DataItem> {
+    map.get_index(position).map(|(key, _)| key)
 }
 
 impl<T, U> From<IndexMap<T, U>> for MapContent
@@ -290,11 +1161,30 @@ where
     fn from(value: IndexMap<T, U>) -> Self {
         Self {
             is_indefinite: false,
-            map: value
-                .into_iter()
-                .map(|(k, v)| (k.into(), v.into()))
-                .collect(),
+            state: Box::new(MapState {
+                map: value
+                    .into_iter()
+                    .map(|(k, v)| (k.into(), v.into()))
+                    .collect(),
+                ..MapState::default()
+            }),
+        }
+    }
+}
+
+impl Debug for MapContent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            return f.write_str(&DataItem::Map(self.clone()).to_diagnostic_pretty(4));
+        }
+        write!(f, "{}", if self.is_indefinite { "{_ " } else { "{" })?;
+        for (index, (key, value)) in self.iter_with_duplicates().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{key:?}: {value:?}")?;
         }
+        write!(f, "}}")
     }
 }
 
@@ -311,7 +1201,17 @@ impl MapContent {
         K: Into<DataItem> + Clone,
         V: Into<DataItem> + Clone,
     {
-        self.map = map.iter().map(|(k, v)| (k.into(), v.into())).collect();
+        self.state.map = map.iter().map(|(k, v)| (k.into(), v.into())).collect();
+        self.invalidate_sorted_index();
+        self.state.digest.take();
+        self
+    }
+
+    /// Set value to a content by taking ownership of a map without cloning
+    pub fn set_content_owned(&mut self, map: IndexMap<DataItem, DataItem>) -> &mut Self {
+        self.state.map = map;
+        self.invalidate_sorted_index();
+        self.state.digest.take();
         self
     }
 
@@ -321,7 +1221,7 @@ impl MapContent {
         K: Into<DataItem>,
         V: Into<DataItem>,
     {
-        self.map.insert(key.into(), value.into());
+        self.insert(key, value);
         self
     }
 
@@ -335,7 +1235,9 @@ impl MapContent {
             .iter()
             .map(|(k, v)| (DataItem::from(k), DataItem::from(v)))
             .collect::<Vec<(_, _)>>();
-        self.map.extend(new_map);
+        self.state.map.extend(new_map);
+        self.invalidate_sorted_index();
+        self.state.digest.take();
         self
     }
 
@@ -348,13 +1250,559 @@ impl MapContent {
     /// Get map
     #[must_use]
     pub fn map(&self) -> &IndexMap<DataItem, DataItem> {
-        &self.map
+        &self.state.map
     }
 
-    /// Get map as mut
+    /// Get map as mut. Sorted index of a map keeps its mode but its positions
+    /// are built again on a next sorted access since a map can be modified
+    /// without updating them
     #[must_use]
     pub fn map_mut(&mut self) -> &mut IndexMap<DataItem, DataItem> {
-        &mut self.map
+        self.invalidate_sorted_index();
+        self.state.digest.take();
+        &mut self.state.map
+    }
+
+    /// Set whether a map content maintains keys sorted by their deterministic
+    /// encoding in provided mode alongside an insertion order. `None` removes
+    /// a sorted index
+    ///
+    /// Sorted index stores a position of each key along with its deterministic
+    /// encoding. It is built lazily on a first sorted access, a single
+    /// inserted or removed key updates it using a binary search and a bulk
+    /// change such as [`MapContent::retain`] or [`MapContent::map_mut`] builds
+    /// it again on a next sorted access. So a map can be iterated in a sorted
+    /// order, a sorted position of a key can be found using a binary search and
+    /// a map can be encoded deterministically in same mode without sorting
+    /// again
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::{DataItem, DeterministicMode, MapContent};
+    ///
+    /// let mut content = MapContent::default();
+    /// content.set_sorted_index(Some(DeterministicMode::Core));
+    /// content.insert("b", 1);
+    /// content.insert(10, 2);
+    /// content.insert("a", 3);
+    /// assert_eq!(content.sorted_position(&DataItem::from("a")), Some(1));
+    /// assert_eq!(
+    ///     content
+    ///         .sorted_iter()
+    ///         .unwrap()
+    ///         .map(|(key, _)| key.clone())
+    ///         .collect::<Vec<_>>(),
+    ///     vec![DataItem::from(10), DataItem::from("a"), DataItem::from("b")]
+    /// );
+    /// assert_eq!(content.keys().next(), Some(&DataItem::from("b")));
+    /// ```
+    pub fn set_sorted_index(&mut self, mode: Option<DeterministicMode>) -> &mut Self {
+        self.state.sorted_index = mode.map(SortedIndex::new);
+        self
+    }
+
+    /// Get a deterministic mode used by a sorted index of a map content
+    #[must_use]
+    pub fn sorted_index_mode(&self) -> Option<DeterministicMode> {
+        self.state
+            .sorted_index
+            .as_ref()
+            .map(|sorted_index| sorted_index.mode)
+    }
+
+    /// Get a position of a key in a sorted order using a binary search.
+    /// Returns `None` if a key is not present or a sorted index is not set
+    #[must_use]
+    pub fn sorted_position(&self, key: &DataItem) -> Option<usize> {
+        self.state
+            .sorted_index
+            .as_ref()?
+            .search(&self.state.map, key)
+            .ok()
+    }
+
+    /// Get a key and value at a position of a sorted order. Returns `None` if
+    /// a position is out of bounds or a sorted index is not set
+    #[must_use]
+    pub fn get_sorted(&self, position: usize) -> Option<(&DataItem, &DataItem)> {
+        let sorted_index = self.state.sorted_index.as_ref()?;
+        let (position, _) = sorted_index.positions(&self.state.map).get(position)?;
+        self.state.map.get_index(*position)
+    }
+
+    /// Get an iterator over key and value pairs in a sorted order. Returns
+    /// `None` if a sorted index is not set
+    #[must_use]
+    pub fn sorted_iter(&self) -> Option<impl Iterator<Item = (&DataItem, &DataItem)>> {
+        let sorted_index = self.state.sorted_index.as_ref()?;
+        Some(
+            sorted_index
+                .positions(&self.state.map)
+                .iter()
+                .filter_map(|(position, _)| self.state.map.get_index(*position)),
+        )
+    }
+
+    /// Get entries in a deterministic order of provided mode along with a
+    /// deterministic encoding of their key using a sorted index. A later entry
+    /// wins when two keys have a same deterministic encoding. Returns `None` if
+    /// a sorted index is not set for provided mode
+    pub(crate) fn deterministic_entries(
+        &self,
+        mode: DeterministicMode,
+    ) -> Option<Vec<(&[u8], &DataItem)>> {
+        let sorted_index = self
+            .state
+            .sorted_index
+            .as_ref()
+            .filter(|sorted_index| sorted_index.mode == mode)?;
+        let mut entries: Vec<(usize, &[u8])> = vec![];
+        for (position, encoded) in sorted_index.positions(&self.state.map) {
+            match entries.last_mut() {
+                Some(last) if last.1 == encoded.as_slice() => {
+                    last.0 = last.0.max(*position);
+                }
+                _ => entries.push((*position, encoded)),
+            }
+        }
+        Some(
+            entries
+                .into_iter()
+                .filter_map(|(position, encoded)| {
+                    self.state
+                        .map
+                        .get_index(position)
+                        .map(|(_, value)| (encoded, value))
+                })
+                .collect(),
+        )
+    }
+
+    /// Remove positions of a sorted index so they are built again on a next
+    /// sorted access
+    fn invalidate_sorted_index(&mut self) {
+        if let Some(sorted_index) = &mut self.state.sorted_index {
+            sorted_index.positions.take();
+        }
+    }
+
+    /// Get a sorted position of a key at provided index of a map, which is
+    /// looked up before a key is removed
+    fn sorted_slot(&self, index: usize) -> Option<usize> {
+        self.state
+            .sorted_index
+            .as_ref()?
+            .slot(&self.state.map, index)
+    }
+
+    /// Update a sorted index after a key at provided index of a map is removed
+    fn remove_sorted(&mut self, slot: Option<usize>, index: usize, shifted: bool) {
+        let len = self.state.map.len();
+        if let Some(sorted_index) = &mut self.state.sorted_index {
+            sorted_index.remove(slot, index, len, shifted);
+        }
+    }
+
+    /// Set entries whose key repeats a key already present in a map by
+    /// overriding old duplicate entries. Each entry holds its index among all
+    /// entries of a map, duplicate included, and entries are kept sorted by it
+    pub fn set_duplicates(
+        &mut self,
+        mut duplicates: Vec<(usize, DataItem, DataItem)>,
+    ) -> &mut Self {
+        duplicates.sort_by_key(|(index, ..)| *index);
+        self.state.duplicates = duplicates;
+        self
+    }
+
+    /// Get entries whose key repeats a key already present in a map along
+    /// with their index among all decoded entries of a map. Only populated when
+    /// decoding with [`crate::DecodeOptions::set_preserve_duplicate_keys`]
+    /// where a map keeps a first entry of a key
+    ///
+    /// Duplicate entries are encoded and printed as diagnostic notation at
+    /// their index so a decoded map is encoded back to same bytes, while a
+    /// deterministic encoding drops them since a deterministic map cannot have
+    /// a repeated key
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::{DataItem, DecodeOptions};
+    ///
+    /// let bytes = [0xa2, 0x01, 0x02, 0x01, 0x03];
+    /// let mut options = DecodeOptions::default();
+    /// options.set_preserve_duplicate_keys(true);
+    /// let data_item = DataItem::decode_with_options(&bytes, &options).unwrap();
+    /// let DataItem::Map(map_content) = &data_item else {
+    ///     unreachable!()
+    /// };
+    /// assert_eq!(
+    ///     map_content.get(&DataItem::from(1)),
+    ///     Some(&DataItem::from(2))
+    /// );
+    /// assert_eq!(
+    ///     map_content.duplicates(),
+    ///     &[(1, DataItem::from(1), DataItem::from(3))]
+    /// );
+    /// assert_eq!(data_item.encode(), bytes);
+    /// assert_eq!(format!("{data_item:?}"), "{1: 2, 1: 3}");
+    /// ```
+    #[must_use]
+    pub fn duplicates(&self) -> &[(usize, DataItem, DataItem)] {
+        &self.state.duplicates
+    }
+
+    /// Get an iterator over entries along with duplicate entries placed at
+    /// their index. Duplicate entry whose index is past all entries, such as
+    /// after entries of a map are removed, is placed at an end
+    pub fn iter_with_duplicates(&self) -> impl Iterator<Item = (&DataItem, &DataItem)> {
+        let mut entries = self.state.map.iter();
+        let mut duplicates = self.state.duplicates.iter().peekable();
+        let mut position = 0;
+        std::iter::from_fn(move || {
+            let entry = match duplicates.next_if(|(index, ..)| *index <= position) {
+                Some((_, key, value)) => Some((key, value)),
+                None => {
+                    entries
+                        .next()
+                        .or_else(|| duplicates.next().map(|(_, key, value)| (key, value)))
+                }
+            };
+            position += 1;
+            entry
+        })
+    }
+
+    /// Get a digest of entries which doesn't depend on an order of entries.
+    /// Digest is computed lazily and cached until a map is mutated so hashing
+    /// a same map again doesn't walk all of its entries. Since a cached digest
+    /// only depends on entries, a data item containing a map can be used as a
+    /// hash map key even though clippy reports it as a mutable key type
+    ///
+    /// Each entry is hashed with a standard library default hasher and mixed
+    /// before entries are summed, so a sum doesn't depend on an order of
+    /// entries while entries with related hash don't cancel out. Since an
+    /// output of a default hasher is not guaranteed to be same across rust
+    /// releases, digest is only meaningful inside a same process and
+    /// shouldn't be persisted
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::MapContent;
+    ///
+    /// let first = MapContent::from_iter([(1, "a"), (2, "b")]);
+    /// let mut second = MapContent::from_iter([(2, "b"), (1, "a")]);
+    /// assert_eq!(first.digest(), second.digest());
+    /// second.insert(3, "c");
+    /// assert_ne!(first.digest(), second.digest());
+    /// ```
+    #[must_use]
+    pub fn digest(&self) -> u64 {
+        *self.state.digest.get_or_init(|| {
+            self.state.map.iter().fold(0, |digest: u64, entry| {
+                let mut hasher = DefaultHasher::new();
+                entry.hash(&mut hasher);
+                digest.wrapping_add(mix_hash(hasher.finish()))
+            })
+        })
+    }
+
+    /// Insert a key and value returning an old value of a key if present. New
+    /// key is inserted at an end while existing key keeps its position
+    pub fn insert<K, V>(&mut self, key: K, value: V) -> Option<DataItem>
+    where
+        K: Into<DataItem>,
+        V: Into<DataItem>,
+    {
+        let key = key.into();
+        self.state.digest.take();
+        let (index, old_value) = self.state.map.insert_full(key, value.into());
+        if old_value.is_none() {
+            let state = &mut *self.state;
+            if let Some(sorted_index) = &mut state.sorted_index {
+                sorted_index.insert(&state.map, index);
+            }
+        }
+        old_value
+    }
+
+    /// Remove a key returning its value by replacing it with a last entry of a
+    /// map. Use [`MapContent::shift_remove`] to preserve an order of entries
+    pub fn remove(&mut self, key: &DataItem) -> Option<DataItem> {
+        let index = self.state.map.get_index_of(key)?;
+        let slot = self.sorted_slot(index);
+        let (_, value) = self.state.map.swap_remove_index(index)?;
+        self.state.digest.take();
+        self.remove_sorted(slot, index, false);
+        Some(value)
+    }
+
+    /// Remove a key returning its value by shifting all entries after it
+    pub fn shift_remove(&mut self, key: &DataItem) -> Option<DataItem> {
+        let index = self.state.map.get_index_of(key)?;
+        self.shift_remove_index(index)
+    }
+
+    /// Check whether a map contains a key
+    #[must_use]
+    pub fn contains_key(&self, key: &DataItem) -> bool {
+        self.state.map.contains_key(key)
+    }
+
+    /// Get a value of a key
+    #[must_use]
+    pub fn get(&self, key: &DataItem) -> Option<&DataItem> {
+        self.state.map.get(key)
+    }
+
+    /// Get a mutable value of a key
+    pub fn get_mut(&mut self, key: &DataItem) -> Option<&mut DataItem> {
+        self.state.digest.take();
+        self.state.map.get_mut(key)
+    }
+
+    /// Get a value of a first key which is equal to provided key after
+    /// removing tags wrapping both keys
+    #[must_use]
+    pub fn get_ignoring_tags(&self, key: &DataItem) -> Option<&DataItem> {
+        let key = untagged(key);
+        self.state
+            .map
+            .iter()
+            .find_map(|(map_key, value)| (untagged(map_key) == key).then_some(value))
+    }
+
+    /// Get a value of a text string key without constructing a key. Both
+    /// definite and indefinite length text string key are matched
+    #[must_use]
+    pub fn get_str(&self, key: &str) -> Option<&DataItem> {
+        self.state.map.get(&TextKey(key))
+    }
+
+    /// Get a mutable value of a text string key without constructing a key
+    pub(crate) fn get_str_mut(&mut self, key: &str) -> Option<&mut DataItem> {
+        self.state.digest.take();
+        self.state.map.get_mut(&TextKey(key))
+    }
+
+    /// Remove a text string key returning its value by shifting all entries
+    /// after it without constructing a key
+    pub(crate) fn shift_remove_str(&mut self, key: &str) -> Option<DataItem> {
+        let index = self.state.map.get_index_of(&TextKey(key))?;
+        self.shift_remove_index(index)
+    }
+
+    /// Remove an entry at provided index returning its value by shifting all
+    /// entries after it
+    fn shift_remove_index(&mut self, index: usize) -> Option<DataItem> {
+        let slot = self.sorted_slot(index);
+        let (_, value) = self.state.map.shift_remove_index(index)?;
+        self.state.digest.take();
+        self.remove_sorted(slot, index, true);
+        Some(value)
+    }
+
+    /// Insert a value for a text string key returning an old value if present.
+    /// Existing definite or indefinite length text string key keeps its
+    /// position while a new key is inserted at an end as a definite length text
+    /// string
+    pub fn insert_str<V>(&mut self, key: &str, value: V) -> Option<DataItem>
+    where
+        V: Into<DataItem>,
+    {
+        match self.state.map.get_index_of(&TextKey(key)) {
+            Some(index) => {
+                self.state.digest.take();
+                Some(std::mem::replace(&mut self.state.map[index], value.into()))
+            }
+            None => self.insert(key, value),
+        }
+    }
+
+    /// Get a number of entries in a map
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.state.map.len()
+    }
+
+    /// Check whether a map has no entry
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.state.map.is_empty()
+    }
+
+    /// Get an iterator over keys in an order of a map
+    #[must_use]
+    pub fn keys(&self) -> Keys<'_, DataItem, DataItem> {
+        self.state.map.keys()
+    }
+
+    /// Get an iterator over values in an order of a map
+    #[must_use]
+    pub fn values(&self) -> Values<'_, DataItem, DataItem> {
+        self.state.map.values()
+    }
+
+    /// Get an iterator over key and value pairs in an order of a map
+    #[must_use]
+    pub fn iter(&self) -> Iter<'_, DataItem, DataItem> {
+        self.state.map.iter()
+    }
+
+    /// Get an iterator over key and mutable value pairs in an order of a map
+    #[must_use]
+    pub fn iter_mut(&mut self) -> IterMut<'_, DataItem, DataItem> {
+        self.state.digest.take();
+        self.state.map.iter_mut()
+    }
+
+    /// Retain only entries for which a predicate returns true while preserving
+    /// an order of remaining entries
+    pub fn retain<F>(&mut self, keep: F)
+    where
+        F: FnMut(&DataItem, &mut DataItem) -> bool,
+    {
+        self.state.map.retain(keep);
+        self.invalidate_sorted_index();
+        self.state.digest.take();
+    }
+
+    /// Sort entries in place using a comparator which receives key and value
+    /// of both entries
+    pub fn sort_by<F>(&mut self, compare: F)
+    where
+        F: FnMut(&DataItem, &DataItem, &DataItem, &DataItem) -> Ordering,
+    {
+        self.state.map.sort_by(compare);
+        self.invalidate_sorted_index();
+    }
+
+    /// Sort entries in place by a deterministic encoding of their key using
+    /// an order of provided deterministic mode
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::{DataItem, DeterministicMode, MapContent};
+    ///
+    /// let mut content = MapContent::default();
+    /// content.insert("bb", 1);
+    /// content.insert(1000, 2);
+    /// content.insert("a", 3);
+    /// content.sort_keys(&DeterministicMode::LengthFirst);
+    /// assert_eq!(
+    ///     content.keys().cloned().collect::<Vec<_>>(),
+    ///     vec![
+    ///         DataItem::from("a"),
+    ///         DataItem::from(1000),
+    ///         DataItem::from("bb")
+    ///     ]
+    /// );
+    /// ```
+    pub fn sort_keys(&mut self, mode: &DeterministicMode) {
+        self.invalidate_sorted_index();
+        self.state.map.sort_by_cached_key(|key, _| {
+            let encoded = key.encode_deterministic(mode);
+            match mode {
+                DeterministicMode::Core => (0, encoded),
+                DeterministicMode::LengthFirst => (encoded.len(), encoded),
+            }
+        });
+    }
+
+    /// Remove entries whose key has same deterministic encoding as a key of an
+    /// earlier entry, such as an indefinite length text string key and a
+    /// definite length text string key with same text. First entry is kept
+    pub fn dedup_keys(&mut self) {
+        let mut seen = HashSet::new();
+        self.state
+            .map
+            .retain(|key, _| seen.insert(key.encode_deterministic(&DeterministicMode::Core)));
+        self.invalidate_sorted_index();
+        self.state.digest.take();
+    }
+
+    /// Create an empty map content which can hold at least provided number of
+    /// entries without reallocating
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            is_indefinite: false,
+            state: Box::new(MapState {
+                map: IndexMap::with_capacity(capacity),
+                ..MapState::default()
+            }),
+        }
+    }
+
+    /// Get a number of entries a map content can hold without reallocating
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.state.map.capacity()
+    }
+
+    /// Reserve capacity for at least provided number of additional entries
+    pub fn reserve(&mut self, additional: usize) -> &mut Self {
+        self.state.map.reserve(additional);
+        self
+    }
+
+    /// Shrink capacity of a map content as much as possible
+    pub fn shrink_to_fit(&mut self) -> &mut Self {
+        self.state.map.shrink_to_fit();
+        self
+    }
+}
+
+impl<'a> IntoIterator for &'a MapContent {
+    type IntoIter = Iter<'a, DataItem, DataItem>;
+    type Item = (&'a DataItem, &'a DataItem);
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut MapContent {
+    type IntoIter = IterMut<'a, DataItem, DataItem>;
+    type Item = (&'a DataItem, &'a mut DataItem);
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl IntoIterator for MapContent {
+    type IntoIter = IntoIter<DataItem, DataItem>;
+    type Item = (DataItem, DataItem);
+
+    fn into_iter(mut self) -> Self::IntoIter {
+        std::mem::take(&mut self.state.map).into_iter()
+    }
+}
+
+impl<K, V> FromIterator<(K, V)> for MapContent
+where
+    K: Into<DataItem>,
+    V: Into<DataItem>,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map_content = Self::default();
+        map_content.extend(iter);
+        map_content
+    }
+}
+
+impl<K, V> Extend<(K, V)> for MapContent
+where
+    K: Into<DataItem>,
+    V: Into<DataItem>,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        self.state
+            .map
+            .extend(iter.into_iter().map(|(k, v)| (k.into(), v.into())));
+        self.invalidate_sorted_index();
+        self.state.digest.take();
     }
 }
 
@@ -378,6 +1826,65 @@ where
     }
 }
 
+impl Drop for TagContent {
+    fn drop(&mut self) {
+        if has_nested_content(&self.content) {
+            drop_iteratively(vec![std::mem::replace(&mut *self.content, DataItem::Null)]);
+        }
+    }
+}
+
+/// Check whether dropping a data item would drop another array, map or tag
+/// inside of it
+fn has_nested_content(data_item: &DataItem) -> bool {
+    match data_item {
+        DataItem::Array(array_content) => !array_content.array.is_empty(),
+        DataItem::Map(map_content) => {
+            !map_content.state.map.is_empty() || !map_content.state.duplicates.is_empty()
+        }
+        DataItem::Tag(_) => true,
+        _ => false,
+    }
+}
+
+/// Drop data items without recursion by moving a content of every array, map
+/// and tag into an explicit stack before dropping it, so dropping a deeply
+/// nested data item doesn't overflow a stack
+fn drop_iteratively(mut stack: Vec<DataItem>) {
+    while let Some(mut data_item) = stack.pop() {
+        match &mut data_item {
+            DataItem::Array(array_content) => stack.append(&mut array_content.array),
+            DataItem::Map(map_content) => {
+                let state = &mut *map_content.state;
+                stack.extend(
+                    std::mem::take(&mut state.map)
+                        .into_iter()
+                        .chain(
+                            state
+                                .duplicates
+                                .drain(..)
+                                .map(|(_, key, value)| (key, value)),
+                        )
+                        .flat_map(|(key, value)| [key, value]),
+                );
+            }
+            DataItem::Tag(tag_content) => {
+                stack.push(std::mem::replace(&mut *tag_content.content, DataItem::Null));
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Debug for TagContent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            return f.write_str(&DataItem::Tag(self.clone()).to_diagnostic_pretty(4));
+        }
+        write!(f, "{}({:?})", self.number, self.content)
+    }
+}
+
 impl TagContent {
     /// Get a number of tag
     #[must_use]
@@ -390,20 +1897,285 @@ impl TagContent {
     pub fn content(&self) -> &DataItem {
         &self.content
     }
+
+    /// Get a mutable content of tag
+    pub fn content_mut(&mut self) -> &mut DataItem {
+        &mut self.content
+    }
+
+    /// Set a number of tag
+    pub fn set_number(&mut self, number: u64) -> &mut Self {
+        self.number = number;
+        self
+    }
+
+    /// Set a content of tag by overriding old content
+    pub fn set_content<T>(&mut self, content: T) -> &mut Self
+    where
+        T: Into<DataItem>,
+    {
+        *self.content = content.into();
+        self
+    }
+
+    /// Transform a content of tag while keeping a number of tag
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::{DataItem, TagContent};
+    ///
+    /// let tag_content = TagContent::from((1, 10))
+    ///     .map_content(|content| DataItem::from(content.as_unsigned().unwrap_or_default() * 2));
+    /// assert_eq!(tag_content.into_parts(), (1, DataItem::from(20)));
+    /// ```
+    #[must_use]
+    pub fn map_content<F>(mut self, transform: F) -> Self
+    where
+        F: FnOnce(DataItem) -> DataItem,
+    {
+        let content = std::mem::replace(&mut *self.content, DataItem::Null);
+        *self.content = transform(content);
+        self
+    }
+
+    /// Split a tag into its number and content
+    #[must_use]
+    pub fn into_parts(mut self) -> (u64, DataItem) {
+        (
+            self.number,
+            std::mem::replace(&mut *self.content, DataItem::Null),
+        )
+    }
+
+    /// Create a standard date/time string (tag 0) from a RFC 3339 formatted
+    /// date time
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::TagContent;
+    ///
+    /// assert!(TagContent::datetime("2013-03-21T20:04:00Z").is_ok());
+    /// assert!(TagContent::datetime("2013-03-21").is_err());
+    /// ```
+    ///
+    /// # Errors
+    /// If date time is not a valid RFC 3339 date time
+    pub fn datetime(date_time: &str) -> Result<Self, Error> {
+        if !is_rfc3339(date_time) {
+            return Err(Error::InvalidTagContent {
+                tag: tag::DATETIME_STRING,
+                expected: "RFC 3339 date time string",
+            });
+        }
+        Ok(Self::from((tag::DATETIME_STRING, date_time)))
+    }
+
+    /// Create an epoch based date/time (tag 1) from a seconds since unix epoch.
+    /// Integer is used when seconds doesn't have a fractional part
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::{DataItem, TagContent};
+    ///
+    /// assert_eq!(
+    ///     TagContent::epoch(1_363_896_240.0).unwrap().content(),
+    ///     &DataItem::from(1_363_896_240)
+    /// );
+    /// assert!(TagContent::epoch(f64::NAN).is_err());
+    /// ```
+    ///
+    /// # Errors
+    /// If seconds is not a finite number
+    pub fn epoch(seconds: f64) -> Result<Self, Error> {
+        if !seconds.is_finite() {
+            return Err(Error::InvalidTagContent {
+                tag: tag::EPOCH,
+                expected: "integer or finite floating number",
+            });
+        }
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "we only want to check whether seconds is an integer in range of i64"
+        )]
+        if seconds.fract() == 0.0 && seconds.abs() < 9_223_372_036_854_775_808.0 {
+            return Ok(Self::from((tag::EPOCH, seconds as i64)));
+        }
+        Ok(Self::from((tag::EPOCH, seconds)))
+    }
+
+    /// Create an URI (tag 32)
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::TagContent;
+    ///
+    /// assert!(TagContent::uri("http://www.example.com").is_ok());
+    /// assert!(TagContent::uri("www.example.com").is_err());
+    /// ```
+    ///
+    /// # Errors
+    /// If URI doesn't starts with a valid scheme
+    pub fn uri(uri: &str) -> Result<Self, Error> {
+        if !is_uri(uri) {
+            return Err(Error::InvalidTagContent {
+                tag: tag::URI,
+                expected: "URI text string",
+            });
+        }
+        Ok(Self::from((tag::URI, uri)))
+    }
+
+    /// Create a positive bignum (tag 2) or negative bignum (tag 3) from a big
+    /// endian magnitude bytes. For negative bignum value represented is -1
+    /// minus magnitude
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::{DataItem, TagContent};
+    ///
+    /// assert_eq!(
+    ///     DataItem::from(TagContent::bignum(&[1, 0, 0, 0, 0, 0, 0, 0, 0], true)),
+    ///     DataItem::from(-18_446_744_073_709_551_617_i128)
+    /// );
+    /// ```
+    #[must_use]
+    pub fn bignum(magnitude: &[u8], negative: bool) -> Self {
+        let number = if negative {
+            tag::NEG_BIGNUM
+        } else {
+            tag::POS_BIGNUM
+        };
+        Self::from((number, magnitude))
+    }
+
+    /// Create a mathematical finite set (tag 258) containing provided items as
+    /// an array
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::collections::BTreeSet;
+    ///
+    /// use cbor_next::{DataItem, TagContent};
+    ///
+    /// let set = BTreeSet::from([2, 1]);
+    /// assert_eq!(
+    ///     format!("{:?}", DataItem::from(TagContent::set(set))),
+    ///     "258([1, 2])"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn set<I, T>(items: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<DataItem>,
+    {
+        Self::from((
+            tag::SET,
+            items.into_iter().map(Into::into).collect::<Vec<DataItem>>(),
+        ))
+    }
+}
+
+/// Check if a text starts with a valid URI scheme
+pub(crate) fn is_uri(text: &str) -> bool {
+    let Some((scheme, _)) = text.split_once(':') else {
+        return false;
+    };
+    let mut scheme_chars = scheme.chars();
+    scheme_chars
+        .next()
+        .is_some_and(|character| character.is_ascii_alphabetic())
+        && scheme_chars.all(|character| {
+            character.is_ascii_alphanumeric() || matches!(character, '+' | '-' | '.')
+        })
 }
 
-/// struct representing simple value which only allow number between 0-19 and
+/// Check if a text is a RFC 3339 formatted date time
+pub(crate) fn is_rfc3339(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    let number = |range: std::ops::Range<usize>| -> Option<u32> {
+        let digits = bytes.get(range)?;
+        if !digits.iter().all(u8::is_ascii_digit) {
+            return None;
+        }
+        digits.iter().try_fold(0u32, |value, digit| {
+            Some(value * 10 + u32::from(digit - b'0'))
+        })
+    };
+    let separator = |index: usize, expected: &[u8]| {
+        bytes
+            .get(index)
+            .is_some_and(|character| expected.contains(character))
+    };
+    let (Some(year), Some(month), Some(day), Some(hour), Some(minute), Some(second)) = (
+        number(0..4),
+        number(5..7),
+        number(8..10),
+        number(11..13),
+        number(14..16),
+        number(17..19),
+    ) else {
+        return false;
+    };
+    let is_leap_year = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year => 29,
+        2 => 28,
+        _ => return false,
+    };
+    if !(separator(4, b"-")
+        && separator(7, b"-")
+        && separator(10, b"Tt")
+        && separator(13, b":")
+        && separator(16, b":")
+        && (1..=days_in_month).contains(&day)
+        && hour <= 23
+        && minute <= 59
+        && second <= 60)
+    {
+        return false;
+    }
+    let mut index = 19;
+    if separator(index, b".") {
+        index += 1;
+        let fraction_start = index;
+        while bytes.get(index).is_some_and(u8::is_ascii_digit) {
+            index += 1;
+        }
+        if index == fraction_start {
+            return false;
+        }
+    }
+    if separator(index, b"Zz") {
+        return index + 1 == bytes.len();
+    }
+    separator(index, b"+-")
+        && index + 6 == bytes.len()
+        && separator(index + 3, b":")
+        && number(index + 1..index + 3).is_some_and(|offset_hour| offset_hour <= 23)
+        && number(index + 4..index + 6).is_some_and(|offset_minute| offset_minute <= 59)
+}
+
+/// struct representing simple value which only allow number between 0-23 and
 /// 32-255. This struct is simple wrapper above `u8` with validation
 ///
+/// Simple value between 20-23 are false, true, null and undefined which are
+/// converted to their dedicated data item variant when converting to a data
+/// item
+///
 /// # Example
 /// ```rust
-/// use cbor_next::SimpleValue;
+/// use cbor_next::{DataItem, SimpleValue};
 ///
 /// assert!(SimpleValue::try_from(10).is_ok());
 /// assert!(SimpleValue::try_from(100).is_ok());
 /// assert!(SimpleValue::try_from(255).is_ok());
 /// assert!(SimpleValue::try_from(24).is_err());
 /// assert!(SimpleValue::try_from(29).is_err());
+/// assert_eq!(SimpleValue::try_from(22).unwrap(), SimpleValue::NULL);
+/// assert_eq!(DataItem::from(SimpleValue::TRUE), DataItem::Boolean(true));
 /// ```
 #[derive(PartialEq, Hash, Clone)]
 pub struct SimpleValue(u8);
@@ -417,7 +2189,7 @@ impl Deref for SimpleValue {
 }
 
 impl Debug for SimpleValue {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "simple({})", self.0)
     }
 }
@@ -426,9 +2198,76 @@ impl TryFrom<u8> for SimpleValue {
     type Error = Error;
 
     fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Self::new(value).ok_or(Error::InvalidSimple)
+    }
+}
+
+impl Display for SimpleValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "simple({})", self.0)
+    }
+}
+
+impl SimpleValue {
+    /// Simple value false (20)
+    pub const FALSE: Self = Self(20);
+    /// Simple value null (22)
+    pub const NULL: Self = Self(22);
+    /// Simple value true (21)
+    pub const TRUE: Self = Self(21);
+    /// Simple value undefined (23)
+    pub const UNDEFINED: Self = Self(23);
+
+    /// Create a simple value. Returns `None` if a number is between 24-31
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::SimpleValue;
+    ///
+    /// const SIMPLE: Option<SimpleValue> = SimpleValue::new(45);
+    /// assert_eq!(SIMPLE.map(|simple| simple.value()), Some(45));
+    /// assert_eq!(SimpleValue::new(45).unwrap().to_string(), "simple(45)");
+    /// assert!(SimpleValue::new(24).is_none());
+    /// ```
+    #[must_use]
+    pub const fn new(value: u8) -> Option<Self> {
         match value {
-            0..=19 | 32..=u8::MAX => Ok(Self(value)),
-            _ => Err(Error::InvalidSimple),
+            0..=23 | 32..=u8::MAX => Some(Self(value)),
+            _ => None,
+        }
+    }
+
+    /// Get a number of a simple value
+    #[must_use]
+    pub const fn value(&self) -> u8 {
+        self.0
+    }
+
+    /// Get a name of a simple value registered in IANA CBOR simple values
+    /// registry
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::SimpleValue;
+    ///
+    /// assert_eq!(SimpleValue::UNDEFINED.name(), Some("undefined"));
+    /// assert_eq!(SimpleValue::try_from(16).unwrap().name(), None);
+    /// ```
+    #[must_use]
+    pub fn name(&self) -> Option<&'static str> {
+        match self.0 {
+            20 => Some("false"),
+            21 => Some("true"),
+            22 => Some("null"),
+            23 => Some("undefined"),
+            _ => None,
         }
     }
+
+    /// Check whether a simple value is not assigned in IANA CBOR simple values
+    /// registry
+    #[must_use]
+    pub fn is_unassigned(&self) -> bool {
+        self.name().is_none()
+    }
 }
@@ -1,15 +1,36 @@
 use core::f64;
+use std::borrow::Cow;
 use std::cmp::Ordering;
-use std::fmt::{Debug, Write as _};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Debug;
 use std::hash::Hash;
-use std::num::TryFromIntError;
+use std::ops::RangeBounds;
 use std::slice::Iter;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
 
 use indexmap::IndexMap;
 
+use crate::builder::{ArrayBuilder, MapBuilder};
 use crate::content::{ArrayContent, ByteContent, MapContent, SimpleValue, TagContent, TextContent};
+use crate::data_item::private::Integer;
+#[cfg(any(feature = "chrono", feature = "time"))]
+use crate::datetime::Timestamp;
+use crate::decode_options::DecodeOptions;
 use crate::deterministic::DeterministicMode;
-use crate::error::Error;
+use crate::diagnostic_options::DiagnosticOptions;
+use crate::error::{Error, ItemKind, Limit, Malformed};
+use crate::fixed::FixedEncoder;
+use crate::float16::{f16_bits_to_f64, f64_to_f16_bits};
+use crate::index::{Get as _, PathSegment};
+use crate::intern::Interner;
+use crate::json_options::JsonOptions;
+use crate::merge_options::MergeOptions;
+use crate::span::{SpanCollector, SpanTree};
+use crate::tag;
+use crate::tag_registry::TagRegistry;
+use crate::typed_array::{Endianness, TypedArrayElement};
+use crate::write::CborWrite;
 
 /// Enum representing different types of data item that can be encoded or
 /// decoded in `CBOR` (Concise Binary Object Representation).
@@ -17,7 +38,7 @@ use crate::error::Error;
 /// `CBOR` is a data format designed for small code and message size, often used
 /// in constrained environments. This `DataItem` enum covers all major types
 /// defined in the `CBOR` specification (RFC 8949).
-#[derive(PartialEq, Clone)]
+#[derive(Clone)]
 #[non_exhaustive]
 pub enum DataItem {
     /// Unsigned integer represented by `CBOR` major type 0.
@@ -85,9 +106,12 @@ pub enum DataItem {
 
 impl Debug for DataItem {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() && matches!(self, Self::Array(_) | Self::Map(_) | Self::Tag(_)) {
+            return f.write_str(&self.to_diagnostic_pretty(4));
+        }
         match self {
             Self::Unsigned(number) => number.fmt(f),
-            Self::Signed(number) => (-i128::from(number + 1)).fmt(f),
+            Self::Signed(number) => (-1 - i128::from(*number)).fmt(f),
             Self::Floating(number) => {
                 if number.is_nan() {
                     return write!(f, "NaN");
@@ -102,74 +126,23 @@ impl Debug for DataItem {
             Self::Null => write!(f, "null"),
             Self::Undefined => write!(f, "undefined"),
             Self::GenericSimple(simple_number) => simple_number.fmt(f),
-            Self::Byte(bytes) => {
-                if bytes.is_indefinite() {
-                    write!(f, "(_ ")?;
-                    let mut chunk_contents = vec![];
-                    for chunk in bytes.chunk() {
-                        let mut content = "h'".to_string();
-                        for byte in chunk {
-                            write!(content, "{byte:02x}")?;
-                        }
-                        content.push('\'');
-                        chunk_contents.push(content);
-                    }
-                    let content = chunk_contents.join(", ");
-                    write!(f, "{content}")?;
-                    write!(f, ")")
-                } else {
-                    write!(f, "h'")?;
-                    for byte in bytes.full() {
-                        write!(f, "{byte:02x}")?;
-                    }
-                    write!(f, "'")
-                }
-            }
-            Self::Text(text_content) => {
-                if text_content.is_indefinite() {
-                    write!(f, "(_ ")?;
-                    let mut chunk_contents = vec![];
-                    for chunk in text_content.chunk() {
-                        chunk_contents.push(format!("{chunk:?}"));
-                    }
-                    let content = chunk_contents.join(", ");
-                    write!(f, "{content}")?;
-                    write!(f, ")")
-                } else {
-                    write!(f, "{:?}", text_content.full())
-                }
-            }
-            Self::Array(array) => {
-                let mut array_item_vec = vec![];
-                for item in array.array() {
-                    array_item_vec.push(format!("{item:?}"));
-                }
-                let array_item_str = array_item_vec.join(", ");
-                if array.is_indefinite() {
-                    write!(f, "[_ {array_item_str}]")
-                } else {
-                    write!(f, "[{array_item_str}]")
-                }
-            }
-            Self::Map(map) => {
-                let mut array_item_vec = vec![];
-                for (key, value) in map.map() {
-                    array_item_vec.push(format!("{key:?}: {value:?}"));
-                }
-                let array_item_str = array_item_vec.join(", ");
-                if map.is_indefinite() {
-                    write!(f, "{{_ {array_item_str}}}")
-                } else {
-                    write!(f, "{{{array_item_str}}}")
-                }
-            }
-            Self::Tag(tag_content) => {
-                write!(f, "{:?}({:?})", tag_content.number(), tag_content.content())
-            }
+            Self::Byte(byte_content) => byte_content.fmt(f),
+            Self::Text(text_content) => text_content.fmt(f),
+            Self::Array(array_content) => array_content.fmt(f),
+            Self::Map(map_content) => map_content.fmt(f),
+            Self::Tag(tag_content) => tag_content.fmt(f),
         }
     }
 }
 
+impl FromStr for DataItem {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_diagnostic(s)
+    }
+}
+
 impl Hash for DataItem {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         std::mem::discriminant(self).hash(state);
@@ -178,10 +151,10 @@ impl Hash for DataItem {
             Self::Byte(items) => items.hash(state),
             Self::Text(text) => text.hash(state),
             Self::Array(values) => values.hash(state),
-            Self::Map(index_map) => {
-                index_map.is_indefinite().hash(state);
-                let vals = index_map.map().iter().collect::<Vec<(_, _)>>();
-                vals.hash(state);
+            Self::Map(map_content) => {
+                map_content.is_indefinite().hash(state);
+                map_content.len().hash(state);
+                map_content.digest().hash(state);
             }
             Self::Tag(tag_content) => {
                 tag_content.number().hash(state);
@@ -195,8 +168,83 @@ impl Hash for DataItem {
     }
 }
 
+/// Floating point number is equal when its bits are same, which keeps an
+/// equality consistent with a hash and an order of a data item
+impl PartialEq for DataItem {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Unsigned(first), Self::Unsigned(second))
+            | (Self::Signed(first), Self::Signed(second)) => first == second,
+            (Self::Byte(first), Self::Byte(second)) => first == second,
+            (Self::Text(first), Self::Text(second)) => first == second,
+            (Self::Array(first), Self::Array(second)) => first == second,
+            (Self::Map(first), Self::Map(second)) => first == second,
+            (Self::Tag(first), Self::Tag(second)) => first == second,
+            (Self::Boolean(first), Self::Boolean(second)) => first == second,
+            (Self::Floating(first), Self::Floating(second)) => first.to_bits() == second.to_bits(),
+            (Self::GenericSimple(first), Self::GenericSimple(second)) => first == second,
+            (Self::Null, Self::Null) | (Self::Undefined, Self::Undefined) => true,
+            _ => false,
+        }
+    }
+}
+
 impl Eq for DataItem {}
 
+/// Default data item is null
+impl Default for DataItem {
+    fn default() -> Self {
+        Self::Null
+    }
+}
+
+/// Data item are ordered by a bytewise order of their core deterministic
+/// encoding, which is compared structurally without encoding a data item:
+/// major type first, then an argument such as a number, a length or a tag
+/// number, then nested data items where entries of a map are compared in a
+/// sorted order of their key. Data item with same deterministic encoding, such
+/// as an indefinite and a definite length string with same content, are then
+/// ordered by their remaining structure so an order agrees with an equality
+///
+/// Floating point number is compared by its bits, so `0.0` and `-0.0` are
+/// different while NaN is equal to a NaN with a same payload
+///
+/// # Example
+/// ```rust
+/// use std::collections::BTreeSet;
+///
+/// use cbor_next::DataItem;
+///
+/// let set = BTreeSet::from([
+///     DataItem::from("a"),
+///     DataItem::from(100),
+///     DataItem::from(-1),
+///     DataItem::from(1),
+/// ]);
+/// assert_eq!(
+///     set.into_iter().collect::<Vec<_>>(),
+///     vec![
+///         DataItem::from(1),
+///         DataItem::from(100),
+///         DataItem::from(-1),
+///         DataItem::from("a")
+///     ]
+/// );
+/// assert_ne!(DataItem::from(0.0), DataItem::from(-0.0));
+/// assert_eq!(DataItem::from(f64::NAN), DataItem::from(f64::NAN));
+/// ```
+impl Ord for DataItem {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare_deterministic(self, other).then_with(|| compare_exact(self, other))
+    }
+}
+
+impl PartialOrd for DataItem {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl From<u64> for DataItem {
     fn from(value: u64) -> Self {
         Self::Unsigned(value)
@@ -217,18 +265,19 @@ macro_rules! impl_from {
 
 impl_from!(u64, u32, u16, u8);
 
-impl TryFrom<u128> for DataItem {
-    type Error = TryFromIntError;
-
-    fn try_from(value: u128) -> Result<Self, Self::Error> {
-        Ok(u64::try_from(value)?.into())
+impl From<u128> for DataItem {
+    fn from(value: u128) -> Self {
+        match u64::try_from(value) {
+            Ok(u64_val) => Self::Unsigned(u64_val),
+            Err(_) => TagContent::from((tag::POS_BIGNUM, bignum_bytes(value).as_slice())).into(),
+        }
     }
 }
 
 impl From<i64> for DataItem {
     fn from(value: i64) -> Self {
         if value.is_negative() {
-            let positive_val = -value - 1;
+            let positive_val = -(value + 1);
             let u64_val =
                 u64::try_from(positive_val).expect("i64 positive can be converted to u64");
             Self::Signed(u64_val)
@@ -241,15 +290,36 @@ impl From<i64> for DataItem {
 
 impl_from!(i64, i32, i16, i8);
 
-impl TryFrom<i128> for DataItem {
-    type Error = TryFromIntError;
+mod private {
+    use super::DataItem;
+
+    /// Primitive integer which can be converted to an integer data item
+    pub trait Integer: Into<DataItem> {}
+    impl Integer for u8 {}
+    impl Integer for u16 {}
+    impl Integer for u32 {}
+    impl Integer for u64 {}
+    impl Integer for u128 {}
+    impl Integer for i8 {}
+    impl Integer for i16 {}
+    impl Integer for i32 {}
+    impl Integer for i64 {}
+    impl Integer for i128 {}
+}
 
-    fn try_from(value: i128) -> Result<Self, Self::Error> {
+impl From<i128> for DataItem {
+    fn from(value: i128) -> Self {
         if value.is_negative() {
-            let positive_val = -value - 1;
-            Ok(Self::Signed(u64::try_from(positive_val)?))
+            let positive_val = (value + 1).unsigned_abs();
+            match u64::try_from(positive_val) {
+                Ok(u64_val) => Self::Signed(u64_val),
+                Err(_) => {
+                    TagContent::from((tag::NEG_BIGNUM, bignum_bytes(positive_val).as_slice()))
+                        .into()
+                }
+            }
         } else {
-            Ok(Self::Unsigned(u64::try_from(value)?))
+            value.unsigned_abs().into()
         }
     }
 }
@@ -272,6 +342,18 @@ impl From<&str> for DataItem {
     }
 }
 
+impl From<Cow<'static, str>> for DataItem {
+    fn from(value: Cow<'static, str>) -> Self {
+        Self::Text(value.into())
+    }
+}
+
+impl From<char> for DataItem {
+    fn from(value: char) -> Self {
+        Self::Text(value.to_string().into())
+    }
+}
+
 impl From<bool> for DataItem {
     fn from(value: bool) -> Self {
         Self::Boolean(value)
@@ -284,7 +366,9 @@ impl From<f64> for DataItem {
     }
 }
 
-impl_from!(f64, f32, half::f16);
+impl_from!(f64, f32);
+#[cfg(feature = "half")]
+impl_from!(f64, half::f16);
 
 impl From<ArrayContent> for DataItem {
     fn from(value: ArrayContent) -> Self {
@@ -333,6 +417,107 @@ where
     }
 }
 
+impl<T, const N: usize> From<[T; N]> for DataItem
+where
+    T: Into<DataItem>,
+{
+    fn from(value: [T; N]) -> Self {
+        ArrayContent::from(value.into_iter().map(Into::into).collect::<Vec<_>>()).into()
+    }
+}
+
+macro_rules! impl_from_tuple {
+    ($(($($name:ident),+)),+) => {
+        $(
+        impl<$($name),+> From<($($name,)+)> for DataItem
+        where
+            $($name: Into<DataItem>,)+
+        {
+            #[expect(non_snake_case, reason = "tuple field is named after its type")]
+            fn from(($($name,)+): ($($name,)+)) -> Self {
+                ArrayContent::from(vec![$($name.into()),+]).into()
+            }
+        }
+    )+
+    };
+}
+
+// pair is not converted to an array since a vector of pair is converted to a
+// map
+impl_from_tuple!(
+    (A),
+    (A, B, C),
+    (A, B, C, D),
+    (A, B, C, D, E),
+    (A, B, C, D, E, F),
+    (A, B, C, D, E, F, G),
+    (A, B, C, D, E, F, G, H)
+);
+
+impl<T, U, S> From<HashMap<T, U, S>> for DataItem
+where
+    T: Into<DataItem>,
+    U: Into<DataItem>,
+{
+    fn from(value: HashMap<T, U, S>) -> Self {
+        MapContent::from(
+            value
+                .into_iter()
+                .map(|(t, u)| (t.into(), u.into()))
+                .collect::<IndexMap<_, _>>(),
+        )
+        .into()
+    }
+}
+
+impl<T, U> From<BTreeMap<T, U>> for DataItem
+where
+    T: Into<DataItem>,
+    U: Into<DataItem>,
+{
+    fn from(value: BTreeMap<T, U>) -> Self {
+        MapContent::from(
+            value
+                .into_iter()
+                .map(|(t, u)| (t.into(), u.into()))
+                .collect::<IndexMap<_, _>>(),
+        )
+        .into()
+    }
+}
+
+/// Convert a set into an array. Use [`TagContent::set`] to mark an array as a
+/// set using tag 258
+impl<T, S> From<HashSet<T, S>> for DataItem
+where
+    T: Into<DataItem>,
+{
+    fn from(value: HashSet<T, S>) -> Self {
+        ArrayContent::from(value.into_iter().map(Into::into).collect::<Vec<_>>()).into()
+    }
+}
+
+/// Convert a set into an array. Use [`TagContent::set`] to mark an array as a
+/// set using tag 258
+impl<T> From<BTreeSet<T>> for DataItem
+where
+    T: Into<DataItem>,
+{
+    fn from(value: BTreeSet<T>) -> Self {
+        ArrayContent::from(value.into_iter().map(Into::into).collect::<Vec<_>>()).into()
+    }
+}
+
+/// Convert `None` into a null and `Some` into its value
+impl<T> From<Option<T>> for DataItem
+where
+    T: Into<DataItem>,
+{
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Null, Into::into)
+    }
+}
+
 impl From<TagContent> for DataItem {
     fn from(value: TagContent) -> Self {
         Self::Tag(value)
@@ -341,7 +526,13 @@ impl From<TagContent> for DataItem {
 
 impl From<SimpleValue> for DataItem {
     fn from(value: SimpleValue) -> Self {
-        Self::GenericSimple(value)
+        match *value {
+            20 => Self::Boolean(false),
+            21 => Self::Boolean(true),
+            22 => Self::Null,
+            23 => Self::Undefined,
+            _ => Self::GenericSimple(value),
+        }
     }
 }
 
@@ -354,6 +545,157 @@ where
     }
 }
 
+macro_rules! impl_try_from_integer {
+    ($($t:ty),+) => {
+        $(
+        impl TryFrom<&DataItem> for $t {
+            type Error = Error;
+
+            fn try_from(value: &DataItem) -> Result<Self, Self::Error> {
+                let number = value
+                    .as_number()
+                    .ok_or_else(|| type_mismatch(ItemKind::Integer, value))?;
+                Ok(Self::try_from(number)?)
+            }
+        }
+
+        impl TryFrom<DataItem> for $t {
+            type Error = Error;
+
+            fn try_from(value: DataItem) -> Result<Self, Self::Error> {
+                Self::try_from(&value)
+            }
+        }
+    )+
+    };
+}
+
+impl_try_from_integer!(u64, u32, u16, u8, i64, i32, i16, i8);
+
+impl TryFrom<&DataItem> for f64 {
+    type Error = Error;
+
+    fn try_from(value: &DataItem) -> Result<Self, Self::Error> {
+        value
+            .as_floating()
+            .ok_or_else(|| type_mismatch(ItemKind::Floating, value))
+    }
+}
+
+impl TryFrom<DataItem> for f64 {
+    type Error = Error;
+
+    fn try_from(value: DataItem) -> Result<Self, Self::Error> {
+        Self::try_from(&value)
+    }
+}
+
+impl TryFrom<&DataItem> for bool {
+    type Error = Error;
+
+    fn try_from(value: &DataItem) -> Result<Self, Self::Error> {
+        value
+            .as_boolean()
+            .ok_or_else(|| type_mismatch(ItemKind::Boolean, value))
+    }
+}
+
+impl TryFrom<DataItem> for bool {
+    type Error = Error;
+
+    fn try_from(value: DataItem) -> Result<Self, Self::Error> {
+        Self::try_from(&value)
+    }
+}
+
+impl TryFrom<&DataItem> for String {
+    type Error = Error;
+
+    fn try_from(value: &DataItem) -> Result<Self, Self::Error> {
+        value
+            .as_text()
+            .ok_or_else(|| type_mismatch(ItemKind::Text, value))
+    }
+}
+
+impl TryFrom<DataItem> for String {
+    type Error = Error;
+
+    fn try_from(value: DataItem) -> Result<Self, Self::Error> {
+        match value {
+            DataItem::Text(text_content) => Ok(text_content.into_full()),
+            _ => Err(type_mismatch(ItemKind::Text, &value)),
+        }
+    }
+}
+
+/// Convert an array where each item is converted to `T`. Byte string is also
+/// supported where each byte is converted from an unsigned integer so that
+/// `Vec<u8>` can be converted from both a byte string and an array
+impl<T> TryFrom<DataItem> for Vec<T>
+where
+    T: TryFrom<DataItem, Error = Error>,
+{
+    type Error = Error;
+
+    fn try_from(value: DataItem) -> Result<Self, Self::Error> {
+        match value {
+            DataItem::Array(array_content) => array_content.into_iter().map(T::try_from).collect(),
+            DataItem::Byte(byte_content) => {
+                byte_content
+                    .full()
+                    .iter()
+                    .map(|byte| T::try_from(DataItem::from(*byte)))
+                    .collect()
+            }
+            _ => Err(type_mismatch(ItemKind::Array, &value)),
+        }
+    }
+}
+
+impl<T> TryFrom<&DataItem> for Vec<T>
+where
+    T: TryFrom<DataItem, Error = Error>,
+{
+    type Error = Error;
+
+    fn try_from(value: &DataItem) -> Result<Self, Self::Error> {
+        Self::try_from(value.clone())
+    }
+}
+
+impl<K, V> TryFrom<DataItem> for IndexMap<K, V>
+where
+    K: TryFrom<DataItem, Error = Error> + Hash + Eq,
+    V: TryFrom<DataItem, Error = Error>,
+{
+    type Error = Error;
+
+    fn try_from(value: DataItem) -> Result<Self, Self::Error> {
+        match value {
+            DataItem::Map(map_content) => {
+                map_content
+                    .into_iter()
+                    .map(|(key, value)| Ok((K::try_from(key)?, V::try_from(value)?)))
+                    .collect()
+            }
+            _ => Err(type_mismatch(ItemKind::Map, &value)),
+        }
+    }
+}
+
+impl<K, V> TryFrom<&DataItem> for IndexMap<K, V>
+where
+    K: TryFrom<DataItem, Error = Error> + Hash + Eq,
+    V: TryFrom<DataItem, Error = Error>,
+{
+    type Error = Error;
+
+    fn try_from(value: &DataItem) -> Result<Self, Self::Error> {
+        Self::try_from(value.clone())
+    }
+}
+
 impl DataItem {
     /// Is a unsigned integer value?
     ///
@@ -589,7 +931,7 @@ impl DataItem {
     #[must_use]
     pub fn as_signed(&self) -> Option<i128> {
         match self {
-            Self::Signed(num) => Some(-i128::from(num + 1)),
+            Self::Signed(num) => Some(-1 - i128::from(*num)),
             _ => None,
         }
     }
@@ -607,516 +949,2425 @@ impl DataItem {
     pub fn as_number(&self) -> Option<i128> {
         match self {
             Self::Unsigned(num) => Some(i128::from(*num)),
-            Self::Signed(num) => Some(-i128::from(num + 1)),
+            Self::Signed(num) => Some(-1 - i128::from(*num)),
             _ => None,
         }
     }
 
-    /// Get as byte
+    /// Get as `u8` if an integer is in range of `u8`
     ///
     /// # Example
     /// ```
     /// use cbor_next::DataItem;
     ///
-    /// assert_eq!(
-    ///     DataItem::from(vec![0x6a].as_slice()).as_byte(),
-    ///     Some(vec![0x6a])
-    /// );
+    /// assert_eq!(DataItem::from(255).as_u8(), Some(255));
+    /// assert_eq!(DataItem::from(256).as_u8(), None);
     /// ```
     #[must_use]
-    pub fn as_byte(&self) -> Option<Vec<u8>> {
-        match self {
-            Self::Byte(byte) => Some(byte.full()),
-            _ => None,
-        }
+    pub fn as_u8(&self) -> Option<u8> {
+        self.as_number().and_then(|num| u8::try_from(num).ok())
     }
 
-    /// Get as text
+    /// Get as `u16` if an integer is in range of `u16`
     ///
     /// # Example
     /// ```
     /// use cbor_next::DataItem;
     ///
-    /// assert_eq!(DataItem::from("cbor").as_text(), Some("cbor".to_string()));
+    /// assert_eq!(DataItem::from(65_535).as_u16(), Some(65_535));
+    /// assert_eq!(DataItem::from(-1).as_u16(), None);
     /// ```
     #[must_use]
-    pub fn as_text(&self) -> Option<String> {
-        match self {
-            Self::Text(text_content) => Some(text_content.full()),
-            _ => None,
-        }
+    pub fn as_u16(&self) -> Option<u16> {
+        self.as_number().and_then(|num| u16::try_from(num).ok())
     }
 
-    /// Get as array
+    /// Get as `u32` if an integer is in range of `u32`
     ///
     /// # Example
     /// ```
     /// use cbor_next::DataItem;
     ///
-    /// assert_eq!(DataItem::from(vec![12u64]).as_array().unwrap(), [12.into()]);
+    /// assert_eq!(DataItem::from(u32::MAX).as_u32(), Some(u32::MAX));
+    /// assert_eq!(DataItem::from(u64::MAX).as_u32(), None);
     /// ```
     #[must_use]
-    pub fn as_array(&self) -> Option<&[DataItem]> {
-        match self {
-            Self::Array(arr) => Some(arr.array()),
-            _ => None,
-        }
+    pub fn as_u32(&self) -> Option<u32> {
+        self.as_number().and_then(|num| u32::try_from(num).ok())
     }
 
-    /// Get as map
+    /// Get as `i32` if an integer is in range of `i32`
     ///
     /// # Example
     /// ```
     /// use cbor_next::DataItem;
-    /// use indexmap::IndexMap;
     ///
-    /// assert_eq!(
-    ///     DataItem::from(IndexMap::<DataItem, DataItem>::new()).as_map(),
-    ///     Some(&IndexMap::new())
-    /// );
+    /// assert_eq!(DataItem::from(i32::MIN).as_i32(), Some(i32::MIN));
+    /// assert_eq!(DataItem::from(i64::MIN).as_i32(), None);
     /// ```
     #[must_use]
-    pub fn as_map(&self) -> Option<&IndexMap<DataItem, DataItem>> {
-        match self {
-            Self::Map(map) => Some(map.map()),
-            _ => None,
-        }
+    pub fn as_i32(&self) -> Option<i32> {
+        self.as_number().and_then(|num| i32::try_from(num).ok())
     }
 
-    /// Get as tag
+    /// Get as unsigned 128 bit number. Unsigned integer as well as positive
+    /// bignum (tag 2) which fits in `u128` are supported
     ///
     /// # Example
     /// ```
-    /// use cbor_next::{DataItem, TagContent};
+    /// use cbor_next::DataItem;
     ///
-    /// assert_eq!(
-    ///     DataItem::from(TagContent::from((20, -21))).as_tag(),
-    ///     Some((20, &DataItem::Signed(20)))
-    /// );
+    /// assert_eq!(DataItem::from(20).as_u128(), Some(20));
+    /// assert_eq!(DataItem::from(u128::MAX).as_u128(), Some(u128::MAX));
+    /// assert_eq!(DataItem::from(-20).as_u128(), None);
     /// ```
     #[must_use]
-    pub fn as_tag(&self) -> Option<(u64, &DataItem)> {
+    pub fn as_u128(&self) -> Option<u128> {
         match self {
-            Self::Tag(tag_content) => Some((tag_content.number(), tag_content.content())),
+            Self::Unsigned(num) => Some(u128::from(*num)),
+            Self::Tag(tag_content) if tag_content.number() == tag::POS_BIGNUM => {
+                bignum_magnitude(tag_content.content())
+            }
             _ => None,
         }
     }
 
-    /// Get a list of nested list of tags and its internal data item
+    /// Get as signed 128 bit number. Both signed and unsigned integer as well
+    /// as positive bignum (tag 2) and negative bignum (tag 3) which fits in
+    /// `i128` are supported
     ///
     /// # Example
-    /// ```rust
-    /// use cbor_next::{DataItem, TagContent};
-    ///
-    /// let tag = DataItem::from(TagContent::from((20, TagContent::from((30, -21)))));
-    /// let tag_unwrapped = tag.as_tag_nested();
-    /// assert_eq!(tag_unwrapped, Some((vec![20, 30], DataItem::from(-21))));
+    /// ```
+    /// use cbor_next::DataItem;
     ///
-    /// let untagged = DataItem::from(21);
-    /// let untagged_unwrapped = untagged.as_tag_nested();
-    /// assert_eq!(untagged_unwrapped, None);
+    /// assert_eq!(DataItem::from(-20).as_i128(), Some(-20));
+    /// assert_eq!(DataItem::from(i128::MIN).as_i128(), Some(i128::MIN));
+    /// assert_eq!(DataItem::from(u128::MAX).as_i128(), None);
     /// ```
     #[must_use]
-    pub fn as_tag_nested(&self) -> Option<(Vec<u64>, DataItem)> {
+    pub fn as_i128(&self) -> Option<i128> {
         match self {
-            Self::Tag(_) => {
-                let mut tags = vec![];
-                let data_item = as_tag_nested(self, &mut tags);
-                Some((tags, data_item))
+            Self::Unsigned(_) | Self::Signed(_) => self.as_number(),
+            Self::Tag(tag_content) if tag_content.number() == tag::POS_BIGNUM => {
+                i128::try_from(bignum_magnitude(tag_content.content())?).ok()
+            }
+            Self::Tag(tag_content) if tag_content.number() == tag::NEG_BIGNUM => {
+                let magnitude = i128::try_from(bignum_magnitude(tag_content.content())?).ok()?;
+                Some(-magnitude - 1)
             }
             _ => None,
         }
     }
 
-    /// Get as boolean number
+    /// Get an integer created by [`DataItem::integer`]. Both signed and
+    /// unsigned integer as well as positive bignum (tag 2) and negative bignum
+    /// (tag 3) which fits in `i128` are supported
     ///
     /// # Example
     /// ```
     /// use cbor_next::DataItem;
     ///
-    /// assert_eq!(DataItem::from(true).as_boolean(), Some(true));
+    /// assert_eq!(
+    ///     DataItem::Signed(u64::MAX).as_integer(),
+    ///     Some(-1 - i128::from(u64::MAX))
+    /// );
+    /// assert_eq!(DataItem::integer(i128::MAX).as_integer(), Some(i128::MAX));
+    /// assert_eq!(DataItem::from("1").as_integer(), None);
     /// ```
     #[must_use]
-    pub fn as_boolean(&self) -> Option<bool> {
-        match self {
-            Self::Boolean(bool_val) => Some(*bool_val),
-            _ => None,
-        }
+    pub fn as_integer(&self) -> Option<i128> {
+        self.as_i128()
     }
 
-    /// Get as floating number
+    /// Get as big integer. Both signed and unsigned integer as well as positive
+    /// bignum (tag 2) and negative bignum (tag 3) are supported
     ///
     /// # Example
     /// ```
     /// use cbor_next::DataItem;
+    /// use num_bigint::BigInt;
     ///
-    /// assert_eq!(DataItem::from(-20.0).as_floating(), Some(-20.0));
+    /// let big_int = BigInt::from(u128::MAX) * 4u8;
+    /// assert_eq!(DataItem::from(big_int.clone()).as_bigint(), Some(big_int));
+    /// assert_eq!(DataItem::from(-20).as_bigint(), Some(BigInt::from(-20)));
     /// ```
+    #[cfg(feature = "num-bigint")]
     #[must_use]
-    pub fn as_floating(&self) -> Option<f64> {
+    pub fn as_bigint(&self) -> Option<num_bigint::BigInt> {
         match self {
-            Self::Floating(num) => Some(*num),
+            Self::Unsigned(num) => Some((*num).into()),
+            Self::Signed(num) => Some(-num_bigint::BigInt::from(*num) - 1u8),
+            Self::Tag(tag_content) => {
+                crate::bignum::from_tag_content(tag_content.number(), tag_content.content())
+            }
             _ => None,
         }
     }
 
-    /// Get as simple index value
+    /// Create a builder of an array data item
     ///
     /// # Example
     /// ```
-    /// use cbor_next::{DataItem, SimpleValue};
+    /// use cbor_next::DataItem;
+    ///
+    /// let value = DataItem::array_builder().push(1).push("a").build();
+    /// assert_eq!(format!("{value:?}"), "[1, \"a\"]");
+    /// ```
+    pub fn array_builder() -> ArrayBuilder {
+        ArrayBuilder::new()
+    }
+
+    /// Create a builder of a map data item
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::DataItem;
+    ///
+    /// let value = DataItem::map_builder().insert("alg", -7).build();
+    /// assert_eq!(format!("{value:?}"), "{\"alg\": -7}");
+    /// ```
+    pub fn map_builder() -> MapBuilder {
+        MapBuilder::new()
+    }
+
+    /// Create an integer from any primitive integer including `i128` and
+    /// `u128`. An integer is unsigned integer, negative integer or, if an
+    /// integer is out of range of CBOR integer, a bignum (tag 2 or tag 3). Use
+    /// [`DataItem::as_integer`] to get an integer back including a bignum
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::DataItem;
+    ///
+    /// assert_eq!(DataItem::integer(-1), DataItem::Signed(0));
+    /// let big = DataItem::integer(i128::MIN);
+    /// assert!(big.is_tag());
+    /// assert_eq!(big.as_integer(), Some(i128::MIN));
+    /// assert!(DataItem::integer(u128::MAX).is_tag());
+    /// ```
+    #[must_use]
+    pub fn integer(number: impl Integer) -> Self {
+        number.into()
+    }
+
+    /// Create a text string
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::DataItem;
+    ///
+    /// assert_eq!(DataItem::text("abc").as_text(), Some("abc".to_string()));
+    /// ```
+    #[must_use]
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::Text(text.into().into())
+    }
+
+    /// Create a byte string
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::DataItem;
+    ///
+    /// assert_eq!(DataItem::bytes([1, 2]).as_byte(), Some(vec![1, 2]));
+    /// ```
+    #[must_use]
+    pub fn bytes(bytes: impl Into<Vec<u8>>) -> Self {
+        Self::Byte(bytes.into().into())
+    }
+
+    /// Create an empty definite length map
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::DataItem;
+    ///
+    /// assert_eq!(DataItem::map().encode(), vec![0xa0]);
+    /// assert_eq!(DataItem::array().encode(), vec![0x80]);
+    /// assert_eq!(DataItem::bytes_empty().encode(), vec![0x40]);
+    /// assert_eq!(DataItem::text_empty().encode(), vec![0x60]);
+    /// assert_eq!(DataItem::default(), DataItem::Null);
+    /// ```
+    #[must_use]
+    pub fn map() -> Self {
+        Self::Map(MapContent::default())
+    }
+
+    /// Create an empty definite length array
+    #[must_use]
+    pub fn array() -> Self {
+        Self::Array(ArrayContent::default())
+    }
+
+    /// Create an empty definite length byte string
+    #[must_use]
+    pub fn bytes_empty() -> Self {
+        Self::Byte(ByteContent::default())
+    }
+
+    /// Create an empty definite length text string
+    #[must_use]
+    pub fn text_empty() -> Self {
+        Self::Text(TextContent::default())
+    }
+
+    /// Create a decimal fraction (tag 4) which represents a value of mantissa
+    /// multiplied by 10 raised to the power of exponent. Mantissa is encoded as
+    /// bignum if it doesn't fit in CBOR integer
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::DataItem;
     ///
     /// assert_eq!(
-    ///     DataItem::from(SimpleValue::try_from(10).unwrap()).as_simple(),
-    ///     Some(10)
+    ///     DataItem::decimal_fraction(-2, 27315).encode(),
+    ///     vec![0xc4, 0x82, 0x21, 0x19, 0x6a, 0xb3]
     /// );
     /// ```
     #[must_use]
-    pub fn as_simple(&self) -> Option<u8> {
+    pub fn decimal_fraction(exponent: i64, mantissa: i128) -> Self {
+        TagContent::from((
+            tag::DECIMAL_FRACTION,
+            vec![Self::from(exponent), Self::from(mantissa)],
+        ))
+        .into()
+    }
+
+    /// Create a bigfloat (tag 5) which represents a value of mantissa
+    /// multiplied by 2 raised to the power of exponent. Mantissa is encoded as
+    /// bignum if it doesn't fit in CBOR integer
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::DataItem;
+    ///
+    /// assert_eq!(
+    ///     DataItem::bigfloat(-1, 3).encode(),
+    ///     vec![0xc5, 0x82, 0x20, 0x03]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn bigfloat(exponent: i64, mantissa: i128) -> Self {
+        TagContent::from((
+            tag::BIGFLOAT,
+            vec![Self::from(exponent), Self::from(mantissa)],
+        ))
+        .into()
+    }
+
+    /// Get as decimal fraction (tag 4) exponent and mantissa
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::DataItem;
+    ///
+    /// assert_eq!(
+    ///     DataItem::decimal_fraction(-2, 27315).as_decimal_fraction(),
+    ///     Some((-2, 27315))
+    /// );
+    /// assert_eq!(DataItem::bigfloat(-2, 27315).as_decimal_fraction(), None);
+    /// ```
+    #[must_use]
+    pub fn as_decimal_fraction(&self) -> Option<(i64, i128)> {
+        self.as_exponent_mantissa(tag::DECIMAL_FRACTION)
+    }
+
+    /// Get as bigfloat (tag 5) exponent and mantissa
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::DataItem;
+    ///
+    /// assert_eq!(DataItem::bigfloat(-1, 3).as_bigfloat(), Some((-1, 3)));
+    /// assert_eq!(DataItem::decimal_fraction(-1, 3).as_bigfloat(), None);
+    /// ```
+    #[must_use]
+    pub fn as_bigfloat(&self) -> Option<(i64, i128)> {
+        self.as_exponent_mantissa(tag::BIGFLOAT)
+    }
+
+    /// Get as decimal from a decimal fraction (tag 4)
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::DataItem;
+    /// use rust_decimal::Decimal;
+    ///
+    /// assert_eq!(
+    ///     DataItem::decimal_fraction(-2, 27315).as_decimal(),
+    ///     Some(Decimal::new(27315, 2))
+    /// );
+    /// ```
+    #[cfg(feature = "rust_decimal")]
+    #[must_use]
+    pub fn as_decimal(&self) -> Option<rust_decimal::Decimal> {
+        let (exponent, mantissa) = self.as_decimal_fraction()?;
+        crate::decimal::from_exponent_mantissa(exponent, mantissa)
+    }
+
+    fn as_exponent_mantissa(&self, tag: u64) -> Option<(i64, i128)> {
         match self {
-            Self::GenericSimple(num) => Some(**num),
-            Self::Boolean(false) => Some(20),
-            Self::Boolean(true) => Some(21),
-            Self::Null => Some(22),
-            Self::Undefined => Some(23),
+            Self::Tag(tag_content) if tag_content.number() == tag => {
+                match tag_content.content().as_array()? {
+                    [exponent @ (Self::Unsigned(_) | Self::Signed(_)), mantissa] => {
+                        Some((
+                            i64::try_from(exponent.as_number()?).ok()?,
+                            mantissa.as_i128()?,
+                        ))
+                    }
+                    _ => None,
+                }
+            }
             _ => None,
         }
     }
 
-    /// Get a major type of a value
+    /// Get as byte
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::DataItem;
+    ///
+    /// assert_eq!(
+    ///     DataItem::from(vec![0x6a].as_slice()).as_byte(),
+    ///     Some(vec![0x6a])
+    /// );
+    /// ```
     #[must_use]
-    pub fn major_type(&self) -> u8 {
+    pub fn as_byte(&self) -> Option<Vec<u8>> {
         match self {
-            Self::Unsigned(_) => 0,
-            Self::Signed(_) => 1,
-            Self::Byte(_) => 2,
-            Self::Text(_) => 3,
-            Self::Array(_) => 4,
-            Self::Map(_) => 5,
-            Self::Tag(..) => 6,
-            Self::Boolean(_)
-            | Self::Null
-            | Self::Undefined
-            | Self::Floating(_)
-            | Self::GenericSimple(_) => 7,
+            Self::Byte(byte) => Some(byte.full().into_owned()),
+            _ => None,
+        }
+    }
+
+    /// Get as mutable byte content
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::DataItem;
+    ///
+    /// let mut value = DataItem::from(vec![0x6a].as_slice());
+    /// value.as_byte_mut().unwrap().push_bytes(&[0x6b]);
+    /// assert_eq!(value.as_byte(), Some(vec![0x6a, 0x6b]));
+    /// ```
+    pub fn as_byte_mut(&mut self) -> Option<&mut ByteContent> {
+        match self {
+            Self::Byte(byte) => Some(byte),
+            _ => None,
+        }
+    }
+
+    /// Get as text
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::DataItem;
+    ///
+    /// assert_eq!(DataItem::from("cbor").as_text(), Some("cbor".to_string()));
+    /// ```
+    #[must_use]
+    pub fn as_text(&self) -> Option<String> {
+        match self {
+            Self::Text(text_content) => Some(text_content.full().into_owned()),
+            _ => None,
+        }
+    }
+
+    /// Get as mutable text content
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::DataItem;
+    ///
+    /// let mut value = DataItem::from("abc");
+    /// value.as_text_mut().unwrap().push_string("def");
+    /// assert_eq!(value.as_text(), Some("abcdef".to_string()));
+    /// ```
+    pub fn as_text_mut(&mut self) -> Option<&mut TextContent> {
+        match self {
+            Self::Text(text_content) => Some(text_content),
+            _ => None,
+        }
+    }
+
+    /// Get as array
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::DataItem;
+    ///
+    /// assert_eq!(DataItem::from(vec![12u64]).as_array().unwrap(), [12.into()]);
+    /// ```
+    #[must_use]
+    pub fn as_array(&self) -> Option<&[DataItem]> {
+        match self {
+            Self::Array(arr) => Some(arr.array()),
+            _ => None,
+        }
+    }
+
+    /// Get a nested data item by walking through a path of map key and array
+    /// index. Tag is skipped transparently when walking through a path
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::DataItem;
+    /// use cbor_next::error::Error;
+    ///
+    /// let value = DataItem::from_diagnostic(r#"{"a": [1, 24({"b": 2})]}"#).unwrap();
+    /// assert_eq!(
+    ///     value.get_path(&["a".into(), 1.into(), "b".into()]),
+    ///     Ok(&DataItem::from(2))
+    /// );
+    /// assert_eq!(
+    ///     value.get_path(&["a".into(), 2.into()]),
+    ///     Err(Error::PathNotFound { segment: 1 })
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    /// If a segment of a path cannot be found where error contains a position
+    /// of a failed segment
+    pub fn get_path(&self, path: &[PathSegment]) -> Result<&Self, Error> {
+        let mut current = self;
+        for (position, segment) in path.iter().enumerate() {
+            while let Self::Tag(tag_content) = current {
+                current = tag_content.content();
+            }
+            let next = match segment {
+                PathSegment::Key(key) => current.get(key.clone()),
+                PathSegment::Index(index) => current.get(*index),
+            };
+            current = next.ok_or(Error::PathNotFound { segment: position })?;
+        }
+        Ok(current)
+    }
+
+    /// Get a mutable nested data item by walking through a path of map key and
+    /// array index. Tag is skipped transparently when walking through a path
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::DataItem;
+    ///
+    /// let mut value = DataItem::from_diagnostic(r#"{"a": [1]}"#).unwrap();
+    /// *value.get_path_mut(&["a".into(), 0.into()]).unwrap() = DataItem::from(2);
+    /// assert_eq!(value, DataItem::from_diagnostic(r#"{"a": [2]}"#).unwrap());
+    /// ```
+    ///
+    /// # Errors
+    /// If a segment of a path cannot be found where error contains a position
+    /// of a failed segment
+    pub fn get_path_mut(&mut self, path: &[PathSegment]) -> Result<&mut Self, Error> {
+        let mut current = self;
+        for (position, segment) in path.iter().enumerate() {
+            while let Self::Tag(tag_content) = current {
+                current = tag_content.content_mut();
+            }
+            let next = match segment {
+                PathSegment::Key(key) => current.get_mut(key.clone()),
+                PathSegment::Index(index) => current.get_mut(*index),
+            };
+            current = next.ok_or(Error::PathNotFound { segment: position })?;
+        }
+        Ok(current)
+    }
+
+    /// Get a mutable value of a map key inserting a value created by provided
+    /// function if a key is missing. Return `None` if a data item is not a
+    /// map
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::DataItem;
+    ///
+    /// let mut value = DataItem::from(vec![("a", 1)]);
+    /// value
+    ///     .get_or_insert_with("b", || Vec::<DataItem>::new())
+    ///     .and_then(DataItem::as_array_mut)
+    ///     .unwrap()
+    ///     .push_content(2);
+    /// assert_eq!(
+    ///     value.get_or_insert_with("a", || 3),
+    ///     Some(&mut DataItem::from(1))
+    /// );
+    /// assert_eq!(
+    ///     value,
+    ///     DataItem::from(vec![
+    ///         ("a", DataItem::from(1)),
+    ///         ("b", DataItem::from(vec![2]))
+    ///     ])
+    /// );
+    /// ```
+    pub fn get_or_insert_with<K, V, F>(&mut self, key: K, default: F) -> Option<&mut Self>
+    where
+        K: Into<DataItem>,
+        V: Into<DataItem>,
+        F: FnOnce() -> V,
+    {
+        match self {
+            Self::Map(map_content) => {
+                let key = key.into();
+                if !map_content.contains_key(&key) {
+                    map_content.insert(key.clone(), default());
+                }
+                map_content.get_mut(&key)
+            }
+            _ => None,
+        }
+    }
+
+    /// Select all nested data item matching a query
+    ///
+    /// Query is a subset of `JSONPath` which optionally starts with `$`
+    /// followed by selectors where tag is skipped transparently. Supported
+    /// selectors are
+    /// - `.name` and `["name"]` select a value of a text string map key
+    /// - `[index]` selects an array item where negative index counts from an
+    ///   end of an array
+    /// - `.*` and `[*]` select all array item or map value
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::DataItem;
+    ///
+    /// let value = DataItem::from_diagnostic(r#"{"claims": [{"exp": 10}, {"exp": 20}]}"#).unwrap();
+    /// assert_eq!(
+    ///     value.query(".claims[*].exp").unwrap(),
+    ///     vec![&DataItem::from(10), &DataItem::from(20)]
+    /// );
+    /// assert_eq!(
+    ///     value.query("$.claims[-1][\"exp\"]").unwrap(),
+    ///     vec![&DataItem::from(20)]
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    /// If a query is not valid
+    pub fn query(&self, query: &str) -> Result<Vec<&Self>, Error> {
+        crate::query::query(self, query)
+    }
+
+    /// Find all nested data item including a data item itself which matches
+    /// a predicate using a depth first search. Predicate receives a path of a
+    /// data item and a data item itself
+    ///
+    /// Content of a tag shares a path with a tag since tag is skipped
+    /// transparently by a path. Map key is not searched
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::{DataItem, PathSegment};
+    ///
+    /// let value = DataItem::from_diagnostic(r#"{"a": [h'0102', 1(h'03')], "b": h''}"#).unwrap();
+    /// let found = value.find_all(|_, item| item.as_byte().is_some_and(|bytes| !bytes.is_empty()));
+    /// assert_eq!(
+    ///     found,
+    ///     vec![
+    ///         (
+    ///             vec!["a".into(), 0.into()],
+    ///             &DataItem::from([1u8, 2].as_slice())
+    ///         ),
+    ///         (
+    ///             vec!["a".into(), 1.into()],
+    ///             &DataItem::from([3u8].as_slice())
+    ///         ),
+    ///     ]
+    /// );
+    /// ```
+    pub fn find_all<F>(&self, mut predicate: F) -> Vec<(Vec<PathSegment>, &Self)>
+    where
+        F: FnMut(&[PathSegment], &Self) -> bool,
+    {
+        let mut found = vec![];
+        find_item(self, &mut vec![], &mut predicate, &mut found);
+        found
+    }
+
+    /// Get mutable references to several disjoint children of an array or a
+    /// map at once. Array child is referred by an index and map child is
+    /// referred by a key. Return `None` if any child is not found or same
+    /// child is referred more than once
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::DataItem;
+    ///
+    /// let mut value = DataItem::from(vec![("a", vec![1]), ("b", vec![2])]);
+    /// let [a, b] = value.get_many_mut(["a".into(), "b".into()]).unwrap();
+    /// std::mem::swap(a, b);
+    /// assert_eq!(value, DataItem::from(vec![("a", vec![2]), ("b", vec![1])]));
+    /// assert!(value.get_many_mut(["a".into(), "a".into()]).is_none());
+    /// ```
+    pub fn get_many_mut<const N: usize>(
+        &mut self,
+        segments: [PathSegment; N],
+    ) -> Option<[&mut Self; N]> {
+        let (positions, mut children) = match self {
+            Self::Array(array_content) => {
+                let positions = segments.map(|segment| {
+                    match segment {
+                        PathSegment::Index(index) => Some(index),
+                        PathSegment::Key(_) => None,
+                    }
+                });
+                let children = array_content
+                    .array_mut()
+                    .iter_mut()
+                    .map(Some)
+                    .collect::<Vec<_>>();
+                (positions, children)
+            }
+            Self::Map(map_content) => {
+                let positions = segments.map(|segment| {
+                    match segment {
+                        PathSegment::Key(key) => map_content.map().get_index_of(&key),
+                        PathSegment::Index(_) => None,
+                    }
+                });
+                let children = map_content
+                    .iter_mut()
+                    .map(|(_, value)| Some(value))
+                    .collect::<Vec<_>>();
+                (positions, children)
+            }
+            _ => return None,
+        };
+        // taking a child out makes a repeated position to return `None`
+        positions
+            .into_iter()
+            .map(|position| children.get_mut(position?)?.take())
+            .collect::<Option<Vec<_>>>()?
+            .try_into()
+            .ok()
+    }
+
+    /// Get a sub slice of an array. Return `None` if a data item is not an
+    /// array or a range is out of bounds
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::DataItem;
+    ///
+    /// let value = DataItem::from(vec![1, 2, 3, 4, 5]);
+    /// assert_eq!(
+    ///     value.get_range(1..4),
+    ///     Some([DataItem::from(2), DataItem::from(3), DataItem::from(4)].as_slice())
+    /// );
+    /// assert_eq!(
+    ///     value.get_range(3..),
+    ///     Some([DataItem::from(4), DataItem::from(5)].as_slice())
+    /// );
+    /// assert_eq!(value.get_range(4..6), None);
+    /// ```
+    #[must_use]
+    pub fn get_range<R>(&self, range: R) -> Option<&[Self]>
+    where
+        R: RangeBounds<usize>,
+    {
+        self.as_array()?
+            .get((range.start_bound().cloned(), range.end_bound().cloned()))
+    }
+
+    /// Get an array containing a sub slice of an array. Indefinite length of an
+    /// array is preserved. Return `None` if a data item is not an array or a
+    /// range is out of bounds
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::DataItem;
+    ///
+    /// let value = DataItem::from(vec![1, 2, 3]);
+    /// assert_eq!(value.slice(..2), Some(DataItem::from(vec![1, 2])));
+    /// ```
+    #[must_use]
+    pub fn slice<R>(&self, range: R) -> Option<Self>
+    where
+        R: RangeBounds<usize>,
+    {
+        let Self::Array(array_content) = self else {
+            return None;
+        };
+        let items = self.get_range(range)?;
+        Some(
+            ArrayContent::from(items.to_vec())
+                .set_indefinite(array_content.is_indefinite())
+                .clone()
+                .into(),
+        )
+    }
+
+    /// Remove an array item at an index replacing it with a last item of an
+    /// array. Return `None` if a data item is not an array or an index is out
+    /// of bounds
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::DataItem;
+    ///
+    /// let mut value = DataItem::from(vec![1, 2, 3]);
+    /// assert_eq!(value.swap_remove(0), Some(DataItem::from(1)));
+    /// assert_eq!(value, DataItem::from(vec![3, 2]));
+    /// ```
+    pub fn swap_remove(&mut self, index: usize) -> Option<Self> {
+        self.as_array_mut()?.swap_remove_content(index)
+    }
+
+    /// Get an array item counting from an end of an array where 0 is a last
+    /// item
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::DataItem;
+    ///
+    /// let value = DataItem::from(vec![1, 2, 3]);
+    /// assert_eq!(value.get_back(0), Some(&DataItem::from(3)));
+    /// assert_eq!(value.get_back(2), Some(&DataItem::from(1)));
+    /// assert_eq!(value.get_back(3), None);
+    /// ```
+    #[must_use]
+    pub fn get_back(&self, index: usize) -> Option<&Self> {
+        let array = self.as_array()?;
+        array.get(array.len().checked_sub(index.checked_add(1)?)?)
+    }
+
+    /// Get a mutable array item counting from an end of an array where 0 is a
+    /// last item
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::DataItem;
+    ///
+    /// let mut value = DataItem::from(vec![1, 2, 3]);
+    /// *value.get_back_mut(0).unwrap() = DataItem::from(4);
+    /// assert_eq!(value, DataItem::from(vec![1, 2, 4]));
+    /// ```
+    pub fn get_back_mut(&mut self, index: usize) -> Option<&mut Self> {
+        let array = self.as_array_mut()?.array_mut();
+        let position = array.len().checked_sub(index.checked_add(1)?)?;
+        array.get_mut(position)
+    }
+
+    /// Get as mutable array content
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::DataItem;
+    ///
+    /// let mut value = DataItem::from(vec![1]);
+    /// value.as_array_mut().unwrap().push_content(2);
+    /// assert_eq!(value, DataItem::from(vec![1, 2]));
+    /// ```
+    pub fn as_array_mut(&mut self) -> Option<&mut ArrayContent> {
+        match self {
+            Self::Array(arr) => Some(arr),
+            _ => None,
+        }
+    }
+
+    /// Get as map
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::DataItem;
+    /// use indexmap::IndexMap;
+    ///
+    /// assert_eq!(
+    ///     DataItem::from(IndexMap::<DataItem, DataItem>::new()).as_map(),
+    ///     Some(&IndexMap::new())
+    /// );
+    /// ```
+    #[must_use]
+    pub fn as_map(&self) -> Option<&IndexMap<DataItem, DataItem>> {
+        match self {
+            Self::Map(map) => Some(map.map()),
+            _ => None,
+        }
+    }
+
+    /// Get as mutable map content
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::DataItem;
+    ///
+    /// let mut value = DataItem::from(vec![("a", 1)]);
+    /// value.as_map_mut().unwrap().insert_content("b", 2);
+    /// assert_eq!(value, DataItem::from(vec![("a", 1), ("b", 2)]));
+    /// ```
+    pub fn as_map_mut(&mut self) -> Option<&mut MapContent> {
+        match self {
+            Self::Map(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    /// Get as tag
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::{DataItem, TagContent};
+    ///
+    /// assert_eq!(
+    ///     DataItem::from(TagContent::from((20, -21))).as_tag(),
+    ///     Some((20, &DataItem::Signed(20)))
+    /// );
+    /// ```
+    #[must_use]
+    pub fn as_tag(&self) -> Option<(u64, &DataItem)> {
+        match self {
+            Self::Tag(tag_content) => Some((tag_content.number(), tag_content.content())),
+            _ => None,
+        }
+    }
+
+    /// Get as tag number and mutable tag content
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::{DataItem, TagContent};
+    ///
+    /// let mut value = DataItem::from(TagContent::from((20, -21)));
+    /// *value.as_tag_mut().unwrap().1 = DataItem::from(1);
+    /// assert_eq!(value.as_tag(), Some((20, &DataItem::Unsigned(1))));
+    /// ```
+    pub fn as_tag_mut(&mut self) -> Option<(u64, &mut DataItem)> {
+        match self {
+            Self::Tag(tag_content) => Some((tag_content.number(), tag_content.content_mut())),
+            _ => None,
+        }
+    }
+
+    /// Get a content of a tag if data item is tagged with an expected tag
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::error::Error;
+    /// use cbor_next::{DataItem, TagContent};
+    ///
+    /// let tag = DataItem::from(TagContent::from((20, -21)));
+    /// assert_eq!(tag.require_tag(20), Ok(&DataItem::from(-21)));
+    /// assert_eq!(
+    ///     tag.require_tag(21),
+    ///     Err(Error::WrongTag {
+    ///         expected: 21,
+    ///         found: Some(20)
+    ///     })
+    /// );
+    /// assert_eq!(
+    ///     DataItem::from(-21).require_tag(20),
+    ///     Err(Error::WrongTag {
+    ///         expected: 20,
+    ///         found: None
+    ///     })
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    /// If data item is not tagged with an expected tag
+    pub fn require_tag(&self, tag: u64) -> Result<&DataItem, Error> {
+        match self.as_tag() {
+            Some((number, content)) if number == tag => Ok(content),
+            found => {
+                Err(Error::WrongTag {
+                    expected: tag,
+                    found: found.map(|(number, _)| number),
+                })
+            }
+        }
+    }
+
+    /// Get a list of nested list of tags and its internal data item
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::{DataItem, TagContent};
+    ///
+    /// let tag = DataItem::from(TagContent::from((20, TagContent::from((30, -21)))));
+    /// let tag_unwrapped = tag.as_tag_nested();
+    /// assert_eq!(tag_unwrapped, Some((vec![20, 30], DataItem::from(-21))));
+    ///
+    /// let untagged = DataItem::from(21);
+    /// let untagged_unwrapped = untagged.as_tag_nested();
+    /// assert_eq!(untagged_unwrapped, None);
+    /// ```
+    #[must_use]
+    pub fn as_tag_nested(&self) -> Option<(Vec<u64>, DataItem)> {
+        match self {
+            Self::Tag(_) => {
+                let mut tags = vec![];
+                let data_item = as_tag_nested(self, &mut tags);
+                Some((tags, data_item))
+            }
+            _ => None,
+        }
+    }
+
+    /// Wrap a data item inside nested tags where a first tag is an outermost
+    /// tag
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::DataItem;
+    ///
+    /// let data_item = DataItem::from(-21).wrap_in(&[20, 30]);
+    /// assert_eq!(format!("{data_item:?}"), "20(30(-21))");
+    /// assert_eq!(data_item.unwrap_all(), (vec![20, 30], DataItem::from(-21)));
+    /// assert_eq!(DataItem::from(1).unwrap_all(), (vec![], DataItem::from(1)));
+    /// ```
+    #[must_use]
+    pub fn wrap_in(self, tags: &[u64]) -> Self {
+        tags.iter().rev().fold(self, |data_item, number| {
+            Self::Tag(TagContent::from((*number, data_item)))
+        })
+    }
+
+    /// Remove all nested tags returning their numbers from an outermost tag
+    /// with an untagged data item
+    #[must_use]
+    pub fn unwrap_all(self) -> (Vec<u64>, Self) {
+        let mut tags = vec![];
+        let mut data_item = self;
+        while let Self::Tag(tag_content) = data_item {
+            let (number, content) = tag_content.into_parts();
+            tags.push(number);
+            data_item = content;
+        }
+        (tags, data_item)
+    }
+
+    /// Get as date time from a standard date/time string (tag 0) or epoch based
+    /// date/time (tag 1)
+    ///
+    /// # Example
+    /// ```rust
+    /// # #[cfg(feature = "chrono")]
+    /// # {
+    /// use cbor_next::{DataItem, TagContent};
+    ///
+    /// let date_time = DataItem::from(TagContent::from((1, 1_363_896_240)));
+    /// assert_eq!(
+    ///     date_time
+    ///         .as_datetime::<chrono::DateTime<chrono::Utc>>()
+    ///         .unwrap()
+    ///         .to_rfc3339(),
+    ///     "2013-03-21T20:04:00+00:00"
+    /// );
+    /// # }
+    /// ```
+    #[cfg(any(feature = "chrono", feature = "time"))]
+    #[must_use]
+    pub fn as_datetime<T>(&self) -> Option<T>
+    where
+        T: Timestamp,
+    {
+        match self {
+            Self::Tag(tag_content) => {
+                crate::datetime::from_tag_content(tag_content.number(), tag_content.content())
+            }
+            _ => None,
+        }
+    }
+
+    /// Get as UUID from a tag 37 with a 16 byte string
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::DataItem;
+    ///
+    /// let uuid = uuid::Uuid::from_u128(0x8d8ac610_566d_4ef0_9c22_186b2a5ed793);
+    /// assert_eq!(DataItem::from(uuid).as_uuid(), Some(uuid));
+    /// ```
+    #[cfg(feature = "uuid")]
+    #[must_use]
+    pub fn as_uuid(&self) -> Option<uuid::Uuid> {
+        match self {
+            Self::Tag(tag_content) if tag_content.number() == tag::UUID => {
+                crate::uuid::from_tag_content(tag_content.content())
+            }
+            _ => None,
+        }
+    }
+
+    /// Get as system time from an epoch based date/time (tag 1)
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::time::{Duration, SystemTime};
+    ///
+    /// use cbor_next::{DataItem, TagContent};
+    ///
+    /// assert_eq!(
+    ///     DataItem::from(TagContent::from((1, 1.5))).as_epoch(),
+    ///     Some(SystemTime::UNIX_EPOCH + Duration::from_millis(1500))
+    /// );
+    /// assert_eq!(DataItem::from(1.5).as_epoch(), None);
+    /// ```
+    #[must_use]
+    pub fn as_epoch(&self) -> Option<SystemTime> {
+        let epoch = match self {
+            Self::Tag(tag_content) if tag_content.number() == tag::EPOCH => tag_content.content(),
+            _ => return None,
+        };
+        let (seconds, nanoseconds) = match epoch {
+            Self::Unsigned(_) | Self::Signed(_) => (epoch.as_number()?, 0),
+            Self::Floating(number) if number.is_finite() => {
+                let seconds = number.floor();
+                #[expect(
+                    clippy::cast_possible_truncation,
+                    clippy::cast_sign_loss,
+                    reason = "fractional part of seconds is always between 0 to 1e9"
+                )]
+                let nanoseconds = ((number - seconds) * 1e9).round().min(999_999_999.0) as u32;
+                #[expect(
+                    clippy::cast_possible_truncation,
+                    reason = "out of range seconds are saturated and rejected by system time"
+                )]
+                (i128::from(seconds as i64), nanoseconds)
+            }
+            _ => return None,
+        };
+        let duration = Duration::new(u64::try_from(seconds.unsigned_abs()).ok()?, 0);
+        let system_time = if seconds < 0 {
+            SystemTime::UNIX_EPOCH.checked_sub(duration)?
+        } else {
+            SystemTime::UNIX_EPOCH.checked_add(duration)?
+        };
+        system_time.checked_add(Duration::from_nanos(u64::from(nanoseconds)))
+    }
+
+    /// Get as bignum magnitude bytes along with whether bignum is negative from
+    /// a positive bignum (tag 2) or negative bignum (tag 3). For negative
+    /// bignum value represented is -1 minus magnitude
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::DataItem;
+    ///
+    /// assert_eq!(
+    ///     DataItem::from(u128::MAX).as_bignum(),
+    ///     Some((vec![0xff; 16], false))
+    /// );
+    /// assert_eq!(DataItem::from(20).as_bignum(), None);
+    /// ```
+    #[must_use]
+    pub fn as_bignum(&self) -> Option<(Vec<u8>, bool)> {
+        match self {
+            Self::Tag(tag_content) if tag_content.number() == tag::POS_BIGNUM => {
+                Some((tag_content.content().as_byte()?, false))
+            }
+            Self::Tag(tag_content) if tag_content.number() == tag::NEG_BIGNUM => {
+                Some((tag_content.content().as_byte()?, true))
+            }
+            _ => None,
+        }
+    }
+
+    /// Get as URI from a tag 32 with a text string which starts with a valid
+    /// URI scheme
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::{DataItem, TagContent};
+    ///
+    /// let uri = DataItem::from(TagContent::from((32, "http://www.example.com")));
+    /// assert_eq!(uri.as_uri(), Some("http://www.example.com".to_string()));
+    /// assert_eq!(
+    ///     DataItem::from(TagContent::from((32, "www.example.com"))).as_uri(),
+    ///     None
+    /// );
+    /// ```
+    #[must_use]
+    pub fn as_uri(&self) -> Option<String> {
+        self.as_tagged_text(tag::URI)
+            .filter(|uri| crate::content::is_uri(uri))
+    }
+
+    /// Get as decoded bytes from a base64url encoded text (tag 33) or base64
+    /// encoded text (tag 34)
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::{DataItem, TagContent};
+    ///
+    /// assert_eq!(
+    ///     DataItem::from(TagContent::from((34, "Zm9vYg=="))).as_base64(),
+    ///     Some(b"foob".to_vec())
+    /// );
+    /// assert_eq!(
+    ///     DataItem::from(TagContent::from((33, "-_8"))).as_base64(),
+    ///     Some(vec![0xfb, 0xff])
+    /// );
+    /// ```
+    #[must_use]
+    pub fn as_base64(&self) -> Option<Vec<u8>> {
+        if let Some(text) = self.as_tagged_text(tag::BASE64URL) {
+            return crate::encoding::decode_base64(&text, crate::encoding::BASE64URL_ALPHABET);
+        }
+        crate::encoding::decode_base64(
+            &self.as_tagged_text(tag::BASE64)?,
+            crate::encoding::BASE64_ALPHABET,
+        )
+    }
+
+    /// Get as regular expression pattern from a tag 35 with a text string
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::{DataItem, TagContent};
+    ///
+    /// let regex = DataItem::from(TagContent::from((35, "^[a-z]+$")));
+    /// assert_eq!(regex.as_regex(), Some("^[a-z]+$".to_string()));
+    /// ```
+    #[must_use]
+    pub fn as_regex(&self) -> Option<String> {
+        self.as_tagged_text(tag::REGEX)
+    }
+
+    /// Get as MIME message from a tag 36 with a text string
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::{DataItem, TagContent};
+    ///
+    /// let mime = DataItem::from(TagContent::from((36, "Content-Type: text/plain\n\nhello")));
+    /// assert_eq!(
+    ///     mime.as_mime(),
+    ///     Some("Content-Type: text/plain\n\nhello".to_string())
+    /// );
+    /// ```
+    #[must_use]
+    pub fn as_mime(&self) -> Option<String> {
+        self.as_tagged_text(tag::MIME)
+    }
+
+    fn as_tagged_text(&self, tag: u64) -> Option<String> {
+        match self {
+            Self::Tag(tag_content) if tag_content.number() == tag => {
+                tag_content.content().as_text()
+            }
+            _ => None,
+        }
+    }
+
+    /// Create a typed array (tag 64 to tag 87) from a list of number stored in
+    /// provided byte order
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::DataItem;
+    /// use cbor_next::typed_array::Endianness;
+    ///
+    /// let typed_array = DataItem::typed_array(&[1u16, 2], Endianness::Big);
+    /// assert_eq!(
+    ///     typed_array.encode(),
+    ///     vec![0xd8, 0x41, 0x44, 0x00, 0x01, 0x00, 0x02]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn typed_array<T>(values: &[T], endianness: Endianness) -> Self
+    where
+        T: TypedArrayElement,
+    {
+        let number = match endianness {
+            Endianness::Big => T::TAGS.0,
+            Endianness::Little => T::TAGS.1,
+        };
+        let bytes = values
+            .iter()
+            .flat_map(|value| value.to_bytes(endianness))
+            .collect::<Vec<_>>();
+        TagContent::from((number, bytes.as_slice())).into()
+    }
+
+    /// Get as list of number from a typed array (tag 64 to tag 87) of either
+    /// byte order
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::DataItem;
+    /// use cbor_next::typed_array::Endianness;
+    ///
+    /// let typed_array = DataItem::typed_array(&[1.5f32, -2.0], Endianness::Little);
+    /// assert_eq!(typed_array.as_typed_array::<f32>(), Some(vec![1.5, -2.0]));
+    /// assert_eq!(typed_array.as_typed_array::<u32>(), None);
+    /// ```
+    #[must_use]
+    pub fn as_typed_array<T>(&self) -> Option<Vec<T>>
+    where
+        T: TypedArrayElement,
+    {
+        let (number, content) = self.as_tag()?;
+        let endianness = T::endianness(number)?;
+        let bytes = content.as_byte()?;
+        let size = size_of::<T>();
+        if !bytes.len().is_multiple_of(size) {
+            return None;
+        }
+        bytes
+            .chunks_exact(size)
+            .map(|chunk| T::from_bytes(chunk, endianness))
+            .collect()
+    }
+
+    /// Get as boolean number
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::DataItem;
+    ///
+    /// assert_eq!(DataItem::from(true).as_boolean(), Some(true));
+    /// ```
+    #[must_use]
+    pub fn as_boolean(&self) -> Option<bool> {
+        match self {
+            Self::Boolean(bool_val) => Some(*bool_val),
+            _ => None,
+        }
+    }
+
+    /// Get as floating number
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::DataItem;
+    ///
+    /// assert_eq!(DataItem::from(-20.0).as_floating(), Some(-20.0));
+    /// ```
+    #[must_use]
+    pub fn as_floating(&self) -> Option<f64> {
+        match self {
+            Self::Floating(num) => Some(*num),
+            _ => None,
+        }
+    }
+
+    /// Get as `f32` if a floating number can be represented as `f32` without
+    /// losing a precision
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::DataItem;
+    ///
+    /// assert_eq!(DataItem::from(1.5).as_f32(), Some(1.5));
+    /// assert!(DataItem::from(f64::NAN).as_f32().unwrap().is_nan());
+    /// assert_eq!(DataItem::from(0.1).as_f32(), None);
+    /// ```
+    #[must_use]
+    pub fn as_f32(&self) -> Option<f32> {
+        let num = self.as_floating()?;
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "conversion is checked by converting back to f64"
+        )]
+        let f32_num = num as f32;
+        (f64::from(f32_num).to_bits() == num.to_bits() || num.is_nan()).then_some(f32_num)
+    }
+
+    /// Get as simple index value
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::{DataItem, SimpleValue};
+    ///
+    /// assert_eq!(
+    ///     DataItem::from(SimpleValue::try_from(10).unwrap()).as_simple(),
+    ///     Some(10)
+    /// );
+    /// ```
+    #[must_use]
+    pub fn as_simple(&self) -> Option<u8> {
+        self.as_simple_value().map(|simple_value| *simple_value)
+    }
+
+    /// Get as simple value including false, true, null and undefined
+    ///
+    /// # Example
+    /// ```
+    /// use cbor_next::{DataItem, SimpleValue};
+    ///
+    /// assert_eq!(DataItem::Null.as_simple_value(), Some(SimpleValue::NULL));
+    /// assert_eq!(DataItem::from(1).as_simple_value(), None);
+    /// ```
+    #[must_use]
+    pub fn as_simple_value(&self) -> Option<SimpleValue> {
+        match self {
+            Self::GenericSimple(simple_value) => Some(simple_value.clone()),
+            Self::Boolean(false) => Some(SimpleValue::FALSE),
+            Self::Boolean(true) => Some(SimpleValue::TRUE),
+            Self::Null => Some(SimpleValue::NULL),
+            Self::Undefined => Some(SimpleValue::UNDEFINED),
+            _ => None,
+        }
+    }
+
+    /// Get a major type of a value
+    #[must_use]
+    pub fn major_type(&self) -> u8 {
+        match self {
+            Self::Unsigned(_) => 0,
+            Self::Signed(_) => 1,
+            Self::Byte(_) => 2,
+            Self::Text(_) => 3,
+            Self::Array(_) => 4,
+            Self::Map(_) => 5,
+            Self::Tag(..) => 6,
+            Self::Boolean(_)
+            | Self::Null
+            | Self::Undefined
+            | Self::Floating(_)
+            | Self::GenericSimple(_) => 7,
+        }
+    }
+
+    /// Get a diagnostic notation of value where nested array and map are
+    /// spread across multiple lines and indented with provided indent width
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::{DataItem, TagContent};
+    ///
+    /// let value = DataItem::from(vec![
+    ///     DataItem::from(1),
+    ///     DataItem::from(vec![("a", TagContent::from((32, "b")))]),
+    ///     DataItem::from(Vec::<u8>::new()),
+    /// ]);
+    /// assert_eq!(
+    ///     value.to_diagnostic_pretty(2),
+    ///     "[\n  1,\n  {\n    \"a\": 32(\"b\")\n  },\n  []\n]"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn to_diagnostic_pretty(&self, indent_width: usize) -> String {
+        self.to_diagnostic(DiagnosticOptions::default().set_indent(indent_width))
+    }
+
+    /// Get a diagnostic notation of value printed using provided options
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::{DataItem, DiagnosticOptions};
+    ///
+    /// let value = DataItem::from("IETF".as_bytes());
+    /// assert_eq!(
+    ///     value.to_diagnostic(&DiagnosticOptions::default()),
+    ///     "h'49455446'"
+    /// );
+    /// assert_eq!(
+    ///     value.to_diagnostic(DiagnosticOptions::default().set_extended(true)),
+    ///     "'IETF'"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn to_diagnostic(&self, options: &DiagnosticOptions) -> String {
+        crate::diagnostic::print(self, options)
+    }
+
+    /// Get a lowercase hex encoded CBOR representation of value
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::DataItem;
+    ///
+    /// let value = DataItem::from(vec![1, 2, 3]);
+    /// assert_eq!(value.encode_hex(), "83010203");
+    /// ```
+    #[must_use]
+    pub fn encode_hex(&self) -> String {
+        crate::encoding::encode_hex(&self.encode())
+    }
+
+    /// Decode a hex encoded CBOR representation to a value. Whitespace present
+    /// in a hex text is ignored
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::DataItem;
+    ///
+    /// assert_eq!(
+    ///     DataItem::decode_hex("83 01 02 03").unwrap(),
+    ///     DataItem::from(vec![1, 2, 3])
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    /// If provided text is not a valid hex text or decoded bytes cannot be
+    /// converted to CBOR
+    pub fn decode_hex(val: &str) -> Result<Self, Error> {
+        let hex = val
+            .chars()
+            .filter(|character| !character.is_whitespace())
+            .collect::<String>();
+        let bytes = crate::encoding::decode_hex(&hex).ok_or(Error::InvalidHex)?;
+        Self::decode(&bytes)
+    }
+
+    /// Parse a JSON text into a value following RFC 8949 section 6.2
+    ///
+    /// Number without fraction and exponent is converted to an integer if it
+    /// fits in CBOR integer otherwise to a floating number
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::DataItem;
+    ///
+    /// let value = DataItem::from_json_str(r#"{"a": [1, -2, 1.5, "b", null]}"#).unwrap();
+    /// assert_eq!(
+    ///     value,
+    ///     DataItem::from_diagnostic(r#"{"a": [1, -2, 1.5, "b", null]}"#).unwrap()
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    /// If provided text is not a valid JSON text
+    pub fn from_json_str(text: &str) -> Result<Self, Error> {
+        Self::from_json_str_with_options(text, &JsonOptions::default())
+    }
+
+    /// Parse a JSON text into a value using provided options
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::{DataItem, JsonOptions, TagContent};
+    ///
+    /// let value = DataItem::from(vec![
+    ///     (DataItem::from(1), DataItem::from([0xfbu8, 0xff].as_slice())),
+    ///     (
+    ///         DataItem::from(2),
+    ///         TagContent::from((1, DataItem::Undefined)).into(),
+    ///     ),
+    /// ]);
+    /// let mut options = JsonOptions::default();
+    /// options.set_lossless(true);
+    /// let json = value.to_json_string(&options).unwrap();
+    /// assert_eq!(
+    ///     json,
+    ///     r#"{"$map":[[1,{"$bytes":"-_8"}],[2,{"$tag":1,"$value":{"$undefined":true}}]]}"#
+    /// );
+    /// assert_eq!(
+    ///     DataItem::from_json_str_with_options(&json, &options).unwrap(),
+    ///     value
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    /// If provided text is not a valid JSON text or, when lossless conversion
+    /// is used, contains an invalid lossless object
+    pub fn from_json_str_with_options(text: &str, options: &JsonOptions) -> Result<Self, Error> {
+        crate::json::parse(text, *options)
+    }
+
+    /// Convert a value to a JSON text following RFC 8949 section 6.1
+    ///
+    /// Conversion follows same rule as `to_json_value` except that integer is
+    /// always written without loss of precision
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::{DataItem, JsonOptions};
+    ///
+    /// let value = DataItem::from(vec![
+    ///     (
+    ///         DataItem::from("a"),
+    ///         DataItem::from([0xfbu8, 0xff].as_slice()),
+    ///     ),
+    ///     (DataItem::from("b"), DataItem::from(f64::NAN)),
+    /// ]);
+    /// let mut options = JsonOptions::default();
+    /// assert_eq!(
+    ///     value.to_json_string(&options).unwrap(),
+    ///     r#"{"a":"-_8","b":null}"#
+    /// );
+    /// options.set_non_finite_as_string(true);
+    /// assert_eq!(
+    ///     value.to_json_string(&options).unwrap(),
+    ///     r#"{"a":"-_8","b":"NaN"}"#
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    /// If a map contains a key which is not a text string and provided options
+    /// does not stringify a key
+    pub fn to_json_string(&self, options: &JsonOptions) -> Result<String, Error> {
+        let mut text = String::new();
+        crate::json::write_text(self, *options, None, &mut text)?;
+        Ok(text)
+    }
+
+    /// Convert a value to a JSON value following RFC 8949 section 6.1
+    ///
+    /// Byte string is converted to a base64url encoded string unless it is
+    /// enclosed inside an expected conversion tag (tag 21 to tag 23), bignum is
+    /// converted to a base64url encoded string where negative bignum is
+    /// prefixed with `~`, non finite floating number and simple value other
+    /// than boolean are converted to null unless provided options converts non
+    /// finite floating number to a string and other tags are removed
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::{DataItem, JsonOptions, TagContent};
+    /// use serde_json::json;
+    ///
+    /// let value = DataItem::from(vec![
+    ///     (DataItem::from("a"), DataItem::from([0xfbu8, 0xff].as_slice())),
+    ///     (
+    ///         DataItem::from("b"),
+    ///         TagContent::from((23, [0xfbu8, 0xff].as_slice())).into(),
+    ///     ),
+    /// ]);
+    /// assert_eq!(
+    ///     value.to_json_value(&JsonOptions::default()).unwrap(),
+    ///     json!({"a": "-_8", "b": "fbff"})
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    /// If a map contains a key which is not a text string and provided options
+    /// does not stringify a key
+    #[cfg(feature = "json")]
+    pub fn to_json_value(&self, options: &JsonOptions) -> Result<serde_json::Value, Error> {
+        crate::json::to_value(self, *options, None)
+    }
+
+    /// Get an annotated hex dump of a CBOR bytes where each line contains an
+    /// offset, hex encoded bytes and a comment describing those bytes
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::DataItem;
+    ///
+    /// let annotation = DataItem::annotate(&[0xa1, 0x61, 0x61, 0x82, 0x01, 0x20]).unwrap();
+    /// assert_eq!(
+    ///     annotation,
+    ///     "0000: a1       # map(1)\n\
+    ///      0001:    61    #   text(1)\n\
+    ///      0002:       61 #     \"a\"\n\
+    ///      0003:    82    #   array(2)\n\
+    ///      0004:       01 #     unsigned(1)\n\
+    ///      0005:       20 #     negative(-1)\n"
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    /// If provided bytes cannot be converted to CBOR
+    pub fn annotate(val: &[u8]) -> Result<String, Error> {
+        crate::annotate::annotate(val)
+    }
+
+    /// Parse a CBOR diagnostic notation into a value. Same value can also be
+    /// parsed using [`str::parse`]
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::DataItem;
+    ///
+    /// let value = DataItem::from_diagnostic("{\"a\": h'0102', \"b\": 1(1363896240)}").unwrap();
+    /// assert_eq!(
+    ///     value.encode(),
+    ///     vec![
+    ///         0xa2, 0x61, 0x61, 0x42, 0x01, 0x02, 0x61, 0x62, 0xc1, 0x1a, 0x51, 0x4b, 0x67, 0xb0
+    ///     ]
+    /// );
+    /// assert_eq!(
+    ///     "[_ 1, -2]".parse::<DataItem>().unwrap().encode(),
+    ///     vec![0x9f, 0x01, 0x21, 0xff]
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    /// If provided text is not a valid diagnostic notation or its data item are
+    /// nested deeper than 256 level
+    pub fn from_diagnostic(text: &str) -> Result<Self, Error> {
+        crate::diagnostic::parse(text)
+    }
+
+    /// Get a CBOR encoded representation of value
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::DataItem;
+    ///
+    /// let value = DataItem::Unsigned(10_000_000);
+    /// let vector_data = vec![0x1a, 0x00, 0x98, 0x96, 0x80];
+    /// assert_eq!(value.encode(), vector_data);
+    /// ```
+    #[must_use]
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![];
+        if encode_into(self, &mut bytes).is_err() {
+            unreachable!("writing into a vector cannot fail");
+        }
+        bytes
+    }
+
+    /// Encode a CBOR representation of value into provided writer without
+    /// building an intermediate vector
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::DataItem;
+    ///
+    /// let mut bytes = vec![0xff];
+    /// DataItem::Unsigned(500).encode_to(&mut bytes).unwrap();
+    /// assert_eq!(bytes, vec![0xff, 0x19, 0x01, 0xf4]);
+    /// ```
+    ///
+    /// # Errors
+    /// If a writer fails to write bytes
+    pub fn encode_to<W>(&self, writer: &mut W) -> Result<(), Error>
+    where
+        W: CborWrite + ?Sized,
+    {
+        encode_into(self, writer)
+    }
+
+    /// Encode a data item to a CBOR representation where items of a top level
+    /// array or entries of a top level map are encoded in parallel. Output is
+    /// identical to [`DataItem::encode`]
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::DataItem;
+    ///
+    /// let value = DataItem::from((0..10_000).map(DataItem::from).collect::<Vec<_>>());
+    /// assert_eq!(value.encode_parallel(), value.encode());
+    /// ```
+    #[cfg(feature = "rayon")]
+    #[must_use]
+    pub fn encode_parallel(&self) -> Vec<u8> {
+        crate::parallel::encode(self)
+    }
+
+    /// Decode a CBOR representation to a value
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::DataItem;
+    ///
+    /// let vector_data = vec![0x1a, 0x00, 0x98, 0x96, 0x80];
+    /// let value = DataItem::Unsigned(10_000_000);
+    /// assert_eq!(DataItem::decode(&vector_data).unwrap(), value);
+    /// ```
+    ///
+    /// # Errors
+    /// If provided bytes cannot be converted to CBOR
+    pub fn decode(val: &[u8]) -> Result<Self, Error> {
+        Self::decode_with_options(val, &DecodeOptions::default())
+    }
+
+    /// Decode a CBOR representation to a value while applying resource limits
+    /// of provided decode options
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::{DataItem, DecodeOptions};
+    ///
+    /// let mut options = DecodeOptions::default();
+    /// options.set_max_length(2);
+    /// assert!(DataItem::decode_with_options(&[0x82, 0x01, 0x02], &options).is_ok());
+    /// assert!(DataItem::decode_with_options(&[0x83, 0x01, 0x02, 0x03], &options).is_err());
+    /// ```
+    ///
+    /// # Errors
+    /// If provided bytes cannot be converted to CBOR or any limit is exceeded
+    pub fn decode_with_options(val: &[u8], options: &DecodeOptions) -> Result<Self, Error> {
+        check_size(options, val.len())?;
+        decode_root(
+            val,
+            options,
+            &mut DecodeState::new(SpanCollector::disabled()),
+        )
+    }
+
+    /// Decode a CBOR representation to a value along with a span tree which
+    /// records byte range of a value and all of its nested value. Span tree
+    /// follows an encoded structure so string reference namespace (tag 256) and
+    /// string reference (tag 25) which are expanded in a value are still
+    /// present in a span tree
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::DataItem;
+    ///
+    /// let bytes = [0x82, 0x01, 0x19, 0x03, 0xe8];
+    /// let (value, span) = DataItem::decode_with_span(&bytes).unwrap();
+    /// assert_eq!(value, DataItem::from(vec![1, 1000]));
+    /// assert_eq!(span.children()[1].range(), 2..5);
+    /// ```
+    ///
+    /// # Errors
+    /// If provided bytes cannot be converted to CBOR
+    pub fn decode_with_span(val: &[u8]) -> Result<(Self, SpanTree), Error> {
+        let mut state = DecodeState::new(SpanCollector::new(val.len()));
+        let data_item = decode_root(val, &DecodeOptions::default(), &mut state)?;
+        let span_tree = state.spans.into_span_tree().ok_or(Error::Incomplete)?;
+        Ok((data_item, span_tree))
+    }
+
+    /// Decode a CBOR representation to a value and apply validator and
+    /// converter of provided tag registry
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::{DataItem, TagRegistry};
+    ///
+    /// let registry = TagRegistry::standard();
+    /// assert!(DataItem::decode_with(&[0xc1, 0x01], &registry).is_ok());
+    /// assert!(DataItem::decode_with(&[0xc1, 0x61, 0x61], &registry).is_err());
+    /// ```
+    ///
+    /// # Errors
+    /// If provided bytes cannot be converted to CBOR or tag registry fails to
+    /// validate or convert a tag
+    pub fn decode_with(val: &[u8], registry: &TagRegistry) -> Result<Self, Error> {
+        registry.apply(&Self::decode(val)?)
+    }
+
+    /// Decode a CBOR representation which is tagged with an expected tag and
+    /// return a content of a tag
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::DataItem;
+    /// use cbor_next::error::Error;
+    ///
+    /// assert_eq!(
+    ///     DataItem::decode_expect_tag(32, &[0xd8, 0x20, 0x61, 0x61]).unwrap(),
+    ///     DataItem::from("a")
+    /// );
+    /// assert_eq!(
+    ///     DataItem::decode_expect_tag(32, &[0xc1, 0x01]),
+    ///     Err(Error::WrongTag {
+    ///         expected: 32,
+    ///         found: Some(1)
+    ///     })
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    /// If provided bytes cannot be converted to CBOR or decoded data item is
+    /// not tagged with an expected tag
+    pub fn decode_expect_tag(tag: u64, val: &[u8]) -> Result<Self, Error> {
+        Self::decode(val)?.require_tag(tag).cloned()
+    }
+
+    /// Validate all tags present in a data item using provided tag registry
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::{DataItem, TagContent, TagRegistry};
+    ///
+    /// let registry = TagRegistry::standard();
+    /// let uri = DataItem::from(vec![TagContent::from((32, "http://www.example.com"))]);
+    /// assert!(uri.validate(&registry).is_ok());
+    /// let uri = DataItem::from(vec![TagContent::from((32, 1))]);
+    /// assert!(uri.validate(&registry).is_err());
+    /// ```
+    ///
+    /// # Errors
+    /// If tag registry fails to validate a tag
+    pub fn validate(&self, registry: &TagRegistry) -> Result<(), Error> {
+        registry.validate(self)
+    }
+
+    /// Compress repeated byte string and text string into a string reference
+    /// (tag 25) inside a string reference namespace (tag 256). String
+    /// reference are expanded again when decoding
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::DataItem;
+    ///
+    /// let value = DataItem::from(vec![
+    ///     DataItem::from(vec![("name", 1)]),
+    ///     DataItem::from(vec![("name", 2)]),
+    /// ]);
+    /// let compressed = value.compress_stringref();
+    /// assert_eq!(
+    ///     format!("{compressed:?}"),
+    ///     "256([{\"name\": 1}, {25(0): 2}])"
+    /// );
+    /// assert_eq!(DataItem::decode(&compressed.encode()).unwrap(), value);
+    /// ```
+    #[must_use]
+    pub fn compress_stringref(&self) -> Self {
+        crate::stringref::compress(self)
+    }
+
+    /// Expand all string reference (tag 25) of a string reference namespace
+    /// (tag 256) and remove namespace tag
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::{DataItem, TagContent};
+    ///
+    /// let compressed = DataItem::from(TagContent::from((
+    ///     256,
+    ///     vec![DataItem::from("abc"), TagContent::from((25, 0)).into()],
+    /// )));
+    /// assert_eq!(
+    ///     compressed.expand_stringref().unwrap(),
+    ///     DataItem::from(vec!["abc", "abc"])
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    /// If string reference refers to a string which is not defined
+    pub fn expand_stringref(&self) -> Result<Self, Error> {
+        crate::stringref::expand(self)
+    }
+
+    /// Take a data item out leaving a null in its place
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::{DataItem, Get};
+    ///
+    /// let mut value = DataItem::from(vec![("a", vec![1, 2])]);
+    /// let taken = value.get_mut(DataItem::from("a")).unwrap().take();
+    /// assert_eq!(taken, DataItem::from(vec![1, 2]));
+    /// assert_eq!(value, DataItem::from(vec![("a", DataItem::Null)]));
+    /// ```
+    #[must_use]
+    pub fn take(&mut self) -> Self {
+        std::mem::replace(self, Self::Null)
+    }
+
+    /// Replace a data item with a new data item and return an old data item
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::DataItem;
+    ///
+    /// let mut value = DataItem::from(1);
+    /// assert_eq!(value.replace(DataItem::from("a")), DataItem::from(1));
+    /// assert_eq!(value, DataItem::from("a"));
+    /// ```
+    #[must_use]
+    pub fn replace(&mut self, new: Self) -> Self {
+        std::mem::replace(self, new)
+    }
+
+    /// Merge a patch into a data item following JSON merge patch (RFC 7386)
+    /// semantics
+    ///
+    /// If a patch is a map then each of its entries is merged recursively into
+    /// a data item where a null value removes a key. Any other patch replaces
+    /// a data item
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::DataItem;
+    ///
+    /// let mut config =
+    ///     DataItem::from_diagnostic(r#"{"port": 80, "tls": {"enabled": false, "cert": "a"}}"#)
+    ///         .unwrap();
+    /// let patch = r#"{"tls": {"enabled": true, "cert": null}}"#;
+    /// config.merge(&DataItem::from_diagnostic(patch).unwrap());
+    /// assert_eq!(
+    ///     config,
+    ///     DataItem::from_diagnostic(r#"{"port": 80, "tls": {"enabled": true}}"#).unwrap()
+    /// );
+    /// ```
+    pub fn merge(&mut self, patch: &Self) {
+        self.merge_with_options(patch, &MergeOptions::default());
+    }
+
+    /// Merge a patch into a data item using provided merge options
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::{DataItem, MergeOptions};
+    ///
+    /// let mut value = DataItem::from_diagnostic(r#"{"a": [1], "b": [2]}"#).unwrap();
+    /// let mut options = MergeOptions::default();
+    /// options.set_concat_array(true);
+    /// value.merge_with_options(
+    ///     &DataItem::from_diagnostic(r#"{"a": [3], "b": 4}"#).unwrap(),
+    ///     &options,
+    /// );
+    /// assert_eq!(
+    ///     value,
+    ///     DataItem::from_diagnostic(r#"{"a": [1, 3], "b": 4}"#).unwrap()
+    /// );
+    /// ```
+    pub fn merge_with_options(&mut self, patch: &Self, options: &MergeOptions) {
+        match (&mut *self, patch) {
+            (Self::Array(array_content), Self::Array(patch_content))
+                if options.is_concat_array() =>
+            {
+                array_content.extend_content(patch_content.array());
+            }
+            (_, Self::Map(patch_content)) => {
+                if !self.is_map() {
+                    *self = MapContent::default().into();
+                }
+                if let Self::Map(map_content) = self {
+                    for (key, value) in patch_content {
+                        if value.is_null() {
+                            map_content.shift_remove(key);
+                        } else {
+                            if !map_content.contains_key(key) {
+                                map_content.insert(key.clone(), Self::Null);
+                            }
+                            if let Some(old_value) = map_content.get_mut(key) {
+                                old_value.merge_with_options(value, options);
+                            }
+                        }
+                    }
+                }
+            }
+            _ => *self = patch.clone(),
         }
     }
 
-    /// Get a CBOR encoded representation of value
+    /// Check current data item is deterministic form, which is when its
+    /// encoding is same as its deterministic encoding in provided mode. It is
+    /// checked structurally so only a map key is encoded
     ///
     /// # Example
     /// ```rust
-    /// use cbor_next::DataItem;
+    /// use cbor_next::{DataItem, DeterministicMode};
     ///
-    /// let value = DataItem::Unsigned(10_000_000);
-    /// let vector_data = vec![0x1a, 0x00, 0x98, 0x96, 0x80];
-    /// assert_eq!(value.encode(), vector_data);
+    /// let value = DataItem::from_diagnostic(r#"{10: 1, "a": [2]}"#).unwrap();
+    /// assert!(value.is_deterministic(&DeterministicMode::Core));
+    /// let value = DataItem::from_diagnostic(r#"{"a": 1, 10: [_ 2]}"#).unwrap();
+    /// assert!(!value.is_deterministic(&DeterministicMode::Core));
     /// ```
     #[must_use]
-    pub fn encode(&self) -> Vec<u8> {
-        match self {
-            Self::Unsigned(number) | Self::Signed(number) => {
-                encode_u64_number(self.major_type(), *number)
-            }
-            Self::Byte(byte) => encode_vec_u8(self.major_type(), byte),
-            Self::Text(text_content) => {
-                encode_vec_u8(self.major_type(), &text_content.clone().into())
-            }
-            Self::Array(array) => {
-                let mut array_bytes = vec![];
-                if array.is_indefinite() {
-                    array_bytes.push(self.major_type() << 5 | 31);
-                    for val in array.array() {
-                        array_bytes.append(&mut val.encode());
+    pub fn is_deterministic(&self, mode: &DeterministicMode) -> bool {
+        let mut stack = vec![self];
+        while let Some(data_item) = stack.pop() {
+            match data_item {
+                Self::Map(map_content) => {
+                    if map_content.is_indefinite() || !map_content.duplicates().is_empty() {
+                        return false;
                     }
-                    array_bytes.push(255);
-                } else {
-                    let array_len = u64::try_from(array.array().len());
-                    if let Ok(length) = array_len {
-                        array_bytes.extend(encode_u64_number(self.major_type(), length));
-                        for val in array.array() {
-                            array_bytes.append(&mut val.encode());
-                        }
-                    } else {
-                        array_bytes.extend(
-                            Self::Array(
-                                ArrayContent::default()
-                                    .set_indefinite(true)
-                                    .set_content(array.array())
-                                    .clone(),
-                            )
-                            .encode(),
-                        );
+                    let encoded_keys = map_content
+                        .keys()
+                        .map(|key| key.encode_deterministic(mode))
+                        .collect::<Vec<_>>();
+                    if !encoded_keys
+                        .windows(2)
+                        .all(|pair| mode.compare(&pair[0], &pair[1]) == Ordering::Less)
+                    {
+                        return false;
                     }
+                    stack.extend(map_content.iter().flat_map(|(key, value)| [key, value]));
                 }
-                array_bytes
-            }
-            Self::Map(map) => {
-                let mut map_bytes = vec![];
-                if map.is_indefinite() {
-                    map_bytes.push(self.major_type() << 5 | 31);
-                    for (key, value) in map.map() {
-                        map_bytes.append(&mut key.encode());
-                        map_bytes.append(&mut value.encode());
-                    }
-                    map_bytes.push(255);
-                } else {
-                    let map_len = u64::try_from(map.map().len());
-                    if let Ok(length) = map_len {
-                        map_bytes.extend(encode_u64_number(self.major_type(), length));
-                        for (key, value) in map.map() {
-                            map_bytes.append(&mut key.encode());
-                            map_bytes.append(&mut value.encode());
-                        }
-                    } else {
-                        map_bytes.extend(
-                            Self::Map(
-                                MapContent::default()
-                                    .set_indefinite(true)
-                                    .set_content(map.map())
-                                    .clone(),
-                            )
-                            .encode(),
-                        );
+                Self::Array(array_content) => {
+                    if array_content.is_indefinite() {
+                        return false;
                     }
+                    stack.extend(array_content.iter());
                 }
-                map_bytes
+                Self::Tag(tag_content) => stack.push(tag_content.content()),
+                Self::Byte(byte_content) if byte_content.is_indefinite() => return false,
+                Self::Text(text_content) if text_content.is_indefinite() => return false,
+                _ => {}
             }
-            Self::Tag(tag_content) => {
-                let mut tag_bytes = encode_u64_number(self.major_type(), tag_content.number());
-                tag_bytes.append(&mut tag_content.content().encode());
-                tag_bytes
-            }
-            Self::Boolean(bool_val) => {
-                match bool_val {
-                    false => vec![self.major_type() << 5 | 0x14], // 20
-                    true => vec![self.major_type() << 5 | 0x15],  // 21
+        }
+        true
+    }
+
+    /// Get a deterministic ordering form in provided mode. Data item is
+    /// rewritten in place in a single pass where each map key is encoded once
+    /// for sorting, and a later entry wins when two keys have a same
+    /// deterministic encoding
+    #[must_use]
+    pub fn deterministic(mut self, mode: &DeterministicMode) -> Self {
+        // content is rewritten in place while nested data item are pushed to an
+        // explicit stack so a deeply nested data item cannot overflow a call stack
+        let mut stack = vec![&mut self];
+        while let Some(data_item) = stack.pop() {
+            match data_item {
+                Self::Map(map_content) => {
+                    // key is encoded once so sorting doesn't encode it on every comparison
+                    let mut entries = std::mem::take(map_content)
+                        .into_iter()
+                        .map(|(key, value)| (key.encode_deterministic(mode), key, value))
+                        .collect::<Vec<_>>();
+                    entries.sort_by(|(k1, ..), (k2, ..)| mode.compare(k1, k2));
+                    *map_content = entries
+                        .into_iter()
+                        .map(|(_, key, value)| (key.deterministic(mode), value))
+                        .collect();
+                    stack.extend(map_content.iter_mut().map(|(_, value)| value));
                 }
-            }
-            Self::Null => vec![self.major_type() << 5 | 0x16], // 22
-            Self::Undefined => vec![self.major_type() << 5 | 0x17], // 23
-            Self::Floating(number) => encode_f64_number(self.major_type(), *number),
-            Self::GenericSimple(simple_number) => {
-                if **simple_number <= 23 {
-                    vec![self.major_type() << 5 | **simple_number]
-                } else {
-                    vec![self.major_type() << 5 | 0x18, **simple_number] // 24
+                Self::Array(array_content) => {
+                    array_content.set_indefinite(false);
+                    stack.extend(array_content.iter_mut());
+                }
+                Self::Tag(tag_content) => stack.push(tag_content.content_mut()),
+                Self::Byte(byte_content) if byte_content.is_indefinite() => {
+                    *byte_content = ByteContent::default()
+                        .set_indefinite(false)
+                        .push_bytes(&byte_content.full())
+                        .clone();
+                }
+                Self::Text(text_content) if text_content.is_indefinite() => {
+                    *text_content = TextContent::default()
+                        .set_indefinite(false)
+                        .push_string(&text_content.full())
+                        .clone();
                 }
+                _ => {}
             }
         }
+        self
     }
 
-    /// Decode a CBOR representation to a value
+    /// Get a CBOR encoded representation of a deterministic form in provided
+    /// mode. It is same as encoding a data item returned by
+    /// [`DataItem::deterministic`] without cloning a data item
     ///
     /// # Example
     /// ```rust
-    /// use cbor_next::DataItem;
+    /// use cbor_next::{DataItem, DeterministicMode};
     ///
-    /// let vector_data = vec![0x1a, 0x00, 0x98, 0x96, 0x80];
-    /// let value = DataItem::Unsigned(10_000_000);
-    /// assert_eq!(DataItem::decode(&vector_data).unwrap(), value);
+    /// let value = DataItem::from_diagnostic(r#"{_ "bb": 1, 10: [_ 2]}"#).unwrap();
+    /// assert_eq!(
+    ///     value.encode_deterministic(&DeterministicMode::Core),
+    ///     vec![0xa2, 0x0a, 0x81, 0x02, 0x62, 0x62, 0x62, 0x01]
+    /// );
+    /// assert_eq!(
+    ///     value.encode_deterministic(&DeterministicMode::Core),
+    ///     value
+    ///         .clone()
+    ///         .deterministic(&DeterministicMode::Core)
+    ///         .encode()
+    /// );
     /// ```
+    #[must_use]
+    pub fn encode_deterministic(&self, mode: &DeterministicMode) -> Vec<u8> {
+        let mut bytes = vec![];
+        if encode_deterministic(self, *mode, &mut bytes).is_err() {
+            unreachable!("writing into a vector cannot fail");
+        }
+        bytes
+    }
+
+    /// Encode a deterministic form in provided mode into provided writer
     ///
     /// # Errors
-    /// If provided bytes cannot be converted to CBOR
-    pub fn decode(val: &[u8]) -> Result<Self, Error> {
-        let mut iter = val.iter();
-        decode_value(&mut iter)
+    /// If a writer fails to write bytes
+    pub fn encode_deterministic_to<W>(
+        &self,
+        writer: &mut W,
+        mode: &DeterministicMode,
+    ) -> Result<(), Error>
+    where
+        W: CborWrite + ?Sized,
+    {
+        encode_deterministic(self, *mode, writer)
     }
 
-    /// Check current data item is deterministic form
-    #[must_use]
-    pub fn is_deterministic(&self, mode: &DeterministicMode) -> bool {
-        match self {
-            Self::Map(index_map) => {
-                if index_map.is_indefinite() {
-                    return false;
-                }
-                let map = index_map.map();
-                map.iter()
-                    .zip(map.iter().skip(1))
-                    .all(|((k1, _), (k2, _))| {
-                        let key1_encode = k1.encode();
-                        let key2_encode = k2.encode();
-                        match mode {
-                            DeterministicMode::Core => key1_encode <= key2_encode,
-                            DeterministicMode::LengthFirst => {
-                                match key1_encode.len().cmp(&key2_encode.len()) {
-                                    Ordering::Equal => key1_encode <= key2_encode,
-                                    Ordering::Greater => false,
-                                    Ordering::Less => true,
-                                }
-                            }
-                        }
-                    })
+    /// Encode a deterministic form in provided mode into provided buffer
+    /// without allocating. Returns a number of bytes written. Output is same
+    /// as [`DataItem::encode_deterministic`]
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::{DataItem, DeterministicMode};
+    ///
+    /// let value = DataItem::from_diagnostic(r#"{"bb": 1, 10: [_ 2], "a": h'01'}"#).unwrap();
+    /// let mut buffer = [0; 16];
+    /// let length = value
+    ///     .encode_deterministic_into(&mut buffer, &DeterministicMode::Core)
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     &buffer[..length],
+    ///     value.encode_deterministic(&DeterministicMode::Core)
+    /// );
+    /// assert!(
+    ///     value
+    ///         .encode_deterministic_into(&mut [0; 8], &DeterministicMode::Core)
+    ///         .is_err()
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    /// If a buffer doesn't have enough space for an encoded data item or arrays
+    /// and maps are nested deeper than 64 levels, since nesting is tracked in a
+    /// fixed stack. Content of a buffer is unspecified in that case
+    pub fn encode_deterministic_into(
+        &self,
+        buffer: &mut [u8],
+        mode: &DeterministicMode,
+    ) -> Result<usize, Error> {
+        let mut encoder = FixedEncoder::new(buffer);
+        crate::fixed::encode_deterministic(self, *mode, &mut encoder)?;
+        Ok(encoder.as_bytes().len())
+    }
+}
+
+fn as_tag_nested(item: &DataItem, tags: &mut Vec<u64>) -> DataItem {
+    match item {
+        DataItem::Tag(tag_content) => {
+            tags.push(tag_content.number());
+            as_tag_nested(tag_content.content(), tags)
+        }
+        _ => item.clone(),
+    }
+}
+
+/// Push a data item and its nested data item which matches a predicate in a
+/// depth first order
+fn find_item<'a, F>(
+    data_item: &'a DataItem,
+    path: &mut Vec<PathSegment>,
+    predicate: &mut F,
+    found: &mut Vec<(Vec<PathSegment>, &'a DataItem)>,
+) where
+    F: FnMut(&[PathSegment], &DataItem) -> bool,
+{
+    if predicate(path, data_item) {
+        found.push((path.clone(), data_item));
+    }
+    match data_item {
+        DataItem::Array(array_content) => {
+            for (index, item) in array_content.array().iter().enumerate() {
+                path.push(PathSegment::Index(index));
+                find_item(item, path, predicate, found);
+                path.pop();
             }
-            Self::Array(val) => {
-                if val.is_indefinite() {
-                    return false;
-                }
-                val.array().iter().all(|v| v.is_deterministic(mode))
+        }
+        DataItem::Map(map_content) => {
+            for (key, value) in map_content.map() {
+                path.push(PathSegment::Key(key.clone()));
+                find_item(value, path, predicate, found);
+                path.pop();
             }
-            Self::Tag(tag_content) => tag_content.content().is_deterministic(mode),
-            Self::Byte(byte_content) => !byte_content.is_indefinite(),
-            Self::Text(text_content) => !text_content.is_indefinite(),
-            _ => true,
         }
+        DataItem::Tag(tag_content) => find_item(tag_content.content(), path, predicate, found),
+        _ => {}
     }
+}
 
-    /// Get a deterministic ordering form in provided mode
-    #[must_use]
-    pub fn deterministic(self, mode: &DeterministicMode) -> Self {
-        match self {
-            Self::Map(map_content) => {
-                let mut data = map_content
-                    .map()
-                    .iter()
-                    .map(|(k, v)| (k.clone().deterministic(mode), v.clone().deterministic(mode)))
-                    .collect::<Vec<(_, _)>>();
-                data.sort_by(|(k1, _), (k2, _)| {
-                    let key1_encode = k1.encode();
-                    let key2_encode = k2.encode();
-                    match mode {
-                        DeterministicMode::Core => key1_encode.cmp(&key2_encode),
-                        DeterministicMode::LengthFirst => {
-                            match key1_encode.len().cmp(&key2_encode.len()) {
-                                Ordering::Equal => key1_encode.cmp(&key2_encode),
-                                order => order,
-                            }
-                        }
-                    }
-                });
-                let mut index_map = IndexMap::new();
-                index_map.extend(data);
-                Self::Map(
-                    MapContent::default()
-                        .set_indefinite(false)
-                        .set_content(&index_map)
-                        .clone(),
-                )
+/// Create a type mismatch error for a data item which cannot be converted to
+/// an expected type
+fn type_mismatch(expected: ItemKind, data_item: &DataItem) -> Error {
+    Error::TypeMismatch {
+        expected,
+        found: ItemKind::of(data_item),
+    }
+}
+
+/// Get a big endian bytes of a number without a leading zero which is used as
+/// a content of a bignum
+fn bignum_bytes(number: u128) -> Vec<u8> {
+    number
+        .to_be_bytes()
+        .into_iter()
+        .skip_while(|byte| *byte == 0)
+        .collect()
+}
+
+/// Get a magnitude of a bignum content if it fits in `u128`
+fn bignum_magnitude(content: &DataItem) -> Option<u128> {
+    let bytes = content.as_byte()?;
+    let bytes = bytes
+        .iter()
+        .skip_while(|byte| **byte == 0)
+        .copied()
+        .collect::<Vec<_>>();
+    if bytes.len() > 16 {
+        return None;
+    }
+    let mut array = [0u8; 16];
+    array[16 - bytes.len()..].copy_from_slice(&bytes);
+    Some(u128::from_be_bytes(array))
+}
+
+/// Encode a data item by writing its CBOR representation to a writer
+pub(crate) fn encode_into<W>(root: &DataItem, writer: &mut W) -> Result<(), Error>
+where
+    W: CborWrite + ?Sized,
+{
+    // nested data item are pushed to an explicit stack instead of recursing
+    // so encoding a deeply nested data item cannot overflow a call stack
+    let mut stack = vec![EncodeStep::Item(root)];
+    while let Some(step) = stack.pop() {
+        let data_item = match step {
+            EncodeStep::Item(data_item) => data_item,
+            EncodeStep::Break => {
+                writer.write_bytes(&[255])?;
+                continue;
             }
-            Self::Array(val) => {
-                Self::Array(
-                    ArrayContent::default()
-                        .set_indefinite(false)
-                        .set_content(
-                            &val.array()
-                                .iter()
-                                .map(|v| v.clone().deterministic(mode))
-                                .collect::<Vec<_>>(),
-                        )
-                        .clone(),
-                )
+            EncodeStep::Encoded(bytes) => {
+                writer.write_bytes(&bytes)?;
+                continue;
             }
-            Self::Tag(tag_content) => {
-                Self::Tag(TagContent::from((
-                    tag_content.number(),
-                    tag_content.content().clone().deterministic(mode),
-                )))
+        };
+        match data_item {
+            DataItem::Unsigned(number) | DataItem::Signed(number) => {
+                encode_u64_number(writer, data_item.major_type(), *number)?;
             }
-            Self::Byte(byte_content) => {
-                if byte_content.is_indefinite() {
-                    Self::Byte(
-                        ByteContent::default()
-                            .set_indefinite(false)
-                            .push_bytes(&byte_content.full())
-                            .clone(),
-                    )
-                } else {
-                    Self::Byte(byte_content)
+            DataItem::Byte(byte_content) => {
+                encode_string(
+                    writer,
+                    data_item.major_type(),
+                    byte_content.is_indefinite(),
+                    byte_content.chunk().iter().map(Vec::as_slice),
+                )?;
+            }
+            DataItem::Text(text_content) => {
+                encode_string(
+                    writer,
+                    data_item.major_type(),
+                    text_content.is_indefinite(),
+                    text_content.chunk().iter().map(|chunk| chunk.as_bytes()),
+                )?;
+            }
+            DataItem::Array(array) => {
+                if encode_length_header(
+                    writer,
+                    data_item.major_type(),
+                    array.is_indefinite(),
+                    array.len(),
+                )? {
+                    stack.push(EncodeStep::Break);
+                }
+                stack.extend(array.iter().rev().map(EncodeStep::Item));
+            }
+            DataItem::Map(map) => {
+                if encode_length_header(
+                    writer,
+                    data_item.major_type(),
+                    map.is_indefinite(),
+                    map.len() + map.duplicates().len(),
+                )? {
+                    stack.push(EncodeStep::Break);
+                }
+                // entries are pushed in order and then reversed so duplicate entries
+                // are placed at their index without collecting entries
+                let start = stack.len();
+                for (key, value) in map.iter_with_duplicates() {
+                    stack.push(EncodeStep::Item(key));
+                    stack.push(EncodeStep::Item(value));
                 }
+                stack[start..].reverse();
             }
-            Self::Text(text_content) => {
-                if text_content.is_indefinite() {
-                    Self::Text(
-                        TextContent::default()
-                            .set_indefinite(false)
-                            .push_string(&text_content.full())
-                            .clone(),
-                    )
+            DataItem::Tag(tag_content) => {
+                encode_u64_number(writer, data_item.major_type(), tag_content.number())?;
+                stack.push(EncodeStep::Item(tag_content.content()));
+            }
+            DataItem::Boolean(false) => writer.write_bytes(&[0xf4])?, // 20
+            DataItem::Boolean(true) => writer.write_bytes(&[0xf5])?,  // 21
+            DataItem::Null => writer.write_bytes(&[0xf6])?,           // 22
+            DataItem::Undefined => writer.write_bytes(&[0xf7])?,      // 23
+            DataItem::Floating(number) => {
+                encode_f64_number(writer, data_item.major_type(), *number)?;
+            }
+            DataItem::GenericSimple(simple_number) => {
+                if **simple_number <= 23 {
+                    writer.write_bytes(&[data_item.major_type() << 5 | **simple_number])?;
                 } else {
-                    Self::Text(text_content)
+                    writer.write_bytes(&[0xf8, **simple_number])?; // 24
                 }
             }
-            _ => self,
         }
     }
+    Ok(())
 }
 
-fn as_tag_nested(item: &DataItem, tags: &mut Vec<u64>) -> DataItem {
-    match item {
-        DataItem::Tag(tag_content) => {
-            tags.push(tag_content.number());
-            as_tag_nested(tag_content.content(), tags)
-        }
-        _ => item.clone(),
+/// Step of an explicit stack used while encoding a data item
+enum EncodeStep<'a> {
+    /// Data item which is yet to be encoded
+    Item(&'a DataItem),
+    /// Break stop of an indefinite length array or map
+    Break,
+    /// Bytes which are already encoded such as a key of a deterministic map
+    Encoded(Cow<'a, [u8]>),
+}
+
+/// Encode a header of an array or a map. Returns true if an indefinite length
+/// header is encoded so content must be followed by a break stop
+pub(crate) fn encode_length_header<W>(
+    writer: &mut W,
+    major_type: u8,
+    is_indefinite: bool,
+    length: usize,
+) -> Result<bool, Error>
+where
+    W: CborWrite + ?Sized,
+{
+    if is_indefinite {
+        writer.write_bytes(&[major_type << 5 | 31])?;
+    } else {
+        encode_u64_number(writer, major_type, length as u64)?;
     }
+    Ok(is_indefinite)
 }
 
-fn encode_u64_number(major_type: u8, number: u64) -> Vec<u8> {
+fn encode_u64_number<W>(writer: &mut W, major_type: u8, number: u64) -> Result<(), Error>
+where
+    W: CborWrite + ?Sized,
+{
     let shifted_major_type = major_type << 5;
-    let mut cbor_representation = vec![];
     if let Ok(u8_value) = u8::try_from(number) {
         if u8_value <= 23 {
-            cbor_representation.push(shifted_major_type | u8_value);
+            writer.write_bytes(&[shifted_major_type | u8_value])
         } else {
-            cbor_representation.push(shifted_major_type | 0x18); // 24
-            cbor_representation.push(u8_value);
+            writer.write_bytes(&[shifted_major_type | 0x18, u8_value]) // 24
         }
     } else if let Ok(u16_value) = u16::try_from(number) {
-        cbor_representation.push(shifted_major_type | 0x19); // 25
-        for byte in u16_value.to_be_bytes() {
-            cbor_representation.push(byte);
-        }
+        writer.write_bytes(&[shifted_major_type | 0x19])?; // 25
+        writer.write_bytes(&u16_value.to_be_bytes())
     } else if let Ok(u32_value) = u32::try_from(number) {
-        cbor_representation.push(shifted_major_type | 0x1A); // 26
-        for byte in u32_value.to_be_bytes() {
-            cbor_representation.push(byte);
-        }
+        writer.write_bytes(&[shifted_major_type | 0x1A])?; // 26
+        writer.write_bytes(&u32_value.to_be_bytes())
     } else {
-        cbor_representation.push(shifted_major_type | 0x1B); // 27
-        for byte in number.to_be_bytes() {
-            cbor_representation.push(byte);
-        }
+        writer.write_bytes(&[shifted_major_type | 0x1B])?; // 27
+        writer.write_bytes(&number.to_be_bytes())
     }
-    cbor_representation
 }
 
-fn encode_vec_u8(major_type: u8, byte: &ByteContent) -> Vec<u8> {
-    let mut bytes = vec![];
-    if byte.is_indefinite() {
-        bytes.push(major_type << 5 | 31);
-        for chunk in byte.chunk() {
-            let mut encoded_fixed_length = encode_vec_u8(
-                major_type,
-                ByteContent::default()
-                    .set_indefinite(false)
-                    .set_bytes(chunk),
-            );
-            bytes.append(&mut encoded_fixed_length);
+/// Encode a byte string or text string from its chunks. Definite length
+/// string joins all chunks while indefinite length string encodes every chunk
+/// as a definite length string
+fn encode_string<'a, W, I>(
+    writer: &mut W,
+    major_type: u8,
+    is_indefinite: bool,
+    chunks: I,
+) -> Result<(), Error>
+where
+    W: CborWrite + ?Sized,
+    I: IntoIterator<Item = &'a [u8]>,
+    I::IntoIter: Clone,
+{
+    let chunks = chunks.into_iter();
+    if is_indefinite {
+        writer.write_bytes(&[major_type << 5 | 31])?;
+        for chunk in chunks {
+            encode_u64_number(writer, major_type, chunk.len() as u64)?;
+            writer.write_bytes(chunk)?;
         }
-        bytes.push(255);
+        writer.write_bytes(&[255])
     } else {
-        let byte_length = u64::try_from(byte.full().len());
-        if let Ok(length) = byte_length {
-            bytes.append(&mut encode_u64_number(major_type, length));
-            bytes.append(&mut byte.full().clone());
-        } else {
-            bytes.append(&mut encode_vec_u8(
-                major_type,
-                ByteContent::default()
-                    .set_indefinite(true)
-                    .set_bytes(&byte.full()),
-            ));
+        let length = chunks.clone().map(<[u8]>::len).sum::<usize>();
+        encode_u64_number(writer, major_type, length as u64)?;
+        for chunk in chunks {
+            writer.write_bytes(chunk)?;
         }
+        Ok(())
     }
-    bytes
 }
 
-fn encode_f64_number(major_type: u8, f64_number: f64) -> Vec<u8> {
-    let shifted_major_type = major_type << 5;
-    let mut cbor_representation = vec![];
-    let f16_num = half::f16::from_f64(f64_number);
+fn encode_f64_number<W>(writer: &mut W, major_type: u8, f64_number: f64) -> Result<(), Error>
+where
+    W: CborWrite + ?Sized,
+{
+    let (additional, bits) = shortest_float(f64_number);
+    writer.write_bytes(&[major_type << 5 | additional])?;
+    writer.write_bytes(&bits.to_be_bytes()[8 - (1 << (additional - 24))..])
+}
+
+/// Get additional info and bits of a shortest floating point number which
+/// preserves a value of provided number
+fn shortest_float(f64_number: f64) -> (u8, u64) {
     #[expect(
         clippy::float_cmp,
         reason = "we want to compare without margin or error"
@@ -1125,47 +3376,175 @@ fn encode_f64_number(major_type: u8, f64_number: f64) -> Vec<u8> {
         clippy::cast_possible_truncation,
         reason = "we only want to check truncation data loss"
     )]
-    if f16_num.to_f64() == f64_number {
-        cbor_representation.push(shifted_major_type | 0x19); // 25
-        for byte in (f16_num).to_be_bytes() {
-            cbor_representation.push(byte);
-        }
+    if let Some(f16_bits) = f64_to_f16_bits(f64_number) {
+        (25, u64::from(f16_bits))
     } else if f64::from(f64_number as f32) == f64_number {
-        cbor_representation.push(shifted_major_type | 0x1A); // 26
-        for byte in (f64_number as f32).to_be_bytes() {
-            cbor_representation.push(byte);
-        }
+        (26, u64::from((f64_number as f32).to_bits()))
     } else {
-        cbor_representation.push(shifted_major_type | 0x1B); // 27
-        for byte in f64_number.to_be_bytes() {
-            cbor_representation.push(byte);
+        (27, f64_number.to_bits())
+    }
+}
+
+/// Get entries of a map in a deterministic order of provided mode along with a
+/// deterministic encoding of their key. A sorted index of a map is used when
+/// it is set for same mode
+fn deterministic_map_entries(
+    map_content: &MapContent,
+    mode: DeterministicMode,
+) -> Vec<(Cow<'_, [u8]>, &DataItem)> {
+    if let Some(entries) = map_content.deterministic_entries(mode) {
+        return entries
+            .into_iter()
+            .map(|(key, value)| (Cow::Borrowed(key), value))
+            .collect();
+    }
+    // entries are reversed before a stable sort so a later entry wins when two
+    // keys have a same deterministic encoding
+    let mut entries = map_content
+        .iter()
+        .rev()
+        .map(|(key, value)| (Cow::Owned(key.encode_deterministic(&mode)), value))
+        .collect::<Vec<_>>();
+    entries.sort_by(|(k1, _), (k2, _)| mode.compare(k1, k2));
+    entries.dedup_by(|(k1, _), (k2, _)| k1 == k2);
+    entries
+}
+
+/// Encode a deterministic form of a data item in provided mode
+fn encode_deterministic<W>(
+    root: &DataItem,
+    mode: DeterministicMode,
+    writer: &mut W,
+) -> Result<(), Error>
+where
+    W: CborWrite + ?Sized,
+{
+    let mut stack = vec![EncodeStep::Item(root)];
+    while let Some(step) = stack.pop() {
+        let data_item = match step {
+            EncodeStep::Item(data_item) => data_item,
+            EncodeStep::Encoded(bytes) => {
+                writer.write_bytes(&bytes)?;
+                continue;
+            }
+            EncodeStep::Break => unreachable!("deterministic form doesn't have a break stop"),
+        };
+        let major_type = data_item.major_type();
+        match data_item {
+            DataItem::Map(map_content) => {
+                let entries = deterministic_map_entries(map_content, mode);
+                encode_u64_number(writer, major_type, entries.len() as u64)?;
+                for (key, value) in entries.into_iter().rev() {
+                    stack.push(EncodeStep::Item(value));
+                    stack.push(EncodeStep::Encoded(key));
+                }
+            }
+            DataItem::Array(array_content) => {
+                encode_u64_number(writer, major_type, array_content.len() as u64)?;
+                stack.extend(array_content.iter().rev().map(EncodeStep::Item));
+            }
+            DataItem::Tag(tag_content) => {
+                encode_u64_number(writer, major_type, tag_content.number())?;
+                stack.push(EncodeStep::Item(tag_content.content()));
+            }
+            DataItem::Byte(byte_content) => {
+                encode_string(
+                    writer,
+                    major_type,
+                    false,
+                    byte_content.chunk().iter().map(Vec::as_slice),
+                )?;
+            }
+            DataItem::Text(text_content) => {
+                encode_string(
+                    writer,
+                    major_type,
+                    false,
+                    text_content.chunk().iter().map(|chunk| chunk.as_bytes()),
+                )?;
+            }
+            _ => encode_into(data_item, writer)?,
+        }
+    }
+    Ok(())
+}
+
+/// State which is shared by every nested data item of a single decode call
+struct DecodeState {
+    spans: SpanCollector,
+    keys: Interner,
+}
+
+impl DecodeState {
+    fn new(spans: SpanCollector) -> Self {
+        Self {
+            spans,
+            keys: Interner::default(),
         }
     }
-    cbor_representation
 }
 
-fn decode_value(iter: &mut Iter<'_, u8>) -> Result<DataItem, Error> {
+fn decode_root(
+    val: &[u8],
+    options: &DecodeOptions,
+    state: &mut DecodeState,
+) -> Result<DataItem, Error> {
+    let mut iter = val.iter();
+    let data_item = decode_value(&mut iter, options, 0, state)?;
+    if crate::stringref::has_namespace(&data_item) {
+        return crate::stringref::expand(&data_item);
+    }
+    Ok(data_item)
+}
+
+fn decode_value(
+    iter: &mut Iter<'_, u8>,
+    options: &DecodeOptions,
+    depth: usize,
+    state: &mut DecodeState,
+) -> Result<DataItem, Error> {
+    state.spans.enter(iter.len());
+    let data_item = decode_content(iter, options, depth, state)?;
+    state.spans.leave(iter.len());
+    Ok(data_item)
+}
+
+fn decode_content(
+    iter: &mut Iter<'_, u8>,
+    options: &DecodeOptions,
+    depth: usize,
+    state: &mut DecodeState,
+) -> Result<DataItem, Error> {
     let initial_info = iter.next().ok_or(Error::Incomplete)?;
     let major_type = initial_info >> 5;
     let additional = initial_info & 0b0001_1111;
     match major_type {
-        0 => Ok(DataItem::Unsigned(extract_number(additional, iter)?)),
-        1 => Ok(DataItem::Signed(extract_number(additional, iter)?)),
+        0 => {
+            Ok(DataItem::Unsigned(extract_number(
+                major_type, additional, iter,
+            )?))
+        }
+        1 => {
+            Ok(DataItem::Signed(extract_number(
+                major_type, additional, iter,
+            )?))
+        }
         2 => {
             Ok(DataItem::Byte(decode_byte_or_text(
-                major_type, additional, iter,
+                major_type, additional, iter, options, state,
             )?))
         }
         3 => {
             Ok(DataItem::Text(
-                decode_byte_or_text(major_type, additional, iter)?.try_into()?,
+                decode_byte_or_text(major_type, additional, iter, options, state)?.try_into()?,
             ))
         }
-        4 => decode_array(additional, iter),
-        5 => decode_map(additional, iter),
+        4 => decode_array(additional, iter, options, depth + 1, state),
+        5 => decode_map(additional, iter, options, depth + 1, state),
         6 => {
-            let tag_number = extract_number(additional, iter)?;
-            let tag_value = decode_value(iter)?;
+            let tag_number = extract_number(major_type, additional, iter)?;
+            check_depth(options, depth + 1)?;
+            let tag_value = decode_value(iter, options, depth + 1, state)?;
             Ok(DataItem::Tag(TagContent::from((tag_number, tag_value))))
         }
         7 => decode_simple_or_floating(additional, iter),
@@ -1177,31 +3556,42 @@ fn decode_byte_or_text(
     major_type: u8,
     additional: u8,
     iter: &mut Iter<'_, u8>,
+    options: &DecodeOptions,
+    state: &mut DecodeState,
 ) -> Result<ByteContent, Error> {
     let length = extract_optional_number(additional, iter)?;
     let mut byte_content = ByteContent::default();
     if let Some(num) = length {
+        check_length(options, num)?;
         byte_content.set_indefinite(false);
-        byte_content.set_bytes(&collect_vec_u8(iter, num)?);
+        byte_content.set_bytes_owned(collect_vec_u8(iter, num)?);
     } else {
         byte_content.set_indefinite(true);
-        byte_content.extend_bytes(&decode_indefinite_byte_or_text(major_type, iter)?);
-        iter.next();
+        decode_indefinite_byte_or_text(major_type, iter, options, state, &mut byte_content)?;
     }
     Ok(byte_content)
 }
 
-fn decode_array(additional: u8, iter: &mut Iter<'_, u8>) -> Result<DataItem, Error> {
+fn decode_array(
+    additional: u8,
+    iter: &mut Iter<'_, u8>,
+    options: &DecodeOptions,
+    depth: usize,
+    state: &mut DecodeState,
+) -> Result<DataItem, Error> {
+    check_depth(options, depth)?;
     let length = extract_optional_number(additional, iter)?;
     let mut val_vec = vec![];
     let mut array_content = ArrayContent::default();
     array_content.set_indefinite(length.is_none());
     if let Some(num) = length {
+        check_length(options, num)?;
+        val_vec.reserve(initial_capacity(options, num, iter.len(), 1));
         for _ in 0..num {
-            val_vec.push(decode_value(iter)?);
+            val_vec.push(decode_value(iter, options, depth, state)?);
         }
     } else {
-        val_vec.append(&mut extract_array_item(iter)?);
+        val_vec.append(&mut extract_array_item(iter, options, depth, state)?);
         match iter.clone().next() {
             Some(255) => {
                 iter.next();
@@ -1212,26 +3602,47 @@ fn decode_array(additional: u8, iter: &mut Iter<'_, u8>) -> Result<DataItem, Err
             _ => unreachable!("non 255 some value should be handled already"),
         }
     }
-    Ok(DataItem::Array(array_content.set_content(&val_vec).clone()))
+    array_content.set_content_owned(val_vec);
+    Ok(DataItem::Array(array_content))
 }
 
-fn decode_map(additional: u8, iter: &mut Iter<'_, u8>) -> Result<DataItem, Error> {
+fn decode_map(
+    additional: u8,
+    iter: &mut Iter<'_, u8>,
+    options: &DecodeOptions,
+    depth: usize,
+    state: &mut DecodeState,
+) -> Result<DataItem, Error> {
+    check_depth(options, depth)?;
     let length: Option<u64> = extract_optional_number(additional, iter)?;
     let mut map_index_map = IndexMap::new();
+    let mut duplicates = vec![];
     let mut map_content = MapContent::default();
     map_content.set_indefinite(length.is_none());
     if let Some(num) = length {
+        check_length(options, num)?;
+        map_index_map.reserve(initial_capacity(options, num, iter.len(), 2));
         for _ in 0..num {
-            let key = decode_value(iter)?;
-            let val = decode_value(iter)?;
-            if map_index_map.insert(key.clone(), val).is_some() {
-                return Err(Error::NotWellFormed(format!(
-                    "same map key {key:#?} is repeated multiple times"
-                )));
-            }
+            let key = decode_value(iter, options, depth, state)?;
+            let val = decode_value(iter, options, depth, state)?;
+            insert_map_item(
+                &mut map_index_map,
+                &mut duplicates,
+                key,
+                val,
+                options,
+                &mut state.keys,
+            )?;
         }
     } else {
-        map_index_map.extend(extract_map_item(iter)?);
+        extract_map_item(
+            iter,
+            options,
+            depth,
+            state,
+            &mut map_index_map,
+            &mut duplicates,
+        )?;
         match iter.clone().next() {
             Some(255) => {
                 iter.next();
@@ -1242,9 +3653,10 @@ fn decode_map(additional: u8, iter: &mut Iter<'_, u8>) -> Result<DataItem, Error
             _ => unreachable!("non 255 some value should be handled already"),
         }
     }
-    Ok(DataItem::Map(
-        map_content.set_content(&map_index_map).clone(),
-    ))
+    map_content
+        .set_content_owned(map_index_map)
+        .set_duplicates(duplicates);
+    Ok(DataItem::Map(map_content))
 }
 
 fn decode_simple_or_floating(additional: u8, iter: &mut Iter<'_, u8>) -> Result<DataItem, Error> {
@@ -1266,101 +3678,203 @@ fn decode_simple_or_floating(additional: u8, iter: &mut Iter<'_, u8>) -> Result<
             }
         }
         25 => {
-            let number_representation = u16::try_from(extract_number(additional, iter)?)?;
-            Ok(DataItem::Floating(f64::from(half::f16::from_bits(
-                number_representation,
-            ))))
+            let number_representation = u16::try_from(extract_number(7, additional, iter)?)?;
+            Ok(DataItem::Floating(f16_bits_to_f64(number_representation)))
         }
         26 => {
-            let number_representation = u32::try_from(extract_number(additional, iter)?)?;
+            let number_representation = u32::try_from(extract_number(7, additional, iter)?)?;
             Ok(DataItem::Floating(f64::from(f32::from_bits(
                 number_representation,
             ))))
         }
         27 => {
-            let f64_number_representation = extract_number(additional, iter)?;
+            let f64_number_representation = extract_number(7, additional, iter)?;
             Ok(DataItem::Floating(f64::from_bits(
                 f64_number_representation,
             )))
         }
         28..=30 => {
-            Err(Error::NotWellFormed(format!(
-                "invalid value {additional} for major type 7"
-            )))
+            Err(Error::NotWellFormed(Malformed::ReservedAdditionalInfo {
+                additional,
+            }))
         }
         31 => Err(Error::InvalidBreakStop),
         _ => unreachable!("Cannot have additional info value greater than 31"),
     }
 }
 
+/// Decode chunks of an indefinite length byte string or text string up to and
+/// including a break stop. Every chunk and a total length of chunks are
+/// checked against a maximum length
 fn decode_indefinite_byte_or_text(
     expected_major_type: u8,
     iter: &mut Iter<'_, u8>,
-) -> Result<Vec<Vec<u8>>, Error> {
-    let mut result = vec![];
-    if let Some(peek_val) = iter.clone().next() {
-        if *peek_val == 255 {
-            return Ok(result);
+    options: &DecodeOptions,
+    state: &mut DecodeState,
+    byte_content: &mut ByteContent,
+) -> Result<(), Error> {
+    let mut chunk_count = 0u64;
+    let mut total_length = 0u64;
+    loop {
+        let initial_info = *iter.as_slice().first().ok_or(Error::IncompleteIndefinite)?;
+        if initial_info == 255 {
+            iter.next();
+            return Ok(());
         }
-        let initial_info = iter.next().ok_or(Error::Incomplete)?;
+        state.spans.enter(iter.len());
+        iter.next();
         let major_type = initial_info >> 5;
         if expected_major_type != major_type {
-            return Err(Error::NotWellFormed(format!(
-                "contains invalid major type {major_type} for indefinite major type \
-                 {expected_major_type}"
-            )));
+            return Err(Error::NotWellFormed(Malformed::InvalidChunk {
+                expected: expected_major_type,
+                found: major_type,
+            }));
         }
+        chunk_count += 1;
+        check_length(options, chunk_count)?;
         let additional = initial_info & 0b0001_1111;
-        let length = extract_number(additional, iter)?;
-        result.push(collect_vec_u8(iter, length)?);
-        result.extend(decode_indefinite_byte_or_text(expected_major_type, iter)?);
-        return Ok(result);
+        let length = extract_number(major_type, additional, iter)?;
+        total_length = total_length.saturating_add(length);
+        check_length(options, total_length)?;
+        byte_content.push_chunk(collect_vec_u8(iter, length)?);
+        state.spans.leave(iter.len());
     }
-    Err(Error::IncompleteIndefinite)
 }
 
-fn extract_array_item(iter: &mut Iter<'_, u8>) -> Result<Vec<DataItem>, Error> {
+fn extract_array_item(
+    iter: &mut Iter<'_, u8>,
+    options: &DecodeOptions,
+    depth: usize,
+    state: &mut DecodeState,
+) -> Result<Vec<DataItem>, Error> {
     let mut result = vec![];
-    if let Some(peek_val) = iter.clone().next()
+    while let Some(peek_val) = iter.clone().next()
         && *peek_val != 255
     {
-        result.push(decode_value(iter)?);
-        result.append(&mut extract_array_item(iter)?);
+        check_length(options, result.len() as u64 + 1)?;
+        result.push(decode_value(iter, options, depth, state)?);
     }
     Ok(result)
 }
 
-fn extract_map_item(iter: &mut Iter<'_, u8>) -> Result<IndexMap<DataItem, DataItem>, Error> {
-    let mut result = IndexMap::new();
-    if let Some(peek_val) = iter.clone().next()
+fn extract_map_item(
+    iter: &mut Iter<'_, u8>,
+    options: &DecodeOptions,
+    depth: usize,
+    state: &mut DecodeState,
+    map: &mut IndexMap<DataItem, DataItem>,
+    duplicates: &mut Vec<(usize, DataItem, DataItem)>,
+) -> Result<(), Error> {
+    while let Some(peek_val) = iter.clone().next()
         && *peek_val != 255
     {
-        let key = decode_value(iter)?;
-        let val = decode_value(iter)?;
-        if result.insert(key.clone(), val).is_some() {
-            return Err(Error::NotWellFormed(format!(
-                "same map key {key:#?} is repeated multiple times"
-            )));
+        check_length(options, (map.len() + duplicates.len()) as u64 + 1)?;
+        let key = decode_value(iter, options, depth, state)?;
+        let val = decode_value(iter, options, depth, state)?;
+        insert_map_item(map, duplicates, key, val, options, &mut state.keys)?;
+    }
+    Ok(())
+}
+
+fn insert_map_item(
+    map: &mut IndexMap<DataItem, DataItem>,
+    duplicates: &mut Vec<(usize, DataItem, DataItem)>,
+    key: DataItem,
+    val: DataItem,
+    options: &DecodeOptions,
+    keys: &mut Interner,
+) -> Result<(), Error> {
+    let key = if options.intern_keys() {
+        intern_key(key, keys)
+    } else {
+        key
+    };
+    if !map.contains_key(&key) {
+        map.insert(key, val);
+    } else if options.preserve_duplicate_keys() {
+        duplicates.push((map.len() + duplicates.len(), key, val));
+    } else {
+        return Err(Error::NotWellFormed(Malformed::DuplicateKey {
+            index: map.len() + duplicates.len(),
+        }));
+    }
+    Ok(())
+}
+
+/// Replace a definite length text string key with a string shared with every
+/// same key of a decode call
+fn intern_key(key: DataItem, keys: &mut Interner) -> DataItem {
+    if let DataItem::Text(text_content) = &key
+        && !text_content.is_indefinite()
+        && let Some(interned) = text_content.as_str().and_then(|key| keys.intern(key))
+    {
+        return DataItem::Text(interned.into());
+    }
+    key
+}
+
+pub(crate) fn check_depth(options: &DecodeOptions, depth: usize) -> Result<(), Error> {
+    let max_depth = options.max_depth();
+    if depth > max_depth {
+        return Err(Error::LimitExceeded(Limit::Depth { depth, max_depth }));
+    }
+    Ok(())
+}
+
+pub(crate) fn check_size(options: &DecodeOptions, size: usize) -> Result<(), Error> {
+    match options.max_size() {
+        Some(max_size) if size > max_size => {
+            Err(Error::LimitExceeded(Limit::Size { size, max_size }))
         }
-        result.extend(extract_map_item(iter)?);
+        _ => Ok(()),
     }
-    Ok(result)
 }
 
-fn collect_vec_u8(iter: &mut Iter<'_, u8>, number: u64) -> Result<Vec<u8>, Error> {
-    let mut collected_val = Vec::new();
-    for i in 0..number {
-        match iter.next() {
-            Some(item) => collected_val.push(*item),
-            None => {
-                return Err(Error::NotWellFormed(format!(
-                    "incomplete array of byte missing {} byte",
-                    number - i
-                )));
-            }
+pub(crate) fn check_length(options: &DecodeOptions, length: u64) -> Result<(), Error> {
+    match options.max_length() {
+        Some(max_length) if length > max_length => {
+            Err(Error::LimitExceeded(Limit::Length { length, max_length }))
         }
+        _ => Ok(()),
+    }
+}
+
+fn collect_vec_u8(iter: &mut Iter<'_, u8>, number: u64) -> Result<Vec<u8>, Error> {
+    let remaining = iter.as_slice();
+    let Some(collected_val) = usize::try_from(number)
+        .ok()
+        .and_then(|length| remaining.get(..length))
+    else {
+        return Err(Error::NotWellFormed(Malformed::MissingBytes {
+            missing: number - remaining.len() as u64,
+        }));
+    };
+    *iter = remaining[collected_val.len()..].iter();
+    Ok(collected_val.to_vec())
+}
+
+/// Maximum initial capacity of an array or a map whose length header is not
+/// trusted
+const UNTRUSTED_CAPACITY: usize = 1024;
+
+/// Get an initial capacity of a definite length array or map. Every item needs
+/// at least `item_size` bytes so capacity never exceeds what remaining bytes
+/// can hold. Length header is only trusted when a maximum length is configured,
+/// otherwise capacity is also capped to `UNTRUSTED_CAPACITY`
+fn initial_capacity(
+    options: &DecodeOptions,
+    length: u64,
+    remaining: usize,
+    item_size: usize,
+) -> usize {
+    let capacity = usize::try_from(length)
+        .unwrap_or(usize::MAX)
+        .min(remaining / item_size);
+    if options.max_length().is_some() {
+        capacity
+    } else {
+        capacity.min(UNTRUSTED_CAPACITY)
     }
-    Ok(collected_val)
 }
 
 fn extract_optional_number(additional: u8, iter: &mut Iter<'_, u8>) -> Result<Option<u64>, Error> {
@@ -1374,16 +3888,180 @@ fn extract_optional_number(additional: u8, iter: &mut Iter<'_, u8>) -> Result<Op
             Ok(Some(u64::from_be_bytes(array)))
         }
         28..=30 => {
-            Err(Error::NotWellFormed(format!(
-                "invalid additional number {additional}"
-            )))
+            Err(Error::NotWellFormed(Malformed::ReservedAdditionalInfo {
+                additional,
+            }))
         }
         31 => Ok(None),
         _ => unreachable!("Cannot have additional info value greater than 31"),
     }
 }
 
-fn extract_number(additional: u8, iter: &mut Iter<'_, u8>) -> Result<u64, Error> {
-    extract_optional_number(additional, iter)?
-        .ok_or(Error::NotWellFormed("failed to extract number".to_string()))
+fn extract_number(major_type: u8, additional: u8, iter: &mut Iter<'_, u8>) -> Result<u64, Error> {
+    extract_optional_number(additional, iter)?.ok_or(Error::NotWellFormed(
+        Malformed::IndefiniteLength { major_type },
+    ))
+}
+
+/// Get an initial byte without its major type and an argument of a core
+/// deterministic encoding of a data item. Data items of a same major type are
+/// ordered by it same as a bytewise order of their header. Length of a map is
+/// compared separately after removing its repeated key
+fn deterministic_header(data_item: &DataItem) -> (u8, u8, u64) {
+    let major_type = data_item.major_type();
+    match data_item {
+        DataItem::Unsigned(number) | DataItem::Signed(number) => (major_type, 0, *number),
+        DataItem::Byte(byte_content) => (major_type, 0, byte_content.len() as u64),
+        DataItem::Text(text_content) => (major_type, 0, text_content.len() as u64),
+        DataItem::Array(array_content) => (major_type, 0, array_content.len() as u64),
+        DataItem::Map(_) => (major_type, 0, 0),
+        DataItem::Tag(tag_content) => (major_type, 0, tag_content.number()),
+        DataItem::GenericSimple(simple_value) if **simple_value < 24 => {
+            (major_type, **simple_value, 0)
+        }
+        DataItem::GenericSimple(simple_value) => (major_type, 24, u64::from(**simple_value)),
+        DataItem::Boolean(false) => (major_type, 20, 0),
+        DataItem::Boolean(true) => (major_type, 21, 0),
+        DataItem::Null => (major_type, 22, 0),
+        DataItem::Undefined => (major_type, 23, 0),
+        DataItem::Floating(number) => {
+            let (additional, bits) = shortest_float(*number);
+            (major_type, additional, bits)
+        }
+    }
+}
+
+/// Compare data items by a bytewise order of their core deterministic encoding
+/// without encoding them. Nested data items are compared through an explicit
+/// stack in a same order as they appear in an encoding
+fn compare_deterministic(first: &DataItem, second: &DataItem) -> Ordering {
+    let mut stack = vec![(first, second)];
+    while let Some((first, second)) = stack.pop() {
+        let ordering = deterministic_header(first)
+            .cmp(&deterministic_header(second))
+            .then_with(|| {
+                match (first, second) {
+                    (DataItem::Byte(first), DataItem::Byte(second)) => {
+                        first
+                            .chunk()
+                            .iter()
+                            .flatten()
+                            .cmp(second.chunk().iter().flatten())
+                    }
+                    (DataItem::Text(first), DataItem::Text(second)) => {
+                        first
+                            .chunk()
+                            .iter()
+                            .flat_map(|chunk| chunk.bytes())
+                            .cmp(second.chunk().iter().flat_map(|chunk| chunk.bytes()))
+                    }
+                    (DataItem::Array(first), DataItem::Array(second)) => {
+                        stack.extend(first.iter().zip(second.iter()).rev());
+                        Ordering::Equal
+                    }
+                    (DataItem::Map(first), DataItem::Map(second)) => {
+                        let first = deterministic_entries(first);
+                        let second = deterministic_entries(second);
+                        let ordering = first.len().cmp(&second.len());
+                        stack.extend(first.into_iter().zip(second).rev().flat_map(
+                            |((first_key, first_value), (second_key, second_value))| {
+                                [(first_value, second_value), (first_key, second_key)]
+                            },
+                        ));
+                        ordering
+                    }
+                    (DataItem::Tag(first), DataItem::Tag(second)) => {
+                        stack.push((first.content(), second.content()));
+                        Ordering::Equal
+                    }
+                    _ => Ordering::Equal,
+                }
+            });
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+/// Get entries of a map in a core deterministic order where a later entry wins
+/// when two keys have a same deterministic encoding
+fn deterministic_entries(map_content: &MapContent) -> Vec<(&DataItem, &DataItem)> {
+    let mut entries = map_content.iter().rev().collect::<Vec<_>>();
+    entries.sort_by(|(first, _), (second, _)| compare_deterministic(first, second));
+    entries.dedup_by(|(first, _), (second, _)| {
+        compare_deterministic(first, second) == Ordering::Equal
+    });
+    entries
+}
+
+/// Compare data items whose core deterministic encoding is same by their
+/// remaining structure, which is whether a content is indefinite, chunks of a
+/// string and all entries of a map including a repeated key
+fn compare_exact(first: &DataItem, second: &DataItem) -> Ordering {
+    let mut stack = vec![(first, second)];
+    while let Some((first, second)) = stack.pop() {
+        let ordering = match (first, second) {
+            (DataItem::Byte(first), DataItem::Byte(second)) => {
+                first
+                    .is_indefinite()
+                    .cmp(&second.is_indefinite())
+                    .then_with(|| first.chunk().cmp(second.chunk()))
+            }
+            (DataItem::Text(first), DataItem::Text(second)) => {
+                first
+                    .is_indefinite()
+                    .cmp(&second.is_indefinite())
+                    .then_with(|| first.chunk().cmp(second.chunk()))
+            }
+            (DataItem::Array(first), DataItem::Array(second)) => {
+                stack.extend(first.iter().zip(second.iter()).rev());
+                first
+                    .is_indefinite()
+                    .cmp(&second.is_indefinite())
+                    .then_with(|| first.len().cmp(&second.len()))
+            }
+            (DataItem::Map(first), DataItem::Map(second)) => {
+                let first_entries = sorted_entries(first);
+                let second_entries = sorted_entries(second);
+                let first_duplicates = first
+                    .duplicates()
+                    .iter()
+                    .map(|(_, key, value)| (key, value));
+                let second_duplicates = second
+                    .duplicates()
+                    .iter()
+                    .map(|(_, key, value)| (key, value));
+                for (first_entry, second_entry) in first_duplicates
+                    .zip(second_duplicates)
+                    .rev()
+                    .chain(first_entries.into_iter().zip(second_entries).rev())
+                {
+                    stack.push((first_entry.1, second_entry.1));
+                    stack.push((first_entry.0, second_entry.0));
+                }
+                first
+                    .is_indefinite()
+                    .cmp(&second.is_indefinite())
+                    .then_with(|| first.len().cmp(&second.len()))
+                    .then_with(|| first.duplicates().len().cmp(&second.duplicates().len()))
+            }
+            (DataItem::Tag(first), DataItem::Tag(second)) => {
+                stack.push((first.content(), second.content()));
+                first.number().cmp(&second.number())
+            }
+            _ => compare_deterministic(first, second),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+/// Get entries of a map sorted by an order of their key
+fn sorted_entries(map_content: &MapContent) -> Vec<(&DataItem, &DataItem)> {
+    let mut entries = map_content.iter().collect::<Vec<_>>();
+    entries.sort_by_key(|(key, _)| *key);
+    entries
 }
@@ -0,0 +1,251 @@
+use crate::content::TagContent;
+use crate::data_item::DataItem;
+use crate::tag;
+
+/// Trait implemented by date time types which can be represented by a standard
+/// date/time string (tag 0) or an epoch based date/time (tag 1)
+pub trait Timestamp: Sized {
+    /// Parse a RFC 3339 formatted date time string
+    fn from_rfc3339(value: &str) -> Option<Self>;
+
+    /// Format a date time as RFC 3339 string
+    fn to_rfc3339(&self) -> Option<String>;
+
+    /// Create a date time from a seconds and nanoseconds since unix epoch
+    fn from_unix(seconds: i64, nanoseconds: u32) -> Option<Self>;
+
+    /// Get seconds and nanoseconds since unix epoch
+    fn to_unix(&self) -> (i64, u32);
+}
+
+#[cfg(feature = "chrono")]
+impl Timestamp for chrono::DateTime<chrono::Utc> {
+    fn from_rfc3339(value: &str) -> Option<Self> {
+        chrono::DateTime::parse_from_rfc3339(value)
+            .ok()
+            .map(|date_time| date_time.with_timezone(&chrono::Utc))
+    }
+
+    fn to_rfc3339(&self) -> Option<String> {
+        Some(self.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true))
+    }
+
+    fn from_unix(seconds: i64, nanoseconds: u32) -> Option<Self> {
+        chrono::DateTime::from_timestamp(seconds, nanoseconds)
+    }
+
+    fn to_unix(&self) -> (i64, u32) {
+        (self.timestamp(), self.timestamp_subsec_nanos())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::DateTime<chrono::Utc>> for DataItem {
+    fn from(value: chrono::DateTime<chrono::Utc>) -> Self {
+        to_string_data_item(&value)
+    }
+}
+
+#[cfg(feature = "time")]
+impl Timestamp for time::OffsetDateTime {
+    fn from_rfc3339(value: &str) -> Option<Self> {
+        Self::parse(value, &time::format_description::well_known::Rfc3339).ok()
+    }
+
+    fn to_rfc3339(&self) -> Option<String> {
+        self.to_offset(time::UtcOffset::UTC)
+            .format(&time::format_description::well_known::Rfc3339)
+            .ok()
+    }
+
+    fn from_unix(seconds: i64, nanoseconds: u32) -> Option<Self> {
+        Self::from_unix_timestamp(seconds)
+            .ok()?
+            .replace_nanosecond(nanoseconds)
+            .ok()
+    }
+
+    fn to_unix(&self) -> (i64, u32) {
+        (self.unix_timestamp(), self.nanosecond())
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<time::OffsetDateTime> for DataItem {
+    fn from(value: time::OffsetDateTime) -> Self {
+        to_string_data_item(&value)
+    }
+}
+
+/// Convert a date time to a standard date/time string (tag 0). Fallback to
+/// epoch based date/time (tag 1) if date time cannot be formatted as RFC 3339
+/// string
+pub(crate) fn to_string_data_item<T>(value: &T) -> DataItem
+where
+    T: Timestamp,
+{
+    match value.to_rfc3339() {
+        Some(date_time) => TagContent::from((tag::DATETIME_STRING, date_time)).into(),
+        None => to_epoch_data_item(value),
+    }
+}
+
+/// Convert a date time to a epoch based date/time (tag 1). Integer is used
+/// when date time doesn't have a fractional seconds
+pub(crate) fn to_epoch_data_item<T>(value: &T) -> DataItem
+where
+    T: Timestamp,
+{
+    let (seconds, nanoseconds) = value.to_unix();
+    if nanoseconds == 0 {
+        TagContent::from((tag::EPOCH, seconds)).into()
+    } else {
+        #[expect(
+            clippy::cast_precision_loss,
+            reason = "epoch based date time with fractional seconds is a floating number"
+        )]
+        let epoch = seconds as f64 + f64::from(nanoseconds) / 1e9;
+        TagContent::from((tag::EPOCH, epoch)).into()
+    }
+}
+
+/// Parse a date time from a content of a tag 0 or tag 1
+pub(crate) fn from_tag_content<T>(tag: u64, content: &DataItem) -> Option<T>
+where
+    T: Timestamp,
+{
+    match (tag, content) {
+        (tag::DATETIME_STRING, DataItem::Text(text_content)) => {
+            T::from_rfc3339(&text_content.full())
+        }
+        (tag::EPOCH, DataItem::Unsigned(_) | DataItem::Signed(_)) => {
+            T::from_unix(i64::try_from(content.as_number()?).ok()?, 0)
+        }
+        (tag::EPOCH, DataItem::Floating(epoch)) => {
+            if !epoch.is_finite() {
+                return None;
+            }
+            let seconds = epoch.floor();
+            #[expect(
+                clippy::cast_possible_truncation,
+                clippy::cast_sign_loss,
+                reason = "fractional part of seconds is always between 0 to 1e9"
+            )]
+            let nanoseconds = ((epoch - seconds) * 1e9).round().min(999_999_999.0) as u32;
+            #[expect(
+                clippy::cast_possible_truncation,
+                reason = "out of range seconds are rejected by date time type"
+            )]
+            T::from_unix(seconds as i64, nanoseconds)
+        }
+        _ => None,
+    }
+}
+
+/// Serde `with` module which serialize a date time as a standard date/time
+/// string (tag 0) and deserialize a date time from either tag 0 or tag 1
+///
+/// # Example
+/// ```rust
+/// # #[cfg(feature = "chrono")]
+/// # {
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// struct Event {
+///     #[serde(with = "cbor_next::datetime::rfc3339")]
+///     created_at: chrono::DateTime<chrono::Utc>,
+/// }
+/// # }
+/// ```
+#[cfg(feature = "serde")]
+pub mod rfc3339 {
+    use serde::{Deserializer, Serialize as _, Serializer};
+
+    use super::Timestamp;
+
+    /// Serialize a date time as a tag 0
+    ///
+    /// # Errors
+    /// If serializer fails to serialize a date time
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Timestamp,
+        S: Serializer,
+    {
+        super::to_string_data_item(value).serialize(serializer)
+    }
+
+    /// Deserialize a date time from either tag 0 or tag 1
+    ///
+    /// # Errors
+    /// If deserializer fails to deserialize a date time
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Timestamp,
+        D: Deserializer<'de>,
+    {
+        super::deserialize(deserializer)
+    }
+}
+
+/// Serde `with` module which serialize a date time as an epoch based date/time
+/// (tag 1) and deserialize a date time from either tag 0 or tag 1
+///
+/// # Example
+/// ```rust
+/// # #[cfg(feature = "time")]
+/// # {
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// struct Event {
+///     #[serde(with = "cbor_next::datetime::epoch")]
+///     created_at: time::OffsetDateTime,
+/// }
+/// # }
+/// ```
+#[cfg(feature = "serde")]
+pub mod epoch {
+    use serde::{Deserializer, Serialize as _, Serializer};
+
+    use super::Timestamp;
+
+    /// Serialize a date time as a tag 1
+    ///
+    /// # Errors
+    /// If serializer fails to serialize a date time
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Timestamp,
+        S: Serializer,
+    {
+        super::to_epoch_data_item(value).serialize(serializer)
+    }
+
+    /// Deserialize a date time from either tag 0 or tag 1
+    ///
+    /// # Errors
+    /// If deserializer fails to deserialize a date time
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Timestamp,
+        D: Deserializer<'de>,
+    {
+        super::deserialize(deserializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: Timestamp,
+    D: serde::Deserializer<'de>,
+{
+    use serde::Deserialize as _;
+    use serde::de::Error as _;
+
+    let (tag, content) = crate::tagged::Tagged::<DataItem>::deserialize(deserializer)?.into_parts();
+    let date_time = match (tag, &content) {
+        (Some(tag), _) => from_tag_content(tag, &content),
+        (None, DataItem::Text(_)) => from_tag_content(tag::DATETIME_STRING, &content),
+        (None, _) => from_tag_content(tag::EPOCH, &content),
+    };
+    date_time.ok_or_else(|| D::Error::custom("invalid date time value"))
+}
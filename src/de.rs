@@ -0,0 +1,1918 @@
+use std::borrow::Cow;
+use std::fmt::Formatter;
+use std::io::Read;
+
+use indexmap::IndexMap;
+use serde::de::value::{BorrowedStrDeserializer, SeqDeserializer};
+use serde::de::{
+    DeserializeOwned, DeserializeSeed, EnumAccess, Error as _, IgnoredAny, IntoDeserializer,
+    MapAccess, SeqAccess, Unexpected, VariantAccess, Visitor,
+};
+use serde::{Deserialize, forward_to_deserialize_any};
+
+use crate::content::{ArrayContent, ByteContent, MapContent, SimpleValue, TagContent, TextContent};
+use crate::data_item::{DataItem, check_depth, check_length, check_size};
+use crate::decode_options::DecodeOptions;
+use crate::error::{Error, Malformed};
+use crate::float16::f16_bits_to_f64;
+use crate::maybe::UNDEFINED_STRUCT_NAME;
+use crate::read::{CborRead, IoReader};
+use crate::ser::SIMPLE_STRUCT_NAME;
+use crate::tagged::{TAG_STRUCT_NAME, Tagged};
+
+/// Name of newtype struct used by [`DataItem`] deserialize implementation.
+/// Deserializer of this crate passes a tag and an undefined value as a map with
+/// a single reserved key when deserializing with this name so they are
+/// preserved instead of being stripped
+const DATA_ITEM_STRUCT_NAME: &str = "\0cbor_next::DataItem";
+
+/// Key of a single entry map used by `serde_json` with an arbitrary precision
+/// to pass a number as its text
+#[cfg(feature = "json-arbitrary-precision")]
+const JSON_NUMBER_TOKEN: &str = "$serde_json::private::Number";
+
+/// Deserialize a value from a data item
+///
+/// # Example
+/// ```rust
+/// use cbor_next::DataItem;
+///
+/// let value: Vec<u64> = cbor_next::from_data_item(DataItem::from(vec![1, 2, 3])).unwrap();
+/// assert_eq!(value, vec![1, 2, 3]);
+/// ```
+///
+/// # Errors
+/// If data item cannot be deserialized into a provided type
+pub fn from_data_item<T>(data_item: DataItem) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    T::deserialize(Deserializer::new(data_item))
+}
+
+/// Deserialize a value from a CBOR encoded bytes
+///
+/// Bytes are read by a [`StreamDeserializer`] without decoding a [`DataItem`]
+/// first, so this function differs from deserializing a decoded data item
+/// with [`from_data_item`] in three ways. A repeated map key is passed to a
+/// deserialized type instead of being rejected, where a struct rejects a
+/// repeated field and a map keeps a last value. A string reference namespace
+/// (tag 256) is not expanded. A trailing byte after a value is an error
+/// instead of being ignored. Decode bytes with [`DataItem::decode`] first when
+/// a behavior of a data item is needed
+///
+/// # Example
+/// ```rust
+/// use std::collections::BTreeMap;
+///
+/// use cbor_next::DataItem;
+///
+/// let value: Vec<u64> = cbor_next::from_bytes(&[0x83, 0x01, 0x02, 0x03]).unwrap();
+/// assert_eq!(value, vec![1, 2, 3]);
+///
+/// let repeated = [0xa2, 0x01, 0x02, 0x01, 0x03];
+/// let map: BTreeMap<u64, u64> = cbor_next::from_bytes(&repeated).unwrap();
+/// assert_eq!(map, BTreeMap::from([(1, 3)]));
+/// assert!(DataItem::decode(&repeated).is_err());
+/// ```
+///
+/// # Errors
+/// If bytes cannot be decoded, contain a trailing byte after a value or cannot
+/// be deserialized into a provided type
+pub fn from_bytes<T>(bytes: &[u8]) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    from_bytes_with_options(bytes, &DeserializerOptions::default())
+}
+
+/// Deserialize a value from a data item using a provided deserialize seed
+///
+/// # Example
+/// ```rust
+/// use cbor_next::DataItem;
+///
+/// let value: u64 =
+///     cbor_next::de::from_data_item_seed(DataItem::from(10), std::marker::PhantomData).unwrap();
+/// assert_eq!(value, 10);
+/// ```
+///
+/// # Errors
+/// If data item cannot be deserialized by a provided seed
+pub fn from_data_item_seed<'de, S>(data_item: DataItem, seed: S) -> Result<S::Value, Error>
+where
+    S: DeserializeSeed<'de>,
+{
+    seed.deserialize(Deserializer::new(data_item))
+}
+
+/// Deserialize a value from a CBOR encoded bytes using a provided deserialize
+/// seed. Bytes are read in same way as [`from_bytes`]
+///
+/// # Example
+/// ```rust
+/// let value: Vec<u64> =
+///     cbor_next::de::from_bytes_seed(&[0x82, 0x01, 0x02], std::marker::PhantomData).unwrap();
+/// assert_eq!(value, vec![1, 2]);
+/// ```
+///
+/// # Errors
+/// If bytes cannot be decoded, contain a trailing byte after a value or cannot
+/// be deserialized by a provided seed
+pub fn from_bytes_seed<'de, S>(bytes: &'de [u8], seed: S) -> Result<S::Value, Error>
+where
+    S: DeserializeSeed<'de>,
+{
+    let mut deserializer = StreamDeserializer::new(bytes);
+    let value = seed.deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(value)
+}
+
+/// Deserialize a value from a data item with provided deserializer options
+///
+/// # Example
+/// ```rust
+/// use cbor_next::DataItem;
+/// use cbor_next::de::DeserializerOptions;
+///
+/// let mut options = DeserializerOptions::default();
+/// options.set_reject_undefined(true);
+/// assert!(
+///     cbor_next::de::from_data_item_with_options::<Option<u64>>(DataItem::Undefined, &options)
+///         .is_err()
+/// );
+/// ```
+///
+/// # Errors
+/// If data item cannot be deserialized into a provided type
+pub fn from_data_item_with_options<T>(
+    data_item: DataItem,
+    options: &DeserializerOptions,
+) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    T::deserialize(Deserializer::with_options(data_item, *options))
+}
+
+/// Deserialize a value from a CBOR encoded bytes with provided deserializer
+/// options. Bytes are read in same way as [`from_bytes`] while limits of decode
+/// options are applied during reading
+///
+/// # Example
+/// ```rust
+/// use cbor_next::de::DeserializerOptions;
+///
+/// let mut options = DeserializerOptions::default();
+/// options.set_reject_undefined(true);
+/// assert!(cbor_next::de::from_bytes_with_options::<Option<u64>>(&[0xf7], &options).is_err());
+/// ```
+///
+/// # Errors
+/// If bytes cannot be decoded, contain a trailing byte after a value or cannot
+/// be deserialized into a provided type
+pub fn from_bytes_with_options<T>(bytes: &[u8], options: &DeserializerOptions) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    let mut deserializer = StreamDeserializer::with_options(bytes, *options);
+    let value = T::deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(value)
+}
+
+/// Deserialize a value from a reader which contains CBOR encoded bytes with
+/// provided deserializer options. Value is deserialized while bytes are read
+/// using a [`StreamDeserializer`] so a whole input is never buffered, and
+/// reading stops with an error as soon as a maximum size of decode options is
+/// exceeded. Reader must not contain any byte after a value
+///
+/// # Example
+/// ```rust
+/// use cbor_next::DecodeOptions;
+/// use cbor_next::de::DeserializerOptions;
+///
+/// let mut decode_options = DecodeOptions::default();
+/// decode_options.set_max_size(3);
+/// let mut options = DeserializerOptions::default();
+/// options.set_decode_options(decode_options);
+/// let reader = std::io::Cursor::new(vec![0x83, 0x01, 0x02, 0x03]);
+/// assert!(cbor_next::de::from_reader_with_options::<Vec<u64>, _>(reader, &options).is_err());
+/// ```
+///
+/// # Errors
+/// If reader fails to read bytes or read bytes cannot be deserialized into a
+/// provided type
+pub fn from_reader_with_options<T, R>(reader: R, options: &DeserializerOptions) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+    R: Read,
+{
+    let mut deserializer = StreamDeserializer::with_options(IoReader::new(reader), *options);
+    let value = T::deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(value)
+}
+
+/// Deserialize a value from a reader which contains CBOR encoded bytes. Value
+/// is deserialized while bytes are read without buffering a whole input
+///
+/// # Example
+/// ```rust
+/// let reader = std::io::Cursor::new(vec![0x83, 0x01, 0x02, 0x03]);
+/// let value: Vec<u64> = cbor_next::from_reader(reader).unwrap();
+/// assert_eq!(value, vec![1, 2, 3]);
+/// ```
+///
+/// # Errors
+/// If reader fails to read bytes or read bytes cannot be deserialized into a
+/// provided type
+pub fn from_reader<T, R>(reader: R) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+    R: Read,
+{
+    from_reader_with_options(reader, &DeserializerOptions::default())
+}
+
+/// Options which control how a value is deserialized from a [`DataItem`]
+#[derive(Default, Clone, Copy)]
+pub struct DeserializerOptions {
+    reject_undefined: bool,
+    decode_options: DecodeOptions,
+}
+
+impl DeserializerOptions {
+    /// Set whether undefined should be rejected instead of being treated as a
+    /// null
+    pub fn set_reject_undefined(&mut self, reject_undefined: bool) -> &mut Self {
+        self.reject_undefined = reject_undefined;
+        self
+    }
+
+    /// Get whether undefined is rejected instead of being treated as a null
+    #[must_use]
+    pub fn is_reject_undefined(&self) -> bool {
+        self.reject_undefined
+    }
+
+    /// Set decode options whose resource limits are applied when decoding a
+    /// CBOR bytes before deserializing a value
+    pub fn set_decode_options(&mut self, decode_options: DecodeOptions) -> &mut Self {
+        self.decode_options = decode_options;
+        self
+    }
+
+    /// Get decode options
+    #[must_use]
+    pub fn decode_options(&self) -> &DecodeOptions {
+        &self.decode_options
+    }
+}
+
+/// Deserializer which deserialize a value from a [`DataItem`]
+pub struct Deserializer {
+    data_item: DataItem,
+    options: DeserializerOptions,
+}
+
+impl Deserializer {
+    /// Create a new deserializer from a data item
+    #[must_use]
+    pub fn new(data_item: DataItem) -> Self {
+        Self::with_options(data_item, DeserializerOptions::default())
+    }
+
+    /// Create a new deserializer from a data item with provided options
+    #[must_use]
+    pub fn with_options(data_item: DataItem, options: DeserializerOptions) -> Self {
+        Self { data_item, options }
+    }
+
+    /// Deserialize a content of a data item after removing all of its tag
+    fn deserialize_untagged<'de, V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let mut data_item = self.data_item;
+        while let DataItem::Tag(tag_content) = data_item {
+            data_item = tag_content.into_parts().1;
+        }
+        serde::Deserializer::deserialize_any(
+            Deserializer::with_options(data_item, self.options),
+            visitor,
+        )
+    }
+}
+
+/// Implement deserialize methods which expect a specific type by deserializing
+/// a content of a data item after removing its tag
+macro_rules! deserialize_untagged {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                self.deserialize_untagged(visitor)
+            }
+        )*
+    };
+}
+
+/// Implement deserialize methods whose extra arguments are ignored by
+/// deserializing a content of a data item after removing its tag
+macro_rules! deserialize_untagged_compound {
+    () => {
+        fn deserialize_unit_struct<V>(
+            self,
+            _name: &'static str,
+            visitor: V,
+        ) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            self.deserialize_untagged(visitor)
+        }
+
+        fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            self.deserialize_untagged(visitor)
+        }
+
+        fn deserialize_tuple_struct<V>(
+            self,
+            _name: &'static str,
+            _len: usize,
+            visitor: V,
+        ) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            self.deserialize_untagged(visitor)
+        }
+
+        fn deserialize_struct<V>(
+            self,
+            _name: &'static str,
+            _fields: &'static [&'static str],
+            visitor: V,
+        ) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            self.deserialize_untagged(visitor)
+        }
+    };
+}
+
+impl<'de> serde::Deserializer<'de> for Deserializer {
+    type Error = Error;
+
+    deserialize_untagged! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64 deserialize_f32
+        deserialize_f64 deserialize_char deserialize_str deserialize_string deserialize_bytes
+        deserialize_byte_buf deserialize_unit deserialize_map deserialize_identifier
+        deserialize_ignored_any
+    }
+
+    deserialize_untagged_compound!();
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.data_item {
+            DataItem::Unsigned(number) => visitor.visit_u64(number),
+            DataItem::Signed(_) => {
+                let number = self
+                    .data_item
+                    .as_signed()
+                    .expect("signed data item is always a number");
+                match i64::try_from(number) {
+                    Ok(i64_number) => visitor.visit_i64(i64_number),
+                    Err(_) => visitor.visit_i128(number),
+                }
+            }
+            DataItem::Byte(byte_content) => {
+                visitor.visit_byte_buf(byte_content.full().into_owned())
+            }
+            DataItem::Text(text_content) => visitor.visit_string(text_content.full().into_owned()),
+            DataItem::Array(array_content) => {
+                visitor.visit_seq(ArrayDeserializer::new(array_content, self.options))
+            }
+            DataItem::Map(map_content) => {
+                visitor.visit_map(MapDeserializer::new(map_content, self.options))
+            }
+            DataItem::Tag(tag_content) => {
+                Deserializer::with_options(tag_content.into_parts().1, self.options)
+                    .deserialize_any(visitor)
+            }
+            DataItem::Boolean(bool_val) => visitor.visit_bool(bool_val),
+            DataItem::Undefined if self.options.reject_undefined => {
+                Err(Error::invalid_type(
+                    Unexpected::Other("undefined"),
+                    &visitor,
+                ))
+            }
+            DataItem::Null | DataItem::Undefined => visitor.visit_unit(),
+            DataItem::Floating(number) => visitor.visit_f64(number),
+            DataItem::GenericSimple(simple_value) => visitor.visit_u8(simple_value.value()),
+        }
+    }
+
+    fn deserialize_i128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match (&self.data_item, self.data_item.as_i128()) {
+            (DataItem::Tag(_), Some(number)) => visitor.visit_i128(number),
+            _ => self.deserialize_untagged(visitor),
+        }
+    }
+
+    fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match (&self.data_item, self.data_item.as_u128()) {
+            (DataItem::Tag(_), Some(number)) => visitor.visit_u128(number),
+            _ => self.deserialize_untagged(visitor),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.data_item {
+            DataItem::Undefined if self.options.reject_undefined => {
+                Err(Error::invalid_type(
+                    Unexpected::Other("undefined"),
+                    &visitor,
+                ))
+            }
+            DataItem::Null | DataItem::Undefined => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        if name == DATA_ITEM_STRUCT_NAME {
+            return match self.data_item {
+                DataItem::Tag(tag_content) => {
+                    let (number, content) = tag_content.into_parts();
+                    let tagged_content = vec![DataItem::Unsigned(number), content];
+                    visitor.visit_map(MapDeserializer::new(
+                        MapContent::from_iter([(TAG_STRUCT_NAME, tagged_content)]),
+                        self.options,
+                    ))
+                }
+                DataItem::Undefined if !self.options.reject_undefined => {
+                    visitor.visit_map(MapDeserializer::new(
+                        MapContent::from_iter([(UNDEFINED_STRUCT_NAME, DataItem::Null)]),
+                        self.options,
+                    ))
+                }
+                DataItem::GenericSimple(simple_value) => {
+                    visitor.visit_map(MapDeserializer::new(
+                        MapContent::from_iter([(SIMPLE_STRUCT_NAME, simple_value.value())]),
+                        self.options,
+                    ))
+                }
+                _ => self.deserialize_any(visitor),
+            };
+        }
+        if name == SIMPLE_STRUCT_NAME {
+            return match self.data_item.as_simple_value() {
+                Some(simple_value) => visitor.visit_u8(simple_value.value()),
+                None => self.deserialize_untagged(visitor),
+            };
+        }
+        if name == UNDEFINED_STRUCT_NAME {
+            return match self.data_item {
+                DataItem::Undefined => visitor.visit_unit(),
+                DataItem::Null => visitor.visit_none(),
+                _ => visitor.visit_some(self),
+            };
+        }
+        if name != TAG_STRUCT_NAME {
+            return visitor.visit_newtype_struct(self);
+        }
+        let tagged_content = match self.data_item {
+            DataItem::Tag(tag_content) => {
+                let (number, content) = tag_content.into_parts();
+                vec![DataItem::Unsigned(number), content]
+            }
+            data_item => vec![DataItem::Null, data_item],
+        };
+        visitor.visit_newtype_struct(Deserializer::with_options(
+            tagged_content.into(),
+            self.options,
+        ))
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.data_item {
+            DataItem::Byte(byte_content) => {
+                let mut deserializer =
+                    SeqDeserializer::<_, Error>::new(byte_content.full().into_owned().into_iter());
+                let value = visitor.visit_seq(&mut deserializer)?;
+                deserializer.end()?;
+                Ok(value)
+            }
+            DataItem::Tag(tag_content) => {
+                Deserializer::with_options(tag_content.into_parts().1, self.options)
+                    .deserialize_seq(visitor)
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.data_item {
+            DataItem::Text(_) => {
+                visitor.visit_enum(EnumDeserializer {
+                    variant: self.data_item,
+                    value: None,
+                    options: self.options,
+                })
+            }
+            DataItem::Map(map_content) if map_content.len() == 1 => {
+                let (variant, value) = map_content
+                    .into_iter()
+                    .next()
+                    .expect("map with single entry should have first entry");
+                visitor.visit_enum(EnumDeserializer {
+                    variant,
+                    value: Some(value),
+                    options: self.options,
+                })
+            }
+            DataItem::Tag(tag_content) => {
+                Deserializer::with_options(tag_content.into_parts().1, self.options)
+                    .deserialize_enum(name, variants, visitor)
+            }
+            _ => {
+                Err(Error::invalid_type(
+                    Unexpected::Other(&format!("{:?}", self.data_item)),
+                    &"string or map with single key",
+                ))
+            }
+        }
+    }
+}
+
+/// Deserializer which deserializes a value directly from a CBOR encoded bytes
+/// without decoding a [`DataItem`] first
+///
+/// Byte string and text string are borrowed when reading from a slice. A type
+/// which expects a specific value as well as `deserialize_any` reads a content
+/// of a tag after removing the tag, a tag is only kept by [`Tagged`] and
+/// [`DataItem`]. Depth,
+/// length and size limits of decode options are applied while reading, but a
+/// duplicate map key is not detected and a string reference is not expanded
+/// since no data item is built
+///
+/// # Example
+/// ```rust
+/// use cbor_next::de::StreamDeserializer;
+/// use serde::Deserialize as _;
+///
+/// let bytes = [0x82, 0x61, 0x61, 0x62, 0x62, 0x63];
+/// let mut deserializer = StreamDeserializer::new(bytes.as_slice());
+/// let value = <(&str, &str)>::deserialize(&mut deserializer).unwrap();
+/// assert_eq!(value, ("a", "bc"));
+/// deserializer.end().unwrap();
+/// ```
+pub struct StreamDeserializer<R> {
+    reader: R,
+    options: DeserializerOptions,
+    depth: usize,
+    size: usize,
+}
+
+impl<R> StreamDeserializer<R> {
+    /// Create a new deserializer which reads from a provided reader
+    #[must_use]
+    pub fn new(reader: R) -> Self {
+        Self::with_options(reader, DeserializerOptions::default())
+    }
+
+    /// Create a new deserializer which reads from a provided reader with
+    /// provided options
+    #[must_use]
+    pub fn with_options(reader: R, options: DeserializerOptions) -> Self {
+        Self {
+            reader,
+            options,
+            depth: 0,
+            size: 0,
+        }
+    }
+
+    /// Get back an underlying reader
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<'de, R> StreamDeserializer<R>
+where
+    R: CborRead<'de>,
+{
+    /// Check that a reader doesn't contain any byte after a deserialized value
+    ///
+    /// # Errors
+    /// If reader fails to read a byte or contains a trailing byte
+    pub fn end(&mut self) -> Result<(), Error> {
+        match self.reader.peek_byte()? {
+            Some(_) => Err(Error::custom("trailing bytes after a value")),
+            None => Ok(()),
+        }
+    }
+
+    fn consume(&mut self, count: u64) -> Result<(), Error> {
+        self.size = self
+            .size
+            .saturating_add(usize::try_from(count).unwrap_or(usize::MAX));
+        check_size(self.options.decode_options(), self.size)
+    }
+
+    fn next_byte(&mut self) -> Result<u8, Error> {
+        let byte = self.reader.read_byte()?.ok_or(Error::Incomplete)?;
+        self.consume(1)?;
+        Ok(byte)
+    }
+
+    fn next_bytes(&mut self, length: u64) -> Result<Cow<'de, [u8]>, Error> {
+        check_length(self.options.decode_options(), length)?;
+        self.consume(length)?;
+        self.reader.read_bytes(length)
+    }
+
+    fn is_break(&mut self) -> Result<bool, Error> {
+        Ok(self
+            .reader
+            .peek_byte()?
+            .ok_or(Error::IncompleteIndefinite)?
+            == 255)
+    }
+
+    fn enter(&mut self) -> Result<(), Error> {
+        self.depth += 1;
+        check_depth(self.options.decode_options(), self.depth)
+    }
+
+    fn leave(&mut self) {
+        self.depth -= 1;
+    }
+
+    /// Read an initial byte along with an argument of a header. Argument is
+    /// `None` for an indefinite length
+    fn read_header(&mut self) -> Result<(u8, Option<u64>), Error> {
+        let initial_info = self.next_byte()?;
+        let additional = initial_info & 0b0001_1111;
+        let argument = match additional {
+            0..=23 => Some(u64::from(additional)),
+            24..=27 => {
+                let length = 1 << (additional - 24);
+                let bytes = self.reader.read_bytes(length)?;
+                self.consume(length)?;
+                Some(
+                    bytes
+                        .iter()
+                        .fold(0, |number, byte| (number << 8) | u64::from(*byte)),
+                )
+            }
+            28..=30 => {
+                return Err(Error::NotWellFormed(Malformed::ReservedAdditionalInfo {
+                    additional,
+                }));
+            }
+            _ => None,
+        };
+        Ok((initial_info, argument))
+    }
+
+    /// Read a header after skipping all tags. Every skipped tag is counted
+    /// towards a depth of a deserializer
+    fn read_untagged_header(&mut self) -> Result<(u8, Option<u64>), Error> {
+        loop {
+            let (initial_info, argument) = self.read_header()?;
+            if initial_info >> 5 != 6 || argument.is_none() {
+                return Ok((initial_info, argument));
+            }
+            self.enter()?;
+        }
+    }
+
+    /// Read a byte string or text string of a provided length. All chunks of an
+    /// indefinite length string are joined
+    fn read_string(
+        &mut self,
+        major_type: u8,
+        length: Option<u64>,
+    ) -> Result<Cow<'de, [u8]>, Error> {
+        if let Some(length) = length {
+            return self.next_bytes(length);
+        }
+        let mut bytes = vec![];
+        let mut chunk_count = 0u64;
+        while !self.is_break()? {
+            let (initial_info, argument) = self.read_header()?;
+            if initial_info >> 5 != major_type {
+                return Err(Error::NotWellFormed(Malformed::InvalidChunk {
+                    expected: major_type,
+                    found: initial_info >> 5,
+                }));
+            }
+            let length = argument.ok_or(Error::NotWellFormed(Malformed::IndefiniteLength {
+                major_type,
+            }))?;
+            chunk_count += 1;
+            check_length(self.options.decode_options(), chunk_count)?;
+            check_length(
+                self.options.decode_options(),
+                (bytes.len() as u64).saturating_add(length),
+            )?;
+            bytes.extend_from_slice(&self.next_bytes(length)?);
+        }
+        self.next_byte()?;
+        Ok(Cow::Owned(bytes))
+    }
+
+    fn read_text(&mut self, length: Option<u64>) -> Result<Cow<'de, str>, Error> {
+        match self.read_string(3, length)? {
+            Cow::Borrowed(bytes) => {
+                Ok(Cow::Borrowed(std::str::from_utf8(bytes).map_err(
+                    |error| Error::NotWellFormed(Malformed::InvalidUtf8(error)),
+                )?))
+            }
+            Cow::Owned(bytes) => {
+                Ok(Cow::Owned(String::from_utf8(bytes).map_err(|error| {
+                    Error::NotWellFormed(Malformed::InvalidUtf8(error.utf8_error()))
+                })?))
+            }
+        }
+    }
+
+    /// Visit a data item whose header is already read
+    fn visit_header<V>(
+        &mut self,
+        initial_info: u8,
+        argument: Option<u64>,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let major_type = initial_info >> 5;
+        let additional = initial_info & 0b0001_1111;
+        match (major_type, argument) {
+            (0, Some(number)) => visitor.visit_u64(number),
+            (1, Some(number)) => {
+                let number = -1 - i128::from(number);
+                match i64::try_from(number) {
+                    Ok(i64_number) => visitor.visit_i64(i64_number),
+                    Err(_) => visitor.visit_i128(number),
+                }
+            }
+            (2, length) => {
+                match self.read_string(2, length)? {
+                    Cow::Borrowed(bytes) => visitor.visit_borrowed_bytes(bytes),
+                    Cow::Owned(bytes) => visitor.visit_byte_buf(bytes),
+                }
+            }
+            (3, length) => {
+                match self.read_text(length)? {
+                    Cow::Borrowed(text) => visitor.visit_borrowed_str(text),
+                    Cow::Owned(text) => visitor.visit_string(text),
+                }
+            }
+            (4, length) => {
+                self.enter()?;
+                if let Some(length) = length {
+                    check_length(self.options.decode_options(), length)?;
+                }
+                let mut access = StreamSeqAccess {
+                    deserializer: self,
+                    remaining: length,
+                    count: 0,
+                };
+                let value = visitor.visit_seq(&mut access)?;
+                access.finish()?;
+                self.leave();
+                Ok(value)
+            }
+            (5, length) => {
+                self.enter()?;
+                if let Some(length) = length {
+                    check_length(self.options.decode_options(), length)?;
+                }
+                let mut access = StreamMapAccess {
+                    deserializer: self,
+                    remaining: length,
+                    count: 0,
+                };
+                let value = visitor.visit_map(&mut access)?;
+                access.finish()?;
+                self.leave();
+                Ok(value)
+            }
+            (7, None) => Err(Error::InvalidBreakStop),
+            (7, Some(argument)) => {
+                match additional {
+                    20 => visitor.visit_bool(false),
+                    21 => visitor.visit_bool(true),
+                    23 if self.options.reject_undefined => {
+                        Err(Error::invalid_type(
+                            Unexpected::Other("undefined"),
+                            &visitor,
+                        ))
+                    }
+                    22 | 23 => visitor.visit_unit(),
+                    25 => visitor.visit_f64(f16_bits_to_f64(u16::try_from(argument)?)),
+                    26 => visitor.visit_f64(f64::from(f32::from_bits(u32::try_from(argument)?))),
+                    27 => visitor.visit_f64(f64::from_bits(argument)),
+                    _ => {
+                        let value =
+                            simple_value(additional, argument)?.ok_or(Error::InvalidSimple)?;
+                        visitor.visit_u8(value)
+                    }
+                }
+            }
+            (major_type, _) => {
+                Err(Error::NotWellFormed(Malformed::IndefiniteLength {
+                    major_type,
+                }))
+            }
+        }
+    }
+
+    /// Deserialize a content of a data item after removing all of its tag
+    fn deserialize_untagged<V>(&mut self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let depth = self.depth;
+        let (initial_info, argument) = self.read_untagged_header()?;
+        let value = self.visit_header(initial_info, argument, visitor)?;
+        self.depth = depth;
+        Ok(value)
+    }
+
+    /// Deserialize a bignum (tag 2 or tag 3) or any other data item after
+    /// removing its tag
+    fn deserialize_big_integer<V>(&mut self, visitor: V, is_signed: bool) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let depth = self.depth;
+        let mut bignum_tag = None;
+        let (initial_info, argument) = loop {
+            let (initial_info, argument) = self.read_header()?;
+            match (initial_info >> 5, argument) {
+                (6, Some(number)) => {
+                    self.enter()?;
+                    bignum_tag = Some(number);
+                }
+                _ => break (initial_info, argument),
+            }
+        };
+        let value = match bignum_tag {
+            Some(number @ (2 | 3)) if initial_info >> 5 == 2 => {
+                let bytes = self.read_string(2, argument)?;
+                let magnitude =
+                    bytes
+                        .iter()
+                        .skip_while(|byte| **byte == 0)
+                        .try_fold(0u128, |number, byte| {
+                            number
+                                .checked_mul(256)
+                                .map(|number| number | u128::from(*byte))
+                        });
+                let signed_magnitude =
+                    magnitude.and_then(|magnitude| i128::try_from(magnitude).ok());
+                match (number, is_signed, magnitude, signed_magnitude) {
+                    (2, false, Some(magnitude), _) => visitor.visit_u128(magnitude),
+                    (2, true, _, Some(magnitude)) => visitor.visit_i128(magnitude),
+                    (3, true, _, Some(magnitude)) => visitor.visit_i128(-1 - magnitude),
+                    _ => visitor.visit_bytes(&bytes),
+                }
+            }
+            _ => self.visit_header(initial_info, argument, visitor),
+        }?;
+        self.depth = depth;
+        Ok(value)
+    }
+}
+
+impl<'de, R> serde::Deserializer<'de> for &mut StreamDeserializer<R>
+where
+    R: CborRead<'de>,
+{
+    type Error = Error;
+
+    deserialize_untagged! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64 deserialize_f32
+        deserialize_f64 deserialize_char deserialize_str deserialize_string deserialize_bytes
+        deserialize_byte_buf deserialize_unit deserialize_map deserialize_identifier
+        deserialize_ignored_any
+    }
+
+    deserialize_untagged_compound!();
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_untagged(visitor)
+    }
+
+    fn deserialize_i128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_big_integer(visitor, true)
+    }
+
+    fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_big_integer(visitor, false)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.reader.peek_byte()? {
+            Some(0xf7) if self.options.reject_undefined => {
+                Err(Error::invalid_type(
+                    Unexpected::Other("undefined"),
+                    &visitor,
+                ))
+            }
+            Some(0xf6 | 0xf7) => {
+                self.next_byte()?;
+                visitor.visit_none()
+            }
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let peeked = self.reader.peek_byte()?;
+        if name == DATA_ITEM_STRUCT_NAME {
+            return match peeked {
+                Some(initial_info) if initial_info >> 5 == 6 => {
+                    let (_, argument) = self.read_header()?;
+                    let number =
+                        argument.ok_or(Error::NotWellFormed(Malformed::IndefiniteLength {
+                            major_type: 6,
+                        }))?;
+                    self.enter()?;
+                    let value = visitor.visit_map(StreamTagMapAccess {
+                        deserializer: &mut *self,
+                        number: Some(number),
+                    })?;
+                    self.leave();
+                    Ok(value)
+                }
+                Some(0xf7) if !self.options.reject_undefined => {
+                    self.next_byte()?;
+                    visitor.visit_map(MapDeserializer::new(
+                        MapContent::from_iter([(UNDEFINED_STRUCT_NAME, DataItem::Null)]),
+                        self.options,
+                    ))
+                }
+                Some(0xe0..=0xf3 | 0xf8) => {
+                    let (initial_info, argument) = self.read_header()?;
+                    let value =
+                        simple_value(initial_info & 0b0001_1111, argument.unwrap_or_default())?
+                            .ok_or(Error::InvalidSimple)?;
+                    visitor.visit_map(MapDeserializer::new(
+                        MapContent::from_iter([(SIMPLE_STRUCT_NAME, value)]),
+                        self.options,
+                    ))
+                }
+                _ => self.deserialize_any(visitor),
+            };
+        }
+        if name == SIMPLE_STRUCT_NAME {
+            return match peeked {
+                Some(0xe0..=0xf8) => {
+                    let (initial_info, argument) = self.read_header()?;
+                    let additional = initial_info & 0b0001_1111;
+                    let value = simple_value(additional, argument.unwrap_or_default())?
+                        .unwrap_or(additional);
+                    visitor.visit_u8(value)
+                }
+                _ => self.deserialize_untagged(visitor),
+            };
+        }
+        if name == UNDEFINED_STRUCT_NAME {
+            return match peeked {
+                Some(0xf7) => {
+                    self.next_byte()?;
+                    visitor.visit_unit()
+                }
+                Some(0xf6) => {
+                    self.next_byte()?;
+                    visitor.visit_none()
+                }
+                _ => visitor.visit_some(self),
+            };
+        }
+        if name != TAG_STRUCT_NAME {
+            return visitor.visit_newtype_struct(self);
+        }
+        let number = match peeked {
+            Some(initial_info) if initial_info >> 5 == 6 => {
+                let (_, argument) = self.read_header()?;
+                Some(
+                    argument.ok_or(Error::NotWellFormed(Malformed::IndefiniteLength {
+                        major_type: 6,
+                    }))?,
+                )
+            }
+            _ => None,
+        };
+        let depth = self.depth;
+        if number.is_some() {
+            self.enter()?;
+        }
+        let value = visitor.visit_newtype_struct(StreamTagDeserializer {
+            deserializer: &mut *self,
+            number,
+        })?;
+        self.depth = depth;
+        Ok(value)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let depth = self.depth;
+        let (initial_info, argument) = self.read_untagged_header()?;
+        let value = if initial_info >> 5 == 2 {
+            let bytes = self.read_string(2, argument)?;
+            let mut deserializer = SeqDeserializer::<_, Error>::new(bytes.iter().copied());
+            let value = visitor.visit_seq(&mut deserializer)?;
+            deserializer.end()?;
+            value
+        } else {
+            self.visit_header(initial_info, argument, visitor)?
+        };
+        self.depth = depth;
+        Ok(value)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let depth = self.depth;
+        let (initial_info, argument) = self.read_untagged_header()?;
+        let value = match (initial_info >> 5, argument) {
+            (3, length) => {
+                let variant = self.read_text(length)?.into_owned();
+                visitor.visit_enum(IntoDeserializer::<Error>::into_deserializer(variant))?
+            }
+            (5, Some(1) | None) => {
+                self.enter()?;
+                let value = visitor.visit_enum(StreamEnumAccess {
+                    deserializer: &mut *self,
+                })?;
+                if argument.is_none() {
+                    if !self.is_break()? {
+                        return Err(Error::invalid_length(2, &"map with single key"));
+                    }
+                    self.next_byte()?;
+                }
+                self.leave();
+                value
+            }
+            (major_type, _) => {
+                return Err(Error::invalid_type(
+                    Unexpected::Other(&format!("data item of major type {major_type}")),
+                    &"string or map with single key",
+                ));
+            }
+        };
+        self.depth = depth;
+        Ok(value)
+    }
+}
+
+struct StreamSeqAccess<'a, R> {
+    deserializer: &'a mut StreamDeserializer<R>,
+    remaining: Option<u64>,
+    count: u64,
+}
+
+impl<'de, R> StreamSeqAccess<'_, R>
+where
+    R: CborRead<'de>,
+{
+    /// Skip all elements which are not read by a visitor along with a break
+    /// stop of an indefinite length array
+    fn finish(&mut self) -> Result<(), Error> {
+        while self.next_element::<IgnoredAny>()?.is_some() {}
+        if self.remaining.is_none() {
+            self.deserializer.next_byte()?;
+        }
+        Ok(())
+    }
+}
+
+impl<'de, R> SeqAccess<'de> for StreamSeqAccess<'_, R>
+where
+    R: CborRead<'de>,
+{
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match &mut self.remaining {
+            Some(0) => return Ok(None),
+            Some(remaining) => *remaining -= 1,
+            None => {
+                if self.deserializer.is_break()? {
+                    return Ok(None);
+                }
+                self.count += 1;
+                check_length(self.deserializer.options.decode_options(), self.count)?;
+            }
+        }
+        seed.deserialize(&mut *self.deserializer).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.remaining
+            .and_then(|remaining| usize::try_from(remaining).ok())
+    }
+}
+
+struct StreamMapAccess<'a, R> {
+    deserializer: &'a mut StreamDeserializer<R>,
+    remaining: Option<u64>,
+    count: u64,
+}
+
+impl<'de, R> StreamMapAccess<'_, R>
+where
+    R: CborRead<'de>,
+{
+    /// Skip all entries which are not read by a visitor along with a break stop
+    /// of an indefinite length map
+    fn finish(&mut self) -> Result<(), Error> {
+        while self.next_entry::<IgnoredAny, IgnoredAny>()?.is_some() {}
+        if self.remaining.is_none() {
+            self.deserializer.next_byte()?;
+        }
+        Ok(())
+    }
+}
+
+impl<'de, R> MapAccess<'de> for StreamMapAccess<'_, R>
+where
+    R: CborRead<'de>,
+{
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match &mut self.remaining {
+            Some(0) => return Ok(None),
+            Some(remaining) => *remaining -= 1,
+            None => {
+                if self.deserializer.is_break()? {
+                    return Ok(None);
+                }
+                self.count += 1;
+                check_length(self.deserializer.options.decode_options(), self.count)?;
+            }
+        }
+        seed.deserialize(&mut *self.deserializer).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        seed.deserialize(&mut *self.deserializer)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.remaining
+            .and_then(|remaining| usize::try_from(remaining).ok())
+    }
+}
+
+/// Access of a tag as a tuple of tag number and content. Tag number is `None`
+/// when an untagged data item is deserialized as a [`Tagged`]
+struct StreamTagAccess<'a, R> {
+    deserializer: &'a mut StreamDeserializer<R>,
+    number: Option<u64>,
+    index: u8,
+}
+
+impl<'de, R> StreamTagAccess<'_, R>
+where
+    R: CborRead<'de>,
+{
+    /// Skip a content of a tag when it is not read by a visitor
+    fn finish(&mut self) -> Result<(), Error> {
+        while self.next_element::<IgnoredAny>()?.is_some() {}
+        Ok(())
+    }
+}
+
+impl<'de, R> SeqAccess<'de> for StreamTagAccess<'_, R>
+where
+    R: CborRead<'de>,
+{
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        self.index = self.index.saturating_add(1);
+        match self.index {
+            1 => {
+                let number = self.number.map_or(DataItem::Null, DataItem::Unsigned);
+                seed.deserialize(Deserializer::with_options(
+                    number,
+                    self.deserializer.options,
+                ))
+                .map(Some)
+            }
+            2 => seed.deserialize(&mut *self.deserializer).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(usize::from(2u8.saturating_sub(self.index)))
+    }
+}
+
+/// Deserializer of a content of [`Tagged`] newtype struct
+struct StreamTagDeserializer<'a, R> {
+    deserializer: &'a mut StreamDeserializer<R>,
+    number: Option<u64>,
+}
+
+impl<'de, R> serde::Deserializer<'de> for StreamTagDeserializer<'_, R>
+where
+    R: CborRead<'de>,
+{
+    type Error = Error;
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let mut access = StreamTagAccess {
+            deserializer: self.deserializer,
+            number: self.number,
+            index: 0,
+        };
+        let value = visitor.visit_seq(&mut access)?;
+        access.finish()?;
+        Ok(value)
+    }
+}
+
+/// Access of a tag as a map with a single reserved key which is used when
+/// deserializing a [`DataItem`]
+struct StreamTagMapAccess<'a, R> {
+    deserializer: &'a mut StreamDeserializer<R>,
+    number: Option<u64>,
+}
+
+impl<'de, R> MapAccess<'de> for StreamTagMapAccess<'_, R>
+where
+    R: CborRead<'de>,
+{
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        if self.number.is_none() {
+            return Ok(None);
+        }
+        seed.deserialize(BorrowedStrDeserializer::<Error>::new(TAG_STRUCT_NAME))
+            .map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let number = self
+            .number
+            .take()
+            .ok_or_else(|| Error::custom("next value called before next key"))?;
+        seed.deserialize(StreamTagDeserializer {
+            deserializer: &mut *self.deserializer,
+            number: Some(number),
+        })
+    }
+}
+
+struct StreamEnumAccess<'a, R> {
+    deserializer: &'a mut StreamDeserializer<R>,
+}
+
+impl<'de, R> EnumAccess<'de> for StreamEnumAccess<'_, R>
+where
+    R: CborRead<'de>,
+{
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(&mut *self.deserializer)?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de, R> VariantAccess<'de> for StreamEnumAccess<'_, R>
+where
+    R: CborRead<'de>,
+{
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        <()>::deserialize(self.deserializer)
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self.deserializer)
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        serde::Deserializer::deserialize_seq(self.deserializer, visitor)
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        serde::Deserializer::deserialize_map(self.deserializer, visitor)
+    }
+}
+
+struct ArrayDeserializer {
+    iter: std::vec::IntoIter<DataItem>,
+    options: DeserializerOptions,
+}
+
+impl ArrayDeserializer {
+    fn new(array: ArrayContent, options: DeserializerOptions) -> Self {
+        Self {
+            iter: array.into_iter(),
+            options,
+        }
+    }
+}
+
+impl<'de> SeqAccess<'de> for ArrayDeserializer {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some(data_item) => {
+                seed.deserialize(Deserializer::with_options(data_item, self.options))
+                    .map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+struct MapDeserializer {
+    iter: indexmap::map::IntoIter<DataItem, DataItem>,
+    value: Option<DataItem>,
+    options: DeserializerOptions,
+}
+
+impl MapDeserializer {
+    fn new(map: MapContent, options: DeserializerOptions) -> Self {
+        Self {
+            iter: map.into_iter(),
+            value: None,
+            options,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for MapDeserializer {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(Deserializer::with_options(key, self.options))
+                    .map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| Error::custom("next value called before next key"))?;
+        seed.deserialize(Deserializer::with_options(value, self.options))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+struct EnumDeserializer {
+    variant: DataItem,
+    value: Option<DataItem>,
+    options: DeserializerOptions,
+}
+
+impl<'de> EnumAccess<'de> for EnumDeserializer {
+    type Error = Error;
+    type Variant = VariantDeserializer;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(Deserializer::with_options(self.variant, self.options))?;
+        Ok((
+            variant,
+            VariantDeserializer {
+                value: self.value,
+                options: self.options,
+            },
+        ))
+    }
+}
+
+struct VariantDeserializer {
+    value: Option<DataItem>,
+    options: DeserializerOptions,
+}
+
+impl<'de> VariantAccess<'de> for VariantDeserializer {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        match self.value {
+            None | Some(DataItem::Null) => Ok(()),
+            Some(data_item) => {
+                Err(Error::invalid_type(
+                    Unexpected::Other(&format!("{data_item:?}")),
+                    &"unit variant",
+                ))
+            }
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.value {
+            Some(data_item) => {
+                seed.deserialize(Deserializer::with_options(data_item, self.options))
+            }
+            None => {
+                Err(Error::invalid_type(
+                    Unexpected::UnitVariant,
+                    &"newtype variant",
+                ))
+            }
+        }
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Some(data_item) => {
+                serde::Deserializer::deserialize_seq(
+                    Deserializer::with_options(data_item, self.options),
+                    visitor,
+                )
+            }
+            None => {
+                Err(Error::invalid_type(
+                    Unexpected::UnitVariant,
+                    &"tuple variant",
+                ))
+            }
+        }
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Some(data_item) => {
+                serde::Deserializer::deserialize_map(
+                    Deserializer::with_options(data_item, self.options),
+                    visitor,
+                )
+            }
+            None => {
+                Err(Error::invalid_type(
+                    Unexpected::UnitVariant,
+                    &"struct variant",
+                ))
+            }
+        }
+    }
+}
+
+struct DataItemVisitor;
+
+impl<'de> Visitor<'de> for DataItemVisitor {
+    type Value = DataItem;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str("any valid CBOR data item")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v.into())
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v.into())
+    }
+
+    fn visit_i128<E>(self, v: i128) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v.into())
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v.into())
+    }
+
+    fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v.into())
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v.into())
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v.into())
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v.into())
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(v.into())
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(DataItem::Byte(v.into()))
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(DataItem::Null)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        DataItem::deserialize(deserializer)
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(DataItem::Null)
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut array = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(item) = seq.next_element::<DataItem>()? {
+            array.push(item);
+        }
+        Ok(ArrayContent::from(array).into())
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut index_map = IndexMap::with_capacity(map.size_hint().unwrap_or_default());
+        let first_key = map.next_key::<DataItem>()?;
+        if let Some(DataItem::Text(text_content)) = &first_key {
+            match text_content.full().as_ref() {
+                TAG_STRUCT_NAME => {
+                    let (number, content) = map.next_value::<(u64, DataItem)>()?;
+                    return Ok(TagContent::from((number, content)).into());
+                }
+                UNDEFINED_STRUCT_NAME => {
+                    map.next_value::<DataItem>()?;
+                    return Ok(DataItem::Undefined);
+                }
+                SIMPLE_STRUCT_NAME => {
+                    return Ok(map.next_value::<SimpleValue>()?.into());
+                }
+                #[cfg(feature = "json-arbitrary-precision")]
+                JSON_NUMBER_TOKEN => {
+                    let number = map
+                        .next_value::<String>()?
+                        .parse::<serde_json::Number>()
+                        .map_err(A::Error::custom)?;
+                    return Ok(serde_json::Value::Number(number).into());
+                }
+                _ => {}
+            }
+        }
+        if let Some(key) = first_key {
+            index_map.insert(key, map.next_value::<DataItem>()?);
+        }
+        while let Some((key, value)) = map.next_entry::<DataItem, DataItem>()? {
+            index_map.insert(key, value);
+        }
+        Ok(MapContent::from(index_map).into())
+    }
+}
+
+impl<'de> Deserialize<'de> for DataItem {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct(DATA_ITEM_STRUCT_NAME, DataItemVisitor)
+    }
+}
+
+/// Read a value of a simple value header. Returns `None` for a boolean, null,
+/// undefined and floating point number
+fn simple_value(additional: u8, argument: u64) -> Result<Option<u8>, Error> {
+    match (additional, u8::try_from(argument)) {
+        (0..=19, Ok(value)) => Ok(Some(value)),
+        (24, Ok(value)) if value >= 32 => Ok(Some(value)),
+        (24, _) => Err(Error::InvalidSimple),
+        _ => Ok(None),
+    }
+}
+
+/// Create an invalid type error for a data item which is not of an expected
+/// type
+fn unexpected_data_item<E>(data_item: &DataItem, expected: &str) -> E
+where
+    E: serde::de::Error,
+{
+    E::invalid_type(
+        Unexpected::Other(&format!(
+            "data item of major type {}",
+            data_item.major_type()
+        )),
+        &expected,
+    )
+}
+
+impl<'de> Deserialize<'de> for ByteContent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        match DataItem::deserialize(deserializer)? {
+            DataItem::Byte(byte_content) => Ok(byte_content),
+            data_item => Err(unexpected_data_item(&data_item, "byte string")),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for TextContent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        match DataItem::deserialize(deserializer)? {
+            DataItem::Text(text_content) => Ok(text_content),
+            data_item => Err(unexpected_data_item(&data_item, "text string")),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ArrayContent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        match DataItem::deserialize(deserializer)? {
+            DataItem::Array(array_content) => Ok(array_content),
+            data_item => Err(unexpected_data_item(&data_item, "array")),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for MapContent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        match DataItem::deserialize(deserializer)? {
+            DataItem::Map(map_content) => Ok(map_content),
+            data_item => Err(unexpected_data_item(&data_item, "map")),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for TagContent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        match Tagged::<DataItem>::deserialize(deserializer)?.into_parts() {
+            (Some(number), content) => Ok(TagContent::from((number, content))),
+            (None, content) => Err(unexpected_data_item(&content, "tag")),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SimpleValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct(SIMPLE_STRUCT_NAME, SimpleValueVisitor)
+    }
+}
+
+struct SimpleValueVisitor;
+
+impl<'de> Visitor<'de> for SimpleValueVisitor {
+    type Value = SimpleValue;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str("simple value")
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        u8::try_from(v)
+            .ok()
+            .and_then(SimpleValue::new)
+            .ok_or_else(|| E::invalid_value(Unexpected::Unsigned(v), &self))
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_u8(self)
+    }
+}
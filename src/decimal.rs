@@ -0,0 +1,20 @@
+use rust_decimal::Decimal;
+
+use crate::data_item::DataItem;
+
+impl From<Decimal> for DataItem {
+    fn from(value: Decimal) -> Self {
+        Self::decimal_fraction(-i64::from(value.scale()), value.mantissa())
+    }
+}
+
+/// Create a decimal from a exponent and mantissa of a decimal fraction
+pub(crate) fn from_exponent_mantissa(exponent: i64, mantissa: i128) -> Option<Decimal> {
+    if exponent <= 0 {
+        let scale = u32::try_from(exponent.unsigned_abs()).ok()?;
+        Decimal::try_from_i128_with_scale(mantissa, scale).ok()
+    } else {
+        let multiplier = 10i128.checked_pow(u32::try_from(exponent).ok()?)?;
+        Decimal::try_from_i128_with_scale(mantissa.checked_mul(multiplier)?, 0).ok()
+    }
+}
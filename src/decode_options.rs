@@ -0,0 +1,140 @@
+/// Maximum nesting depth which is used when a depth is not set
+const DEFAULT_MAX_DEPTH: usize = 128;
+
+/// Options which control resource limits and handling of a repeated map key
+/// applied while decoding a CBOR bytes
+///
+/// By default nesting depth is limited to 128 so a deeply nested input returns
+/// an error instead of overflowing a stack, no other limit is applied and a
+/// repeated map key is an error
+///
+/// # Example
+/// ```rust
+/// use cbor_next::{DataItem, DecodeOptions};
+///
+/// let mut options = DecodeOptions::default();
+/// options.set_max_depth(1);
+/// assert!(DataItem::decode_with_options(&[0x81, 0x01], &options).is_ok());
+/// assert!(DataItem::decode_with_options(&[0x81, 0x81, 0x01], &options).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeOptions {
+    depth: usize,
+    length: Option<u64>,
+    size: Option<usize>,
+    preserve_duplicate_keys: bool,
+    intern_keys: bool,
+}
+
+impl Default for DecodeOptions {
+    fn default() -> Self {
+        Self {
+            depth: DEFAULT_MAX_DEPTH,
+            length: None,
+            size: None,
+            preserve_duplicate_keys: false,
+            intern_keys: false,
+        }
+    }
+}
+
+impl DecodeOptions {
+    /// Set maximum nesting depth of an array, a map and a tag. Default
+    /// maximum depth is 128
+    ///
+    /// A large depth should only be used when decoding on a thread with a
+    /// large enough stack
+    pub fn set_max_depth(&mut self, max_depth: usize) -> &mut Self {
+        self.depth = max_depth;
+        self
+    }
+
+    /// Set maximum number of item in an array, number of pair in a map and
+    /// number of byte in a byte string or a text string
+    pub fn set_max_length(&mut self, max_length: u64) -> &mut Self {
+        self.length = Some(max_length);
+        self
+    }
+
+    /// Set maximum number of byte of a CBOR bytes which can be decoded
+    pub fn set_max_size(&mut self, max_size: usize) -> &mut Self {
+        self.size = Some(max_size);
+        self
+    }
+
+    /// Set whether a map with a repeated key is decoded by keeping a first
+    /// entry of a key and storing other entries as duplicates of a map content
+    /// instead of returning an error
+    pub fn set_preserve_duplicate_keys(&mut self, preserve: bool) -> &mut Self {
+        self.preserve_duplicate_keys = preserve;
+        self
+    }
+
+    /// Set whether a short text string map key is interned so every decoded
+    /// occurrence of a same key shares a single reference counted string
+    /// instead of allocating its own string
+    ///
+    /// Interned keys are only shared inside a single decode call, whose table
+    /// is dropped once decoding finishes, so an interned key is freed with a
+    /// last data item which uses it. Only a key of at most 64 bytes is
+    /// interned, any other key is allocated as usual. It is meant for a large
+    /// document which repeats a small set of map keys
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::content::TextChunk;
+    /// use cbor_next::{DataItem, DecodeOptions};
+    ///
+    /// let bytes = [0x82, 0xa1, 0x61, 0x61, 0x01, 0xa1, 0x61, 0x61, 0x02];
+    /// let mut options = DecodeOptions::default();
+    /// options.set_intern_keys(true);
+    /// let value = DataItem::decode_with_options(&bytes, &options).unwrap();
+    /// let keys = value
+    ///     .as_array()
+    ///     .unwrap()
+    ///     .iter()
+    ///     .flat_map(|map| map.as_map().unwrap().keys())
+    ///     .collect::<Vec<_>>();
+    /// let [DataItem::Text(x), DataItem::Text(y)] = keys.as_slice() else {
+    ///     panic!("keys should be text strings");
+    /// };
+    /// let ([TextChunk::Shared(a)], [TextChunk::Shared(b)]) = (x.chunk(), y.chunk()) else {
+    ///     panic!("keys should be interned");
+    /// };
+    /// assert!(std::sync::Arc::ptr_eq(a, b));
+    /// ```
+    pub fn set_intern_keys(&mut self, intern: bool) -> &mut Self {
+        self.intern_keys = intern;
+        self
+    }
+
+    /// Get maximum nesting depth
+    #[must_use]
+    pub fn max_depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Get maximum length of an array, a map, a byte string and a text string
+    #[must_use]
+    pub fn max_length(&self) -> Option<u64> {
+        self.length
+    }
+
+    /// Get maximum size of a CBOR bytes
+    #[must_use]
+    pub fn max_size(&self) -> Option<usize> {
+        self.size
+    }
+
+    /// Get whether a repeated map key is preserved as a duplicate entry
+    #[must_use]
+    pub fn preserve_duplicate_keys(&self) -> bool {
+        self.preserve_duplicate_keys
+    }
+
+    /// Get whether a text string map key is interned
+    #[must_use]
+    pub fn intern_keys(&self) -> bool {
+        self.intern_keys
+    }
+}
@@ -1,4 +1,7 @@
+use std::cmp::Ordering;
+
 /// Different mode supported for deterministic format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[non_exhaustive]
 pub enum DeterministicMode {
     /// Core
@@ -6,3 +9,18 @@ pub enum DeterministicMode {
     /// Length first
     LengthFirst,
 }
+
+impl DeterministicMode {
+    /// Compare deterministic encoding of two map keys in an order of a mode
+    pub(crate) fn compare(self, first: &[u8], second: &[u8]) -> Ordering {
+        match self {
+            Self::Core => first.cmp(second),
+            Self::LengthFirst => {
+                first
+                    .len()
+                    .cmp(&second.len())
+                    .then_with(|| first.cmp(second))
+            }
+        }
+    }
+}
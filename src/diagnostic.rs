@@ -0,0 +1,765 @@
+use std::fmt::Write as _;
+
+use indexmap::IndexMap;
+use indexmap::map::Entry;
+
+use crate::content::{ArrayContent, ByteContent, MapContent, SimpleValue, TagContent, TextContent};
+use crate::data_item::DataItem;
+use crate::diagnostic_options::DiagnosticOptions;
+use crate::encoding::{
+    BASE64_ALPHABET, BASE64URL_ALPHABET, decode_base64, decode_hex, encode_base64,
+};
+use crate::error::{Error, Syntax};
+use crate::tag;
+
+/// Maximum nesting of array, map, tag and embedded data item which is parsed
+/// from a diagnostic notation
+const MAX_DEPTH: usize = 256;
+
+/// Parse a CBOR diagnostic notation into a data item
+pub(crate) fn parse(text: &str) -> Result<DataItem, Error> {
+    let mut parser = Parser {
+        text,
+        position: 0,
+        depth: 0,
+    };
+    let data_item = parser.parse_item()?;
+    parser.skip_whitespace()?;
+    if parser.position < text.len() {
+        return Err(parser.error(Syntax::TrailingCharacter));
+    }
+    Ok(data_item)
+}
+
+/// Print a data item as a diagnostic notation using provided options
+pub(crate) fn print(data_item: &DataItem, options: &DiagnosticOptions) -> String {
+    let mut diagnostic = String::new();
+    write_item(data_item, options, None, 0, &mut diagnostic);
+    diagnostic
+}
+
+#[expect(
+    clippy::use_debug,
+    reason = "diagnostic notation of a scalar data item is its debug representation"
+)]
+/// Write a data item where `expected_encoding` is a tag number of an innermost
+/// expected conversion tag (tag 21 to tag 23) enclosing a data item
+fn write_item(
+    root: &DataItem,
+    options: &DiagnosticOptions,
+    expected_encoding: Option<u64>,
+    level: usize,
+    diagnostic: &mut String,
+) {
+    // nested data item are pushed to an explicit stack instead of recursing
+    // so printing a deeply nested data item cannot overflow a call stack
+    let mut stack = vec![PrintStep::Item(root, expected_encoding, level)];
+    while let Some(step) = stack.pop() {
+        let (data_item, expected_encoding, level) = match step {
+            PrintStep::Item(data_item, expected_encoding, level) => {
+                (data_item, expected_encoding, level)
+            }
+            PrintStep::Separator {
+                index,
+                is_indefinite,
+                level,
+            } => {
+                write_separator(index, is_indefinite, level, options, diagnostic);
+                continue;
+            }
+            PrintStep::Close {
+                close,
+                is_empty,
+                is_indefinite,
+                level,
+            } => {
+                write_close(close, is_empty, is_indefinite, level, options, diagnostic);
+                continue;
+            }
+            PrintStep::Text(text) => {
+                diagnostic.push_str(text);
+                continue;
+            }
+        };
+        match data_item {
+            DataItem::Byte(byte_content)
+                if options.is_extended() || expected_encoding.is_some() =>
+            {
+                write_byte_content(byte_content, options, expected_encoding, diagnostic);
+            }
+            DataItem::Array(array_content) => {
+                push_container(
+                    ('[', ']'),
+                    array_content.is_indefinite(),
+                    array_content.array().iter().map(|item| (None, item)),
+                    (expected_encoding, level),
+                    diagnostic,
+                    &mut stack,
+                );
+            }
+            DataItem::Map(map_content) => {
+                push_container(
+                    ('{', '}'),
+                    map_content.is_indefinite(),
+                    map_content
+                        .iter_with_duplicates()
+                        .map(|(key, value)| (Some(key), value)),
+                    (expected_encoding, level),
+                    diagnostic,
+                    &mut stack,
+                );
+            }
+            DataItem::Tag(tag_content)
+                if tag_content.number() == tag::CBOR_DATA && options.is_expand_embedded() =>
+            {
+                let _ = write!(diagnostic, "{}(", tag_content.number());
+                if let Some(embedded) = embedded_item(tag_content.content()) {
+                    // every embedded level is decoded from a byte string of an
+                    // outer level so this recursion is bounded by a size of bytes
+                    diagnostic.push_str("<<");
+                    write_item(&embedded, options, None, level, diagnostic);
+                    diagnostic.push_str(">>)");
+                } else {
+                    stack.push(PrintStep::Text(")"));
+                    stack.push(PrintStep::Item(
+                        tag_content.content(),
+                        expected_encoding,
+                        level,
+                    ));
+                }
+            }
+            DataItem::Tag(tag_content) => {
+                let number = tag_content.number();
+                let expected_encoding = match number {
+                    tag::EXPECTED_BASE64URL | tag::EXPECTED_BASE64 | tag::EXPECTED_BASE16 => {
+                        Some(number)
+                    }
+                    _ => expected_encoding,
+                };
+                let _ = write!(diagnostic, "{number}(");
+                stack.push(PrintStep::Text(")"));
+                stack.push(PrintStep::Item(
+                    tag_content.content(),
+                    expected_encoding,
+                    level,
+                ));
+            }
+            _ => {
+                let _ = write!(diagnostic, "{data_item:?}");
+            }
+        }
+    }
+}
+
+/// Write a byte string along with all of its chunks when it is an indefinite
+/// length byte string
+fn write_byte_content(
+    byte_content: &ByteContent,
+    options: &DiagnosticOptions,
+    expected_encoding: Option<u64>,
+    diagnostic: &mut String,
+) {
+    if byte_content.is_indefinite() {
+        diagnostic.push_str("(_ ");
+        for (index, chunk) in byte_content.chunk().iter().enumerate() {
+            if index > 0 {
+                diagnostic.push_str(", ");
+            }
+            write_byte(chunk, options, expected_encoding, diagnostic);
+        }
+        diagnostic.push(')');
+    } else {
+        write_byte(&byte_content.full(), options, expected_encoding, diagnostic);
+    }
+}
+
+/// Write a separator before an entry of an array or a map
+fn write_separator(
+    index: usize,
+    is_indefinite: bool,
+    level: usize,
+    options: &DiagnosticOptions,
+    diagnostic: &mut String,
+) {
+    if index > 0 {
+        diagnostic.push(',');
+    }
+    match options.indent() {
+        Some(indent_width) => {
+            diagnostic.push('\n');
+            diagnostic.push_str(&" ".repeat(indent_width * (level + 1)));
+        }
+        None if index > 0 || is_indefinite => diagnostic.push(' '),
+        None => {}
+    }
+}
+
+/// Write a closing of an array or a map
+fn write_close(
+    close: char,
+    is_empty: bool,
+    is_indefinite: bool,
+    level: usize,
+    options: &DiagnosticOptions,
+    diagnostic: &mut String,
+) {
+    match options.indent() {
+        _ if is_empty && is_indefinite => diagnostic.push(' '),
+        Some(indent_width) if !is_empty => {
+            diagnostic.push('\n');
+            diagnostic.push_str(&" ".repeat(indent_width * level));
+        }
+        _ => {}
+    }
+    diagnostic.push(close);
+}
+
+/// Step of an explicit stack used while printing a data item
+enum PrintStep<'a> {
+    /// Data item along with an innermost expected conversion tag enclosing it
+    /// and its nesting level
+    Item(&'a DataItem, Option<u64>, usize),
+    /// Separator written before an entry of an array or a map
+    Separator {
+        index: usize,
+        is_indefinite: bool,
+        level: usize,
+    },
+    /// Closing of an array or a map
+    Close {
+        close: char,
+        is_empty: bool,
+        is_indefinite: bool,
+        level: usize,
+    },
+    /// Text which is written as it is
+    Text(&'static str),
+}
+
+/// Decode a content of an encoded CBOR data item. Return `None` if content is
+/// not a definite length byte string which exactly encodes a single data item
+fn embedded_item(content: &DataItem) -> Option<DataItem> {
+    let DataItem::Byte(byte_content) = content else {
+        return None;
+    };
+    if byte_content.is_indefinite() {
+        return None;
+    }
+    let bytes = byte_content.full();
+    DataItem::decode(&bytes)
+        .ok()
+        .filter(|embedded| embedded.encode() == *bytes)
+}
+
+/// Write a byte string using an expected encoding if present. Otherwise write
+/// as a single quoted string if extended diagnostic notation is used and byte
+/// string only contains printable ASCII character or as a hex encoded byte
+/// string
+fn write_byte(
+    bytes: &[u8],
+    options: &DiagnosticOptions,
+    expected_encoding: Option<u64>,
+    diagnostic: &mut String,
+) {
+    if expected_encoding == Some(tag::EXPECTED_BASE64URL) {
+        let _ = write!(
+            diagnostic,
+            "b64'{}'",
+            encode_base64(bytes, BASE64URL_ALPHABET, false)
+        );
+    } else if expected_encoding == Some(tag::EXPECTED_BASE64) {
+        let _ = write!(
+            diagnostic,
+            "b64'{}'",
+            encode_base64(bytes, BASE64_ALPHABET, true)
+        );
+    } else if options.is_extended()
+        && expected_encoding.is_none()
+        && bytes
+            .iter()
+            .all(|byte| (0x20..0x7f).contains(byte) && !matches!(byte, b'\'' | b'\\'))
+    {
+        diagnostic.push('\'');
+        diagnostic.extend(bytes.iter().map(|byte| char::from(*byte)));
+        diagnostic.push('\'');
+    } else {
+        diagnostic.push_str("h'");
+        for byte in bytes {
+            let _ = write!(diagnostic, "{byte:02x}");
+        }
+        diagnostic.push('\'');
+    }
+}
+
+/// Write an opening of an array or a map and push its entries along with its
+/// closing to a stack
+fn push_container<'a, I>(
+    (open, close): (char, char),
+    is_indefinite: bool,
+    entries: I,
+    (expected_encoding, level): (Option<u64>, usize),
+    diagnostic: &mut String,
+    stack: &mut Vec<PrintStep<'a>>,
+) where
+    I: Iterator<Item = (Option<&'a DataItem>, &'a DataItem)>,
+{
+    diagnostic.push(open);
+    if is_indefinite {
+        diagnostic.push('_');
+    }
+    let mut entries = entries.peekable();
+    stack.push(PrintStep::Close {
+        close,
+        is_empty: entries.peek().is_none(),
+        is_indefinite,
+        level,
+    });
+    // steps are pushed in order and then reversed so entries only need to be
+    // iterated forward
+    let start = stack.len();
+    for (index, (key, value)) in entries.enumerate() {
+        stack.push(PrintStep::Separator {
+            index,
+            is_indefinite,
+            level,
+        });
+        if let Some(key) = key {
+            stack.push(PrintStep::Item(key, expected_encoding, level + 1));
+            stack.push(PrintStep::Text(": "));
+        }
+        stack.push(PrintStep::Item(value, expected_encoding, level + 1));
+    }
+    stack[start..].reverse();
+}
+
+/// Parser which reads a diagnostic notation from a start of a text
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, reason: Syntax) -> Error {
+        Error::InvalidDiagnostic {
+            reason,
+            position: self.position,
+        }
+    }
+
+    fn rest(&self) -> &str {
+        &self.text[self.position..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let character = self.peek()?;
+        self.position += character.len_utf8();
+        Some(character)
+    }
+
+    /// Skip whitespace, comment enclosed inside a slash and comment starting
+    /// with a hash till end of line
+    fn skip_whitespace(&mut self) -> Result<(), Error> {
+        loop {
+            match self.peek() {
+                Some(character) if character.is_whitespace() => {
+                    self.next();
+                }
+                Some('/') => {
+                    self.next();
+                    let Some(length) = self.rest().find('/') else {
+                        return Err(self.error(Syntax::UnterminatedComment));
+                    };
+                    self.position += length + 1;
+                }
+                Some('#') => {
+                    let length = self.rest().find('\n').unwrap_or(self.rest().len());
+                    self.position += length;
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn consume(&mut self, prefix: &'static str) -> Result<bool, Error> {
+        self.skip_whitespace()?;
+        if self.rest().starts_with(prefix) {
+            self.position += prefix.len();
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    fn expect(&mut self, prefix: &'static str) -> Result<(), Error> {
+        if self.consume(prefix)? {
+            Ok(())
+        } else {
+            Err(self.error(Syntax::Expected(prefix)))
+        }
+    }
+
+    /// Parse a data item while tracking its nesting so a deeply nested input
+    /// returns an error instead of overflowing a stack
+    fn parse_item(&mut self) -> Result<DataItem, Error> {
+        if self.depth >= MAX_DEPTH {
+            return Err(self.error(Syntax::TooDeep {
+                max_depth: MAX_DEPTH,
+            }));
+        }
+        self.depth += 1;
+        let data_item = self.parse_nested_item();
+        self.depth -= 1;
+        data_item
+    }
+
+    fn parse_nested_item(&mut self) -> Result<DataItem, Error> {
+        self.skip_whitespace()?;
+        match self.peek() {
+            Some('[') => self.parse_array(),
+            Some('{') => self.parse_map(),
+            Some('(') => self.parse_indefinite_string(),
+            _ if self.is_string_start() => self.parse_string(),
+            Some(character) if character.is_ascii_digit() || character == '-' => {
+                self.parse_number()
+            }
+            Some(_) => self.parse_keyword(),
+            None => Err(self.error(Syntax::UnexpectedEnd)),
+        }
+    }
+
+    fn is_string_start(&self) -> bool {
+        ["\"", "'", "h'", "b64'", "<<"]
+            .iter()
+            .any(|prefix| self.rest().starts_with(prefix))
+    }
+
+    /// Parse a text string or a byte string along with all adjacent string
+    /// which are concatenated to it. Concatenated string is a text string if
+    /// first string is a text string
+    fn parse_string(&mut self) -> Result<DataItem, Error> {
+        let is_text = self.rest().starts_with('"');
+        let mut bytes = vec![];
+        loop {
+            if self.rest().starts_with('"') {
+                bytes.extend(self.parse_text()?.into_bytes());
+            } else if self.rest().starts_with("<<") {
+                bytes.extend(self.parse_embedded()?);
+            } else {
+                bytes.extend(self.parse_byte()?);
+            }
+            self.skip_whitespace()?;
+            if !self.is_string_start() {
+                break;
+            }
+        }
+        if is_text {
+            let text = String::from_utf8(bytes).map_err(|_| self.error(Syntax::InvalidUtf8))?;
+            return Ok(DataItem::from(text.as_str()));
+        }
+        Ok(DataItem::from(bytes.as_slice()))
+    }
+
+    /// Parse an embedded CBOR sequence enclosed inside `<<` and `>>` as a byte
+    /// string of encoded data item
+    fn parse_embedded(&mut self) -> Result<Vec<u8>, Error> {
+        self.expect("<<")?;
+        let mut bytes = vec![];
+        let mut is_empty = true;
+        while !self.consume(">>")? {
+            if !is_empty {
+                self.expect(",")?;
+            }
+            bytes.extend(self.parse_item()?.encode());
+            is_empty = false;
+        }
+        Ok(bytes)
+    }
+
+    fn parse_keyword(&mut self) -> Result<DataItem, Error> {
+        let length = self
+            .rest()
+            .find(|character: char| !character.is_ascii_alphanumeric())
+            .unwrap_or(self.rest().len());
+        let keyword = &self.rest()[..length];
+        let data_item = match keyword {
+            "true" => DataItem::Boolean(true),
+            "false" => DataItem::Boolean(false),
+            "null" => DataItem::Null,
+            "undefined" => DataItem::Undefined,
+            "NaN" => DataItem::Floating(f64::NAN),
+            "Infinity" => DataItem::Floating(f64::INFINITY),
+            "simple" => {
+                self.position += length;
+                self.expect("(")?;
+                self.skip_whitespace()?;
+                let number = self.parse_unsigned()?;
+                self.expect(")")?;
+                return u8::try_from(number)
+                    .ok()
+                    .and_then(|number| SimpleValue::try_from(number).ok())
+                    .map(DataItem::from)
+                    .ok_or_else(|| self.error(Syntax::InvalidSimple));
+            }
+            _ => return Err(self.error(Syntax::UnknownKeyword)),
+        };
+        self.position += length;
+        Ok(data_item)
+    }
+
+    fn parse_unsigned(&mut self) -> Result<u64, Error> {
+        let number = self.parse_magnitude()?;
+        u64::try_from(number).map_err(|_| self.error(Syntax::UnsignedOutOfRange))
+    }
+
+    fn parse_magnitude(&mut self) -> Result<u128, Error> {
+        let (radix, prefix_length) = match self.rest().get(..2) {
+            Some("0x" | "0X") => (16, 2),
+            Some("0o" | "0O") => (8, 2),
+            Some("0b" | "0B") => (2, 2),
+            _ => (10, 0),
+        };
+        self.position += prefix_length;
+        let length = self
+            .rest()
+            .find(|character: char| !character.is_digit(radix))
+            .unwrap_or(self.rest().len());
+        let number = u128::from_str_radix(&self.rest()[..length], radix)
+            .map_err(|_| self.error(Syntax::InvalidInteger))?;
+        self.position += length;
+        Ok(number)
+    }
+
+    fn parse_number(&mut self) -> Result<DataItem, Error> {
+        let start = self.position;
+        let is_negative = self.rest().starts_with('-');
+        if is_negative {
+            self.next();
+            if self.rest().starts_with("Infinity") {
+                self.position += "Infinity".len();
+                return Ok(DataItem::Floating(f64::NEG_INFINITY));
+            }
+        }
+        let is_float = {
+            let length = self
+                .rest()
+                .find(|character: char| !character.is_ascii_alphanumeric() && character != '.')
+                .unwrap_or(self.rest().len());
+            let token = &self.rest()[..length];
+            !token.starts_with("0x") && !token.starts_with("0X") && token.contains(['.', 'e', 'E'])
+        };
+        if is_float {
+            let length = self
+                .rest()
+                .char_indices()
+                .find(|(index, character)| {
+                    !(character.is_ascii_digit()
+                        || matches!(character, '.' | 'e' | 'E')
+                        || (matches!(character, '+' | '-')
+                            && self.rest()[..*index].ends_with(['e', 'E'])))
+                })
+                .map_or(self.rest().len(), |(index, _)| index);
+            self.position += length;
+            let number = self.text[start..self.position]
+                .parse::<f64>()
+                .map_err(|_| self.error(Syntax::InvalidFloating))?;
+            return Ok(DataItem::Floating(number));
+        }
+        let magnitude = self.parse_magnitude()?;
+        if is_negative {
+            if magnitude == 0 {
+                return Ok(DataItem::Unsigned(0));
+            }
+            return u64::try_from(magnitude - 1)
+                .map(DataItem::Signed)
+                .map_err(|_| self.error(Syntax::NegativeOutOfRange));
+        }
+        let number =
+            u64::try_from(magnitude).map_err(|_| self.error(Syntax::UnsignedOutOfRange))?;
+        if self.consume("(")? {
+            let content = self.parse_item()?;
+            self.expect(")")?;
+            return Ok(TagContent::from((number, content)).into());
+        }
+        Ok(DataItem::Unsigned(number))
+    }
+
+    fn parse_text(&mut self) -> Result<String, Error> {
+        self.expect("\"")?;
+        let mut text = String::new();
+        loop {
+            match self.next() {
+                Some('"') => return Ok(text),
+                Some('\\') => text.push(self.parse_escape()?),
+                Some(character) => text.push(character),
+                None => return Err(self.error(Syntax::UnterminatedText)),
+            }
+        }
+    }
+
+    fn parse_escape(&mut self) -> Result<char, Error> {
+        let character = match self.next() {
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('b') => '\u{8}',
+            Some('f') => '\u{c}',
+            Some('0') => '\0',
+            Some(character @ ('"' | '\'' | '\\' | '/')) => character,
+            Some('u') if self.peek() == Some('{') => {
+                self.next();
+                let Some(length) = self.rest().find('}') else {
+                    return Err(self.error(Syntax::UnterminatedUnicodeEscape));
+                };
+                let code = u32::from_str_radix(&self.rest()[..length], 16)
+                    .map_err(|_| self.error(Syntax::InvalidUnicodeEscape))?;
+                self.position += length + 1;
+                return char::from_u32(code)
+                    .ok_or_else(|| self.error(Syntax::InvalidUnicodeEscape));
+            }
+            Some('u') => {
+                let high = self.parse_utf16_unit()?;
+                if !(0xd800..0xdc00).contains(&high) {
+                    return char::from_u32(high)
+                        .ok_or_else(|| self.error(Syntax::InvalidUnicodeEscape));
+                }
+                if !self.rest().starts_with("\\u") {
+                    return Err(self.error(Syntax::MissingLowSurrogate));
+                }
+                self.position += 2;
+                let low = self.parse_utf16_unit()?;
+                if !(0xdc00..0xe000).contains(&low) {
+                    return Err(self.error(Syntax::InvalidLowSurrogate));
+                }
+                let code = 0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00);
+                return char::from_u32(code)
+                    .ok_or_else(|| self.error(Syntax::InvalidUnicodeEscape));
+            }
+            _ => return Err(self.error(Syntax::InvalidEscape)),
+        };
+        Ok(character)
+    }
+
+    fn parse_utf16_unit(&mut self) -> Result<u32, Error> {
+        let unit = self
+            .rest()
+            .get(..4)
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .ok_or_else(|| self.error(Syntax::InvalidUnicodeEscape))?;
+        self.position += 4;
+        Ok(unit)
+    }
+
+    fn parse_byte(&mut self) -> Result<Vec<u8>, Error> {
+        self.skip_whitespace()?;
+        let prefix = if self.consume("h'")? {
+            "h"
+        } else if self.consume("b64'")? {
+            "b64"
+        } else {
+            self.expect("'")?;
+            ""
+        };
+        let Some(length) = self.rest().find('\'') else {
+            return Err(self.error(Syntax::UnterminatedByte));
+        };
+        let content = &self.rest()[..length];
+        let bytes = match prefix {
+            "h" => {
+                // whitespace and comment enclosed inside a slash are ignored
+                let hex = content
+                    .split('/')
+                    .step_by(2)
+                    .flat_map(str::chars)
+                    .filter(|character| !character.is_whitespace())
+                    .collect::<String>();
+                decode_hex(&hex)
+            }
+            "b64" => {
+                decode_base64(content, BASE64URL_ALPHABET)
+                    .or_else(|| decode_base64(content, BASE64_ALPHABET))
+            }
+            _ => Some(content.as_bytes().to_vec()),
+        }
+        .ok_or_else(|| self.error(Syntax::InvalidByte))?;
+        self.position += length + 1;
+        Ok(bytes)
+    }
+
+    fn parse_indefinite_string(&mut self) -> Result<DataItem, Error> {
+        self.expect("(")?;
+        self.expect("_")?;
+        let mut chunks = vec![];
+        while !self.consume(")")? {
+            if !chunks.is_empty() {
+                self.expect(",")?;
+            }
+            chunks.push(self.parse_item()?);
+        }
+        if chunks.iter().all(DataItem::is_text) && !chunks.is_empty() {
+            let mut text_content = TextContent::default();
+            text_content.set_indefinite(true);
+            for chunk in chunks {
+                if let DataItem::Text(chunk) = chunk {
+                    text_content.push_string(&chunk.full());
+                }
+            }
+            return Ok(DataItem::Text(text_content));
+        }
+        let mut byte_content = ByteContent::default();
+        byte_content.set_indefinite(true);
+        for chunk in chunks {
+            match chunk {
+                DataItem::Byte(chunk) if !chunk.is_indefinite() => {
+                    byte_content.push_bytes(&chunk.full());
+                }
+                _ => return Err(self.error(Syntax::InvalidChunk)),
+            }
+        }
+        Ok(DataItem::Byte(byte_content))
+    }
+
+    fn parse_array(&mut self) -> Result<DataItem, Error> {
+        self.expect("[")?;
+        let is_indefinite = self.consume("_")?;
+        let mut array = vec![];
+        while !self.consume("]")? {
+            if !array.is_empty() {
+                self.expect(",")?;
+            }
+            array.push(self.parse_item()?);
+        }
+        let mut array_content = ArrayContent::from(array);
+        array_content.set_indefinite(is_indefinite);
+        Ok(DataItem::Array(array_content))
+    }
+
+    fn parse_map(&mut self) -> Result<DataItem, Error> {
+        self.expect("{")?;
+        let is_indefinite = self.consume("_")?;
+        let mut map = IndexMap::new();
+        while !self.consume("}")? {
+            if !map.is_empty() {
+                self.expect(",")?;
+            }
+            let key = self.parse_item()?;
+            self.expect(":")?;
+            let value = self.parse_item()?;
+            let index = map.len();
+            match map.entry(key) {
+                Entry::Occupied(_) => {
+                    return Err(self.error(Syntax::DuplicateKey { index }));
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(value);
+                }
+            }
+        }
+        let mut map_content = MapContent::from(map);
+        map_content.set_indefinite(is_indefinite);
+        Ok(DataItem::Map(map_content))
+    }
+}
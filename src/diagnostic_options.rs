@@ -0,0 +1,65 @@
+/// Options which control how a data item is printed as a diagnostic notation
+///
+/// By default a data item is printed in a single line using plain diagnostic
+/// notation. Byte string enclosed inside an expected conversion tag (tag 21 to
+/// tag 23) is always printed using its expected base64url, base64 or base16
+/// encoding
+///
+/// # Example
+/// ```rust
+/// use cbor_next::{DataItem, DiagnosticOptions};
+///
+/// let value = DataItem::from(vec![DataItem::from("a".as_bytes()), DataItem::from(1)]);
+/// let mut options = DiagnosticOptions::default();
+/// assert_eq!(value.to_diagnostic(&options), "[h'61', 1]");
+/// options.set_extended(true).set_indent(2);
+/// assert_eq!(value.to_diagnostic(&options), "[\n  'a',\n  1\n]");
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DiagnosticOptions {
+    indent: Option<usize>,
+    extended: bool,
+    expand_embedded: bool,
+}
+
+impl DiagnosticOptions {
+    /// Set indent width used to spread nested array and map across multiple
+    /// lines
+    pub fn set_indent(&mut self, indent_width: usize) -> &mut Self {
+        self.indent = Some(indent_width);
+        self
+    }
+
+    /// Set whether extended diagnostic notation (RFC 8610 Appendix G) is used
+    /// where byte string containing only printable ASCII character is printed
+    /// as a single quoted string
+    pub fn set_extended(&mut self, extended: bool) -> &mut Self {
+        self.extended = extended;
+        self
+    }
+
+    /// Set whether content of an encoded CBOR data item (tag 24) is decoded and
+    /// printed inside `<<` and `>>` instead of a hex encoded byte string
+    pub fn set_expand_embedded(&mut self, expand_embedded: bool) -> &mut Self {
+        self.expand_embedded = expand_embedded;
+        self
+    }
+
+    /// Get indent width of nested array and map
+    #[must_use]
+    pub fn indent(&self) -> Option<usize> {
+        self.indent
+    }
+
+    /// Get whether extended diagnostic notation is used
+    #[must_use]
+    pub fn is_extended(&self) -> bool {
+        self.extended
+    }
+
+    /// Get whether content of an encoded CBOR data item is expanded
+    #[must_use]
+    pub fn is_expand_embedded(&self) -> bool {
+        self.expand_embedded
+    }
+}
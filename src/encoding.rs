@@ -0,0 +1,71 @@
+const HEX_ALPHABET: &[u8; 16] = b"0123456789abcdef";
+
+/// Alphabet of base64 encoding
+pub(crate) const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Alphabet of base64url encoding
+pub(crate) const BASE64URL_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Encode bytes as a lowercase hex text
+pub(crate) fn encode_hex(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        text.push(char::from(HEX_ALPHABET[usize::from(byte >> 4)]));
+        text.push(char::from(HEX_ALPHABET[usize::from(byte & 0x0f)]));
+    }
+    text
+}
+
+/// Decode a case insensitive hex text
+pub(crate) fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    let text = text.as_bytes();
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    text.chunks_exact(2)
+        .map(|pair| {
+            let high = char::from(pair[0]).to_digit(16)?;
+            let low = char::from(pair[1]).to_digit(16)?;
+            u8::try_from(high << 4 | low).ok()
+        })
+        .collect()
+}
+
+/// Encode bytes as a base64 text using provided alphabet
+pub(crate) fn encode_base64(bytes: &[u8], alphabet: &[u8; 64], padding: bool) -> String {
+    let mut text = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (index, byte)| {
+            group | u32::from(*byte) << (16 - index * 8)
+        });
+        for index in 0..4 {
+            if index <= chunk.len() {
+                let sextet = (group >> (18 - index * 6)) & 0x3f;
+                text.push(char::from(alphabet[sextet as usize]));
+            } else if padding {
+                text.push('=');
+            }
+        }
+    }
+    text
+}
+
+/// Decode a base64 text using provided alphabet. Padding of text is optional
+pub(crate) fn decode_base64(text: &str, alphabet: &[u8; 64]) -> Option<Vec<u8>> {
+    let text = text.trim_end_matches('=').as_bytes();
+    if text.len() % 4 == 1 {
+        return None;
+    }
+    let mut bytes = Vec::with_capacity(text.len() * 3 / 4);
+    for chunk in text.chunks(4) {
+        let mut group = 0u32;
+        for (index, character) in chunk.iter().enumerate() {
+            let sextet = alphabet.iter().position(|value| value == character)?;
+            group |= u32::try_from(sextet).ok()? << (18 - index * 6);
+        }
+        bytes.extend_from_slice(&group.to_be_bytes()[1..chunk.len()]);
+    }
+    Some(bytes)
+}
@@ -1,6 +1,9 @@
 use std::num::TryFromIntError;
+use std::str::Utf8Error;
 use std::string::FromUtf8Error;
 
+use crate::data_item::DataItem;
+
 /// Enum representing error for a crate
 #[derive(Debug, PartialEq)]
 #[non_exhaustive]
@@ -8,7 +11,7 @@ pub enum Error {
     /// Incomplete CBOR bytes
     Incomplete,
     /// Error generated when converting string from utf8 bytes
-    FromUtf8(FromUtf8Error),
+    Utf8(Utf8Error),
     /// Incomplete indefinite length data
     IncompleteIndefinite,
     /// Invalid simple value
@@ -16,14 +19,464 @@ pub enum Error {
     /// Error converting to a required integer
     FromInt(TryFromIntError),
     /// Not well formed data
-    NotWellFormed(String),
+    NotWellFormed(Malformed),
     /// Invalid break stop position
     InvalidBreakStop,
+    /// Error generated when reading from reader or writing to writer
+    Io(std::io::ErrorKind),
+    /// Decode limit provided by decode options or a fixed limit of an encoder
+    /// is exceeded
+    LimitExceeded(Limit),
+    /// Content is not valid for a tag
+    InvalidTagContent {
+        /// Tag number
+        tag: u64,
+        /// Description of a content which is required by a tag
+        expected: &'static str,
+    },
+    /// Invalid hex encoded text
+    InvalidHex,
+    /// Invalid CBOR diagnostic notation
+    InvalidDiagnostic {
+        /// Reason why a diagnostic notation is invalid
+        reason: Syntax,
+        /// Byte position of a diagnostic notation where an error is found
+        position: usize,
+    },
+    /// Invalid JSON text
+    InvalidJson {
+        /// Reason why a JSON text is invalid
+        reason: Syntax,
+        /// Byte position of a JSON text where an error is found
+        position: usize,
+    },
+    /// Data item cannot be converted to JSON
+    JsonConversion(Conversion),
+    /// Data item cannot be converted to or from other CBOR library value
+    ValueConversion(Conversion),
+    /// Data item cannot be converted to a requested rust type
+    TypeMismatch {
+        /// Kind of data item which can be converted
+        expected: ItemKind,
+        /// Kind of data item which is found
+        found: ItemKind,
+    },
+    /// Path segment cannot be found in a data item
+    PathNotFound {
+        /// Position of a segment which cannot be found inside a path
+        segment: usize,
+    },
+    /// Invalid query
+    InvalidQuery {
+        /// Reason why a query is invalid
+        reason: Syntax,
+        /// Byte position of a query where an error is found
+        position: usize,
+    },
+    /// Patch is malformed or cannot be applied to a data item
+    InvalidPatch(PatchFailure),
+    /// Data item is not tagged with an expected tag
+    WrongTag {
+        /// Expected tag number
+        expected: u64,
+        /// Tag number found in a data item. `None` if data item is not tagged
+        found: Option<u64>,
+    },
+    /// Caller provided buffer doesn't have enough space
+    BufferFull,
+    /// Custom error message generated by serde serializer or deserializer
+    #[cfg(feature = "serde")]
+    SerdeMessage(String),
+}
+
+/// Reason why a CBOR bytes is not well formed
+///
+/// Every reason only carries a small copyable payload so a decoding error
+/// doesn't allocate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Malformed {
+    /// Additional info between 28 to 30 which is reserved
+    ReservedAdditionalInfo {
+        /// Reserved additional info
+        additional: u8,
+    },
+    /// Indefinite length is used for a major type which doesn't allow it
+    IndefiniteLength {
+        /// Major type of a data item
+        major_type: u8,
+    },
+    /// Chunk of an indefinite length byte string or text string is not a
+    /// definite length string of a same major type
+    InvalidChunk {
+        /// Major type of an indefinite length string
+        expected: u8,
+        /// Major type of a chunk
+        found: u8,
+    },
+    /// Text string is not a valid UTF-8
+    InvalidUtf8(Utf8Error),
+    /// Bytes end before a length of a byte string or a text string
+    MissingBytes {
+        /// Number of missing byte
+        missing: u64,
+    },
+    /// Map contains a repeated key
+    DuplicateKey {
+        /// Position of an entry which repeats an earlier key
+        index: usize,
+    },
+}
+
+impl std::fmt::Display for Malformed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ReservedAdditionalInfo { additional } => {
+                write!(f, "reserved additional info {additional}")
+            }
+            Self::IndefiniteLength { major_type } => {
+                write!(
+                    f,
+                    "indefinite length is not allowed for major type {major_type}"
+                )
+            }
+            Self::InvalidChunk { expected, found } => {
+                write!(
+                    f,
+                    "contains invalid chunk of major type {found} for indefinite major type \
+                     {expected}"
+                )
+            }
+            Self::InvalidUtf8(error) => write!(f, "text string is not valid UTF-8 : {error}"),
+            Self::MissingBytes { missing } => write!(f, "missing {missing} byte"),
+            Self::DuplicateKey { index } => {
+                write!(f, "map key of entry {index} is repeated multiple times")
+            }
+        }
+    }
+}
+
+/// Limit which is exceeded
+///
+/// Every limit carries a value which exceeds it along with a maximum value so
+/// an error doesn't allocate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Limit {
+    /// Nesting depth of array, map and tag
+    Depth {
+        /// Depth which is reached
+        depth: usize,
+        /// Maximum allowed depth
+        max_depth: usize,
+    },
+    /// Number of bytes of an input
+    Size {
+        /// Size of an input
+        size: usize,
+        /// Maximum allowed size
+        max_size: usize,
+    },
+    /// Length of a byte string, text string, array or map
+    Length {
+        /// Length which is declared
+        length: u64,
+        /// Maximum allowed length
+        max_length: u64,
+    },
+}
+
+impl std::fmt::Display for Limit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Depth { depth, max_depth } => {
+                write!(f, "depth {depth} is greater than maximum depth {max_depth}")
+            }
+            Self::Size { size, max_size } => {
+                write!(f, "size {size} is greater than maximum size {max_size}")
+            }
+            Self::Length { length, max_length } => {
+                write!(
+                    f,
+                    "length {length} is greater than maximum length {max_length}"
+                )
+            }
+        }
+    }
+}
+
+/// Kind of a data item which is expected or found when converting a data
+/// item into a rust type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ItemKind {
+    /// Unsigned integer
+    Unsigned,
+    /// Negative integer
+    Negative,
+    /// Either an unsigned integer or a negative integer
+    Integer,
+    /// Byte string
+    Byte,
+    /// Text string
+    Text,
+    /// Array
+    Array,
+    /// Map
+    Map,
+    /// Tag
+    Tag,
+    /// Boolean
+    Boolean,
+    /// Null
+    Null,
+    /// Undefined
+    Undefined,
+    /// Floating point number
+    Floating,
+    /// Simple value
+    Simple,
+    /// Data item which is neither an array, a map nor a tag
+    Scalar,
+}
+
+impl ItemKind {
+    /// Get a kind of a data item from its major type
+    pub(crate) fn from_major_type(major_type: u8) -> Self {
+        match major_type {
+            0 => Self::Unsigned,
+            1 => Self::Negative,
+            2 => Self::Byte,
+            3 => Self::Text,
+            4 => Self::Array,
+            5 => Self::Map,
+            6 => Self::Tag,
+            _ => Self::Simple,
+        }
+    }
+
+    /// Get a kind of a data item
+    pub(crate) fn of(data_item: &DataItem) -> Self {
+        match data_item {
+            DataItem::Unsigned(_) => Self::Unsigned,
+            DataItem::Signed(_) => Self::Negative,
+            DataItem::Byte(_) => Self::Byte,
+            DataItem::Text(_) => Self::Text,
+            DataItem::Array(_) => Self::Array,
+            DataItem::Map(_) => Self::Map,
+            DataItem::Tag(_) => Self::Tag,
+            DataItem::Boolean(_) => Self::Boolean,
+            DataItem::Null => Self::Null,
+            DataItem::Undefined => Self::Undefined,
+            DataItem::Floating(_) => Self::Floating,
+            DataItem::GenericSimple(_) => Self::Simple,
+        }
+    }
+}
+
+impl std::fmt::Display for ItemKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self {
+            Self::Unsigned => "unsigned integer",
+            Self::Negative => "negative integer",
+            Self::Integer => "integer",
+            Self::Byte => "byte string",
+            Self::Text => "text string",
+            Self::Array => "array",
+            Self::Map => "map",
+            Self::Tag => "tag",
+            Self::Boolean => "boolean",
+            Self::Null => "null",
+            Self::Undefined => "undefined",
+            Self::Floating => "floating point number",
+            Self::Simple => "simple value",
+            Self::Scalar => "scalar",
+        };
+        f.write_str(kind)
+    }
+}
+
+/// Reason why a CBOR diagnostic notation, a JSON text or a query is invalid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Syntax {
+    /// Character is found after a data item
+    TrailingCharacter,
+    /// Character cannot start a data item or a selector
+    UnexpectedCharacter,
+    /// Text ends before a data item
+    UnexpectedEnd,
+    /// Comment enclosed inside a slash is not closed
+    UnterminatedComment,
+    /// Expected token is not found
+    Expected(&'static str),
+    /// Data item is nested deeper than a maximum depth
+    TooDeep {
+        /// Maximum allowed depth
+        max_depth: usize,
+    },
+    /// Keyword is not known
+    UnknownKeyword,
+    /// Simple value is out of range or reserved
+    InvalidSimple,
+    /// Integer is not valid
+    InvalidInteger,
+    /// Unsigned integer doesn't fit in 64 bit
+    UnsignedOutOfRange,
+    /// Negative integer doesn't fit in 64 bit
+    NegativeOutOfRange,
+    /// Floating point number is not valid
+    InvalidFloating,
+    /// JSON number is not valid
+    InvalidNumber,
+    /// Text string is not closed
+    UnterminatedText,
+    /// Escape sequence is not valid
+    InvalidEscape,
+    /// Control character is not escaped inside a JSON string
+    UnescapedControl,
+    /// Unicode escape sequence is not closed
+    UnterminatedUnicodeEscape,
+    /// Unicode escape sequence is not valid
+    InvalidUnicodeEscape,
+    /// High surrogate is not followed by a low surrogate
+    MissingLowSurrogate,
+    /// Low surrogate following a high surrogate is not valid
+    InvalidLowSurrogate,
+    /// Byte string is not closed
+    UnterminatedByte,
+    /// Byte string is not a valid hex or base64 encoded string
+    InvalidByte,
+    /// Text string concatenated with byte string is not a valid UTF-8
+    InvalidUtf8,
+    /// Chunk of an indefinite length string is not a definite length string
+    /// of a same type
+    InvalidChunk,
+    /// Map contains a repeated key
+    DuplicateKey {
+        /// Position of an entry which repeats an earlier key
+        index: usize,
+    },
+    /// JSON object which uses a reserved `$` key is not a valid lossless
+    /// object
+    InvalidLossless,
+    /// Key name of a query is empty
+    MissingKeyName,
+    /// Bracket selector of a query is not valid
+    InvalidSelector,
+}
+
+impl std::fmt::Display for Syntax {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TrailingCharacter => write!(f, "unexpected trailing character"),
+            Self::UnexpectedCharacter => write!(f, "unexpected character"),
+            Self::UnexpectedEnd => write!(f, "unexpected end of text"),
+            Self::UnterminatedComment => write!(f, "unterminated comment"),
+            Self::Expected(token) => write!(f, "expected \"{token}\""),
+            Self::TooDeep { max_depth } => write!(f, "nesting is deeper than {max_depth}"),
+            Self::UnknownKeyword => write!(f, "unknown keyword"),
+            Self::InvalidSimple => write!(f, "invalid simple value"),
+            Self::InvalidInteger => write!(f, "invalid unsigned integer"),
+            Self::UnsignedOutOfRange => write!(f, "unsigned integer is out of range"),
+            Self::NegativeOutOfRange => write!(f, "negative integer is out of range"),
+            Self::InvalidFloating => write!(f, "invalid floating number"),
+            Self::InvalidNumber => write!(f, "invalid number"),
+            Self::UnterminatedText => write!(f, "unterminated text string"),
+            Self::InvalidEscape => write!(f, "invalid escape sequence"),
+            Self::UnescapedControl => write!(f, "unescaped control character"),
+            Self::UnterminatedUnicodeEscape => write!(f, "unterminated unicode escape"),
+            Self::InvalidUnicodeEscape => write!(f, "invalid unicode escape"),
+            Self::MissingLowSurrogate => write!(f, "missing low surrogate"),
+            Self::InvalidLowSurrogate => write!(f, "invalid low surrogate"),
+            Self::UnterminatedByte => write!(f, "unterminated byte string"),
+            Self::InvalidByte => write!(f, "invalid byte string"),
+            Self::InvalidUtf8 => write!(f, "concatenated text is not UTF-8"),
+            Self::InvalidChunk => write!(f, "indefinite string contains invalid chunk"),
+            Self::DuplicateKey { index } => {
+                write!(f, "map key of entry {index} is repeated multiple times")
+            }
+            Self::InvalidLossless => write!(f, "invalid lossless object"),
+            Self::MissingKeyName => write!(f, "missing key name"),
+            Self::InvalidSelector => write!(f, "invalid bracket selector"),
+        }
+    }
+}
+
+/// Reason why a data item cannot be converted to or from other value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Conversion {
+    /// Map key is not a text string
+    NonTextKey {
+        /// Kind of a map key
+        found: ItemKind,
+    },
+    /// Map contains two keys which are equal after conversion
+    DuplicateKey,
+    /// Integer is out of range of a converted value
+    IntegerOutOfRange,
+    /// Data item kind doesn't have an equivalent value
+    UnsupportedItem {
+        /// Kind of a data item
+        kind: ItemKind,
+    },
+    /// Value doesn't have an equivalent data item
+    UnsupportedValue,
+}
+
+impl std::fmt::Display for Conversion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NonTextKey { found } => write!(f, "map key {found} is not a text string"),
+            Self::DuplicateKey => write!(f, "map key is repeated after conversion"),
+            Self::IntegerOutOfRange => write!(f, "integer is out of range"),
+            Self::UnsupportedItem { kind } => write!(f, "{kind} doesn't have an equivalent value"),
+            Self::UnsupportedValue => write!(f, "value doesn't have an equivalent data item"),
+        }
+    }
+}
+
+/// Reason why a patch is malformed or cannot be applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PatchFailure {
+    /// Patch is not an array of operation
+    NotArray,
+    /// Operation of a patch is not valid
+    InvalidOperation {
+        /// Position of an operation inside a patch
+        index: usize,
+    },
+    /// Root of a data item is removed
+    RootRemoval,
+    /// Path segment of an operation cannot be found in a data item
+    PathNotFound {
+        /// Position of a segment which cannot be found inside a path
+        segment: usize,
+    },
+}
+
+impl std::fmt::Display for PatchFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotArray => write!(f, "patch is not an array"),
+            Self::InvalidOperation { index } => write!(f, "operation {index} is not valid"),
+            Self::RootRemoval => write!(f, "root cannot be removed"),
+            Self::PathNotFound { segment } => write!(f, "path segment {segment} not found"),
+        }
+    }
+}
+
+impl From<Utf8Error> for Error {
+    fn from(value: Utf8Error) -> Self {
+        Self::Utf8(value)
+    }
 }
 
 impl From<FromUtf8Error> for Error {
     fn from(value: FromUtf8Error) -> Self {
-        Self::FromUtf8(value)
+        Self::Utf8(value.utf8_error())
     }
 }
 
@@ -33,11 +486,17 @@ impl From<TryFromIntError> for Error {
     }
 }
 
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value.kind())
+    }
+}
+
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Incomplete => write!(f, "incomplete CBOR bytes"),
-            Self::FromUtf8(internal_err) => internal_err.fmt(f),
+            Self::Utf8(internal_err) => internal_err.fmt(f),
             Self::IncompleteIndefinite => write!(f, "incomplete indefinite length data"),
             Self::InvalidSimple => {
                 write!(
@@ -46,12 +505,68 @@ impl std::fmt::Display for Error {
                 )
             }
             Self::FromInt(internal_err) => internal_err.fmt(f),
-            Self::NotWellFormed(internal_message) => {
-                write!(f, "not well formed data : {internal_message}")
-            }
+            Self::NotWellFormed(malformed) => write!(f, "not well formed data : {malformed}"),
             Self::InvalidBreakStop => write!(f, "break stop position is invalid"),
+            Self::Io(kind) => write!(f, "io error : {kind}"),
+            Self::LimitExceeded(limit) => write!(f, "limit exceeded : {limit}"),
+            Self::InvalidTagContent { tag, expected } => {
+                write!(f, "invalid tag content : tag {tag} requires {expected}")
+            }
+            Self::InvalidHex => write!(f, "invalid hex encoded text"),
+            Self::InvalidDiagnostic { reason, position } => {
+                write!(
+                    f,
+                    "invalid diagnostic notation : {reason} at position {position}"
+                )
+            }
+            Self::InvalidJson { reason, position } => {
+                write!(f, "invalid JSON text : {reason} at position {position}")
+            }
+            Self::JsonConversion(conversion) => write!(f, "json conversion error : {conversion}"),
+            Self::ValueConversion(conversion) => {
+                write!(f, "value conversion error : {conversion}")
+            }
+            Self::TypeMismatch { expected, found } => {
+                write!(f, "type mismatch : expected {expected} but found {found}")
+            }
+            Self::PathNotFound { segment } => write!(f, "path segment {segment} not found"),
+            Self::InvalidQuery { reason, position } => {
+                write!(f, "invalid query : {reason} at position {position}")
+            }
+            Self::InvalidPatch(failure) => write!(f, "invalid patch : {failure}"),
+            Self::WrongTag {
+                expected,
+                found: Some(found),
+            } => write!(f, "expected tag {expected} but found tag {found}"),
+            Self::WrongTag {
+                expected,
+                found: None,
+            } => write!(f, "expected tag {expected} but found untagged data item"),
+            Self::BufferFull => write!(f, "buffer doesn't have enough space"),
+            #[cfg(feature = "serde")]
+            Self::SerdeMessage(message) => write!(f, "{message}"),
         }
     }
 }
 
 impl std::error::Error for Error {}
+
+#[cfg(feature = "serde")]
+impl serde::ser::Error for Error {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: std::fmt::Display,
+    {
+        Self::SerdeMessage(msg.to_string())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::de::Error for Error {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: std::fmt::Display,
+    {
+        Self::SerdeMessage(msg.to_string())
+    }
+}
@@ -0,0 +1,809 @@
+use std::cmp::Ordering;
+
+use crate::content::SimpleValue;
+use crate::data_item::DataItem;
+use crate::deterministic::DeterministicMode;
+use crate::error::{Error, ItemKind, Limit, Malformed};
+use crate::float16::{f16_bits_to_f64, f64_to_f16_bits};
+use crate::span::{Header, read_header};
+
+/// Single token of a CBOR bytes
+///
+/// An array, a map and a tag are represented by a header token followed by
+/// tokens of their nested data item. An indefinite length data item is
+/// represented by a start token followed by its nested data item or chunks and
+/// a [`Token::Break`]
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Token<'a> {
+    /// Unsigned integer
+    Unsigned(u64),
+    /// Negative integer stored in a same way as [`crate::DataItem::Signed`]
+    Signed(u64),
+    /// Definite length byte string or a chunk of an indefinite length byte
+    /// string
+    Byte(&'a [u8]),
+    /// Definite length text string or a chunk of an indefinite length text
+    /// string
+    Text(&'a str),
+    /// Start of an indefinite length byte string
+    ByteIndefinite,
+    /// Start of an indefinite length text string
+    TextIndefinite,
+    /// Header of an array with a number of item. `None` for an indefinite
+    /// length array
+    Array(Option<u64>),
+    /// Header of a map with a number of pair. `None` for an indefinite length
+    /// map
+    Map(Option<u64>),
+    /// Tag number which is followed by a tag content
+    Tag(u64),
+    /// Boolean value
+    Boolean(bool),
+    /// Null value
+    Null,
+    /// Undefined value
+    Undefined,
+    /// Floating point number
+    Floating(f64),
+    /// Other simple value
+    GenericSimple(SimpleValue),
+    /// Break stop of an indefinite length data item
+    Break,
+}
+
+/// Scalar data item which borrows a byte string and a text string from a source
+/// bytes
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Scalar<'a> {
+    /// Unsigned integer
+    Unsigned(u64),
+    /// Negative integer stored in a same way as [`crate::DataItem::Signed`]
+    Signed(u64),
+    /// Definite length byte string
+    Byte(&'a [u8]),
+    /// Definite length text string
+    Text(&'a str),
+    /// Boolean value
+    Boolean(bool),
+    /// Null value
+    Null,
+    /// Undefined value
+    Undefined,
+    /// Floating point number
+    Floating(f64),
+    /// Other simple value
+    GenericSimple(SimpleValue),
+}
+
+impl Scalar<'_> {
+    /// Convert into an owned data item
+    #[must_use]
+    pub fn to_data_item(&self) -> DataItem {
+        match self {
+            Self::Unsigned(number) => DataItem::Unsigned(*number),
+            Self::Signed(number) => DataItem::Signed(*number),
+            Self::Byte(bytes) => DataItem::from(*bytes),
+            Self::Text(text) => DataItem::from(*text),
+            Self::Boolean(bool_val) => DataItem::Boolean(*bool_val),
+            Self::Null => DataItem::Null,
+            Self::Undefined => DataItem::Undefined,
+            Self::Floating(number) => DataItem::Floating(*number),
+            Self::GenericSimple(simple_value) => DataItem::GenericSimple(simple_value.clone()),
+        }
+    }
+}
+
+/// Decode a first data item of provided bytes as a scalar without allocating
+///
+/// # Example
+/// ```rust
+/// use cbor_next::fixed::{Scalar, decode_scalar};
+///
+/// assert_eq!(
+///     decode_scalar(&[0x19, 0x03, 0xe8]),
+///     Ok(Scalar::Unsigned(1000))
+/// );
+/// assert_eq!(decode_scalar(&[0x62, 0x6f, 0x6b]), Ok(Scalar::Text("ok")));
+/// assert_eq!(
+///     decode_scalar(&[0xf9, 0x3e, 0x00]),
+///     Ok(Scalar::Floating(1.5))
+/// );
+/// assert!(decode_scalar(&[0x81, 0x01]).is_err());
+/// ```
+///
+/// # Errors
+/// If bytes are not well formed or a first data item is an array, a map, a
+/// tag or an indefinite length string
+pub fn decode_scalar(bytes: &[u8]) -> Result<Scalar<'_>, Error> {
+    let scalar = match FixedDecoder::<0>::new(bytes).next_token() {
+        Ok(Some(Token::Unsigned(number))) => Scalar::Unsigned(number),
+        Ok(Some(Token::Signed(number))) => Scalar::Signed(number),
+        Ok(Some(Token::Byte(bytes))) => Scalar::Byte(bytes),
+        Ok(Some(Token::Text(text))) => Scalar::Text(text),
+        Ok(Some(Token::Boolean(bool_val))) => Scalar::Boolean(bool_val),
+        Ok(Some(Token::Null)) => Scalar::Null,
+        Ok(Some(Token::Undefined)) => Scalar::Undefined,
+        Ok(Some(Token::Floating(number))) => Scalar::Floating(number),
+        Ok(Some(Token::GenericSimple(simple_value))) => Scalar::GenericSimple(simple_value),
+        Ok(None) => return Err(Error::Incomplete),
+        Ok(Some(_)) | Err(Error::LimitExceeded(_)) => {
+            return Err(Error::TypeMismatch {
+                expected: ItemKind::Scalar,
+                found: ItemKind::from_major_type(bytes[0] >> 5),
+            });
+        }
+        Err(error) => return Err(error),
+    };
+    Ok(scalar)
+}
+
+/// Encoder which writes tokens into a caller provided buffer without
+/// allocating
+///
+/// Encoder doesn't check that written tokens form a well formed data item
+///
+/// # Example
+/// ```rust
+/// use cbor_next::DataItem;
+/// use cbor_next::fixed::{FixedEncoder, Token};
+///
+/// let mut buffer = [0; 16];
+/// let mut encoder = FixedEncoder::new(&mut buffer);
+/// encoder
+///     .push(&Token::Map(Some(1)))
+///     .unwrap()
+///     .push(&Token::Text("a"))
+///     .unwrap()
+///     .push(&Token::Floating(1.5))
+///     .unwrap();
+/// assert_eq!(
+///     encoder.as_bytes(),
+///     DataItem::from_diagnostic(r#"{"a": 1.5}"#).unwrap().encode()
+/// );
+/// assert!(
+///     FixedEncoder::new(&mut [0; 2])
+///         .push(&Token::Text("abc"))
+///         .is_err()
+/// );
+/// ```
+#[derive(Debug)]
+pub struct FixedEncoder<'a> {
+    buffer: &'a mut [u8],
+    position: usize,
+}
+
+impl<'a> FixedEncoder<'a> {
+    /// Create an encoder which writes into provided buffer
+    #[must_use]
+    pub fn new(buffer: &'a mut [u8]) -> Self {
+        Self {
+            buffer,
+            position: 0,
+        }
+    }
+
+    /// Write a token. A floating point number is written in a shortest form
+    /// which preserves its value same as [`crate::DataItem::encode`]
+    ///
+    /// # Errors
+    /// If a buffer doesn't have enough space for a token. Nothing is written
+    /// in that case
+    pub fn push(&mut self, token: &Token<'_>) -> Result<&mut Self, Error> {
+        let start = self.position;
+        let result = self.write_token(token);
+        if result.is_err() {
+            self.position = start;
+        }
+        result.map(|()| self)
+    }
+
+    /// Get bytes written so far
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buffer[..self.position]
+    }
+
+    /// Consume an encoder and get bytes written into a buffer
+    #[must_use]
+    pub fn finish(self) -> &'a [u8] {
+        &self.buffer[..self.position]
+    }
+
+    fn write_token(&mut self, token: &Token<'_>) -> Result<(), Error> {
+        match token {
+            Token::Unsigned(number) => self.write_header(0, *number),
+            Token::Signed(number) => self.write_header(1, *number),
+            Token::Byte(bytes) => {
+                self.write_header(2, bytes.len() as u64)?;
+                self.write(bytes)
+            }
+            Token::Text(text) => {
+                self.write_header(3, text.len() as u64)?;
+                self.write(text.as_bytes())
+            }
+            Token::ByteIndefinite => self.write(&[0x5f]),
+            Token::TextIndefinite => self.write(&[0x7f]),
+            Token::Array(Some(length)) => self.write_header(4, *length),
+            Token::Array(None) => self.write(&[0x9f]),
+            Token::Map(Some(length)) => self.write_header(5, *length),
+            Token::Map(None) => self.write(&[0xbf]),
+            Token::Tag(number) => self.write_header(6, *number),
+            Token::Boolean(false) => self.write(&[0xf4]),
+            Token::Boolean(true) => self.write(&[0xf5]),
+            Token::Null => self.write(&[0xf6]),
+            Token::Undefined => self.write(&[0xf7]),
+            Token::Floating(number) => self.write_floating(*number),
+            Token::GenericSimple(simple_value) => {
+                match simple_value.value() {
+                    value @ 0..=23 => self.write(&[0xe0 | value]),
+                    value => self.write(&[0xf8, value]),
+                }
+            }
+            Token::Break => self.write(&[0xff]),
+        }
+    }
+
+    fn write_header(&mut self, major_type: u8, number: u64) -> Result<(), Error> {
+        let shifted_major_type = major_type << 5;
+        if let Ok(u8_value) = u8::try_from(number) {
+            if u8_value <= 23 {
+                self.write(&[shifted_major_type | u8_value])
+            } else {
+                self.write(&[shifted_major_type | 0x18, u8_value])
+            }
+        } else if let Ok(u16_value) = u16::try_from(number) {
+            self.write(&[shifted_major_type | 0x19])?;
+            self.write(&u16_value.to_be_bytes())
+        } else if let Ok(u32_value) = u32::try_from(number) {
+            self.write(&[shifted_major_type | 0x1a])?;
+            self.write(&u32_value.to_be_bytes())
+        } else {
+            self.write(&[shifted_major_type | 0x1b])?;
+            self.write(&number.to_be_bytes())
+        }
+    }
+
+    fn write_floating(&mut self, number: f64) -> Result<(), Error> {
+        #[expect(
+            clippy::float_cmp,
+            reason = "we want to compare without margin or error"
+        )]
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "we only want to check truncation data loss"
+        )]
+        if let Some(f16_bits) = f64_to_f16_bits(number) {
+            self.write(&[0xf9])?;
+            self.write(&f16_bits.to_be_bytes())
+        } else if f64::from(number as f32) == number {
+            self.write(&[0xfa])?;
+            self.write(&(number as f32).to_be_bytes())
+        } else {
+            self.write(&[0xfb])?;
+            self.write(&number.to_be_bytes())
+        }
+    }
+
+    /// Write a data item which is not an array, a map or a tag in a
+    /// deterministic form where a byte string and a text string are written
+    /// with a definite length
+    fn write_deterministic_scalar(&mut self, data_item: &DataItem) -> Result<(), Error> {
+        match data_item {
+            DataItem::Byte(byte_content) => {
+                self.write_header(2, byte_content.len() as u64)?;
+                for chunk in byte_content.chunk() {
+                    self.write(chunk)?;
+                }
+                Ok(())
+            }
+            DataItem::Text(text_content) => {
+                self.write_header(3, text_content.len() as u64)?;
+                for chunk in text_content.chunk() {
+                    self.write(chunk.as_bytes())?;
+                }
+                Ok(())
+            }
+            DataItem::Unsigned(number) => self.write_header(0, *number),
+            DataItem::Signed(number) => self.write_header(1, *number),
+            DataItem::Floating(number) => self.write_floating(*number),
+            DataItem::Boolean(bool_val) => self.write_token(&Token::Boolean(*bool_val)),
+            DataItem::Null => self.write_token(&Token::Null),
+            DataItem::Undefined => self.write_token(&Token::Undefined),
+            DataItem::GenericSimple(simple_value) => {
+                self.write_token(&Token::GenericSimple(simple_value.clone()))
+            }
+            DataItem::Array(_) | DataItem::Map(_) | DataItem::Tag(_) => {
+                unreachable!("nested data item is written by a caller")
+            }
+        }
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        let end = self.position + bytes.len();
+        self.buffer
+            .get_mut(self.position..end)
+            .ok_or(Error::BufferFull)?
+            .copy_from_slice(bytes);
+        self.position = end;
+        Ok(())
+    }
+}
+
+/// Maximum nesting of an array and a map written by [`encode_deterministic`]
+pub(crate) const ENCODE_DEPTH: usize = 64;
+
+/// Array or map whose entries are still being written by
+/// [`encode_deterministic`]
+enum EncodeFrame<'a> {
+    /// Remaining items of an array
+    Array(std::slice::Iter<'a, DataItem>),
+    /// Remaining entries of a map along with positions inside a buffer
+    Map {
+        entries: indexmap::map::Iter<'a, DataItem, DataItem>,
+        header_start: usize,
+        start: usize,
+        entry_start: usize,
+        length: u64,
+        value: Option<&'a DataItem>,
+    },
+}
+
+/// Write a deterministic form of a data item in provided mode without
+/// allocating. Each map entry is rotated into its sorted position inside a
+/// buffer right after it is written, where a later entry wins when two keys
+/// have a same deterministic encoding. Nesting is tracked in a fixed stack of
+/// [`ENCODE_DEPTH`] entries instead of recursing
+pub(crate) fn encode_deterministic(
+    root: &DataItem,
+    mode: DeterministicMode,
+    encoder: &mut FixedEncoder<'_>,
+) -> Result<(), Error> {
+    let mut stack: [Option<EncodeFrame<'_>>; ENCODE_DEPTH] = [const { None }; ENCODE_DEPTH];
+    let mut depth = 0;
+    let mut next = Some(root);
+    loop {
+        if let Some(data_item) = next.take() {
+            let frame = match data_item {
+                DataItem::Map(map_content) => {
+                    let header_start = encoder.position;
+                    encoder.write_header(5, map_content.len() as u64)?;
+                    Some(EncodeFrame::Map {
+                        entries: map_content.iter(),
+                        header_start,
+                        start: encoder.position,
+                        entry_start: encoder.position,
+                        length: 0,
+                        value: None,
+                    })
+                }
+                DataItem::Array(array_content) => {
+                    encoder.write_header(4, array_content.len() as u64)?;
+                    Some(EncodeFrame::Array(array_content.iter()))
+                }
+                DataItem::Tag(tag_content) => {
+                    encoder.write_header(6, tag_content.number())?;
+                    next = Some(tag_content.content());
+                    continue;
+                }
+                scalar => {
+                    encoder.write_deterministic_scalar(scalar)?;
+                    None
+                }
+            };
+            if let Some(frame) = frame {
+                *stack.get_mut(depth).ok_or_else(|| {
+                    Error::LimitExceeded(Limit::Depth {
+                        depth: depth + 1,
+                        max_depth: ENCODE_DEPTH,
+                    })
+                })? = Some(frame);
+                depth += 1;
+            }
+        }
+        let Some(frame) = depth.checked_sub(1).and_then(|index| stack[index].as_mut()) else {
+            return Ok(());
+        };
+        match frame {
+            EncodeFrame::Array(items) => {
+                next = items.next();
+                if next.is_none() {
+                    depth -= 1;
+                }
+            }
+            EncodeFrame::Map {
+                entries,
+                header_start,
+                start,
+                entry_start,
+                length,
+                value,
+            } => {
+                if let Some(value) = value.take() {
+                    next = Some(value);
+                    continue;
+                }
+                if encoder.position > *entry_start
+                    && !insert_entry(encoder, *start, *entry_start, mode)?
+                {
+                    *length += 1;
+                }
+                if let Some((entry_key, entry_value)) = entries.next() {
+                    *entry_start = encoder.position;
+                    *value = Some(entry_value);
+                    next = Some(entry_key);
+                    continue;
+                }
+                // header of a map whose repeated key is removed can be shorter
+                // than a header written before its entries
+                let end = encoder.position;
+                encoder.position = *header_start;
+                encoder.write_header(5, *length)?;
+                let shift = *start - encoder.position;
+                encoder.buffer[encoder.position..end].rotate_left(shift);
+                encoder.position = end - shift;
+                depth -= 1;
+            }
+        }
+    }
+}
+
+/// Rotate a last written map entry into its position among sorted entries
+/// between provided start and an entry start. Returns true if an earlier entry
+/// with a same key is removed
+fn insert_entry(
+    encoder: &mut FixedEncoder<'_>,
+    start: usize,
+    entry_start: usize,
+    mode: DeterministicMode,
+) -> Result<bool, Error> {
+    let key_end = skip_definite(encoder.buffer, entry_start)?;
+    let entry_length = encoder.position - entry_start;
+    let mut cursor = start;
+    while cursor < entry_start {
+        let cursor_key_end = skip_definite(encoder.buffer, cursor)?;
+        let cursor_entry_end = skip_definite(encoder.buffer, cursor_key_end)?;
+        match mode.compare(
+            &encoder.buffer[cursor..cursor_key_end],
+            &encoder.buffer[entry_start..key_end],
+        ) {
+            Ordering::Less => cursor = cursor_entry_end,
+            Ordering::Equal => {
+                let removed_length = cursor_entry_end - cursor;
+                encoder.buffer[cursor..encoder.position].rotate_left(removed_length);
+                encoder.position -= removed_length;
+                encoder.buffer[cursor..encoder.position].rotate_right(entry_length);
+                return Ok(true);
+            }
+            Ordering::Greater => break,
+        }
+    }
+    encoder.buffer[cursor..encoder.position].rotate_right(entry_length);
+    Ok(false)
+}
+
+/// Get an end position of a data item without an indefinite length data item
+/// starting at provided position
+fn skip_definite(bytes: &[u8], mut position: usize) -> Result<usize, Error> {
+    let mut remaining = 1u64;
+    while remaining > 0 {
+        remaining -= 1;
+        let Header {
+            major_type,
+            argument,
+        } = read_header(bytes, &mut position)?;
+        let argument = argument.ok_or(Error::InvalidBreakStop)?;
+        match major_type {
+            2 | 3 => position += usize::try_from(argument)?,
+            4 => remaining += argument,
+            5 => remaining += argument * 2,
+            6 => remaining += 1,
+            _ => {}
+        }
+    }
+    Ok(position)
+}
+
+/// Nested data item which is still being decoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frame {
+    /// Number of data item left in an array, a map or a tag. `None` for an
+    /// indefinite length array or map
+    Items(Option<u64>),
+    /// Indefinite length byte string or text string of a major type
+    Chunks(u8),
+}
+
+/// Decoder which reads tokens from a CBOR bytes without allocating
+///
+/// Nesting of an array, a map, a tag and an indefinite length string is
+/// tracked in a fixed stack of `DEPTH` entries kept inside a decoder, so a
+/// decoder can be placed on a stack. Byte string and text string tokens borrow
+/// from a source bytes. A sequence of data item is decoded one after another.
+/// Only an error message allocates
+///
+/// # Example
+/// ```rust
+/// use cbor_next::fixed::{FixedDecoder, Token};
+///
+/// let bytes = [0x82, 0x01, 0x9f, 0x61, 0x61, 0xff];
+/// let tokens = FixedDecoder::<2>::new(&bytes)
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+/// assert_eq!(
+///     tokens,
+///     [
+///         Token::Array(Some(2)),
+///         Token::Unsigned(1),
+///         Token::Array(None),
+///         Token::Text("a"),
+///         Token::Break,
+///     ]
+/// );
+/// assert!(FixedDecoder::<1>::new(&bytes).any(|token| token.is_err()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct FixedDecoder<'a, const DEPTH: usize> {
+    bytes: &'a [u8],
+    position: usize,
+    state: ResumableDecoder<DEPTH>,
+}
+
+impl<'a, const DEPTH: usize> FixedDecoder<'a, DEPTH> {
+    /// Create a decoder over provided bytes
+    #[must_use]
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            position: 0,
+            state: ResumableDecoder::new(),
+        }
+    }
+
+    /// Get a number of byte read so far
+    #[must_use]
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Read a next token. Returns `None` once all data item are decoded
+    ///
+    /// # Errors
+    /// If bytes are not well formed or nesting is deeper than `DEPTH`
+    pub fn next_token(&mut self) -> Result<Option<Token<'a>>, Error> {
+        let bytes = &self.bytes[self.position..];
+        if bytes.is_empty() {
+            return match self.state.frame() {
+                None => Ok(None),
+                Some(Frame::Items(Some(_))) => Err(Error::Incomplete),
+                Some(_) => Err(Error::IncompleteIndefinite),
+            };
+        }
+        match self.state.feed(bytes)? {
+            Step::Token(token, consumed) => {
+                self.position += consumed;
+                Ok(Some(token))
+            }
+            Step::NeedMore(_) => Err(Error::Incomplete),
+        }
+    }
+}
+
+impl<'a, const DEPTH: usize> Iterator for FixedDecoder<'a, DEPTH> {
+    type Item = Result<Token<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let token = self.next_token().transpose();
+        if matches!(token, Some(Err(_))) {
+            // stop decoding after an error instead of reading a broken state
+            self.position = self.bytes.len();
+            self.state = ResumableDecoder::new();
+        }
+        token
+    }
+}
+
+/// Result of feeding bytes to a [`ResumableDecoder`]
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Step<'a> {
+    /// Token decoded from a start of provided bytes and a number of byte it
+    /// used. Only those bytes should be dropped before a next feed
+    Token(Token<'a>, usize),
+    /// Number of additional byte required after provided bytes to complete a
+    /// current header or payload. Nothing is consumed and a decoder state is
+    /// left unchanged
+    NeedMore(usize),
+}
+
+/// Decoder whose state persists between calls so bytes can be fed as they
+/// arrive
+///
+/// A decoder only keeps a nesting stack of `DEPTH` entries and never buffers
+/// bytes. Each call to [`ResumableDecoder::feed`] receives bytes starting at a
+/// next unread byte and either decodes one token or reports how many more byte
+/// are required, in which case a caller keeps those bytes and feeds them again
+/// once more are received. A whole payload of a definite length byte string or
+/// text string must be available at once since a token borrows from it
+///
+/// # Example
+/// ```rust
+/// use cbor_next::fixed::{ResumableDecoder, Step, Token};
+///
+/// let mut decoder = ResumableDecoder::<1>::new();
+/// assert_eq!(
+///     decoder.feed(&[0x82, 0x19]).unwrap(),
+///     Step::Token(Token::Array(Some(2)), 1)
+/// );
+/// assert_eq!(decoder.feed(&[0x19]).unwrap(), Step::NeedMore(2));
+/// assert_eq!(
+///     decoder.feed(&[0x19, 0x01, 0x00, 0x62]).unwrap(),
+///     Step::Token(Token::Unsigned(256), 3)
+/// );
+/// assert_eq!(decoder.feed(&[0x62, 0x6f]).unwrap(), Step::NeedMore(1));
+/// assert_eq!(
+///     decoder.feed(&[0x62, 0x6f, 0x6b]).unwrap(),
+///     Step::Token(Token::Text("ok"), 3)
+/// );
+/// assert_eq!(decoder.depth(), 0);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ResumableDecoder<const DEPTH: usize> {
+    stack: [Frame; DEPTH],
+    depth: usize,
+}
+
+impl<const DEPTH: usize> ResumableDecoder<DEPTH> {
+    /// Create a decoder which expects a start of a data item
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            stack: [Frame::Items(Some(0)); DEPTH],
+            depth: 0,
+        }
+    }
+
+    /// Get a number of array, map, tag and indefinite length string which are
+    /// not yet complete. Zero when a decoder is between data item
+    #[must_use]
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Decode a next token from a start of provided bytes
+    ///
+    /// # Errors
+    /// If bytes are not well formed or nesting is deeper than `DEPTH`. A
+    /// decoder state should not be reused after an error
+    pub fn feed<'a>(&mut self, bytes: &'a [u8]) -> Result<Step<'a>, Error> {
+        let frame = self.frame();
+        let Some(&initial) = bytes.first() else {
+            return Ok(Step::NeedMore(1));
+        };
+        if initial == 255 {
+            return match frame {
+                Some(Frame::Items(None) | Frame::Chunks(_)) => {
+                    self.depth -= 1;
+                    self.close_finished();
+                    Ok(Step::Token(Token::Break, 1))
+                }
+                _ => Err(Error::InvalidBreakStop),
+            };
+        }
+        let additional = initial & 0x1f;
+        let header_length = match additional {
+            24..=27 => 1 + (1 << (additional - 24)),
+            _ => 1,
+        };
+        if bytes.len() < header_length {
+            return Ok(Step::NeedMore(header_length - bytes.len()));
+        }
+        let mut position = 0;
+        let Header {
+            major_type,
+            argument,
+        } = read_header(bytes, &mut position)?;
+        if let Some(Frame::Chunks(chunk_major_type)) = frame
+            && (chunk_major_type != major_type || argument.is_none())
+        {
+            return Err(Error::NotWellFormed(Malformed::InvalidChunk {
+                expected: chunk_major_type,
+                found: major_type,
+            }));
+        }
+        let (token, pushed) = match (major_type, argument) {
+            (0, Some(number)) => (Token::Unsigned(number), None),
+            (1, Some(number)) => (Token::Signed(number), None),
+            (2 | 3, Some(length)) => {
+                let length = usize::try_from(length)?;
+                let available = bytes.len() - position;
+                if available < length {
+                    return Ok(Step::NeedMore(length - available));
+                }
+                let payload = &bytes[position..position + length];
+                position += length;
+                if major_type == 2 {
+                    (Token::Byte(payload), None)
+                } else {
+                    let text = std::str::from_utf8(payload)
+                        .map_err(|error| Error::NotWellFormed(Malformed::InvalidUtf8(error)))?;
+                    (Token::Text(text), None)
+                }
+            }
+            (2, None) => (Token::ByteIndefinite, Some(Frame::Chunks(2))),
+            (3, None) => (Token::TextIndefinite, Some(Frame::Chunks(3))),
+            (4, length) => (Token::Array(length), Some(Frame::Items(length))),
+            (5, length) => {
+                let items = length
+                    .map(|length| length.checked_mul(2).ok_or(Error::Incomplete))
+                    .transpose()?;
+                (Token::Map(length), Some(Frame::Items(items)))
+            }
+            (6, Some(number)) => (Token::Tag(number), Some(Frame::Items(Some(1)))),
+            (7, Some(argument)) => (simple_or_floating(additional, argument)?, None),
+            _ => {
+                return Err(Error::NotWellFormed(Malformed::IndefiniteLength {
+                    major_type,
+                }));
+            }
+        };
+        if pushed.is_some() && self.depth == DEPTH {
+            return Err(Error::LimitExceeded(Limit::Depth {
+                depth: self.depth + 1,
+                max_depth: DEPTH,
+            }));
+        }
+        if let Some(Frame::Items(Some(remaining))) = frame {
+            self.stack[self.depth - 1] = Frame::Items(Some(remaining - 1));
+        }
+        if let Some(pushed) = pushed {
+            self.stack[self.depth] = pushed;
+            self.depth += 1;
+        }
+        self.close_finished();
+        Ok(Step::Token(token, position))
+    }
+
+    /// Get an innermost incomplete frame
+    fn frame(&self) -> Option<Frame> {
+        self.depth.checked_sub(1).map(|index| self.stack[index])
+    }
+
+    /// Drop every frame whose all items are decoded
+    fn close_finished(&mut self) {
+        while self.depth > 0 && self.stack[self.depth - 1] == Frame::Items(Some(0)) {
+            self.depth -= 1;
+        }
+    }
+}
+
+impl<const DEPTH: usize> Default for ResumableDecoder<DEPTH> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn simple_or_floating<'a>(additional: u8, argument: u64) -> Result<Token<'a>, Error> {
+    match additional {
+        20 => Ok(Token::Boolean(false)),
+        21 => Ok(Token::Boolean(true)),
+        22 => Ok(Token::Null),
+        23 => Ok(Token::Undefined),
+        0..=19 | 24 => {
+            u8::try_from(argument)
+                .ok()
+                .filter(|value| additional < 24 || *value >= 32)
+                .and_then(SimpleValue::new)
+                .map(Token::GenericSimple)
+                .ok_or(Error::InvalidSimple)
+        }
+        25 => Ok(Token::Floating(f16_bits_to_f64(u16::try_from(argument)?))),
+        26 => {
+            Ok(Token::Floating(f64::from(f32::from_bits(u32::try_from(
+                argument,
+            )?))))
+        }
+        _ => Ok(Token::Floating(f64::from_bits(argument))),
+    }
+}
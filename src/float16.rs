@@ -0,0 +1,52 @@
+/// Convert bits of a half precision floating point number to a double
+/// precision floating point number. Every half precision value including a NaN
+/// payload is representable so conversion is exact
+pub(crate) fn f16_bits_to_f64(bits: u16) -> f64 {
+    let sign = u64::from(bits & 0x8000) << 48;
+    let exponent = u64::from((bits >> 10) & 0x1f);
+    let mantissa = u64::from(bits & 0x3ff);
+    let f64_bits = match (exponent, mantissa) {
+        (0, 0) => sign,
+        (0, _) => {
+            // subnormal half is normalized by moving a highest set bit of a
+            // mantissa to an implicit leading bit
+            let highest_bit = u64::from(mantissa.ilog2());
+            let fraction = (mantissa ^ (1 << highest_bit)) << (52 - highest_bit);
+            sign | (highest_bit + 1023 - 24) << 52 | fraction
+        }
+        (31, 0) => sign | 0x7ff0_0000_0000_0000,
+        (31, _) => sign | 0x7ff8_0000_0000_0000 | mantissa << 42,
+        _ => sign | (exponent + 1023 - 15) << 52 | mantissa << 42,
+    };
+    f64::from_bits(f64_bits)
+}
+
+/// Get bits of a half precision floating point number which represents a
+/// provided number exactly. Returns `None` for a NaN and for a number which
+/// loses precision or range as a half precision number
+pub(crate) fn f64_to_f16_bits(number: f64) -> Option<u16> {
+    let f64_bits = number.to_bits();
+    let sign = u16::from(number.is_sign_negative()) << 15;
+    let exponent = (f64_bits >> 52) & 0x7ff;
+    let mantissa = f64_bits & 0x000f_ffff_ffff_ffff;
+    match exponent {
+        0 if mantissa == 0 => Some(sign),
+        0x7ff if mantissa == 0 => Some(sign | 0x7c00),
+        // normal half with an unbiased exponent between -14 and 15
+        1009..=1038 if mantissa.trailing_zeros() >= 42 => {
+            let half_exponent = u16::try_from(exponent + 15 - 1023).ok()?;
+            let half_mantissa = u16::try_from(mantissa >> 42).ok()?;
+            Some(sign | half_exponent << 10 | half_mantissa)
+        }
+        // subnormal half with an unbiased exponent between -24 and -15
+        999..=1008 => {
+            let shift = 1023 + 28 - exponent;
+            let significand = mantissa | 1 << 52;
+            (significand.trailing_zeros() >= u32::try_from(shift).ok()?)
+                .then(|| u16::try_from(significand >> shift).ok())
+                .flatten()
+                .map(|half_mantissa| sign | half_mantissa)
+        }
+        _ => None,
+    }
+}
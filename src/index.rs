@@ -1,3 +1,9 @@
+use std::hash::{Hash, Hasher};
+use std::mem;
+
+use indexmap::Equivalent;
+
+use crate::content::{TextContent, hash_text};
 use crate::data_item::DataItem;
 use crate::index::private::Sealed;
 
@@ -7,6 +13,7 @@ mod private {
     pub trait Sealed {}
     impl Sealed for usize {}
     impl Sealed for DataItem {}
+    impl Sealed for &str {}
 }
 
 /// Trait which is used to get a data item from data item
@@ -46,6 +53,45 @@ where
     /// assert_eq!(array_value.get(0), Some(&DataItem::Unsigned(20)));
     /// ```
     fn get_mut(&mut self, idx: Idx) -> Option<&mut Self>;
+
+    /// Remove a index value and return it. Array item after a removed item and
+    /// map entry after a removed entry are shifted
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::{DataItem, Get};
+    ///
+    /// let mut array_value = DataItem::from(vec![1, 2, 3]);
+    /// assert_eq!(array_value.remove(0), Some(DataItem::from(1)));
+    /// assert_eq!(array_value, DataItem::from(vec![2, 3]));
+    /// let mut map_value = DataItem::from(vec![("a", 1), ("b", 2)]);
+    /// assert_eq!(map_value.remove("a"), Some(DataItem::from(1)));
+    /// assert_eq!(map_value.remove(DataItem::from("c")), None);
+    /// ```
+    fn remove(&mut self, idx: Idx) -> Option<Self>
+    where
+        Self: Sized;
+
+    /// Get a index value after removing tags wrapping a data item. For a map
+    /// key tags wrapping both a stored key and a provided key are also
+    /// ignored while comparing. Returned value keeps its tags
+    ///
+    /// # Example
+    /// ```rust
+    /// use cbor_next::{DataItem, Get};
+    ///
+    /// let value = DataItem::from_diagnostic(r#"1(["a", {42("id"): 1}])"#).unwrap();
+    /// assert_eq!(value.get(0), None);
+    /// assert_eq!(value.get_ignoring_tags(0), Some(&DataItem::from("a")));
+    /// let map_value = value.get_ignoring_tags(1).unwrap();
+    /// assert_eq!(map_value.get("id"), None);
+    /// assert_eq!(map_value.get_ignoring_tags("id"), Some(&DataItem::from(1)));
+    /// assert_eq!(
+    ///     map_value.get_ignoring_tags(DataItem::from_diagnostic(r#"7("id")"#).unwrap()),
+    ///     Some(&DataItem::from(1))
+    /// );
+    /// ```
+    fn get_ignoring_tags(&self, idx: Idx) -> Option<&Self>;
 }
 
 impl Get<usize> for DataItem {
@@ -62,6 +108,17 @@ impl Get<usize> for DataItem {
             _ => None,
         }
     }
+
+    fn remove(&mut self, idx: usize) -> Option<Self> {
+        match self {
+            Self::Array(a) => a.remove_content(idx),
+            _ => None,
+        }
+    }
+
+    fn get_ignoring_tags(&self, idx: usize) -> Option<&Self> {
+        untagged(self).get(idx)
+    }
 }
 
 impl Get<DataItem> for DataItem {
@@ -74,10 +131,153 @@ impl Get<DataItem> for DataItem {
 
     fn get_mut(&mut self, idx: DataItem) -> Option<&mut Self> {
         match self {
-            Self::Map(m) => m.map_mut().get_mut(&idx),
+            Self::Map(m) => m.get_mut(&idx),
+            _ => None,
+        }
+    }
+
+    fn remove(&mut self, idx: DataItem) -> Option<Self> {
+        match self {
+            Self::Map(m) => m.shift_remove(&idx),
+            _ => None,
+        }
+    }
+
+    fn get_ignoring_tags(&self, idx: DataItem) -> Option<&Self> {
+        match untagged(self) {
+            Self::Map(m) => m.get_ignoring_tags(&idx),
+            _ => None,
+        }
+    }
+}
+
+/// Get a value of a text string key without creating a data item for a key.
+/// Text string key made of multiple chunks is also matched
+///
+/// # Example
+/// ```rust
+/// use cbor_next::{DataItem, Get};
+///
+/// let value = DataItem::from(vec![("name", 1)]);
+/// assert_eq!(value.get("name"), Some(&DataItem::Unsigned(1)));
+/// assert_eq!(value["name"], DataItem::Unsigned(1));
+/// assert_eq!(value.get("other"), None);
+/// ```
+impl Get<&str> for DataItem {
+    fn get(&self, idx: &str) -> Option<&Self> {
+        match self {
+            Self::Map(m) => m.get_str(idx),
             _ => None,
         }
     }
+
+    fn get_mut(&mut self, idx: &str) -> Option<&mut Self> {
+        match self {
+            Self::Map(m) => m.get_str_mut(idx),
+            _ => None,
+        }
+    }
+
+    fn remove(&mut self, idx: &str) -> Option<Self> {
+        match self {
+            Self::Map(m) => m.shift_remove_str(idx),
+            _ => None,
+        }
+    }
+
+    fn get_ignoring_tags(&self, idx: &str) -> Option<&Self> {
+        match untagged(self) {
+            Self::Map(m) => {
+                m.map()
+                    .iter()
+                    .find_map(|(key, value)| is_text_key(untagged(key), idx).then_some(value))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Get a data item after removing all tags wrapping it
+pub(crate) fn untagged(data_item: &DataItem) -> &DataItem {
+    let mut data_item = data_item;
+    while let DataItem::Tag(tag_content) = data_item {
+        data_item = tag_content.content();
+    }
+    data_item
+}
+
+/// Check whether a key is a text string equal to provided text
+pub(crate) fn is_text_key(key: &DataItem, text: &str) -> bool {
+    let DataItem::Text(text_content) = key else {
+        return false;
+    };
+    let mut rest = text;
+    for chunk in text_content.chunk() {
+        match rest.strip_prefix(&**chunk) {
+            Some(remaining) => rest = remaining,
+            None => return false,
+        }
+    }
+    rest.is_empty()
+}
+
+/// Borrowed text which finds a text string key of a map without constructing
+/// a key. It has a same hash as a text string data item with an equal text
+pub(crate) struct TextKey<'a>(pub(crate) &'a str);
+
+impl Hash for TextKey<'_> {
+    fn hash<H>(&self, state: &mut H)
+    where
+        H: Hasher,
+    {
+        mem::discriminant(&DataItem::Text(TextContent::default())).hash(state);
+        hash_text([self.0.as_bytes()].into_iter(), state);
+    }
+}
+
+impl Equivalent<DataItem> for TextKey<'_> {
+    fn equivalent(&self, key: &DataItem) -> bool {
+        is_text_key(key, self.0)
+    }
+}
+
+/// Segment of a path used by [`DataItem::get_path`]
+///
+/// # Example
+/// ```rust
+/// use cbor_next::{DataItem, PathSegment};
+///
+/// assert_eq!(
+///     PathSegment::from("a"),
+///     PathSegment::Key(DataItem::from("a"))
+/// );
+/// assert_eq!(PathSegment::from(1), PathSegment::Index(1));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PathSegment {
+    /// Key of a map
+    Key(DataItem),
+    /// Index of an array
+    Index(usize),
+}
+
+impl From<usize> for PathSegment {
+    fn from(value: usize) -> Self {
+        Self::Index(value)
+    }
+}
+
+impl From<DataItem> for PathSegment {
+    fn from(value: DataItem) -> Self {
+        Self::Key(value)
+    }
+}
+
+impl From<&str> for PathSegment {
+    fn from(value: &str) -> Self {
+        Self::Key(value.into())
+    }
 }
 
 impl<Idx> std::ops::Index<Idx> for DataItem
@@ -0,0 +1,29 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Maximum number of byte of a string which is interned
+const MAX_INTERNED_LEN: usize = 64;
+
+/// Table of interned string which lives for a single decode call. Every
+/// interned string is reference counted so it is freed once a table and all
+/// data items which share it are dropped
+#[derive(Default)]
+pub(crate) struct Interner {
+    table: HashSet<Arc<str>>,
+}
+
+impl Interner {
+    /// Get a shared copy of provided string. Returns `None` when a string is
+    /// too long to be interned
+    pub(crate) fn intern(&mut self, string: &str) -> Option<Arc<str>> {
+        if string.len() > MAX_INTERNED_LEN {
+            return None;
+        }
+        if let Some(interned) = self.table.get(string) {
+            return Some(Arc::clone(interned));
+        }
+        let interned = Arc::<str>::from(string);
+        self.table.insert(Arc::clone(&interned));
+        Some(interned)
+    }
+}
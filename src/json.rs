@@ -0,0 +1,779 @@
+use std::fmt::Write as _;
+
+use indexmap::IndexMap;
+#[cfg(feature = "json")]
+use serde_json::{Map, Number, Value};
+
+use crate::content::{MapContent, SimpleValue, TagContent};
+use crate::data_item::DataItem;
+use crate::encoding::{
+    BASE64_ALPHABET, BASE64URL_ALPHABET, decode_base64, encode_base64, encode_hex,
+};
+use crate::error::{Conversion, Error, ItemKind, Syntax};
+use crate::json_options::JsonOptions;
+use crate::tag;
+
+/// Maximum nesting of array and object which is parsed from a JSON text
+const MAX_DEPTH: usize = 256;
+
+#[cfg(feature = "json")]
+impl From<Value> for DataItem {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Null => Self::Null,
+            Value::Bool(bool_val) => Self::Boolean(bool_val),
+            Value::Number(number) => {
+                if let Some(u64_val) = number.as_u64() {
+                    Self::Unsigned(u64_val)
+                } else if let Some(i64_val) = number.as_i64() {
+                    i64_val.into()
+                } else if let Some(data_item) = big_integer_item(&number.to_string()) {
+                    data_item
+                } else {
+                    Self::Floating(number.as_f64().unwrap_or(f64::NAN))
+                }
+            }
+            Value::String(string) => string.into(),
+            Value::Array(array) => array.into_iter().map(Self::from).collect::<Vec<_>>().into(),
+            Value::Object(object) => {
+                object
+                    .into_iter()
+                    .map(|(key, value)| (Self::from(key), Self::from(value)))
+                    .collect::<Vec<_>>()
+                    .into()
+            }
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl TryFrom<DataItem> for Value {
+    type Error = Error;
+
+    fn try_from(value: DataItem) -> Result<Self, Self::Error> {
+        value.to_json_value(&JsonOptions::default())
+    }
+}
+
+/// Convert a data item into a JSON value where `expected_encoding` is a tag
+/// number of an innermost expected conversion tag (tag 21 to tag 23)
+/// enclosing a data item
+#[cfg(feature = "json")]
+pub(crate) fn to_value(
+    data_item: &DataItem,
+    options: JsonOptions,
+    expected_encoding: Option<u64>,
+) -> Result<Value, Error> {
+    let value = match data_item {
+        DataItem::Unsigned(number) => Value::from(*number),
+        DataItem::Signed(number) => {
+            match i64::try_from(*number) {
+                Ok(i64_val) => Value::from(-1 - i64_val),
+                #[cfg(feature = "json-arbitrary-precision")]
+                Err(_) => Value::Number(big_integer_number(true, &number.to_be_bytes())),
+                #[cfg(not(feature = "json-arbitrary-precision"))]
+                #[expect(
+                    clippy::cast_precision_loss,
+                    reason = "JSON number cannot hold integer smaller than i64 without loss"
+                )]
+                Err(_) => Value::from(-1.0 - *number as f64),
+            }
+        }
+        DataItem::Floating(number) => {
+            match Number::from_f64(*number) {
+                Some(number) => Value::Number(number),
+                None if options.is_non_finite_as_string() => {
+                    Value::String(non_finite_text(*number).to_string())
+                }
+                None => Value::Null,
+            }
+        }
+        DataItem::Byte(byte_content) => {
+            Value::String(encode_byte(&byte_content.full(), expected_encoding))
+        }
+        DataItem::Text(text_content) => Value::String(text_content.full().into_owned()),
+        DataItem::Array(array_content) => {
+            Value::Array(
+                array_content
+                    .array()
+                    .iter()
+                    .map(|item| to_value(item, options, expected_encoding))
+                    .collect::<Result<_, _>>()?,
+            )
+        }
+        DataItem::Map(map_content) => {
+            let mut map = Map::with_capacity(map_content.map().len());
+            for (key, value) in map_content.map() {
+                map.insert(
+                    object_key(key, options)?,
+                    to_value(value, options, expected_encoding)?,
+                );
+            }
+            Value::Object(map)
+        }
+        DataItem::Tag(tag_content) => {
+            match (tag_content.number(), tag_content.content()) {
+                #[cfg(feature = "json-arbitrary-precision")]
+                (number @ (tag::POS_BIGNUM | tag::NEG_BIGNUM), DataItem::Byte(byte_content)) => {
+                    Value::Number(big_integer_number(
+                        number == tag::NEG_BIGNUM,
+                        &byte_content.full(),
+                    ))
+                }
+                #[cfg(not(feature = "json-arbitrary-precision"))]
+                (tag::POS_BIGNUM, DataItem::Byte(byte_content)) => {
+                    Value::String(encode_base64(
+                        &byte_content.full(),
+                        BASE64URL_ALPHABET,
+                        false,
+                    ))
+                }
+                #[cfg(not(feature = "json-arbitrary-precision"))]
+                (tag::NEG_BIGNUM, DataItem::Byte(byte_content)) => {
+                    Value::String(format!(
+                        "~{}",
+                        encode_base64(&byte_content.full(), BASE64URL_ALPHABET, false)
+                    ))
+                }
+                (
+                    number
+                    @ (tag::EXPECTED_BASE64URL | tag::EXPECTED_BASE64 | tag::EXPECTED_BASE16),
+                    content,
+                ) => to_value(content, options, Some(number))?,
+                (_, content) => to_value(content, options, expected_encoding)?,
+            }
+        }
+        DataItem::Boolean(bool_val) => Value::Bool(*bool_val),
+        DataItem::Null | DataItem::Undefined | DataItem::GenericSimple(_) => Value::Null,
+    };
+    Ok(value)
+}
+
+/// Convert a bignum into a JSON number without losing a precision where
+/// negative bignum represents -1 minus magnitude
+#[cfg(feature = "json-arbitrary-precision")]
+fn big_integer_number(negative: bool, magnitude: &[u8]) -> Number {
+    let mut magnitude = magnitude.to_vec();
+    if negative {
+        // increment magnitude since negative bignum stores -1 minus value
+        let carry = magnitude.iter_mut().rev().all(|byte| {
+            let (sum, overflow) = byte.overflowing_add(1);
+            *byte = sum;
+            overflow
+        });
+        if carry {
+            magnitude.insert(0, 1);
+        }
+    }
+    let mut digits = vec![];
+    while magnitude.iter().any(|byte| *byte != 0) {
+        let mut remainder = 0;
+        for byte in &mut magnitude {
+            let value = remainder << 8 | u32::from(*byte);
+            *byte = (value / 10).to_le_bytes()[0];
+            remainder = value % 10;
+        }
+        digits.push(char::from_digit(remainder, 10).unwrap_or('0'));
+    }
+    if digits.is_empty() {
+        digits.push('0');
+    }
+    if negative {
+        digits.push('-');
+    }
+    digits
+        .into_iter()
+        .rev()
+        .collect::<String>()
+        .parse()
+        .expect("decimal integer is a valid JSON number")
+}
+
+/// Encode a byte string using an expected encoding if present otherwise using
+/// base64url encoding
+fn encode_byte(bytes: &[u8], expected_encoding: Option<u64>) -> String {
+    match expected_encoding {
+        Some(tag::EXPECTED_BASE64) => encode_base64(bytes, BASE64_ALPHABET, true),
+        Some(tag::EXPECTED_BASE16) => encode_hex(bytes),
+        _ => encode_base64(bytes, BASE64URL_ALPHABET, false),
+    }
+}
+
+/// Write a data item as a JSON text where `expected_encoding` is a tag number
+/// of an innermost expected conversion tag (tag 21 to tag 23) enclosing a data
+/// item
+#[expect(
+    clippy::use_debug,
+    reason = "debug representation of a finite floating number is a valid JSON number"
+)]
+pub(crate) fn write_text(
+    data_item: &DataItem,
+    options: JsonOptions,
+    expected_encoding: Option<u64>,
+    text: &mut String,
+) -> Result<(), Error> {
+    if options.is_lossless() {
+        write_lossless(data_item, text);
+        return Ok(());
+    }
+    match data_item {
+        DataItem::Unsigned(number) => {
+            let _ = write!(text, "{number}");
+        }
+        DataItem::Signed(number) => {
+            let _ = write!(text, "{}", -1 - i128::from(*number));
+        }
+        DataItem::Floating(number) if number.is_finite() => {
+            let _ = write!(text, "{number:?}");
+        }
+        DataItem::Floating(number) if options.is_non_finite_as_string() => {
+            write_string(non_finite_text(*number), text);
+        }
+        DataItem::Byte(byte_content) => {
+            write_string(&encode_byte(&byte_content.full(), expected_encoding), text);
+        }
+        DataItem::Text(text_content) => write_string(&text_content.full(), text),
+        DataItem::Array(array_content) => {
+            text.push('[');
+            for (index, item) in array_content.array().iter().enumerate() {
+                if index > 0 {
+                    text.push(',');
+                }
+                write_text(item, options, expected_encoding, text)?;
+            }
+            text.push(']');
+        }
+        DataItem::Map(map_content) => {
+            text.push('{');
+            for (index, (key, value)) in map_content.map().iter().enumerate() {
+                if index > 0 {
+                    text.push(',');
+                }
+                write_string(&object_key(key, options)?, text);
+                text.push(':');
+                write_text(value, options, expected_encoding, text)?;
+            }
+            text.push('}');
+        }
+        DataItem::Tag(tag_content) => {
+            match (tag_content.number(), tag_content.content()) {
+                (number @ (tag::POS_BIGNUM | tag::NEG_BIGNUM), DataItem::Byte(byte_content)) => {
+                    write_string(&encode_bignum(number, &byte_content.full()), text);
+                }
+                (
+                    number
+                    @ (tag::EXPECTED_BASE64URL | tag::EXPECTED_BASE64 | tag::EXPECTED_BASE16),
+                    content,
+                ) => write_text(content, options, Some(number), text)?,
+                (_, content) => write_text(content, options, expected_encoding, text)?,
+            }
+        }
+        DataItem::Boolean(bool_val) => {
+            let _ = write!(text, "{bool_val}");
+        }
+        DataItem::Floating(_)
+        | DataItem::Null
+        | DataItem::Undefined
+        | DataItem::GenericSimple(_) => text.push_str("null"),
+    }
+    Ok(())
+}
+
+/// Write a data item as a JSON text where information which cannot be
+/// represented in JSON is written as an object with a key starting with `$`
+#[expect(
+    clippy::use_debug,
+    reason = "debug representation of a finite floating number is a valid JSON number"
+)]
+fn write_lossless(data_item: &DataItem, text: &mut String) {
+    match data_item {
+        DataItem::Unsigned(number) => {
+            let _ = write!(text, "{number}");
+        }
+        DataItem::Signed(number) => {
+            let _ = write!(text, "{}", -1 - i128::from(*number));
+        }
+        DataItem::Floating(number) if number.is_finite() => {
+            let _ = write!(text, "{number:?}");
+        }
+        DataItem::Floating(number) => {
+            text.push_str("{\"$float\":");
+            write_string(non_finite_text(*number), text);
+            text.push('}');
+        }
+        DataItem::Byte(byte_content) => {
+            text.push_str("{\"$bytes\":");
+            write_string(
+                &encode_base64(&byte_content.full(), BASE64URL_ALPHABET, false),
+                text,
+            );
+            text.push('}');
+        }
+        DataItem::Text(text_content) => write_string(&text_content.full(), text),
+        DataItem::Array(array_content) => {
+            text.push('[');
+            for (index, item) in array_content.array().iter().enumerate() {
+                if index > 0 {
+                    text.push(',');
+                }
+                write_lossless(item, text);
+            }
+            text.push(']');
+        }
+        DataItem::Map(map_content) => {
+            let is_object = map_content.map().keys().all(|key| {
+                matches!(key, DataItem::Text(text_content) if !text_content.full().starts_with('$'))
+            });
+            text.push_str(if is_object { "{" } else { "{\"$map\":[" });
+            for (index, (key, value)) in map_content.map().iter().enumerate() {
+                if index > 0 {
+                    text.push(',');
+                }
+                if is_object {
+                    write_lossless(key, text);
+                    text.push(':');
+                    write_lossless(value, text);
+                } else {
+                    text.push('[');
+                    write_lossless(key, text);
+                    text.push(',');
+                    write_lossless(value, text);
+                    text.push(']');
+                }
+            }
+            text.push_str(if is_object { "}" } else { "]}" });
+        }
+        DataItem::Tag(tag_content) => {
+            let _ = write!(text, "{{\"$tag\":{},\"$value\":", tag_content.number());
+            write_lossless(tag_content.content(), text);
+            text.push('}');
+        }
+        DataItem::Boolean(bool_val) => {
+            let _ = write!(text, "{bool_val}");
+        }
+        DataItem::Null => text.push_str("null"),
+        DataItem::Undefined => text.push_str("{\"$undefined\":true}"),
+        DataItem::GenericSimple(simple_value) => {
+            let _ = write!(text, "{{\"$simple\":{}}}", **simple_value);
+        }
+    }
+}
+
+/// Convert a JSON object written by a lossless conversion back into a data
+/// item
+fn lossless_item(map: IndexMap<DataItem, DataItem>) -> Result<DataItem, Syntax> {
+    let is_object = map
+        .keys()
+        .all(|key| !key.as_text().is_some_and(|key| key.starts_with('$')));
+    if is_object {
+        return Ok(DataItem::Map(MapContent::from(map)));
+    }
+    let field = |name: &str| map.get(&DataItem::from(name));
+    let data_item = match (map.len(), field("$tag"), field("$value")) {
+        (2, Some(DataItem::Unsigned(number)), Some(content)) => {
+            TagContent::from((*number, content.clone())).into()
+        }
+        (1, ..) => {
+            let (key, value) = map.first().ok_or(Syntax::InvalidLossless)?;
+            match (key.as_text().as_deref(), value) {
+                (Some("$bytes"), DataItem::Text(text_content))
+                    if !text_content.full().contains('=') =>
+                {
+                    decode_base64(&text_content.full(), BASE64URL_ALPHABET)
+                        .map(|bytes| DataItem::from(bytes.as_slice()))
+                        .ok_or(Syntax::InvalidByte)?
+                }
+                (Some("$float"), DataItem::Text(text_content)) => {
+                    match text_content.full().as_ref() {
+                        "NaN" => DataItem::Floating(f64::NAN),
+                        "Infinity" => DataItem::Floating(f64::INFINITY),
+                        "-Infinity" => DataItem::Floating(f64::NEG_INFINITY),
+                        _ => return Err(Syntax::InvalidFloating),
+                    }
+                }
+                (Some("$undefined"), DataItem::Boolean(true)) => DataItem::Undefined,
+                (Some("$simple"), DataItem::Unsigned(number)) => {
+                    u8::try_from(*number)
+                        .ok()
+                        .and_then(|number| SimpleValue::try_from(number).ok())
+                        .map(DataItem::GenericSimple)
+                        .ok_or(Syntax::InvalidSimple)?
+                }
+                (Some("$map"), DataItem::Array(array_content)) => {
+                    let mut map = IndexMap::with_capacity(array_content.array().len());
+                    for (index, pair) in array_content.array().iter().enumerate() {
+                        let Some([key, value]) = pair.as_array() else {
+                            return Err(Syntax::InvalidLossless);
+                        };
+                        if map.insert(key.clone(), value.clone()).is_some() {
+                            return Err(Syntax::DuplicateKey { index });
+                        }
+                    }
+                    DataItem::Map(MapContent::from(map))
+                }
+                _ => return Err(Syntax::InvalidLossless),
+            }
+        }
+        _ => return Err(Syntax::InvalidLossless),
+    };
+    Ok(data_item)
+}
+
+/// Write a string as an escaped JSON string
+fn write_string(string: &str, text: &mut String) {
+    text.push('"');
+    for character in string.chars() {
+        match character {
+            '"' => text.push_str("\\\""),
+            '\\' => text.push_str("\\\\"),
+            '\n' => text.push_str("\\n"),
+            '\r' => text.push_str("\\r"),
+            '\t' => text.push_str("\\t"),
+            '\u{8}' => text.push_str("\\b"),
+            '\u{c}' => text.push_str("\\f"),
+            '\0'..='\u{1f}' => {
+                let _ = write!(text, "\\u{:04x}", u32::from(character));
+            }
+            _ => text.push(character),
+        }
+    }
+    text.push('"');
+}
+
+/// Get a key of a JSON object from a map key
+fn object_key(key: &DataItem, options: JsonOptions) -> Result<String, Error> {
+    match key {
+        DataItem::Text(text_content) => Ok(text_content.full().into_owned()),
+        _ if options.is_stringify_keys() => Ok(format!("{key:?}")),
+        _ => {
+            Err(Error::JsonConversion(Conversion::NonTextKey {
+                found: ItemKind::of(key),
+            }))
+        }
+    }
+}
+
+/// Encode a magnitude of a bignum as base64url where negative bignum is
+/// prefixed with `~`
+fn encode_bignum(tag: u64, magnitude: &[u8]) -> String {
+    let encoded = encode_base64(magnitude, BASE64URL_ALPHABET, false);
+    if tag == tag::NEG_BIGNUM {
+        format!("~{encoded}")
+    } else {
+        encoded
+    }
+}
+
+/// Get a text of a non finite floating number
+fn non_finite_text(number: f64) -> &'static str {
+    if number.is_nan() {
+        "NaN"
+    } else if number.is_sign_positive() {
+        "Infinity"
+    } else {
+        "-Infinity"
+    }
+}
+
+/// Parse a JSON text into a data item following RFC 8949 section 6.2
+pub(crate) fn parse(text: &str, options: JsonOptions) -> Result<DataItem, Error> {
+    let mut parser = Parser {
+        text,
+        position: 0,
+        depth: 0,
+        lossless: options.is_lossless(),
+    };
+    let data_item = parser.parse_value()?;
+    parser.skip_whitespace();
+    if parser.position < text.len() {
+        return Err(parser.error(Syntax::TrailingCharacter));
+    }
+    Ok(data_item)
+}
+
+/// Parser which reads a JSON text from a start of a text
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+    depth: usize,
+    lossless: bool,
+}
+
+impl Parser<'_> {
+    fn error(&self, reason: Syntax) -> Error {
+        Error::InvalidJson {
+            reason,
+            position: self.position,
+        }
+    }
+
+    fn rest(&self) -> &str {
+        &self.text[self.position..]
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let character = self.rest().chars().next()?;
+        self.position += character.len_utf8();
+        Some(character)
+    }
+
+    fn skip_whitespace(&mut self) {
+        let length = self.rest().len()
+            - self
+                .rest()
+                .trim_start_matches([' ', '\t', '\n', '\r'])
+                .len();
+        self.position += length;
+    }
+
+    fn consume(&mut self, prefix: &str) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(prefix) {
+            self.position += prefix.len();
+            return true;
+        }
+        false
+    }
+
+    fn expect(&mut self, prefix: &'static str) -> Result<(), Error> {
+        if self.consume(prefix) {
+            Ok(())
+        } else {
+            Err(self.error(Syntax::Expected(prefix)))
+        }
+    }
+
+    /// Parse a value while tracking its nesting so a deeply nested input
+    /// returns an error instead of overflowing a stack
+    fn parse_value(&mut self) -> Result<DataItem, Error> {
+        if self.depth >= MAX_DEPTH {
+            return Err(self.error(Syntax::TooDeep {
+                max_depth: MAX_DEPTH,
+            }));
+        }
+        self.depth += 1;
+        let data_item = self.parse_nested_value();
+        self.depth -= 1;
+        data_item
+    }
+
+    fn parse_nested_value(&mut self) -> Result<DataItem, Error> {
+        self.skip_whitespace();
+        match self.rest().chars().next() {
+            Some('{') => self.parse_object(),
+            Some('[') => self.parse_array(),
+            Some('"') => Ok(DataItem::from(self.parse_string()?)),
+            Some('-' | '0'..='9') => self.parse_number(),
+            _ if self.consume("true") => Ok(DataItem::Boolean(true)),
+            _ if self.consume("false") => Ok(DataItem::Boolean(false)),
+            _ if self.consume("null") => Ok(DataItem::Null),
+            Some(_) => Err(self.error(Syntax::UnexpectedCharacter)),
+            None => Err(self.error(Syntax::UnexpectedEnd)),
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<DataItem, Error> {
+        self.expect("{")?;
+        let mut map = IndexMap::new();
+        if !self.consume("}") {
+            loop {
+                self.skip_whitespace();
+                let key = self.parse_string()?;
+                self.expect(":")?;
+                let value = self.parse_value()?;
+                let index = map.len();
+                if map.insert(DataItem::from(key), value).is_some() {
+                    return Err(self.error(Syntax::DuplicateKey { index }));
+                }
+                if self.consume("}") {
+                    break;
+                }
+                self.expect(",")?;
+            }
+        }
+        if self.lossless {
+            return lossless_item(map).map_err(|reason| self.error(reason));
+        }
+        Ok(DataItem::Map(MapContent::from(map)))
+    }
+
+    fn parse_array(&mut self) -> Result<DataItem, Error> {
+        self.expect("[")?;
+        let mut array = vec![];
+        if !self.consume("]") {
+            loop {
+                array.push(self.parse_value()?);
+                if self.consume("]") {
+                    break;
+                }
+                self.expect(",")?;
+            }
+        }
+        Ok(DataItem::from(array))
+    }
+
+    fn parse_number(&mut self) -> Result<DataItem, Error> {
+        let length = self
+            .rest()
+            .find(|character: char| {
+                !(character.is_ascii_digit() || matches!(character, '-' | '+' | '.' | 'e' | 'E'))
+            })
+            .unwrap_or(self.rest().len());
+        let number = &self.rest()[..length];
+        let digits = number.strip_prefix('-').unwrap_or(number);
+        let integer_length = digits
+            .find(|character: char| !character.is_ascii_digit())
+            .unwrap_or(digits.len());
+        let integer = &digits[..integer_length];
+        let fraction = &digits[integer_length..];
+        let is_valid_fraction = {
+            let (fraction, exponent) =
+                fraction.find(['e', 'E']).map_or((fraction, None), |index| {
+                    (&fraction[..index], Some(&fraction[index + 1..]))
+                });
+            let is_valid_decimal = fraction.is_empty()
+                || (fraction.len() > 1
+                    && fraction.starts_with('.')
+                    && fraction[1..].bytes().all(|byte| byte.is_ascii_digit()));
+            let is_valid_exponent = exponent.is_none_or(|exponent| {
+                let exponent = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+                !exponent.is_empty() && exponent.bytes().all(|byte| byte.is_ascii_digit())
+            });
+            is_valid_decimal && is_valid_exponent
+        };
+        if integer.is_empty()
+            || (integer.len() > 1 && integer.starts_with('0'))
+            || !is_valid_fraction
+        {
+            return Err(self.error(Syntax::InvalidNumber));
+        }
+        let data_item = if !fraction.is_empty() {
+            DataItem::Floating(
+                number
+                    .parse()
+                    .map_err(|_| self.error(Syntax::InvalidNumber))?,
+            )
+        } else if let Some(integer) = integer_item(number.starts_with('-'), digits) {
+            integer
+        } else {
+            // integer out of range of CBOR integer is converted to floating number
+            DataItem::Floating(
+                number
+                    .parse()
+                    .map_err(|_| self.error(Syntax::InvalidNumber))?,
+            )
+        };
+        self.position += length;
+        Ok(data_item)
+    }
+
+    fn parse_string(&mut self) -> Result<String, Error> {
+        self.expect("\"")?;
+        let mut string = String::new();
+        loop {
+            match self.next() {
+                Some('"') => return Ok(string),
+                Some('\\') => {
+                    let character = match self.next() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => self.parse_unicode_escape()?,
+                        _ => return Err(self.error(Syntax::InvalidEscape)),
+                    };
+                    string.push(character);
+                }
+                Some('\0'..='\u{1f}') => return Err(self.error(Syntax::UnescapedControl)),
+                Some(character) => string.push(character),
+                None => return Err(self.error(Syntax::UnterminatedText)),
+            }
+        }
+    }
+
+    fn parse_unicode_escape(&mut self) -> Result<char, Error> {
+        let high = self.parse_utf16_unit()?;
+        if !(0xd800..0xdc00).contains(&high) {
+            return char::from_u32(high).ok_or_else(|| self.error(Syntax::InvalidUnicodeEscape));
+        }
+        if !self.rest().starts_with("\\u") {
+            return Err(self.error(Syntax::MissingLowSurrogate));
+        }
+        self.position += 2;
+        let low = self.parse_utf16_unit()?;
+        if !(0xdc00..0xe000).contains(&low) {
+            return Err(self.error(Syntax::InvalidLowSurrogate));
+        }
+        char::from_u32(0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00))
+            .ok_or_else(|| self.error(Syntax::InvalidUnicodeEscape))
+    }
+
+    fn parse_utf16_unit(&mut self) -> Result<u32, Error> {
+        let unit = self
+            .rest()
+            .get(..4)
+            .filter(|hex| hex.bytes().all(|byte| byte.is_ascii_hexdigit()))
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .ok_or_else(|| self.error(Syntax::InvalidUnicodeEscape))?;
+        self.position += 4;
+        Ok(unit)
+    }
+}
+
+/// Convert a text of an integer JSON number which is out of range of `i64` and
+/// `u64` into an integer or bignum data item. Return `None` if a text is not
+/// an integer
+#[cfg(feature = "json")]
+fn big_integer_item(text: &str) -> Option<DataItem> {
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, text),
+    };
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    if let Some(data_item) = integer_item(negative, digits) {
+        return Some(data_item);
+    }
+    let mut magnitude = Vec::<u8>::new();
+    for digit in digits.bytes() {
+        let mut carry = u32::from(digit - b'0');
+        for byte in magnitude.iter_mut().rev() {
+            let value = u32::from(*byte) * 10 + carry;
+            *byte = value.to_le_bytes()[0];
+            carry = value >> 8;
+        }
+        if carry > 0 {
+            magnitude.insert(0, carry.to_le_bytes()[0]);
+        }
+    }
+    if negative {
+        // decrement magnitude since negative bignum stores -1 minus value
+        for byte in magnitude.iter_mut().rev() {
+            let (difference, borrow) = byte.overflowing_sub(1);
+            *byte = difference;
+            if !borrow {
+                break;
+            }
+        }
+        if magnitude.first() == Some(&0) {
+            magnitude.remove(0);
+        }
+    }
+    Some(TagContent::bignum(&magnitude, negative).into())
+}
+
+/// Convert a decimal digits with sign into an integer data item. Return `None`
+/// if integer is out of range of CBOR integer
+fn integer_item(is_negative: bool, digits: &str) -> Option<DataItem> {
+    let magnitude = digits.parse::<u128>().ok()?;
+    match (is_negative, magnitude) {
+        (true, 0) => Some(DataItem::Unsigned(0)),
+        (true, _) => u64::try_from(magnitude - 1).ok().map(DataItem::Signed),
+        (false, _) => u64::try_from(magnitude).ok().map(DataItem::Unsigned),
+    }
+}
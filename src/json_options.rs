@@ -0,0 +1,70 @@
+/// Options which control how a data item is converted to JSON following
+/// RFC 8949 section 6.1
+///
+/// By default map key which is not a text string cannot be converted and non
+/// finite floating number is converted to null
+///
+/// # Example
+/// ```rust
+/// use cbor_next::JsonOptions;
+///
+/// let mut options = JsonOptions::default();
+/// assert!(!options.is_stringify_keys());
+/// options.set_stringify_keys(true);
+/// assert!(options.is_stringify_keys());
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct JsonOptions {
+    stringify_keys: bool,
+    non_finite_as_string: bool,
+    lossless: bool,
+}
+
+impl JsonOptions {
+    /// Set whether map key which is not a text string is converted to a JSON
+    /// string containing its diagnostic notation instead of failing a
+    /// conversion
+    pub fn set_stringify_keys(&mut self, stringify_keys: bool) -> &mut Self {
+        self.stringify_keys = stringify_keys;
+        self
+    }
+
+    /// Set whether non finite floating number is converted to a JSON string
+    /// `"NaN"`, `"Infinity"` or `"-Infinity"` instead of null
+    pub fn set_non_finite_as_string(&mut self, non_finite_as_string: bool) -> &mut Self {
+        self.non_finite_as_string = non_finite_as_string;
+        self
+    }
+
+    /// Set whether a JSON text is converted losslessly so that it can be
+    /// converted back into a same data item
+    ///
+    /// Byte string, tag, undefined, simple value, non finite floating number
+    /// and map which contains a key which is not a text string or a key
+    /// starting with `$` are written as a JSON object with a key `$bytes`,
+    /// `$tag` and `$value`, `$undefined`, `$simple`, `$float` and `$map`
+    /// respectively. Indefinite length is not preserved. This option is only
+    /// used by JSON text conversion
+    pub fn set_lossless(&mut self, lossless: bool) -> &mut Self {
+        self.lossless = lossless;
+        self
+    }
+
+    /// Get whether map key which is not a text string is stringified
+    #[must_use]
+    pub fn is_stringify_keys(&self) -> bool {
+        self.stringify_keys
+    }
+
+    /// Get whether non finite floating number is converted to a JSON string
+    #[must_use]
+    pub fn is_non_finite_as_string(&self) -> bool {
+        self.non_finite_as_string
+    }
+
+    /// Get whether a JSON text is converted losslessly
+    #[must_use]
+    pub fn is_lossless(&self) -> bool {
+        self.lossless
+    }
+}
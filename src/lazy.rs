@@ -0,0 +1,274 @@
+use crate::data_item::DataItem;
+use crate::error::{Error, Malformed};
+use crate::index::is_text_key;
+use crate::span::{Header, read_header};
+
+/// Data item which is kept as a raw CBOR bytes and only decoded on access
+///
+/// Creating a lazy data item only checks that bytes are well framed without
+/// allocating. An array, a map or a tag is walked when a nested data item is
+/// accessed and only a requested data item is decoded, so reading a few fields
+/// of a large document doesn't decode a whole document
+///
+/// # Example
+/// ```rust
+/// use cbor_next::DataItem;
+/// use cbor_next::lazy::LazyDataItem;
+///
+/// let claims =
+///     DataItem::from_diagnostic(r#"{"iss": "coap://as.example.com", "exp": 1444064944}"#)
+///         .unwrap()
+///         .encode();
+/// let lazy = LazyDataItem::new(&claims).unwrap();
+/// let expiry = lazy.get_str("exp").unwrap();
+/// assert_eq!(expiry.decode().unwrap(), DataItem::from(1_444_064_944));
+/// assert!(lazy.get_str("aud").is_none());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LazyDataItem<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> LazyDataItem<'a> {
+    /// Create a lazy data item from a first data item of provided bytes
+    ///
+    /// # Errors
+    /// If a first data item of provided bytes is not well framed
+    pub fn new(bytes: &'a [u8]) -> Result<Self, Error> {
+        let mut position = 0;
+        skip_item(bytes, &mut position)?;
+        Ok(Self {
+            bytes: &bytes[..position],
+        })
+    }
+
+    /// Get a raw CBOR bytes of a data item
+    #[must_use]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Get a major type of a data item
+    #[must_use]
+    pub fn major_type(&self) -> u8 {
+        self.bytes[0] >> 5
+    }
+
+    /// Decode a data item including all of its nested data item
+    ///
+    /// # Errors
+    /// If data item cannot be converted to CBOR
+    pub fn decode(&self) -> Result<DataItem, Error> {
+        DataItem::decode(self.bytes)
+    }
+
+    /// Get an iterator over items of an array. Returns `None` if a data item
+    /// is not an array
+    #[must_use]
+    pub fn items(&self) -> Option<LazyIter<'a>> {
+        (self.major_type() == 4).then(|| LazyIter::new(self.bytes))
+    }
+
+    /// Get an iterator over key and value of a map. Returns `None` if a data
+    /// item is not a map
+    #[must_use]
+    pub fn entries(&self) -> Option<LazyEntries<'a>> {
+        (self.major_type() == 5).then(|| {
+            LazyEntries {
+                iter: LazyIter::new(self.bytes),
+            }
+        })
+    }
+
+    /// Get a tag number and a tag content. Returns `None` if a data item is
+    /// not a tag
+    #[must_use]
+    pub fn tag(&self) -> Option<(u64, Self)> {
+        if self.major_type() != 6 {
+            return None;
+        }
+        let mut position = 0;
+        let number = read_header(self.bytes, &mut position).ok()?.argument?;
+        Some((
+            number,
+            Self {
+                bytes: &self.bytes[position..],
+            },
+        ))
+    }
+
+    /// Get an item of an array at provided index
+    #[must_use]
+    pub fn get_index(&self, index: usize) -> Option<Self> {
+        self.items()?.nth(index)
+    }
+
+    /// Get a value of a map for provided key. Only keys are decoded while
+    /// searching
+    #[must_use]
+    pub fn get(&self, key: &DataItem) -> Option<Self> {
+        self.entries()?
+            .find(|(map_key, _)| {
+                map_key.major_type() == key.major_type()
+                    && map_key.decode().is_ok_and(|map_key| &map_key == key)
+            })
+            .map(|(_, value)| value)
+    }
+
+    /// Get a value of a map for a text string key. Only text string keys are
+    /// decoded while searching
+    #[must_use]
+    pub fn get_str(&self, key: &str) -> Option<Self> {
+        self.entries()?
+            .find(|(map_key, _)| {
+                map_key.major_type() == 3
+                    && map_key
+                        .decode()
+                        .is_ok_and(|map_key| is_text_key(&map_key, key))
+            })
+            .map(|(_, value)| value)
+    }
+}
+
+/// Iterator over nested data item of a lazy array
+#[derive(Debug, Clone)]
+pub struct LazyIter<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    remaining: Option<u64>,
+}
+
+impl<'a> LazyIter<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        let mut position = 0;
+        let remaining = read_header(bytes, &mut position).map_or(Some(0), |header| header.argument);
+        let remaining = match (bytes[0] >> 5, remaining) {
+            (5, Some(length)) => Some(length.saturating_mul(2)),
+            _ => remaining,
+        };
+        Self {
+            bytes,
+            position,
+            remaining,
+        }
+    }
+}
+
+impl<'a> Iterator for LazyIter<'a> {
+    type Item = LazyDataItem<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.remaining {
+            Some(0) => return None,
+            Some(remaining) => *remaining -= 1,
+            None if self.bytes.get(self.position) == Some(&255) => return None,
+            None => {}
+        }
+        let start = self.position;
+        skip_item(self.bytes, &mut self.position).ok()?;
+        Some(LazyDataItem {
+            bytes: &self.bytes[start..self.position],
+        })
+    }
+}
+
+/// Iterator over key and value of a lazy map
+#[derive(Debug, Clone)]
+pub struct LazyEntries<'a> {
+    iter: LazyIter<'a>,
+}
+
+impl<'a> Iterator for LazyEntries<'a> {
+    type Item = (LazyDataItem<'a>, LazyDataItem<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some((self.iter.next()?, self.iter.next()?))
+    }
+}
+
+/// Move position past a data item starting at provided position after checking
+/// its framing. Nested data item are tracked in an explicit stack so a deeply
+/// nested data item cannot overflow a call stack
+fn skip_item(bytes: &[u8], position: &mut usize) -> Result<(), Error> {
+    // number of data item left in every open container, `None` for an
+    // indefinite length container which ends with a break stop
+    let mut pending = vec![Some(1u64)];
+    while let Some(remaining) = pending.last_mut() {
+        match remaining {
+            Some(0) => {
+                pending.pop();
+                continue;
+            }
+            Some(count) => *count -= 1,
+            None => {
+                match bytes.get(*position) {
+                    Some(255) => {
+                        *position += 1;
+                        pending.pop();
+                        continue;
+                    }
+                    Some(_) => {}
+                    None => return Err(Error::IncompleteIndefinite),
+                }
+            }
+        }
+        if bytes.get(*position) == Some(&255) {
+            return Err(Error::InvalidBreakStop);
+        }
+        let Header {
+            major_type,
+            argument,
+        } = read_header(bytes, position)?;
+        match (major_type, argument) {
+            (0 | 1 | 7, Some(_)) => {}
+            (2 | 3, Some(length)) => skip_bytes(bytes, position, length)?,
+            (2 | 3, None) => {
+                loop {
+                    match bytes.get(*position) {
+                        Some(255) => break,
+                        Some(_) => {}
+                        None => return Err(Error::IncompleteIndefinite),
+                    }
+                    match read_header(bytes, position)? {
+                        Header {
+                            major_type: chunk_major_type,
+                            argument: Some(length),
+                        } if chunk_major_type == major_type => {
+                            skip_bytes(bytes, position, length)?;
+                        }
+                        Header {
+                            major_type: chunk_major_type,
+                            ..
+                        } => {
+                            return Err(Error::NotWellFormed(Malformed::InvalidChunk {
+                                expected: major_type,
+                                found: chunk_major_type,
+                            }));
+                        }
+                    }
+                }
+                *position += 1;
+            }
+            (4, Some(length)) => pending.push(Some(length)),
+            (5, Some(length)) => {
+                pending.push(Some(length.checked_mul(2).ok_or(Error::Incomplete)?));
+            }
+            (6, Some(_)) => pending.push(Some(1)),
+            (4 | 5, None) => pending.push(None),
+            _ => {
+                return Err(Error::NotWellFormed(Malformed::IndefiniteLength {
+                    major_type,
+                }));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn skip_bytes(bytes: &[u8], position: &mut usize, length: u64) -> Result<(), Error> {
+    *position = position
+        .checked_add(usize::try_from(length)?)
+        .filter(|end| *end <= bytes.len())
+        .ok_or(Error::Incomplete)?;
+    Ok(())
+}
@@ -1,29 +1,188 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 //! Library to handle a Concise Binary Object Representation (CBOR)
 
+/// Module containing annotated hex dump of a CBOR bytes
+mod annotate;
+
+/// Module containing decoding of a data item into a bump arena
+#[cfg(feature = "bumpalo")]
+pub mod arena;
+
+/// Module containing big integer conversion for positive bignum (tag 2) and
+/// negative bignum (tag 3)
+#[cfg(feature = "num-bigint")]
+pub mod bignum;
+
+/// Module containing fluent builder of an array and a map data item
+pub mod builder;
+
+/// Module containing serde `with` helpers for serializing bytes as CBOR byte
+/// string or as hex or base64 encoded text string
+#[cfg(feature = "serde")]
+pub mod bytes;
+
+/// Module containing conversion between a data item and ciborium value
+#[cfg(feature = "ciborium")]
+mod ciborium;
+
 /// Module for different type of content
 pub mod content;
 
 /// Module containing a data item
 pub mod data_item;
 
+/// Module containing date time conversion for standard date/time string (tag
+/// 0) and epoch based date/time (tag 1)
+#[cfg(any(feature = "chrono", feature = "time"))]
+pub mod datetime;
+
+/// Module containing decimal conversion for decimal fraction (tag 4)
+#[cfg(feature = "rust_decimal")]
+pub mod decimal;
+
+/// Module containing options used when decoding a CBOR bytes
+pub mod decode_options;
+
+/// Module containing serde deserializer
+#[cfg(feature = "serde")]
+pub mod de;
+
 /// Module containing different deterministic mode
 pub mod deterministic;
 
+/// Module containing parser and printer of CBOR diagnostic notation
+mod diagnostic;
+
+/// Module containing options used when printing a diagnostic notation
+pub mod diagnostic_options;
+
+/// Module containing hex and base64 encoding of bytes
+mod encoding;
+
 /// Module containing different type of error
 pub mod error;
 
+/// Module containing allocation free encoder and decoder of CBOR token and
+/// scalar over a caller provided buffer
+pub mod fixed;
+
+/// Module containing conversion of a half precision floating point number
+mod float16;
+
 /// Module for index
 pub mod index;
 
+/// Module containing interning of a decoded map key
+mod intern;
+
+/// Module containing conversion between a data item and JSON
+mod json;
+
+/// Module containing options used when converting a data item to JSON
+pub mod json_options;
+
+/// Module containing lazily decoded data item backed by a raw CBOR bytes
+pub mod lazy;
+
+/// Module containing serde helper for distinguishing absent, null and
+/// undefined value
+#[cfg(feature = "serde")]
+pub mod maybe;
+
+/// Module containing options used when merging a patch into a data item
+pub mod merge_options;
+
+/// Module containing parallel encoding of a large array and map
+#[cfg(feature = "rayon")]
+mod parallel;
+
+/// Module containing structural diff and patch of a data item
+pub mod patch;
+
+/// Module containing query over a data item
+mod query;
+
+/// Module containing source of a CBOR encoded bytes which is read by streaming
+/// deserializer
+pub mod read;
+
+/// Module containing serde serializer
+#[cfg(feature = "serde")]
+pub mod ser;
+
+/// Module containing conversion between a data item and `serde_cbor` value
+#[cfg(feature = "serde_cbor")]
+mod serde_cbor;
+
+/// Module containing conversion between a byte content and reference counted
+/// `bytes::Bytes`
+#[cfg(feature = "bytes")]
+mod shared_bytes;
+
+/// Module containing byte range of decoded data item
+pub mod span;
+
+/// Module containing string reference (tag 25) compression and expansion
+/// inside a string reference namespace (tag 256)
+mod stringref;
+
+/// Module containing named constant for registered tag number
+pub mod tag;
+
+/// Module containing registry of tag validator and converter
+pub mod tag_registry;
+
+/// Module containing serde helper for preserving a tag
+#[cfg(feature = "serde")]
+pub mod tagged;
+
+/// Module containing typed array (tag 64 to tag 87) element and byte order
+pub mod typed_array;
+
+/// Module containing UUID conversion for tag 37 along with serde `with`
+/// helpers
+#[cfg(feature = "uuid")]
+pub mod uuid;
+
+/// Module containing writer abstraction used while encoding a data item
+pub mod write;
+
+#[doc(inline)]
+pub use builder::{ArrayBuilder, MapBuilder};
 #[doc(inline)]
 pub use content::{ArrayContent, ByteContent, MapContent, SimpleValue, TagContent, TextContent};
 #[doc(inline)]
 pub use data_item::DataItem;
+#[cfg(feature = "serde")]
+#[doc(inline)]
+pub use de::{from_bytes, from_data_item, from_reader};
+#[doc(inline)]
+pub use decode_options::DecodeOptions;
 #[doc(inline)]
 pub use deterministic::DeterministicMode;
 #[doc(inline)]
-pub use index::Get;
+pub use diagnostic_options::DiagnosticOptions;
+#[doc(inline)]
+pub use index::{Get, PathSegment};
+#[doc(inline)]
+pub use json_options::JsonOptions;
+#[cfg(feature = "serde")]
+#[doc(inline)]
+pub use maybe::Maybe;
+#[doc(inline)]
+pub use merge_options::MergeOptions;
+#[doc(inline)]
+pub use patch::{Operation, Patch, diff};
+#[cfg(feature = "serde")]
+#[doc(inline)]
+pub use ser::{to_data_item, to_vec, to_writer};
+#[doc(inline)]
+pub use span::SpanTree;
+#[doc(inline)]
+pub use tag_registry::TagRegistry;
+#[cfg(feature = "serde")]
+#[doc(inline)]
+pub use tagged::Tagged;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,136 @@
+use std::fmt::Formatter;
+use std::marker::PhantomData;
+
+use serde::de::Visitor;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Name of unit struct used for passing an undefined value through serde data
+/// model. Serializer and deserializer of this crate treat unit struct with
+/// this name as an undefined simple value
+pub(crate) const UNDEFINED_STRUCT_NAME: &str = "\0cbor_next::Undefined";
+
+/// Enum which distinguish between an absent value, a null value, an undefined
+/// value and a present value
+///
+/// Use `#[serde(default, skip_serializing_if = "Maybe::is_absent")]` on a
+/// field so absent value is not serialized. When used with other serde format
+/// both null and undefined value are represented as a none value
+///
+/// # Example
+/// ```rust
+/// use cbor_next::{DataItem, Maybe};
+///
+/// let data_item =
+///     cbor_next::to_data_item(&vec![Maybe::Null, Maybe::Undefined, Maybe::Value(1)]).unwrap();
+/// assert_eq!(
+///     data_item,
+///     DataItem::from(vec![DataItem::Null, DataItem::Undefined, DataItem::from(1)])
+/// );
+/// assert_eq!(
+///     cbor_next::from_data_item::<Vec<Maybe<u64>>>(data_item).unwrap(),
+///     vec![Maybe::Null, Maybe::Undefined, Maybe::Value(1)]
+/// );
+/// ```
+#[derive(Debug, PartialEq, Clone, Default)]
+#[non_exhaustive]
+pub enum Maybe<T> {
+    /// Value is absent
+    #[default]
+    Absent,
+    /// Value is null
+    Null,
+    /// Value is undefined
+    Undefined,
+    /// Value is present
+    Value(T),
+}
+
+impl<T> Maybe<T> {
+    /// Check if value is absent
+    #[must_use]
+    pub fn is_absent(&self) -> bool {
+        matches!(self, Self::Absent)
+    }
+
+    /// Get a present value
+    #[must_use]
+    pub fn value(&self) -> Option<&T> {
+        match self {
+            Self::Value(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl<T> Serialize for Maybe<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::Absent => serializer.serialize_none(),
+            Self::Null => serializer.serialize_unit(),
+            Self::Undefined => serializer.serialize_unit_struct(UNDEFINED_STRUCT_NAME),
+            Self::Value(value) => value.serialize(serializer),
+        }
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Maybe<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct(UNDEFINED_STRUCT_NAME, MaybeVisitor(PhantomData))
+    }
+}
+
+struct MaybeVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for MaybeVisitor<T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = Maybe<T>;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str("optional value")
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(Maybe::Undefined)
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(Maybe::Null)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(Maybe::Value)
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(match Option::<T>::deserialize(deserializer)? {
+            Some(value) => Maybe::Value(value),
+            None => Maybe::Null,
+        })
+    }
+}
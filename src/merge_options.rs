@@ -0,0 +1,34 @@
+/// Options which control how a patch is merged into a data item
+///
+/// By default merge follows JSON merge patch (RFC 7386) semantics where an
+/// array of a patch replaces an array of a target
+///
+/// # Example
+/// ```rust
+/// use cbor_next::{DataItem, MergeOptions};
+///
+/// let mut value = DataItem::from(vec![1, 2]);
+/// let mut options = MergeOptions::default();
+/// options.set_concat_array(true);
+/// value.merge_with_options(&DataItem::from(vec![3]), &options);
+/// assert_eq!(value, DataItem::from(vec![1, 2, 3]));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MergeOptions {
+    concat_array: bool,
+}
+
+impl MergeOptions {
+    /// Set whether an array of a patch is appended to an array of a target
+    /// instead of replacing it
+    pub fn set_concat_array(&mut self, concat_array: bool) -> &mut Self {
+        self.concat_array = concat_array;
+        self
+    }
+
+    /// Get whether an array of a patch is appended to an array of a target
+    #[must_use]
+    pub fn is_concat_array(&self) -> bool {
+        self.concat_array
+    }
+}
@@ -0,0 +1,74 @@
+use rayon::prelude::*;
+
+use crate::data_item::{DataItem, encode_into, encode_length_header};
+use crate::error::Error;
+
+/// Number of array items or map entries encoded into a single buffer by one
+/// task
+const CHUNK_SIZE: usize = 1024;
+
+/// Encode a data item where items of a top level array or entries of a top
+/// level map are encoded in parallel. Output is same as a sequential encoding
+pub(crate) fn encode(data_item: &DataItem) -> Vec<u8> {
+    let mut bytes = vec![];
+    if encode_into_vec(data_item, &mut bytes).is_err() {
+        unreachable!("writing into a vector cannot fail");
+    }
+    bytes
+}
+
+fn encode_into_vec(data_item: &DataItem, bytes: &mut Vec<u8>) -> Result<(), Error> {
+    let is_indefinite = match data_item {
+        DataItem::Array(array_content) => {
+            let is_indefinite = encode_length_header(
+                bytes,
+                data_item.major_type(),
+                array_content.is_indefinite(),
+                array_content.len(),
+            )?;
+            let buffers = array_content
+                .array()
+                .par_chunks(CHUNK_SIZE)
+                .map(|items| {
+                    let mut buffer = vec![];
+                    for item in items {
+                        encode_into(item, &mut buffer)?;
+                    }
+                    Ok(buffer)
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            bytes.extend(buffers.concat());
+            is_indefinite
+        }
+        DataItem::Map(map_content) => {
+            let is_indefinite = encode_length_header(
+                bytes,
+                data_item.major_type(),
+                map_content.is_indefinite(),
+                map_content.len(),
+            )?;
+            let entries = map_content.iter().collect::<Vec<_>>();
+            let buffers = entries
+                .par_chunks(CHUNK_SIZE)
+                .map(|entries| {
+                    let mut buffer = vec![];
+                    for (key, value) in entries {
+                        encode_into(key, &mut buffer)?;
+                        encode_into(value, &mut buffer)?;
+                    }
+                    Ok(buffer)
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            bytes.extend(buffers.concat());
+            is_indefinite
+        }
+        _ => {
+            encode_into(data_item, bytes)?;
+            false
+        }
+    };
+    if is_indefinite {
+        bytes.push(255);
+    }
+    Ok(())
+}
@@ -0,0 +1,326 @@
+use crate::data_item::DataItem;
+use crate::error::{Error, PatchFailure};
+
+const ADD: u64 = 0;
+const REMOVE: u64 = 1;
+const REPLACE: u64 = 2;
+
+/// Single operation of a patch
+///
+/// Path is a list of map key and array index (unsigned integer) which is
+/// followed from a root data item. An empty path refers to a root data item
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Operation {
+    /// Insert a value into a map or an array at a path. Value of an existing
+    /// map key is overwritten and array item at a path is shifted
+    Add {
+        /// Path where a value is inserted
+        path: Vec<DataItem>,
+        /// Inserted value
+        value: DataItem,
+    },
+    /// Remove a map entry or an array item at a path
+    Remove {
+        /// Path of a removed value
+        path: Vec<DataItem>,
+    },
+    /// Replace an existing value at a path
+    Replace {
+        /// Path of a replaced value
+        path: Vec<DataItem>,
+        /// New value
+        value: DataItem,
+    },
+}
+
+impl Operation {
+    /// Get a path of an operation
+    #[must_use]
+    pub fn path(&self) -> &[DataItem] {
+        match self {
+            Self::Add { path, .. } | Self::Remove { path } | Self::Replace { path, .. } => path,
+        }
+    }
+}
+
+/// List of operations which transform one data item into another
+///
+/// Patch can be converted into a data item to be encoded as a CBOR. Each
+/// operation is encoded as an array of an operation code (0 for add, 1 for
+/// remove and 2 for replace), path array and a value if operation has one
+///
+/// # Example
+/// ```rust
+/// use cbor_next::{DataItem, Patch, diff};
+///
+/// let source = DataItem::from_diagnostic(r#"{"a": [1, 2], "b": true}"#).unwrap();
+/// let target = DataItem::from_diagnostic(r#"{"a": [1, 3, 4]}"#).unwrap();
+/// let patch = diff(&source, &target);
+/// let encoded = DataItem::from(patch).encode();
+///
+/// let patch = Patch::try_from(DataItem::decode(&encoded).unwrap()).unwrap();
+/// let mut value = source.clone();
+/// patch.apply(&mut value).unwrap();
+/// assert_eq!(value, target);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Patch {
+    operations: Vec<Operation>,
+}
+
+impl Patch {
+    /// Get operations of a patch
+    #[must_use]
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
+
+    /// Check whether a patch does not have any operation
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Apply all operations of a patch in order to a data item
+    ///
+    /// # Errors
+    /// If a path of an operation does not exist in a data item. Operations
+    /// applied before a failing operation are not reverted
+    pub fn apply(&self, data_item: &mut DataItem) -> Result<(), Error> {
+        for operation in &self.operations {
+            apply_operation(operation, data_item)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Vec<Operation>> for Patch {
+    fn from(value: Vec<Operation>) -> Self {
+        Self { operations: value }
+    }
+}
+
+impl From<Patch> for DataItem {
+    fn from(value: Patch) -> Self {
+        value
+            .operations
+            .into_iter()
+            .map(|operation| {
+                match operation {
+                    Operation::Add { path, value } => {
+                        vec![Self::from(ADD), Self::from(path), value]
+                    }
+                    Operation::Remove { path } => vec![Self::from(REMOVE), Self::from(path)],
+                    Operation::Replace { path, value } => {
+                        vec![Self::from(REPLACE), Self::from(path), value]
+                    }
+                }
+            })
+            .collect::<Vec<_>>()
+            .into()
+    }
+}
+
+impl TryFrom<DataItem> for Patch {
+    type Error = Error;
+
+    fn try_from(value: DataItem) -> Result<Self, Self::Error> {
+        let operations = value
+            .as_array()
+            .ok_or(Error::InvalidPatch(PatchFailure::NotArray))?;
+        let mut patch_operations = vec![];
+        for (index, operation) in operations.iter().enumerate() {
+            let invalid = Error::InvalidPatch(PatchFailure::InvalidOperation { index });
+            let operation = match operation.as_array().unwrap_or_default() {
+                [code, path] if code.as_unsigned() == Some(REMOVE) => {
+                    Operation::Remove {
+                        path: path.as_array().ok_or(invalid)?.to_vec(),
+                    }
+                }
+                [code, path, value] if code.as_unsigned() == Some(ADD) => {
+                    Operation::Add {
+                        path: path.as_array().ok_or(invalid)?.to_vec(),
+                        value: value.clone(),
+                    }
+                }
+                [code, path, value] if code.as_unsigned() == Some(REPLACE) => {
+                    Operation::Replace {
+                        path: path.as_array().ok_or(invalid)?.to_vec(),
+                        value: value.clone(),
+                    }
+                }
+                _ => return Err(invalid),
+            };
+            patch_operations.push(operation);
+        }
+        Ok(patch_operations.into())
+    }
+}
+
+/// Compute a patch which transforms a source data item into a target data item
+///
+/// Map and array are compared recursively while any other data item which
+/// differs is replaced as a whole. Key added to a map is appended after
+/// existing keys
+///
+/// # Example
+/// ```rust
+/// use cbor_next::{DataItem, Operation, diff};
+///
+/// let source = DataItem::from_diagnostic(r#"{"a": 1, "b": 2}"#).unwrap();
+/// let target = DataItem::from_diagnostic(r#"{"a": 1, "b": 3}"#).unwrap();
+/// assert_eq!(
+///     diff(&source, &target).operations(),
+///     &[Operation::Replace {
+///         path: vec![DataItem::from("b")],
+///         value: DataItem::from(3)
+///     }]
+/// );
+/// ```
+#[must_use]
+pub fn diff(source: &DataItem, target: &DataItem) -> Patch {
+    let mut patch = Patch::default();
+    diff_item(source, target, &mut vec![], &mut patch.operations);
+    patch
+}
+
+/// Push operations which transform a source into a target at a path
+fn diff_item(
+    source: &DataItem,
+    target: &DataItem,
+    path: &mut Vec<DataItem>,
+    operations: &mut Vec<Operation>,
+) {
+    if source == target {
+        return;
+    }
+    match (source, target) {
+        (DataItem::Map(source_map), DataItem::Map(target_map))
+            if source_map.is_indefinite() == target_map.is_indefinite() =>
+        {
+            for (key, source_value) in source_map.map() {
+                path.push(key.clone());
+                match target_map.map().get(key) {
+                    Some(target_value) => diff_item(source_value, target_value, path, operations),
+                    None => {
+                        operations.push(Operation::Remove { path: path.clone() });
+                    }
+                }
+                path.pop();
+            }
+            for (key, target_value) in target_map.map() {
+                if !source_map.map().contains_key(key) {
+                    path.push(key.clone());
+                    operations.push(Operation::Add {
+                        path: path.clone(),
+                        value: target_value.clone(),
+                    });
+                    path.pop();
+                }
+            }
+        }
+        (DataItem::Array(source_array), DataItem::Array(target_array))
+            if source_array.is_indefinite() == target_array.is_indefinite() =>
+        {
+            let source_items = source_array.array();
+            let target_items = target_array.array();
+            for (index, (source_item, target_item)) in
+                source_items.iter().zip(target_items).enumerate()
+            {
+                path.push(index_segment(index));
+                diff_item(source_item, target_item, path, operations);
+                path.pop();
+            }
+            for (index, target_item) in target_items.iter().enumerate().skip(source_items.len()) {
+                path.push(index_segment(index));
+                operations.push(Operation::Add {
+                    path: path.clone(),
+                    value: target_item.clone(),
+                });
+                path.pop();
+            }
+            // remove from an end so that index of remaining item does not shift
+            for index in (target_items.len()..source_items.len()).rev() {
+                path.push(index_segment(index));
+                operations.push(Operation::Remove { path: path.clone() });
+                path.pop();
+            }
+        }
+        _ => {
+            operations.push(Operation::Replace {
+                path: path.clone(),
+                value: target.clone(),
+            });
+        }
+    }
+}
+
+/// Apply a single operation to a data item
+fn apply_operation(operation: &Operation, data_item: &mut DataItem) -> Result<(), Error> {
+    let Some((last, parent_path)) = operation.path().split_last() else {
+        return match operation {
+            Operation::Add { value, .. } | Operation::Replace { value, .. } => {
+                *data_item = value.clone();
+                Ok(())
+            }
+            Operation::Remove { .. } => Err(Error::InvalidPatch(PatchFailure::RootRemoval)),
+        };
+    };
+    let not_found = |segment| Error::InvalidPatch(PatchFailure::PathNotFound { segment });
+    let mut parent = data_item;
+    for (index, segment) in parent_path.iter().enumerate() {
+        parent = child_mut(parent, segment).ok_or(not_found(index))?;
+    }
+    let last_segment = parent_path.len();
+    match (operation, parent) {
+        (Operation::Add { value, .. }, DataItem::Map(map_content)) => {
+            map_content.insert(last.clone(), value.clone());
+        }
+        (Operation::Add { value, .. }, DataItem::Array(array_content)) => {
+            let index = array_index(last)
+                .filter(|index| *index <= array_content.array().len())
+                .ok_or(not_found(last_segment))?;
+            let mut items = array_content.array().to_vec();
+            items.insert(index, value.clone());
+            array_content.set_content(&items);
+        }
+        (Operation::Remove { .. }, DataItem::Map(map_content)) => {
+            map_content
+                .shift_remove(last)
+                .ok_or(not_found(last_segment))?;
+        }
+        (Operation::Remove { .. }, DataItem::Array(array_content)) => {
+            let index = array_index(last)
+                .filter(|index| *index < array_content.array().len())
+                .ok_or(not_found(last_segment))?;
+            let mut items = array_content.array().to_vec();
+            items.remove(index);
+            array_content.set_content(&items);
+        }
+        (Operation::Replace { value, .. }, parent) => {
+            *child_mut(parent, last).ok_or(not_found(last_segment))? = value.clone();
+        }
+        _ => return Err(not_found(last_segment)),
+    }
+    Ok(())
+}
+
+/// Get a mutable child of a map or an array using a path segment
+fn child_mut<'a>(data_item: &'a mut DataItem, segment: &DataItem) -> Option<&'a mut DataItem> {
+    match data_item {
+        DataItem::Map(map_content) => map_content.get_mut(segment),
+        DataItem::Array(array_content) => array_content.array_mut().get_mut(array_index(segment)?),
+        _ => None,
+    }
+}
+
+/// Get an array index from a path segment
+fn array_index(segment: &DataItem) -> Option<usize> {
+    usize::try_from(segment.as_unsigned()?).ok()
+}
+
+/// Get a path segment of an array index
+fn index_segment(index: usize) -> DataItem {
+    DataItem::from(u64::try_from(index).expect("array index can be converted to u64"))
+}
@@ -0,0 +1,113 @@
+use crate::data_item::DataItem;
+use crate::error::{Error, Syntax};
+use crate::index::Get as _;
+
+/// Single selector of a query
+enum Selector {
+    /// Value of a text string map key
+    Key(String),
+    /// Array item where negative index counts from an end of an array
+    Index(i64),
+    /// All array item or map value
+    Wildcard,
+}
+
+/// Select all nested data item matching a query
+pub(crate) fn query<'a>(data_item: &'a DataItem, query: &str) -> Result<Vec<&'a DataItem>, Error> {
+    let selectors = parse(query)?;
+    let mut current = vec![data_item];
+    for selector in &selectors {
+        let mut next = vec![];
+        for mut item in current {
+            while let DataItem::Tag(tag_content) = item {
+                item = tag_content.content();
+            }
+            match selector {
+                Selector::Key(key) => next.extend(item.get(key.as_str())),
+                Selector::Index(index) => {
+                    let found = match usize::try_from(*index) {
+                        Ok(index) => item.get(index),
+                        Err(_) => {
+                            usize::try_from(-1 - *index)
+                                .ok()
+                                .and_then(|index| item.get_back(index))
+                        }
+                    };
+                    next.extend(found);
+                }
+                Selector::Wildcard => {
+                    match item {
+                        DataItem::Array(array_content) => next.extend(array_content.array()),
+                        DataItem::Map(map_content) => next.extend(map_content.map().values()),
+                        _ => {}
+                    }
+                }
+            }
+        }
+        current = next;
+    }
+    Ok(current)
+}
+
+/// Parse a query into a list of selector
+fn parse(query: &str) -> Result<Vec<Selector>, Error> {
+    let invalid = |reason: Syntax, position: usize| Error::InvalidQuery { reason, position };
+    let mut selectors = vec![];
+    let mut position = usize::from(query.starts_with('$'));
+    while position < query.len() {
+        let rest = &query[position..];
+        if let Some(name) = rest.strip_prefix('.') {
+            if name.starts_with('*') {
+                selectors.push(Selector::Wildcard);
+                position += 2;
+                continue;
+            }
+            let length = name
+                .find(|character: char| {
+                    !(character.is_alphanumeric() || character == '_' || character == '-')
+                })
+                .unwrap_or(name.len());
+            if length == 0 {
+                return Err(invalid(Syntax::MissingKeyName, position + 1));
+            }
+            selectors.push(Selector::Key(name[..length].to_string()));
+            position += 1 + length;
+        } else if let Some(inner) = rest.strip_prefix('[') {
+            let (selector, length) =
+                parse_bracket(inner).ok_or_else(|| invalid(Syntax::InvalidSelector, position))?;
+            selectors.push(selector);
+            position += 1 + length;
+        } else {
+            return Err(invalid(Syntax::UnexpectedCharacter, position));
+        }
+    }
+    Ok(selectors)
+}
+
+/// Parse a content of a bracket selector after `[` returning a selector and a
+/// length including a closing `]`
+fn parse_bracket(inner: &str) -> Option<(Selector, usize)> {
+    if inner.starts_with("*]") {
+        return Some((Selector::Wildcard, 2));
+    }
+    if let Some(quoted) = inner.strip_prefix('"') {
+        let mut key = String::new();
+        let mut characters = quoted.char_indices();
+        while let Some((index, character)) = characters.next() {
+            match character {
+                '\\' => key.push(characters.next()?.1),
+                '"' => {
+                    let end = 1 + index + 1;
+                    return inner[end..]
+                        .starts_with(']')
+                        .then_some((Selector::Key(key), end + 1));
+                }
+                _ => key.push(character),
+            }
+        }
+        return None;
+    }
+    let end = inner.find(']')?;
+    let index = inner[..end].trim().parse().ok()?;
+    Some((Selector::Index(index), end + 1))
+}
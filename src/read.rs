@@ -0,0 +1,156 @@
+use std::borrow::Cow;
+use std::io::Read;
+
+use crate::error::{Error, Malformed};
+
+/// Source of a CBOR encoded bytes
+///
+/// Streaming deserialization reads through this trait so a value can be
+/// deserialized from a borrowed slice without copying a byte string and text
+/// string, or from any `std::io::Read` wrapped in an [`IoReader`]
+///
+/// # Example
+/// ```rust
+/// use cbor_next::read::CborRead as _;
+///
+/// let mut bytes = [0x61, 0x61, 0x02].as_slice();
+/// assert_eq!(bytes.peek_byte().unwrap(), Some(0x61));
+/// assert_eq!(bytes.read_byte().unwrap(), Some(0x61));
+/// assert_eq!(bytes.read_bytes(1).unwrap().as_ref(), b"a");
+/// assert_eq!(bytes, [0x02]);
+/// ```
+pub trait CborRead<'de> {
+    /// Get a next byte without consuming it. Returns `None` at an end of input
+    ///
+    /// # Errors
+    /// If a byte cannot be read
+    fn peek_byte(&mut self) -> Result<Option<u8>, Error>;
+
+    /// Consume a next byte. Returns `None` at an end of input
+    ///
+    /// # Errors
+    /// If a byte cannot be read
+    fn read_byte(&mut self) -> Result<Option<u8>, Error>;
+
+    /// Consume exactly provided number of byte
+    ///
+    /// # Errors
+    /// If input ends before all bytes are read, which is reported as a number
+    /// of missing byte, or bytes cannot be read
+    fn read_bytes(&mut self, length: u64) -> Result<Cow<'de, [u8]>, Error>;
+}
+
+impl<'de> CborRead<'de> for &'de [u8] {
+    fn peek_byte(&mut self) -> Result<Option<u8>, Error> {
+        Ok(self.first().copied())
+    }
+
+    fn read_byte(&mut self) -> Result<Option<u8>, Error> {
+        let Some((byte, remaining)) = self.split_first() else {
+            return Ok(None);
+        };
+        *self = remaining;
+        Ok(Some(*byte))
+    }
+
+    /// Read bytes by borrowing them from a slice and move a slice past read
+    /// bytes
+    fn read_bytes(&mut self, length: u64) -> Result<Cow<'de, [u8]>, Error> {
+        let bytes = usize::try_from(length)
+            .ok()
+            .and_then(|length| self.get(..length))
+            .ok_or_else(|| {
+                Error::NotWellFormed(Malformed::MissingBytes {
+                    missing: length - self.len() as u64,
+                })
+            })?;
+        *self = &self[bytes.len()..];
+        Ok(Cow::Borrowed(bytes))
+    }
+}
+
+/// Adapter which reads a CBOR encoded bytes from a `std::io::Read`
+///
+/// A single byte is buffered for peeking. Byte string and text string are
+/// read into a buffer which grows while bytes arrive, so a declared length
+/// alone doesn't allocate
+///
+/// # Example
+/// ```rust
+/// use std::io::Cursor;
+///
+/// use cbor_next::read::{CborRead as _, IoReader};
+///
+/// let mut reader = IoReader::new(Cursor::new(vec![0x61, 0x61]));
+/// assert_eq!(reader.read_byte().unwrap(), Some(0x61));
+/// assert_eq!(reader.read_bytes(1).unwrap().as_ref(), b"a");
+/// assert!(reader.read_bytes(1).is_err());
+/// ```
+#[derive(Debug)]
+pub struct IoReader<R> {
+    reader: R,
+    peeked: Option<u8>,
+}
+
+impl<R> IoReader<R> {
+    /// Create an adapter around provided reader
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            peeked: None,
+        }
+    }
+
+    /// Get back an underlying reader. A peeked byte is lost
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<'de, R> CborRead<'de> for IoReader<R>
+where
+    R: Read,
+{
+    fn peek_byte(&mut self) -> Result<Option<u8>, Error> {
+        if self.peeked.is_none() {
+            self.peeked = self.read_byte()?;
+        }
+        Ok(self.peeked)
+    }
+
+    fn read_byte(&mut self) -> Result<Option<u8>, Error> {
+        if let Some(byte) = self.peeked.take() {
+            return Ok(Some(byte));
+        }
+        let mut byte = [0];
+        loop {
+            match self.reader.read(&mut byte) {
+                Ok(0) => return Ok(None),
+                Ok(_) => return Ok(Some(byte[0])),
+                Err(error) if error.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(error) => return Err(error.into()),
+            }
+        }
+    }
+
+    fn read_bytes(&mut self, length: u64) -> Result<Cow<'de, [u8]>, Error> {
+        let mut bytes = vec![];
+        let mut remaining = length;
+        if remaining > 0
+            && let Some(byte) = self.peeked.take()
+        {
+            bytes.push(byte);
+            remaining -= 1;
+        }
+        self.reader
+            .by_ref()
+            .take(remaining)
+            .read_to_end(&mut bytes)?;
+        if bytes.len() as u64 != length {
+            return Err(Error::NotWellFormed(Malformed::MissingBytes {
+                missing: length - bytes.len() as u64,
+            }));
+        }
+        Ok(Cow::Owned(bytes))
+    }
+}
@@ -0,0 +1,1099 @@
+use std::borrow::Cow;
+use std::io::Write;
+
+use indexmap::IndexMap;
+use serde::ser::{Error as _, Serialize, SerializeMap as _, SerializeSeq as _};
+
+use crate::content::{ArrayContent, ByteContent, MapContent, SimpleValue, TagContent, TextContent};
+use crate::data_item::{DataItem, encode_length_header};
+use crate::error::Error;
+use crate::maybe::UNDEFINED_STRUCT_NAME;
+use crate::tagged::TAG_STRUCT_NAME;
+use crate::write::{CborWrite, IoWriter};
+
+/// Name of newtype struct used for passing a simple value through serde data
+/// model. Serializer and deserializer of this crate treat newtype struct with
+/// this name as a simple value of a wrapped number
+pub(crate) const SIMPLE_STRUCT_NAME: &str = "\0cbor_next::SimpleValue";
+
+/// Serialize a value into a data item
+///
+/// # Example
+/// ```rust
+/// use cbor_next::DataItem;
+///
+/// let value = cbor_next::to_data_item(&vec![1, 2, 3]).unwrap();
+/// assert_eq!(value, DataItem::from(vec![1, 2, 3]));
+/// ```
+///
+/// # Errors
+/// If value cannot be serialized into a data item
+pub fn to_data_item<T>(value: &T) -> Result<DataItem, Error>
+where
+    T: Serialize + ?Sized,
+{
+    value.serialize(Serializer::default())
+}
+
+/// Serialize a value into a data item with provided serializer options
+///
+/// # Example
+/// ```rust
+/// use cbor_next::DataItem;
+/// use cbor_next::ser::SerializerOptions;
+///
+/// let mut options = SerializerOptions::default();
+/// options.set_none_as_undefined(true);
+/// let value = cbor_next::ser::to_data_item_with_options(&None::<u64>, &options).unwrap();
+/// assert_eq!(value, DataItem::Undefined);
+/// ```
+///
+/// # Errors
+/// If value cannot be serialized into a data item
+pub fn to_data_item_with_options<T>(
+    value: &T,
+    options: &SerializerOptions,
+) -> Result<DataItem, Error>
+where
+    T: Serialize + ?Sized,
+{
+    value.serialize(Serializer::new(*options))
+}
+
+/// Serialize a value into a CBOR encoded bytes
+///
+/// # Example
+/// ```rust
+/// let bytes = cbor_next::to_vec(&vec![1, 2, 3]).unwrap();
+/// assert_eq!(bytes, vec![0x83, 0x01, 0x02, 0x03]);
+/// ```
+///
+/// # Errors
+/// If value cannot be serialized into a data item
+pub fn to_vec<T>(value: &T) -> Result<Vec<u8>, Error>
+where
+    T: Serialize + ?Sized,
+{
+    let mut bytes = vec![];
+    value.serialize(StreamSerializer::new(&mut bytes))?;
+    Ok(bytes)
+}
+
+/// Serialize a value into a CBOR encoded bytes with provided serializer
+/// options
+///
+/// # Example
+/// ```rust
+/// use cbor_next::ser::SerializerOptions;
+///
+/// let mut options = SerializerOptions::default();
+/// options.set_none_as_undefined(true);
+/// let bytes = cbor_next::ser::to_vec_with_options(&vec![Some(1), None], &options).unwrap();
+/// assert_eq!(bytes, vec![0x82, 0x01, 0xf7]);
+/// ```
+///
+/// # Errors
+/// If value cannot be serialized into a data item
+pub fn to_vec_with_options<T>(value: &T, options: &SerializerOptions) -> Result<Vec<u8>, Error>
+where
+    T: Serialize + ?Sized,
+{
+    let mut bytes = vec![];
+    value.serialize(StreamSerializer::with_options(&mut bytes, *options))?;
+    Ok(bytes)
+}
+
+/// Serialize a value as a CBOR encoded bytes into a writer
+///
+/// # Example
+/// ```rust
+/// let mut writer = vec![];
+/// cbor_next::to_writer(&mut writer, &vec![1, 2, 3]).unwrap();
+/// assert_eq!(writer, vec![0x83, 0x01, 0x02, 0x03]);
+/// ```
+///
+/// # Errors
+/// If value cannot be serialized into a data item or writer fails to write
+/// bytes
+pub fn to_writer<W, T>(mut writer: W, value: &T) -> Result<(), Error>
+where
+    W: Write,
+    T: Serialize + ?Sized,
+{
+    value.serialize(StreamSerializer::new(&mut IoWriter::new(&mut writer)))
+}
+
+/// Options which control how a value is serialized into a [`DataItem`]
+#[derive(Default, Clone, Copy)]
+pub struct SerializerOptions {
+    none_as_undefined: bool,
+}
+
+impl SerializerOptions {
+    /// Set whether `None` should be serialized as undefined instead of null
+    pub fn set_none_as_undefined(&mut self, none_as_undefined: bool) -> &mut Self {
+        self.none_as_undefined = none_as_undefined;
+        self
+    }
+
+    /// Get whether `None` is serialized as undefined instead of null
+    #[must_use]
+    pub fn is_none_as_undefined(&self) -> bool {
+        self.none_as_undefined
+    }
+}
+
+/// Serializer which converts a serializable value into a [`DataItem`]
+#[derive(Default, Clone, Copy)]
+pub struct Serializer {
+    options: SerializerOptions,
+}
+
+impl Serializer {
+    /// Create a new serializer with provided options
+    #[must_use]
+    pub fn new(options: SerializerOptions) -> Self {
+        Self { options }
+    }
+}
+
+impl serde::Serializer for Serializer {
+    type Error = Error;
+    type Ok = DataItem;
+    type SerializeMap = SerializeMap;
+    type SerializeSeq = SerializeArray;
+    type SerializeStruct = SerializeMap;
+    type SerializeStructVariant = SerializeStructVariant;
+    type SerializeTuple = SerializeArray;
+    type SerializeTupleStruct = SerializeArray;
+    type SerializeTupleVariant = SerializeTupleVariant;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        Ok(v.to_string().into())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        Ok(v.into())
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        if self.options.none_as_undefined {
+            Ok(DataItem::Undefined)
+        } else {
+            Ok(DataItem::Null)
+        }
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        Ok(DataItem::Null)
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
+        if name == UNDEFINED_STRUCT_NAME {
+            return Ok(DataItem::Undefined);
+        }
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        Ok(DataItem::from(Cow::Borrowed(variant)))
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let data_item = value.serialize(self)?;
+        if name == SIMPLE_STRUCT_NAME {
+            return data_item
+                .as_unsigned()
+                .and_then(|number| u8::try_from(number).ok())
+                .and_then(SimpleValue::new)
+                .map(DataItem::from)
+                .ok_or_else(|| {
+                    Error::custom("simple value should be a number outside of 24 to 31")
+                });
+        }
+        if name != TAG_STRUCT_NAME {
+            return Ok(data_item);
+        }
+        match data_item.as_array() {
+            Some([DataItem::Unsigned(number), content]) => {
+                Ok(TagContent::from((*number, content)).into())
+            }
+            _ => {
+                Err(Error::custom(
+                    "tagged value should be a tuple of tag number and content",
+                ))
+            }
+        }
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        Ok(vec![(
+            DataItem::from(Cow::Borrowed(variant)),
+            value.serialize(self)?,
+        )]
+        .into())
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Ok(SerializeArray {
+            serializer: self,
+            array: Vec::with_capacity(len.unwrap_or_default()),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Ok(SerializeTupleVariant {
+            serializer: self,
+            variant,
+            array: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(SerializeMap {
+            serializer: self,
+            map: IndexMap::with_capacity(len.unwrap_or_default()),
+            next_key: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Ok(SerializeStructVariant {
+            serializer: self,
+            variant,
+            map: IndexMap::with_capacity(len),
+        })
+    }
+}
+
+/// Serializer used for serializing a sequence, tuple and tuple struct
+pub struct SerializeArray {
+    serializer: Serializer,
+    array: Vec<DataItem>,
+}
+
+impl serde::ser::SerializeSeq for SerializeArray {
+    type Error = Error;
+    type Ok = DataItem;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.array.push(value.serialize(self.serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(ArrayContent::from(self.array).into())
+    }
+}
+
+impl serde::ser::SerializeTuple for SerializeArray {
+    type Error = Error;
+    type Ok = DataItem;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        serde::ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        serde::ser::SerializeSeq::end(self)
+    }
+}
+
+impl serde::ser::SerializeTupleStruct for SerializeArray {
+    type Error = Error;
+    type Ok = DataItem;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        serde::ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        serde::ser::SerializeSeq::end(self)
+    }
+}
+
+/// Serializer used for serializing a tuple variant
+pub struct SerializeTupleVariant {
+    serializer: Serializer,
+    variant: &'static str,
+    array: Vec<DataItem>,
+}
+
+impl serde::ser::SerializeTupleVariant for SerializeTupleVariant {
+    type Error = Error;
+    type Ok = DataItem;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.array.push(value.serialize(self.serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(vec![(
+            DataItem::from(Cow::Borrowed(self.variant)),
+            DataItem::from(ArrayContent::from(self.array)),
+        )]
+        .into())
+    }
+}
+
+/// Serializer used for serializing a map and struct
+pub struct SerializeMap {
+    serializer: Serializer,
+    map: IndexMap<DataItem, DataItem>,
+    next_key: Option<DataItem>,
+}
+
+impl serde::ser::SerializeMap for SerializeMap {
+    type Error = Error;
+    type Ok = DataItem;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.next_key = Some(key.serialize(self.serializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let key = self
+            .next_key
+            .take()
+            .ok_or_else(|| Error::custom("serialize value called before serialize key"))?;
+        self.map.insert(key, value.serialize(self.serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(MapContent::from(self.map).into())
+    }
+}
+
+impl serde::ser::SerializeStruct for SerializeMap {
+    type Error = Error;
+    type Ok = DataItem;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.map.insert(
+            DataItem::from(Cow::Borrowed(key)),
+            value.serialize(self.serializer)?,
+        );
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        serde::ser::SerializeMap::end(self)
+    }
+}
+
+/// Serializer used for serializing a struct variant
+pub struct SerializeStructVariant {
+    serializer: Serializer,
+    variant: &'static str,
+    map: IndexMap<DataItem, DataItem>,
+}
+
+impl serde::ser::SerializeStructVariant for SerializeStructVariant {
+    type Error = Error;
+    type Ok = DataItem;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.map.insert(
+            DataItem::from(Cow::Borrowed(key)),
+            value.serialize(self.serializer)?,
+        );
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(vec![(
+            DataItem::from(Cow::Borrowed(self.variant)),
+            DataItem::from(MapContent::from(self.map)),
+        )]
+        .into())
+    }
+}
+
+/// Serializer which writes a CBOR encoded bytes of a serializable value
+/// directly into a writer without building a data item
+///
+/// An array or a map whose length is not known up front is written as an
+/// indefinite length array or map. A map entry is written in a serialized
+/// order, so a repeated key is written again instead of replacing an earlier
+/// entry. Only content of a tag is collected into a data item before it is
+/// written. Together with [`crate::de::StreamDeserializer`] it can be used
+/// for converting between CBOR and another serde format
+///
+/// # Example
+/// ```rust
+/// use cbor_next::ser::StreamSerializer;
+/// use serde::Serialize as _;
+///
+/// let mut bytes = vec![];
+/// vec![1, 2, 3]
+///     .serialize(StreamSerializer::new(&mut bytes))
+///     .unwrap();
+/// assert_eq!(bytes, vec![0x83, 0x01, 0x02, 0x03]);
+/// ```
+pub struct StreamSerializer<'a, W>
+where
+    W: CborWrite + ?Sized,
+{
+    writer: &'a mut W,
+    options: SerializerOptions,
+}
+
+impl<'a, W> StreamSerializer<'a, W>
+where
+    W: CborWrite + ?Sized,
+{
+    /// Create a new serializer which writes into provided writer
+    pub fn new(writer: &'a mut W) -> Self {
+        Self::with_options(writer, SerializerOptions::default())
+    }
+
+    /// Create a new serializer which writes into provided writer with
+    /// provided options
+    pub fn with_options(writer: &'a mut W, options: SerializerOptions) -> Self {
+        Self { writer, options }
+    }
+
+    fn write_data_item(self, data_item: &DataItem) -> Result<(), Error> {
+        data_item.encode_to(self.writer)
+    }
+
+    fn write_str(self, major_type: u8, bytes: &[u8]) -> Result<(), Error> {
+        encode_length_header(self.writer, major_type, false, bytes.len())?;
+        self.writer.write_bytes(bytes)
+    }
+
+    fn write_variant(&mut self, variant: &str) -> Result<(), Error> {
+        encode_length_header(self.writer, 5, false, 1)?;
+        encode_length_header(self.writer, 3, false, variant.len())?;
+        self.writer.write_bytes(variant.as_bytes())
+    }
+
+    fn into_compound(
+        self,
+        major_type: u8,
+        length: Option<usize>,
+    ) -> Result<StreamCompound<'a, W>, Error> {
+        let is_indefinite = encode_length_header(
+            self.writer,
+            major_type,
+            length.is_none(),
+            length.unwrap_or_default(),
+        )?;
+        Ok(StreamCompound {
+            writer: self.writer,
+            options: self.options,
+            is_indefinite,
+        })
+    }
+}
+
+impl<'a, W> serde::Serializer for StreamSerializer<'a, W>
+where
+    W: CborWrite + ?Sized,
+{
+    type Error = Error;
+    type Ok = ();
+    type SerializeMap = StreamCompound<'a, W>;
+    type SerializeSeq = StreamCompound<'a, W>;
+    type SerializeStruct = StreamCompound<'a, W>;
+    type SerializeStructVariant = StreamCompound<'a, W>;
+    type SerializeTuple = StreamCompound<'a, W>;
+    type SerializeTupleStruct = StreamCompound<'a, W>;
+    type SerializeTupleVariant = StreamCompound<'a, W>;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+        self.write_data_item(&v.into())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+        self.write_data_item(&v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+        self.write_data_item(&v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+        self.write_data_item(&v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+        self.write_data_item(&v.into())
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
+        self.write_data_item(&v.into())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+        self.write_data_item(&v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+        self.write_data_item(&v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+        self.write_data_item(&v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+        self.write_data_item(&v.into())
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
+        self.write_data_item(&v.into())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+        self.write_data_item(&v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+        self.write_data_item(&v.into())
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        self.write_str(3, v.encode_utf8(&mut [0; 4]).as_bytes())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        self.write_str(3, v.as_bytes())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        self.write_str(2, v)
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        if self.options.none_as_undefined {
+            self.write_data_item(&DataItem::Undefined)
+        } else {
+            self.write_data_item(&DataItem::Null)
+        }
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        self.write_data_item(&DataItem::Null)
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
+        if name == UNDEFINED_STRUCT_NAME {
+            return self.write_data_item(&DataItem::Undefined);
+        }
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.write_str(3, variant.as_bytes())
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        if name == TAG_STRUCT_NAME || name == SIMPLE_STRUCT_NAME {
+            let data_item = Serializer::new(self.options).serialize_newtype_struct(name, value)?;
+            return self.write_data_item(&data_item);
+        }
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        mut self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.write_variant(variant)?;
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        self.into_compound(4, len)
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.into_compound(4, Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.into_compound(4, Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        mut self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        self.write_variant(variant)?;
+        self.into_compound(4, Some(len))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        self.into_compound(5, len)
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        self.into_compound(5, Some(len))
+    }
+
+    fn serialize_struct_variant(
+        mut self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        self.write_variant(variant)?;
+        self.into_compound(5, Some(len))
+    }
+}
+
+/// Serializer used by [`StreamSerializer`] for serializing an array, a map and
+/// their variants. Items are written as soon as they are serialized
+pub struct StreamCompound<'a, W>
+where
+    W: CborWrite + ?Sized,
+{
+    writer: &'a mut W,
+    options: SerializerOptions,
+    is_indefinite: bool,
+}
+
+impl<W> StreamCompound<'_, W>
+where
+    W: CborWrite + ?Sized,
+{
+    fn serialize_item<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        value.serialize(StreamSerializer::with_options(
+            &mut *self.writer,
+            self.options,
+        ))
+    }
+
+    fn finish(self) -> Result<(), Error> {
+        if self.is_indefinite {
+            self.writer.write_bytes(&[255])?;
+        }
+        Ok(())
+    }
+}
+
+impl<W> serde::ser::SerializeSeq for StreamCompound<'_, W>
+where
+    W: CborWrite + ?Sized,
+{
+    type Error = Error;
+    type Ok = ();
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.serialize_item(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.finish()
+    }
+}
+
+impl<W> serde::ser::SerializeTuple for StreamCompound<'_, W>
+where
+    W: CborWrite + ?Sized,
+{
+    type Error = Error;
+    type Ok = ();
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.serialize_item(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.finish()
+    }
+}
+
+impl<W> serde::ser::SerializeTupleStruct for StreamCompound<'_, W>
+where
+    W: CborWrite + ?Sized,
+{
+    type Error = Error;
+    type Ok = ();
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.serialize_item(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.finish()
+    }
+}
+
+impl<W> serde::ser::SerializeTupleVariant for StreamCompound<'_, W>
+where
+    W: CborWrite + ?Sized,
+{
+    type Error = Error;
+    type Ok = ();
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.serialize_item(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.finish()
+    }
+}
+
+impl<W> serde::ser::SerializeMap for StreamCompound<'_, W>
+where
+    W: CborWrite + ?Sized,
+{
+    type Error = Error;
+    type Ok = ();
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.serialize_item(key)
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.serialize_item(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.finish()
+    }
+}
+
+impl<W> serde::ser::SerializeStruct for StreamCompound<'_, W>
+where
+    W: CborWrite + ?Sized,
+{
+    type Error = Error;
+    type Ok = ();
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.serialize_item(key)?;
+        self.serialize_item(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.finish()
+    }
+}
+
+impl<W> serde::ser::SerializeStructVariant for StreamCompound<'_, W>
+where
+    W: CborWrite + ?Sized,
+{
+    type Error = Error;
+    type Ok = ();
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.serialize_item(key)?;
+        self.serialize_item(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.finish()
+    }
+}
+
+impl Serialize for DataItem {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Self::Unsigned(number) => serializer.serialize_u64(*number),
+            Self::Signed(_) => {
+                let number = self
+                    .as_signed()
+                    .expect("signed data item is always a number");
+                match i64::try_from(number) {
+                    Ok(i64_number) => serializer.serialize_i64(i64_number),
+                    Err(_) => serializer.serialize_i128(number),
+                }
+            }
+            Self::Byte(byte_content) => byte_content.serialize(serializer),
+            Self::Text(text_content) => text_content.serialize(serializer),
+            Self::Array(array_content) => array_content.serialize(serializer),
+            Self::Map(map_content) => map_content.serialize(serializer),
+            Self::Tag(tag_content) => tag_content.serialize(serializer),
+            Self::Boolean(bool_val) => serializer.serialize_bool(*bool_val),
+            Self::Null => serializer.serialize_unit(),
+            Self::Undefined => serializer.serialize_unit_struct(UNDEFINED_STRUCT_NAME),
+            Self::Floating(number) => serializer.serialize_f64(*number),
+            Self::GenericSimple(simple_value) => simple_value.serialize(serializer),
+        }
+    }
+}
+
+impl Serialize for ByteContent {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_bytes(&self.full())
+    }
+}
+
+impl Serialize for TextContent {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.full())
+    }
+}
+
+impl Serialize for ArrayContent {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for item in self {
+            seq.serialize_element(item)?;
+        }
+        seq.end()
+    }
+}
+
+impl Serialize for MapContent {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.map().len()))?;
+        for (key, value) in self.map() {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+impl Serialize for TagContent {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_newtype_struct(TAG_STRUCT_NAME, &(self.number(), self.content()))
+    }
+}
+
+impl Serialize for SimpleValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_newtype_struct(SIMPLE_STRUCT_NAME, &self.value())
+    }
+}
@@ -0,0 +1,102 @@
+use std::collections::BTreeMap;
+
+use ::serde_cbor::Value;
+
+use crate::content::TagContent;
+use crate::data_item::DataItem;
+use crate::error::{Conversion, Error, ItemKind};
+
+/// Convert a `serde_cbor` value into a data item
+///
+/// Integer outside of a CBOR integer range is converted to a bignum (tag 2 or
+/// tag 3). Map keeps a canonical key order of a `serde_cbor` value
+///
+/// # Errors
+/// If a value is a hidden variant which is not known to this crate
+impl TryFrom<Value> for DataItem {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        let data_item = match value {
+            Value::Null => Self::Null,
+            Value::Bool(bool_val) => Self::Boolean(bool_val),
+            Value::Integer(number) => number.into(),
+            Value::Float(number) => Self::Floating(number),
+            Value::Bytes(bytes) => bytes.as_slice().into(),
+            Value::Text(string) => string.into(),
+            Value::Array(array) => {
+                array
+                    .into_iter()
+                    .map(Self::try_from)
+                    .collect::<Result<Vec<_>, _>>()?
+                    .into()
+            }
+            Value::Map(map) => {
+                map.into_iter()
+                    .map(|(key, value)| Ok((Self::try_from(key)?, Self::try_from(value)?)))
+                    .collect::<Result<Vec<_>, Error>>()?
+                    .into()
+            }
+            Value::Tag(number, content) => {
+                TagContent::from((number, Self::try_from(*content)?)).into()
+            }
+            _ => return Err(Error::ValueConversion(Conversion::UnsupportedValue)),
+        };
+        Ok(data_item)
+    }
+}
+
+/// Convert a data item into a `serde_cbor` value
+///
+/// Since a `serde_cbor` value sorts a map using a canonical order, insertion
+/// order of a map is not preserved. Indefinite length data item is converted
+/// to a definite length value and undefined is converted to a null same as a
+/// `serde_cbor` deserializer
+///
+/// # Errors
+/// If a data item is a generic simple value or a map have two keys which are
+/// equal after conversion
+impl TryFrom<DataItem> for Value {
+    type Error = Error;
+
+    fn try_from(value: DataItem) -> Result<Self, Self::Error> {
+        let value = match value {
+            DataItem::Unsigned(number) => Self::Integer(number.into()),
+            DataItem::Signed(number) => Self::Integer(-1 - i128::from(number)),
+            DataItem::Floating(number) => Self::Float(number),
+            DataItem::Byte(byte_content) => Self::Bytes(byte_content.into_full()),
+            DataItem::Text(text_content) => Self::Text(text_content.into_full()),
+            DataItem::Array(array_content) => {
+                Self::Array(
+                    array_content
+                        .into_iter()
+                        .map(Self::try_from)
+                        .collect::<Result<_, _>>()?,
+                )
+            }
+            DataItem::Map(map_content) => {
+                let mut map = BTreeMap::new();
+                for (key, value) in map_content {
+                    let key = Self::try_from(key)?;
+                    if map.contains_key(&key) {
+                        return Err(Error::ValueConversion(Conversion::DuplicateKey));
+                    }
+                    map.insert(key, Self::try_from(value)?);
+                }
+                Self::Map(map)
+            }
+            DataItem::Tag(tag_content) => {
+                let (number, content) = tag_content.into_parts();
+                Self::Tag(number, Box::new(Self::try_from(content)?))
+            }
+            DataItem::Boolean(bool_val) => Self::Bool(bool_val),
+            DataItem::Null | DataItem::Undefined => Self::Null,
+            DataItem::GenericSimple(_) => {
+                return Err(Error::ValueConversion(Conversion::UnsupportedItem {
+                    kind: ItemKind::of(&value),
+                }));
+            }
+        };
+        Ok(value)
+    }
+}
@@ -0,0 +1,38 @@
+use ::bytes::Bytes;
+
+use crate::content::ByteContent;
+use crate::data_item::DataItem;
+
+/// Create a definite length byte content from bytes. Allocation of bytes is
+/// reused without copying when bytes is a sole owner of a full allocation
+impl From<Bytes> for ByteContent {
+    fn from(value: Bytes) -> Self {
+        Vec::from(value).into()
+    }
+}
+
+impl From<Bytes> for DataItem {
+    fn from(value: Bytes) -> Self {
+        Self::Byte(value.into())
+    }
+}
+
+/// Create bytes from full bytes of a byte content. Single chunk is moved
+/// without copying while multiple chunks are concatenated
+///
+/// # Example
+/// ```rust
+/// use bytes::Bytes;
+/// use cbor_next::ByteContent;
+///
+/// let content = ByteContent::from(Bytes::from_static(&[1, 2, 3]));
+/// assert_eq!(Bytes::from(content), Bytes::from_static(&[1, 2, 3]));
+/// ```
+impl From<ByteContent> for Bytes {
+    fn from(value: ByteContent) -> Self {
+        match value.into_chunks().as_mut_slice() {
+            [chunk] => Self::from(std::mem::take(chunk)),
+            chunks => Self::from(chunks.concat()),
+        }
+    }
+}
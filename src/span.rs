@@ -0,0 +1,139 @@
+use std::ops::Range;
+
+use crate::error::{Error, Malformed};
+
+/// Tree of byte range of a data item and its nested data item inside a source
+/// CBOR bytes
+///
+/// Children of an array are its items, children of a map are its key and
+/// value in an alternate order, child of a tag is its content and children of
+/// an indefinite length byte string and text string are its chunks
+///
+/// # Example
+/// ```rust
+/// use cbor_next::DataItem;
+///
+/// let bytes = [0xa1, 0x61, 0x61, 0x82, 0x01, 0x18, 0x64];
+/// let (_, span) = DataItem::decode_with_span(&bytes).unwrap();
+/// assert_eq!(span.range(), 0..7);
+/// assert_eq!(span.children()[0].range(), 1..3);
+/// let value_span = &span.children()[1];
+/// assert_eq!(&bytes[value_span.range()], &[0x82, 0x01, 0x18, 0x64]);
+/// assert_eq!(value_span.children()[1].range(), 5..7);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpanTree {
+    range: Range<usize>,
+    children: Vec<SpanTree>,
+}
+
+impl SpanTree {
+    /// Get a byte range of a data item including its header and, for an
+    /// indefinite length data item, its break stop
+    #[must_use]
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Get a span of nested data item
+    #[must_use]
+    pub fn children(&self) -> &[SpanTree] {
+        &self.children
+    }
+}
+
+/// Collector which builds a span tree while a data item is decoded. A disabled
+/// collector doesn't record anything
+pub(crate) struct SpanCollector {
+    length: usize,
+    enabled: bool,
+    /// Start of every data item which is being decoded along with its already
+    /// decoded children. A first entry only collects a root span
+    stack: Vec<(usize, Vec<SpanTree>)>,
+}
+
+impl SpanCollector {
+    /// Create a collector which records nothing
+    pub(crate) fn disabled() -> Self {
+        Self {
+            length: 0,
+            enabled: false,
+            stack: vec![],
+        }
+    }
+
+    /// Create a collector over a source bytes of provided length
+    pub(crate) fn new(length: usize) -> Self {
+        Self {
+            length,
+            enabled: true,
+            stack: vec![(0, vec![])],
+        }
+    }
+
+    /// Start a span of a data item when `remaining` bytes are left to decode
+    pub(crate) fn enter(&mut self, remaining: usize) {
+        if self.enabled {
+            self.stack.push((self.length - remaining, vec![]));
+        }
+    }
+
+    /// Finish a span of a most recently entered data item when `remaining`
+    /// bytes are left to decode
+    pub(crate) fn leave(&mut self, remaining: usize) {
+        if self.enabled
+            && let Some((start, children)) = self.stack.pop()
+            && let Some((_, parent_children)) = self.stack.last_mut()
+        {
+            parent_children.push(SpanTree {
+                range: start..self.length - remaining,
+                children,
+            });
+        }
+    }
+
+    /// Get a span tree of a decoded root data item
+    pub(crate) fn into_span_tree(mut self) -> Option<SpanTree> {
+        self.stack.pop()?.1.pop()
+    }
+}
+
+/// Header of a data item
+pub(crate) struct Header {
+    /// Major type of a data item
+    pub(crate) major_type: u8,
+    /// Argument of a data item. `None` for an indefinite length data item
+    pub(crate) argument: Option<u64>,
+}
+
+/// Read a header of a data item starting at provided position and move
+/// position to the end of a header
+pub(crate) fn read_header(bytes: &[u8], position: &mut usize) -> Result<Header, Error> {
+    let initial = *bytes.get(*position).ok_or(Error::Incomplete)?;
+    *position += 1;
+    let major_type = initial >> 5;
+    let additional = initial & 0x1f;
+    let argument = match additional {
+        0..=23 => Some(u64::from(additional)),
+        24..=27 => {
+            let length = 1 << (additional - 24);
+            let argument = bytes
+                .get(*position..*position + length)
+                .ok_or(Error::Incomplete)?
+                .iter()
+                .fold(0, |number, byte| number << 8 | u64::from(*byte));
+            *position += length;
+            Some(argument)
+        }
+        31 => None,
+        _ => {
+            return Err(Error::NotWellFormed(Malformed::ReservedAdditionalInfo {
+                additional,
+            }));
+        }
+    };
+    Ok(Header {
+        major_type,
+        argument,
+    })
+}
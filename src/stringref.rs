@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+
+use indexmap::IndexMap;
+
+use crate::content::TagContent;
+use crate::data_item::DataItem;
+use crate::error::{Error, Malformed};
+use crate::tag;
+
+/// Compress repeated string of a data item into string reference inside a
+/// new string reference namespace
+pub(crate) fn compress(data_item: &DataItem) -> DataItem {
+    TagContent::from((
+        tag::STRINGREF_NAMESPACE,
+        compress_item(data_item, &mut HashMap::new()),
+    ))
+    .into()
+}
+
+/// Expand all string reference of a string reference namespace present in a
+/// data item
+pub(crate) fn expand(data_item: &DataItem) -> Result<DataItem, Error> {
+    expand_item(data_item, None)
+}
+
+/// Check if a data item contains a string reference namespace
+pub(crate) fn has_namespace(data_item: &DataItem) -> bool {
+    match data_item {
+        DataItem::Array(array_content) => array_content.array().iter().any(has_namespace),
+        DataItem::Map(map_content) => {
+            map_content
+                .map()
+                .iter()
+                .any(|(key, value)| has_namespace(key) || has_namespace(value))
+        }
+        DataItem::Tag(tag_content) => {
+            tag_content.number() == tag::STRINGREF_NAMESPACE || has_namespace(tag_content.content())
+        }
+        _ => false,
+    }
+}
+
+/// Get a length of a definite length string which can be stored in a string
+/// reference table
+fn string_length(data_item: &DataItem) -> Option<usize> {
+    match data_item {
+        DataItem::Byte(byte_content) if !byte_content.is_indefinite() => Some(byte_content.len()),
+        DataItem::Text(text_content) if !text_content.is_indefinite() => Some(text_content.len()),
+        _ => None,
+    }
+}
+
+/// Check if a string of provided length is added to a string reference table
+/// of provided size. String is only added when its reference is shorter than a
+/// string itself
+fn is_referenceable(length: usize, table_size: usize) -> bool {
+    let minimum_length = match table_size {
+        0..=23 => 3,
+        24..=255 => 4,
+        256..=65_535 => 5,
+        65_536..=4_294_967_295 => 7,
+        _ => 11,
+    };
+    length >= minimum_length
+}
+
+#[expect(
+    clippy::mutable_key_type,
+    reason = "cached digest of a map content doesn't affect its hash"
+)]
+fn compress_item(data_item: &DataItem, table: &mut HashMap<DataItem, usize>) -> DataItem {
+    if let Some(length) = string_length(data_item) {
+        if let Some(index) = table.get(data_item) {
+            return TagContent::from((tag::STRING_REFERENCE, *index as u64)).into();
+        }
+        if is_referenceable(length, table.len()) {
+            table.insert(data_item.clone(), table.len());
+        }
+        return data_item.clone();
+    }
+    match data_item {
+        DataItem::Array(array_content) => {
+            let array = array_content
+                .array()
+                .iter()
+                .map(|item| compress_item(item, table))
+                .collect::<Vec<_>>();
+            DataItem::Array(array_content.clone().set_content(&array).clone())
+        }
+        DataItem::Map(map_content) => {
+            let mut map = IndexMap::with_capacity(map_content.map().len());
+            for (key, value) in map_content.map() {
+                let key = compress_item(key, table);
+                let value = compress_item(value, table);
+                map.insert(key, value);
+            }
+            DataItem::Map(map_content.clone().set_content(&map).clone())
+        }
+        DataItem::Tag(tag_content) if tag_content.number() == tag::STRINGREF_NAMESPACE => {
+            compress(tag_content.content())
+        }
+        DataItem::Tag(tag_content) => {
+            TagContent::from((
+                tag_content.number(),
+                compress_item(tag_content.content(), table),
+            ))
+            .into()
+        }
+        _ => data_item.clone(),
+    }
+}
+
+fn expand_item(
+    data_item: &DataItem,
+    mut table: Option<&mut Vec<DataItem>>,
+) -> Result<DataItem, Error> {
+    if let Some(length) = string_length(data_item) {
+        if let Some(table) = table
+            && is_referenceable(length, table.len())
+        {
+            table.push(data_item.clone());
+        }
+        return Ok(data_item.clone());
+    }
+    match data_item {
+        DataItem::Array(array_content) => {
+            let array = array_content
+                .array()
+                .iter()
+                .map(|item| expand_item(item, table.as_deref_mut()))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(DataItem::Array(
+                array_content.clone().set_content(&array).clone(),
+            ))
+        }
+        DataItem::Map(map_content) => {
+            let mut map = IndexMap::with_capacity(map_content.map().len());
+            for (key, value) in map_content.map() {
+                let key = expand_item(key, table.as_deref_mut())?;
+                let value = expand_item(value, table.as_deref_mut())?;
+                if map.insert(key.clone(), value).is_some() {
+                    return Err(Error::NotWellFormed(Malformed::DuplicateKey {
+                        index: map.len(),
+                    }));
+                }
+            }
+            Ok(DataItem::Map(map_content.clone().set_content(&map).clone()))
+        }
+        DataItem::Tag(tag_content) if tag_content.number() == tag::STRINGREF_NAMESPACE => {
+            expand_item(tag_content.content(), Some(&mut vec![]))
+        }
+        DataItem::Tag(tag_content) => {
+            if tag_content.number() == tag::STRING_REFERENCE
+                && let Some(table) = table
+            {
+                return tag_content
+                    .content()
+                    .as_unsigned()
+                    .and_then(|index| table.get(usize::try_from(index).ok()?))
+                    .cloned()
+                    .ok_or(Error::InvalidTagContent {
+                        tag: tag::STRING_REFERENCE,
+                        expected: "index of an earlier string",
+                    });
+            }
+            Ok(TagContent::from((
+                tag_content.number(),
+                expand_item(tag_content.content(), table)?,
+            ))
+            .into())
+        }
+        _ => Ok(data_item.clone()),
+    }
+}
@@ -0,0 +1,149 @@
+/// Standard date/time string
+pub const DATETIME_STRING: u64 = 0;
+/// Epoch based date/time
+pub const EPOCH: u64 = 1;
+/// Unsigned bignum
+pub const POS_BIGNUM: u64 = 2;
+/// Negative bignum
+pub const NEG_BIGNUM: u64 = 3;
+/// Decimal fraction
+pub const DECIMAL_FRACTION: u64 = 4;
+/// Bigfloat
+pub const BIGFLOAT: u64 = 5;
+/// COSE single recipient encrypted data object
+pub const COSE_ENCRYPT0: u64 = 16;
+/// COSE MAC without recipients object
+pub const COSE_MAC0: u64 = 17;
+/// COSE single signer data object
+pub const COSE_SIGN1: u64 = 18;
+/// Expected conversion to base64url encoding
+pub const EXPECTED_BASE64URL: u64 = 21;
+/// Expected conversion to base64 encoding
+pub const EXPECTED_BASE64: u64 = 22;
+/// Expected conversion to base16 encoding
+pub const EXPECTED_BASE16: u64 = 23;
+/// Encoded CBOR data item
+pub const CBOR_DATA: u64 = 24;
+/// Reference the nth previously seen string
+pub const STRING_REFERENCE: u64 = 25;
+/// Mark value as potentially shared
+pub const SHAREABLE: u64 = 28;
+/// Reference nth marked value
+pub const SHARED_REFERENCE: u64 = 29;
+/// Rational number
+pub const RATIONAL: u64 = 30;
+/// URI
+pub const URI: u64 = 32;
+/// base64url encoded text
+pub const BASE64URL: u64 = 33;
+/// base64 encoded text
+pub const BASE64: u64 = 34;
+/// Regular expression
+pub const REGEX: u64 = 35;
+/// MIME message
+pub const MIME: u64 = 36;
+/// Binary UUID
+pub const UUID: u64 = 37;
+/// Language tagged string
+pub const LANGUAGE_TAGGED_STRING: u64 = 38;
+/// IPv4 address or prefix
+pub const IPV4: u64 = 52;
+/// IPv6 address or prefix
+pub const IPV6: u64 = 54;
+/// Encoded CBOR sequence
+pub const CBOR_SEQUENCE: u64 = 63;
+/// COSE encrypted data object
+pub const COSE_ENCRYPT: u64 = 96;
+/// COSE MAC with recipients object
+pub const COSE_MAC: u64 = 97;
+/// COSE signed data object
+pub const COSE_SIGN: u64 = 98;
+/// Number of days since the epoch date 1970-01-01
+pub const DAYS_SINCE_EPOCH: u64 = 100;
+/// Mark value as having string references
+pub const STRINGREF_NAMESPACE: u64 = 256;
+/// Mathematical finite set
+pub const SET: u64 = 258;
+/// Full date string
+pub const FULL_DATE: u64 = 1004;
+/// Self described CBOR
+pub const SELF_DESCRIBED: u64 = 55799;
+
+/// Registered tag number along with its semantics sorted by tag number
+const REGISTERED: &[(u64, &str)] = &[
+    (DATETIME_STRING, "standard date/time string"),
+    (EPOCH, "epoch based date/time"),
+    (POS_BIGNUM, "unsigned bignum"),
+    (NEG_BIGNUM, "negative bignum"),
+    (DECIMAL_FRACTION, "decimal fraction"),
+    (BIGFLOAT, "bigfloat"),
+    (COSE_ENCRYPT0, "COSE single recipient encrypted data object"),
+    (COSE_MAC0, "COSE MAC without recipients object"),
+    (COSE_SIGN1, "COSE single signer data object"),
+    (
+        EXPECTED_BASE64URL,
+        "expected conversion to base64url encoding",
+    ),
+    (EXPECTED_BASE64, "expected conversion to base64 encoding"),
+    (EXPECTED_BASE16, "expected conversion to base16 encoding"),
+    (CBOR_DATA, "encoded CBOR data item"),
+    (STRING_REFERENCE, "reference the nth previously seen string"),
+    (SHAREABLE, "mark value as potentially shared"),
+    (SHARED_REFERENCE, "reference nth marked value"),
+    (RATIONAL, "rational number"),
+    (URI, "URI"),
+    (BASE64URL, "base64url encoded text"),
+    (BASE64, "base64 encoded text"),
+    (REGEX, "regular expression"),
+    (MIME, "MIME message"),
+    (UUID, "binary UUID"),
+    (LANGUAGE_TAGGED_STRING, "language tagged string"),
+    (IPV4, "IPv4 address or prefix"),
+    (IPV6, "IPv6 address or prefix"),
+    (CBOR_SEQUENCE, "encoded CBOR sequence"),
+    (COSE_ENCRYPT, "COSE encrypted data object"),
+    (COSE_MAC, "COSE MAC with recipients object"),
+    (COSE_SIGN, "COSE signed data object"),
+    (
+        DAYS_SINCE_EPOCH,
+        "number of days since the epoch date 1970-01-01",
+    ),
+    (
+        STRINGREF_NAMESPACE,
+        "mark value as having string references",
+    ),
+    (SET, "mathematical finite set"),
+    (FULL_DATE, "full date string"),
+    (SELF_DESCRIBED, "self described CBOR"),
+];
+
+/// Get a semantics of a IANA registered tag number
+///
+/// # Example
+/// ```rust
+/// use cbor_next::tag;
+///
+/// assert_eq!(tag::semantics(tag::URI), Some("URI"));
+/// assert_eq!(tag::semantics(6), None);
+/// ```
+#[must_use]
+pub fn semantics(tag: u64) -> Option<&'static str> {
+    REGISTERED
+        .binary_search_by_key(&tag, |(number, _)| *number)
+        .ok()
+        .map(|index| REGISTERED[index].1)
+}
+
+/// Check if a tag number is registered in IANA registry
+///
+/// # Example
+/// ```rust
+/// use cbor_next::tag;
+///
+/// assert!(tag::is_registered(tag::SELF_DESCRIBED));
+/// assert!(!tag::is_registered(6));
+/// ```
+#[must_use]
+pub fn is_registered(tag: u64) -> bool {
+    semantics(tag).is_some()
+}
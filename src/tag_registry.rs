@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+
+use indexmap::IndexMap;
+
+use crate::content::TagContent;
+use crate::data_item::DataItem;
+use crate::encoding::{BASE64_ALPHABET, BASE64URL_ALPHABET, decode_base64};
+use crate::error::Error;
+use crate::tag;
+
+type Validator = Box<dyn Fn(&DataItem) -> Result<(), Error> + Send + Sync>;
+type Converter = Box<dyn Fn(&DataItem) -> Result<DataItem, Error> + Send + Sync>;
+
+/// Registry of per tag validator and converter which are applied to a tagged
+/// data item
+///
+/// Validator checks a content of a tag and converter replaces a tagged data
+/// item with a converted data item. Content of a tag is processed before a tag
+/// itself
+///
+/// # Example
+/// ```rust
+/// use cbor_next::error::Error;
+/// use cbor_next::{DataItem, TagRegistry};
+///
+/// let mut registry = TagRegistry::standard();
+/// registry.register_validator(1000, |content| {
+///     content
+///         .as_unsigned()
+///         .map(|_| ())
+///         .ok_or(Error::InvalidTagContent {
+///             tag: 1000,
+///             expected: "unsigned integer",
+///         })
+/// });
+/// registry.register_converter(1000, |content| Ok(content.clone()));
+/// assert_eq!(
+///     DataItem::decode_with(&[0xd9, 0x03, 0xe8, 0x01], &registry).unwrap(),
+///     DataItem::from(1)
+/// );
+/// assert!(DataItem::decode_with(&[0xd9, 0x03, 0xe8, 0x20], &registry).is_err());
+/// assert!(DataItem::decode_with(&[0xc2, 0x01], &registry).is_err());
+/// ```
+#[derive(Default)]
+pub struct TagRegistry {
+    validators: HashMap<u64, Validator>,
+    converters: HashMap<u64, Converter>,
+}
+
+impl TagRegistry {
+    /// Create a new empty tag registry
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new tag registry with validator for standard tags defined in
+    /// RFC 8949 which are tag 0 to tag 5, tag 21 to tag 24 and tag 32 to tag
+    /// 36
+    #[must_use]
+    pub fn standard() -> Self {
+        let mut registry = Self::new();
+        registry
+            .register_validator(tag::DATETIME_STRING, |content| {
+                match content {
+                    DataItem::Text(text_content)
+                        if crate::content::is_rfc3339(&text_content.full()) =>
+                    {
+                        Ok(())
+                    }
+                    _ => Err(invalid(tag::DATETIME_STRING, "RFC 3339 date time string")),
+                }
+            })
+            .register_validator(tag::EPOCH, |content| {
+                match content {
+                    DataItem::Unsigned(_) | DataItem::Signed(_) => Ok(()),
+                    DataItem::Floating(number) if number.is_finite() => Ok(()),
+                    _ => Err(invalid(tag::EPOCH, "integer or finite floating number")),
+                }
+            })
+            .register_validator(tag::POS_BIGNUM, |content| {
+                expect_byte(tag::POS_BIGNUM, content)
+            })
+            .register_validator(tag::NEG_BIGNUM, |content| {
+                expect_byte(tag::NEG_BIGNUM, content)
+            })
+            .register_validator(tag::DECIMAL_FRACTION, |content| {
+                expect_exponent_mantissa(tag::DECIMAL_FRACTION, content)
+            })
+            .register_validator(tag::BIGFLOAT, |content| {
+                expect_exponent_mantissa(tag::BIGFLOAT, content)
+            })
+            .register_validator(tag::EXPECTED_BASE64URL, |_| Ok(()))
+            .register_validator(tag::EXPECTED_BASE64, |_| Ok(()))
+            .register_validator(tag::EXPECTED_BASE16, |_| Ok(()))
+            .register_validator(tag::CBOR_DATA, |content| {
+                content
+                    .as_byte()
+                    .filter(|bytes| DataItem::decode(bytes).is_ok())
+                    .map(|_| ())
+                    .ok_or_else(|| invalid(tag::CBOR_DATA, "byte string of well formed CBOR"))
+            })
+            .register_validator(tag::URI, |content| {
+                content
+                    .as_text()
+                    .filter(|uri| crate::content::is_uri(uri))
+                    .map(|_| ())
+                    .ok_or_else(|| invalid(tag::URI, "URI text string"))
+            })
+            .register_validator(tag::BASE64URL, |content| {
+                content
+                    .as_text()
+                    .filter(|text| !text.contains('='))
+                    .and_then(|text| decode_base64(&text, BASE64URL_ALPHABET))
+                    .map(|_| ())
+                    .ok_or_else(|| invalid(tag::BASE64URL, "base64url text string"))
+            })
+            .register_validator(tag::BASE64, |content| {
+                content
+                    .as_text()
+                    .and_then(|text| decode_base64(&text, BASE64_ALPHABET))
+                    .map(|_| ())
+                    .ok_or_else(|| invalid(tag::BASE64, "base64 text string"))
+            })
+            .register_validator(tag::REGEX, |content| expect_text(tag::REGEX, content))
+            .register_validator(tag::MIME, |content| expect_text(tag::MIME, content));
+        registry
+    }
+
+    /// Register a validator for a tag number which checks a content of a tag.
+    /// Previously registered validator for a same tag number is replaced
+    pub fn register_validator<F>(&mut self, tag: u64, validator: F) -> &mut Self
+    where
+        F: Fn(&DataItem) -> Result<(), Error> + Send + Sync + 'static,
+    {
+        self.validators.insert(tag, Box::new(validator));
+        self
+    }
+
+    /// Register a converter for a tag number which converts a content of a tag
+    /// into a data item which replaces a tagged data item. Previously
+    /// registered converter for a same tag number is replaced
+    pub fn register_converter<F>(&mut self, tag: u64, converter: F) -> &mut Self
+    where
+        F: Fn(&DataItem) -> Result<DataItem, Error> + Send + Sync + 'static,
+    {
+        self.converters.insert(tag, Box::new(converter));
+        self
+    }
+
+    /// Remove a validator and converter registered for a tag number
+    pub fn unregister(&mut self, tag: u64) -> &mut Self {
+        self.validators.remove(&tag);
+        self.converters.remove(&tag);
+        self
+    }
+
+    /// Check if a validator or a converter is registered for a tag number
+    #[must_use]
+    pub fn is_registered(&self, tag: u64) -> bool {
+        self.validators.contains_key(&tag) || self.converters.contains_key(&tag)
+    }
+
+    /// Validate all tags present in a data item
+    ///
+    /// # Errors
+    /// If any validator fails to validate a content of a tag
+    pub fn validate(&self, data_item: &DataItem) -> Result<(), Error> {
+        match data_item {
+            DataItem::Array(array_content) => {
+                array_content
+                    .array()
+                    .iter()
+                    .try_for_each(|item| self.validate(item))
+            }
+            DataItem::Map(map_content) => {
+                map_content.map().iter().try_for_each(|(key, value)| {
+                    self.validate(key)?;
+                    self.validate(value)
+                })
+            }
+            DataItem::Tag(tag_content) => {
+                self.validate(tag_content.content())?;
+                match self.validators.get(&tag_content.number()) {
+                    Some(validator) => validator(tag_content.content()),
+                    None => Ok(()),
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Validate all tags present in a data item and convert tags which has a
+    /// registered converter
+    ///
+    /// # Errors
+    /// If any validator fails to validate a content of a tag or any converter
+    /// fails to convert a content of a tag
+    pub fn apply(&self, data_item: &DataItem) -> Result<DataItem, Error> {
+        match data_item {
+            DataItem::Array(array_content) => {
+                let array = array_content
+                    .array()
+                    .iter()
+                    .map(|item| self.apply(item))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(DataItem::Array(
+                    array_content.clone().set_content(&array).clone(),
+                ))
+            }
+            DataItem::Map(map_content) => {
+                let map = map_content
+                    .map()
+                    .iter()
+                    .map(|(key, value)| Ok((self.apply(key)?, self.apply(value)?)))
+                    .collect::<Result<IndexMap<_, _>, Error>>()?;
+                Ok(DataItem::Map(map_content.clone().set_content(&map).clone()))
+            }
+            DataItem::Tag(tag_content) => {
+                let content = self.apply(tag_content.content())?;
+                if let Some(validator) = self.validators.get(&tag_content.number()) {
+                    validator(&content)?;
+                }
+                match self.converters.get(&tag_content.number()) {
+                    Some(converter) => converter(&content),
+                    None => Ok(TagContent::from((tag_content.number(), content)).into()),
+                }
+            }
+            _ => Ok(data_item.clone()),
+        }
+    }
+}
+
+fn invalid(tag: u64, expected: &'static str) -> Error {
+    Error::InvalidTagContent { tag, expected }
+}
+
+fn expect_byte(tag: u64, content: &DataItem) -> Result<(), Error> {
+    match content {
+        DataItem::Byte(_) => Ok(()),
+        _ => Err(invalid(tag, "byte string")),
+    }
+}
+
+fn expect_text(tag: u64, content: &DataItem) -> Result<(), Error> {
+    match content {
+        DataItem::Text(_) => Ok(()),
+        _ => Err(invalid(tag, "text string")),
+    }
+}
+
+fn expect_exponent_mantissa(tag: u64, content: &DataItem) -> Result<(), Error> {
+    let is_integer =
+        |data_item: &DataItem| matches!(data_item, DataItem::Unsigned(_) | DataItem::Signed(_));
+    match content.as_array() {
+        Some([exponent, mantissa])
+            if is_integer(exponent) && (is_integer(mantissa) || mantissa.as_bignum().is_some()) =>
+        {
+            Ok(())
+        }
+        _ => {
+            Err(invalid(
+                tag,
+                "array of integer exponent and integer or bignum mantissa",
+            ))
+        }
+    }
+}
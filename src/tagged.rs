@@ -0,0 +1,123 @@
+use std::fmt::Formatter;
+use std::marker::PhantomData;
+
+use serde::de::{Error as _, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Name of newtype struct used for passing a tag through serde data model.
+/// Serializer and deserializer of this crate treat newtype struct with this
+/// name as a tag with a tuple of tag number and tag content
+pub(crate) const TAG_STRUCT_NAME: &str = "\0cbor_next::Tagged";
+
+/// Struct which holds a value along with an optional CBOR tag number so tag is
+/// preserved when serializing and deserializing with serde
+///
+/// When used with other serde format tagged value is represented as a tuple of
+/// tag number and value
+///
+/// # Example
+/// ```rust
+/// use cbor_next::{DataItem, TagContent, Tagged};
+///
+/// let tagged = Tagged::new(Some(32), "http://www.example.com".to_string());
+/// let data_item = cbor_next::to_data_item(&tagged).unwrap();
+/// assert_eq!(
+///     data_item,
+///     DataItem::from(TagContent::from((32, "http://www.example.com")))
+/// );
+/// assert_eq!(
+///     cbor_next::from_data_item::<Tagged<String>>(data_item).unwrap(),
+///     tagged
+/// );
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct Tagged<T> {
+    tag: Option<u64>,
+    value: T,
+}
+
+impl<T> Tagged<T> {
+    /// Create a new tagged value
+    #[must_use]
+    pub fn new(tag: Option<u64>, value: T) -> Self {
+        Self { tag, value }
+    }
+
+    /// Get a tag number
+    #[must_use]
+    pub fn tag(&self) -> Option<u64> {
+        self.tag
+    }
+
+    /// Get a value
+    #[must_use]
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// Convert into a tag number and value
+    #[must_use]
+    pub fn into_parts(self) -> (Option<u64>, T) {
+        (self.tag, self.value)
+    }
+}
+
+impl<T> Serialize for Tagged<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.tag {
+            Some(tag) => serializer.serialize_newtype_struct(TAG_STRUCT_NAME, &(tag, &self.value)),
+            None => self.value.serialize(serializer),
+        }
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Tagged<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct(TAG_STRUCT_NAME, TaggedVisitor(PhantomData))
+    }
+}
+
+struct TaggedVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for TaggedVisitor<T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = Tagged<T>;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str("tagged value")
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_tuple(2, self)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let tag = seq
+            .next_element::<Option<u64>>()?
+            .ok_or_else(|| A::Error::invalid_length(0, &self))?;
+        let value = seq
+            .next_element::<T>()?
+            .ok_or_else(|| A::Error::invalid_length(1, &self))?;
+        Ok(Tagged { tag, value })
+    }
+}
@@ -4,12 +4,21 @@ use std::vec;
 
 use indexmap::IndexMap;
 use rand::seq::SliceRandom as _;
+#[cfg(not(feature = "serde"))]
+use serde as _;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
-use crate::content::{ArrayContent, ByteContent, MapContent, TagContent, TextContent};
+use crate::content::{ArrayContent, ByteContent, MapContent, SimpleValue, TagContent, TextContent};
 use crate::data_item::DataItem;
+use crate::decode_options::DecodeOptions;
 use crate::deterministic::DeterministicMode;
-use crate::error::Error;
+use crate::diagnostic_options::DiagnosticOptions;
+use crate::error::{Error, ItemKind, Limit, Malformed, PatchFailure, Syntax};
 use crate::index::Get as _;
+use crate::span::SpanTree;
+use crate::tag;
+use crate::tag_registry::TagRegistry;
 
 fn encode_compare<I>(hex_cbor: &str, value_into: I)
 where
@@ -64,7 +73,7 @@ fn integer() {
     compare_cbor_value("1bffffffffffffffff", 18_446_744_073_709_551_615u64);
     compare_cbor_value(
         "3bffffffffffffffff",
-        DataItem::try_from(-18_446_744_073_709_551_616_i128).unwrap(),
+        DataItem::from(-18_446_744_073_709_551_616_i128),
     );
     compare_cbor_value("20", -1);
     compare_cbor_value("29", -10);
@@ -72,6 +81,28 @@ fn integer() {
     compare_cbor_value("3903e7", -1000);
 }
 
+#[test]
+fn bignum() {
+    compare_cbor_value("c249010000000000000000", 18_446_744_073_709_551_616_u128);
+    compare_cbor_value("c349010000000000000000", -18_446_744_073_709_551_617_i128);
+    compare_cbor_value("c250ffffffffffffffffffffffffffffffff", u128::MAX);
+    compare_cbor_value("c3507fffffffffffffffffffffffffffffff", i128::MIN);
+    compare_cbor_value("1bffffffffffffffff", u128::from(u64::MAX));
+    let decoded = DataItem::decode(&hex::decode("c249010000000000000000").unwrap()).unwrap();
+    assert_eq!(decoded.as_u128(), Some(18_446_744_073_709_551_616));
+    assert_eq!(decoded.as_i128(), Some(18_446_744_073_709_551_616));
+    let decoded = DataItem::decode(&hex::decode("c349010000000000000000").unwrap()).unwrap();
+    assert_eq!(decoded.as_u128(), None);
+    assert_eq!(decoded.as_i128(), Some(-18_446_744_073_709_551_617));
+    let leading_zero =
+        DataItem::decode(&hex::decode("c25100000000000000000000000000000000ff").unwrap()).unwrap();
+    assert_eq!(leading_zero.as_u128(), Some(255));
+    let overflow =
+        DataItem::decode(&hex::decode("c25101000000000000000000000000000000ff").unwrap()).unwrap();
+    assert_eq!(overflow.as_u128(), None);
+    assert_eq!(DataItem::from(u128::MAX).as_i128(), None);
+}
+
 #[test]
 fn float() {
     compare_cbor_value("f90000", 0.0);
@@ -300,15 +331,16 @@ fn map() {
 fn failure() {
     assert_eq!(
         DataItem::decode(&hex::decode("1c").unwrap()),
-        Err(Error::NotWellFormed(
-            "invalid additional number 28".to_string()
-        ))
+        Err(Error::NotWellFormed(Malformed::ReservedAdditionalInfo {
+            additional: 28
+        }))
     );
     assert_eq!(
         DataItem::decode(&hex::decode("7f14").unwrap()),
-        Err(Error::NotWellFormed(
-            "contains invalid major type 0 for indefinite major type 3".to_string()
-        ))
+        Err(Error::NotWellFormed(Malformed::InvalidChunk {
+            expected: 3,
+            found: 0
+        }))
     );
     assert_eq!(
         DataItem::decode(&hex::decode("f801").unwrap()),
@@ -316,31 +348,34 @@ fn failure() {
     );
     assert_eq!(
         DataItem::decode(&hex::decode("9fde").unwrap()),
-        Err(Error::NotWellFormed(
-            "invalid additional number 30".to_string()
-        ))
+        Err(Error::NotWellFormed(Malformed::ReservedAdditionalInfo {
+            additional: 30
+        }))
     );
     assert_eq!(
         DataItem::decode(&hex::decode("bf3e").unwrap()),
-        Err(Error::NotWellFormed(
-            "invalid additional number 30".to_string()
-        ))
+        Err(Error::NotWellFormed(Malformed::ReservedAdditionalInfo {
+            additional: 30
+        }))
     );
     assert_eq!(
         DataItem::decode(&hex::decode("dd").unwrap()),
-        Err(Error::NotWellFormed(
-            "invalid additional number 29".to_string()
-        ))
+        Err(Error::NotWellFormed(Malformed::ReservedAdditionalInfo {
+            additional: 29
+        }))
     );
     assert_eq!(
         DataItem::decode(&hex::decode("5f87").unwrap()),
-        Err(Error::NotWellFormed(
-            "contains invalid major type 4 for indefinite major type 2".to_string()
-        ))
+        Err(Error::NotWellFormed(Malformed::InvalidChunk {
+            expected: 2,
+            found: 4
+        }))
     );
     assert_eq!(
         DataItem::decode(&hex::decode("3f").unwrap()),
-        Err(Error::NotWellFormed("failed to extract number".to_string()))
+        Err(Error::NotWellFormed(Malformed::IndefiniteLength {
+            major_type: 1
+        }))
     );
     assert_eq!(
         DataItem::decode(&hex::decode("5f4100").unwrap()),
@@ -348,9 +383,10 @@ fn failure() {
     );
     assert_eq!(
         DataItem::decode(&hex::decode("5fc000ff").unwrap()),
-        Err(Error::NotWellFormed(
-            "contains invalid major type 6 for indefinite major type 2".to_string()
-        ))
+        Err(Error::NotWellFormed(Malformed::InvalidChunk {
+            expected: 2,
+            found: 6
+        }))
     );
     assert_eq!(
         DataItem::decode(&hex::decode("9f819f819f9fffffff").unwrap()),
@@ -362,15 +398,13 @@ fn failure() {
     );
     assert_eq!(
         DataItem::decode(&hex::decode("1a0102").unwrap()),
-        Err(Error::NotWellFormed(
-            "incomplete array of byte missing 2 byte".to_string()
-        ))
+        Err(Error::NotWellFormed(Malformed::MissingBytes { missing: 2 }))
     );
     assert_eq!(
         DataItem::decode(&hex::decode("5affffffff00").unwrap()),
-        Err(Error::NotWellFormed(
-            "incomplete array of byte missing 4294967294 byte".to_string()
-        ))
+        Err(Error::NotWellFormed(Malformed::MissingBytes {
+            missing: 4_294_967_294
+        }))
     );
     assert_eq!(
         DataItem::decode(&hex::decode("bf000000ff").unwrap()),
@@ -382,9 +416,9 @@ fn failure() {
     );
     assert_eq!(
         DataItem::decode(&hex::decode("bffc").unwrap()),
-        Err(Error::NotWellFormed(
-            "invalid value 28 for major type 7".to_string()
-        ))
+        Err(Error::NotWellFormed(Malformed::ReservedAdditionalInfo {
+            additional: 28
+        }))
     );
     assert_eq!(
         DataItem::decode(&hex::decode("ff").unwrap()),
@@ -558,3 +592,4413 @@ fn debug() {
     debug_compare("{_ \"a\": 1, \"b\": [_ 2, 3]}", "bf61610161629f0203ffff");
     debug_compare("[\"a\", {_ \"b\": \"c\"}]", "826161bf61626163ff");
 }
+
+#[test]
+fn registered_tag() {
+    use crate::tag;
+
+    assert_eq!(
+        tag::semantics(tag::DATETIME_STRING),
+        Some("standard date/time string")
+    );
+    assert_eq!(
+        tag::semantics(tag::SELF_DESCRIBED),
+        Some("self described CBOR")
+    );
+    assert_eq!(tag::semantics(6), None);
+    assert!(tag::is_registered(tag::CBOR_DATA));
+    assert!(!tag::is_registered(u64::MAX));
+    assert_eq!(
+        DataItem::from(u128::MAX).as_tag().map(|(number, _)| number),
+        Some(tag::POS_BIGNUM)
+    );
+}
+
+#[test]
+fn standard_tag_accessor() {
+    use std::time::{Duration, SystemTime};
+
+    assert_eq!(
+        DataItem::from(TagContent::from((1, 1_363_896_241))).as_epoch(),
+        Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_363_896_241))
+    );
+    assert_eq!(
+        DataItem::from(TagContent::from((1, -1.5))).as_epoch(),
+        Some(SystemTime::UNIX_EPOCH - Duration::from_millis(1500))
+    );
+    assert_eq!(
+        DataItem::from(TagContent::from((1, f64::NAN))).as_epoch(),
+        None
+    );
+    assert_eq!(
+        DataItem::from(TagContent::from((0, "2013-03-21T20:04:00Z"))).as_epoch(),
+        None
+    );
+    assert_eq!(
+        DataItem::from(-18_446_744_073_709_551_617_i128).as_bignum(),
+        Some((vec![1, 0, 0, 0, 0, 0, 0, 0, 0], true))
+    );
+    assert_eq!(
+        DataItem::from(TagContent::from((32, "urn:isbn:0451450523"))).as_uri(),
+        Some("urn:isbn:0451450523".to_string())
+    );
+    assert_eq!(
+        DataItem::from(TagContent::from((32, "1http://example.com"))).as_uri(),
+        None
+    );
+    assert_eq!(DataItem::from("http://example.com").as_uri(), None);
+    assert_eq!(
+        DataItem::from(TagContent::from((34, "Zm9v*A=="))).as_base64(),
+        None
+    );
+    assert_eq!(
+        DataItem::from(TagContent::from((33, "Zm9v"))).as_base64(),
+        Some(b"foo".to_vec())
+    );
+    assert_eq!(DataItem::from(TagContent::from((35, 1))).as_regex(), None);
+    assert_eq!(DataItem::from(TagContent::from((35, "a+"))).as_mime(), None);
+}
+
+#[test]
+fn standard_tag_constructor() {
+    for (date_time, is_valid) in [
+        ("2013-03-21T20:04:00Z", true),
+        ("2013-03-21t20:04:00.5z", true),
+        ("2013-03-21T20:04:00.123456789+05:45", true),
+        ("2016-12-31T23:59:60-00:00", true),
+        ("2024-02-29T00:00:00Z", true),
+        ("2023-02-29T00:00:00Z", false),
+        ("2013-13-21T20:04:00Z", false),
+        ("2013-03-21 20:04:00Z", false),
+        ("2013-03-21T24:04:00Z", false),
+        ("2013-03-21T20:04:00.Z", false),
+        ("2013-03-21T20:04:00", false),
+        ("2013-03-21T20:04:00+0545", false),
+        ("2013-03-21T20:04:00Z ", false),
+    ] {
+        assert_eq!(
+            TagContent::datetime(date_time).is_ok(),
+            is_valid,
+            "{date_time}"
+        );
+    }
+    assert_eq!(
+        DataItem::from(TagContent::datetime("2013-03-21T20:04:00Z").unwrap()).encode(),
+        hex::decode("c074323031332d30332d32315432303a30343a30305a").unwrap()
+    );
+    assert_eq!(
+        DataItem::from(TagContent::epoch(1_363_896_240.5).unwrap()).encode(),
+        hex::decode("c1fb41d452d9ec200000").unwrap()
+    );
+    assert_eq!(
+        DataItem::from(TagContent::epoch(-1.0).unwrap()),
+        DataItem::from(TagContent::from((1, -1)))
+    );
+    assert!(TagContent::epoch(f64::INFINITY).is_err());
+    assert_eq!(
+        DataItem::from(TagContent::uri("http://www.example.com").unwrap()).encode(),
+        hex::decode("d82076687474703a2f2f7777772e6578616d706c652e636f6d").unwrap()
+    );
+    assert!(matches!(
+        TagContent::uri(":example"),
+        Err(Error::InvalidTagContent { .. })
+    ));
+    assert_eq!(
+        DataItem::from(TagContent::bignum(&[1, 0, 0, 0, 0, 0, 0, 0, 0], false)),
+        DataItem::from(18_446_744_073_709_551_616_u128)
+    );
+}
+
+#[cfg(feature = "half")]
+#[test]
+fn typed_array() {
+    use crate::typed_array::Endianness;
+
+    let typed_array = DataItem::typed_array(&[1u32, 0x0102_0304], Endianness::Little);
+    assert_eq!(
+        typed_array.encode(),
+        hex::decode("d846480100000004030201").unwrap()
+    );
+    assert_eq!(
+        typed_array.as_typed_array::<u32>(),
+        Some(vec![1, 0x0102_0304])
+    );
+    assert_eq!(typed_array.as_typed_array::<i32>(), None);
+    assert_eq!(typed_array.as_typed_array::<u16>(), None);
+
+    for (data_item, hex) in [
+        (
+            DataItem::typed_array(&[1u8, 255], Endianness::Little),
+            "d84042",
+        ),
+        (DataItem::typed_array(&[-1i8, 1], Endianness::Big), "d84842"),
+        (DataItem::typed_array(&[-2i16], Endianness::Big), "d84942"),
+        (
+            DataItem::typed_array(&[-2i64], Endianness::Little),
+            "d84f48",
+        ),
+        (DataItem::typed_array(&[1u64], Endianness::Big), "d84348"),
+        (
+            DataItem::typed_array(&[half::f16::from_f32(1.0)], Endianness::Big),
+            "d85042",
+        ),
+        (
+            DataItem::typed_array(&[1.0f64], Endianness::Little),
+            "d85648",
+        ),
+    ] {
+        assert!(
+            data_item.encode().starts_with(&hex::decode(hex).unwrap()),
+            "{hex}"
+        );
+    }
+    assert_eq!(
+        DataItem::typed_array(&[-2i16, 3], Endianness::Little).as_typed_array::<i16>(),
+        Some(vec![-2, 3])
+    );
+    assert_eq!(
+        DataItem::typed_array(&[1.5f64, f64::MAX], Endianness::Big).as_typed_array::<f64>(),
+        Some(vec![1.5, f64::MAX])
+    );
+    assert_eq!(
+        DataItem::from(TagContent::from((68, [1u8, 2].as_slice()))).as_typed_array::<u8>(),
+        Some(vec![1, 2])
+    );
+    assert_eq!(
+        DataItem::from(TagContent::from((65, [1u8, 2, 3].as_slice()))).as_typed_array::<u16>(),
+        None
+    );
+    assert_eq!(
+        DataItem::from(TagContent::from((65, vec![1, 2]))).as_typed_array::<u16>(),
+        None
+    );
+}
+
+#[test]
+fn stringref() {
+    let strings = [
+        "1", "222", "333", "4", "555", "666", "777", "888", "999", "aaa", "bbb", "ccc", "ddd",
+        "eee", "fff", "ggg", "hhh", "iii", "jjj", "kkk", "lll", "mmm", "nnn", "ooo", "ppp", "qqq",
+        "rrr", "333", "ssss", "qqq", "rrr", "ssss",
+    ];
+    let value = DataItem::from(strings.to_vec());
+    let compressed = value.compress_stringref();
+    let debug = format!("{compressed:?}");
+    assert!(debug.starts_with("256([\"1\", \"222\", \"333\", \"4\", \"555\""));
+    assert!(debug.ends_with("\"rrr\", 25(1), \"ssss\", 25(23), \"rrr\", 25(24)])"));
+    assert_eq!(DataItem::decode(&compressed.encode()).unwrap(), value);
+
+    let value = DataItem::from(vec![
+        DataItem::from(vec![("name", "alpha"), ("kind", "alpha")]),
+        TagContent::from((256, vec![DataItem::from("name"), DataItem::from("name")])).into(),
+        DataItem::from([1u8, 2, 3].as_slice()),
+        DataItem::from(vec![("name", [1u8, 2, 3].as_slice())]),
+    ]);
+    let compressed = value.compress_stringref();
+    assert_eq!(
+        format!("{compressed:?}"),
+        "256([{\"name\": \"alpha\", \"kind\": 25(1)}, 256([\"name\", 25(0)]), h'010203', {25(0): \
+         25(3)}])"
+    );
+    assert_eq!(
+        DataItem::decode(&compressed.encode()).unwrap(),
+        value.expand_stringref().unwrap()
+    );
+    assert_eq!(
+        value.expand_stringref().unwrap(),
+        DataItem::from(vec![
+            DataItem::from(vec![("name", "alpha"), ("kind", "alpha")]),
+            DataItem::from(vec!["name", "name"]),
+            DataItem::from([1u8, 2, 3].as_slice()),
+            DataItem::from(vec![("name", [1u8, 2, 3].as_slice())]),
+        ])
+    );
+
+    assert_eq!(
+        DataItem::decode(&hex::decode("d81900").unwrap()).unwrap(),
+        DataItem::from(TagContent::from((25, 0)))
+    );
+    assert!(matches!(
+        DataItem::decode(&hex::decode("d90100826161d81900").unwrap()),
+        Err(Error::InvalidTagContent { .. })
+    ));
+}
+
+#[test]
+fn decode_default_depth() {
+    let limit = Error::LimitExceeded(Limit::Depth {
+        depth: 129,
+        max_depth: 128,
+    });
+    let mut bytes = vec![0x81; 1_000_000];
+    bytes.push(0x01);
+    assert_eq!(DataItem::decode(&bytes).unwrap_err(), limit);
+    assert_eq!(
+        DataItem::decode_with(&bytes, &TagRegistry::standard()).unwrap_err(),
+        limit
+    );
+    assert_eq!(DataItem::annotate(&bytes).unwrap_err(), limit);
+
+    let mut bytes = vec![0x81; 127];
+    bytes.extend([0xc1, 0x01]);
+    assert!(DataItem::decode(&bytes).is_ok());
+    bytes.insert(0, 0x81);
+    assert_eq!(DataItem::decode(&bytes).unwrap_err(), limit);
+
+    let text = format!("{}1{}", "[".repeat(1_000_000), "]".repeat(1_000_000));
+    assert!(matches!(
+        DataItem::from_json_str(&text),
+        Err(Error::InvalidJson {
+            reason: Syntax::TooDeep { max_depth: 256 },
+            ..
+        })
+    ));
+    assert!(matches!(
+        DataItem::from_diagnostic(&text),
+        Err(Error::InvalidDiagnostic {
+            reason: Syntax::TooDeep { max_depth: 256 },
+            ..
+        })
+    ));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_default_depth() {
+    let mut bytes = vec![0x81; 1_000_000];
+    bytes.push(0x01);
+    for result in [
+        crate::de::from_bytes::<DataItem>(&bytes),
+        crate::de::from_reader::<DataItem, _>(bytes.as_slice()),
+    ] {
+        assert_eq!(
+            result,
+            Err(Error::LimitExceeded(Limit::Depth {
+                depth: 129,
+                max_depth: 128,
+            }))
+        );
+    }
+    assert!(matches!(
+        crate::de::from_bytes::<serde::de::IgnoredAny>(&bytes),
+        Err(Error::LimitExceeded(Limit::Depth { .. }))
+    ));
+}
+
+#[test]
+fn decode_limit() {
+    use crate::DecodeOptions;
+
+    let mut options = DecodeOptions::default();
+    options.set_max_depth(2);
+    assert!(DataItem::decode_with_options(&hex::decode("8181c101").unwrap(), &options).is_err());
+    assert!(DataItem::decode_with_options(&hex::decode("81c101").unwrap(), &options).is_ok());
+    assert!(DataItem::decode_with_options(&hex::decode("a161618101").unwrap(), &options).is_ok());
+    assert!(DataItem::decode_with_options(&hex::decode("a1616181a0").unwrap(), &options).is_err());
+
+    let mut options = DecodeOptions::default();
+    options.set_max_length(3);
+    for (hex, is_ok) in [
+        ("83010203", true),
+        ("8401020304", false),
+        ("9f010203ff", true),
+        ("9f01020304ff", false),
+        ("a3010102020303", true),
+        ("a401010202030304", false),
+        ("bf01010202030304ff", false),
+        ("43010203", true),
+        ("6461626364", false),
+        ("5f42010241035f", false),
+        ("7f6161626263ff", true),
+        ("7f616162626364ff", false),
+    ] {
+        assert_eq!(
+            DataItem::decode_with_options(&hex::decode(hex).unwrap(), &options).is_ok(),
+            is_ok,
+            "{hex}"
+        );
+    }
+    assert_eq!(
+        DataItem::decode_with_options(&hex::decode("8401020304").unwrap(), &options),
+        Err(Error::LimitExceeded(Limit::Length {
+            length: 4,
+            max_length: 3
+        }))
+    );
+
+    let mut options = DecodeOptions::default();
+    options.set_max_size(4);
+    assert!(DataItem::decode_with_options(&hex::decode("83010203").unwrap(), &options).is_ok());
+    assert!(DataItem::decode_with_options(&hex::decode("8401020304").unwrap(), &options).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trip() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    enum Shape {
+        Empty,
+        Circle(u64),
+        Line(i64, i64),
+        Rectangle { width: f64, height: f64 },
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Drawing {
+        name: String,
+        shapes: Vec<Shape>,
+        visible: Option<bool>,
+    }
+
+    let drawing = Drawing {
+        name: "sketch".to_string(),
+        shapes: vec![
+            Shape::Empty,
+            Shape::Circle(10),
+            Shape::Line(-1, 20),
+            Shape::Rectangle {
+                width: 1.5,
+                height: 2.0,
+            },
+        ],
+        visible: None,
+    };
+    let data_item = crate::to_data_item(&drawing).unwrap();
+    assert_eq!(
+        format!("{data_item:?}"),
+        "{\"name\": \"sketch\", \"shapes\": [\"Empty\", {\"Circle\": 10}, {\"Line\": [-1, 20]}, \
+         {\"Rectangle\": {\"width\": 1.5, \"height\": 2.0}}], \"visible\": null}"
+    );
+    let bytes = crate::to_vec(&drawing).unwrap();
+    assert_eq!(bytes, data_item.encode());
+    assert_eq!(crate::from_bytes::<Drawing>(&bytes).unwrap(), drawing);
+    assert_eq!(
+        crate::from_data_item::<DataItem>(data_item.clone()).unwrap(),
+        data_item
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_bytes() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Payload {
+        #[serde(with = "crate::bytes")]
+        signature: Vec<u8>,
+        #[serde(with = "crate::bytes")]
+        key_id: [u8; 2],
+        raw: Vec<u8>,
+    }
+
+    let payload = Payload {
+        signature: vec![0x01, 0x02, 0x03],
+        key_id: [0x0a, 0x0b],
+        raw: vec![0x04],
+    };
+    let data_item = crate::to_data_item(&payload).unwrap();
+    assert_eq!(
+        format!("{data_item:?}"),
+        "{\"signature\": h'010203', \"key_id\": h'0a0b', \"raw\": [4]}"
+    );
+    assert_eq!(
+        crate::from_data_item::<Payload>(data_item).unwrap(),
+        payload
+    );
+
+    let byte_string_payload = DataItem::from(vec![
+        ("signature", DataItem::from([0x01u8, 0x02, 0x03].as_slice())),
+        ("key_id", DataItem::from([0x0au8, 0x0b].as_slice())),
+        ("raw", DataItem::from([0x04u8].as_slice())),
+    ]);
+    assert_eq!(
+        crate::from_data_item::<Payload>(byte_string_payload).unwrap(),
+        payload
+    );
+    let array_payload = DataItem::from(vec![
+        ("signature", DataItem::from(vec![1, 2, 3])),
+        ("key_id", DataItem::from(vec![10, 11])),
+        ("raw", DataItem::from(vec![4])),
+    ]);
+    assert_eq!(
+        crate::from_data_item::<Payload>(array_payload).unwrap(),
+        payload
+    );
+    assert!(crate::from_bytes::<Vec<u8>>(&hex::decode("5f42010243030405ff").unwrap()).is_ok());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_encoded_bytes() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Encoded {
+        #[serde(with = "crate::bytes::hex")]
+        hex: Vec<u8>,
+        #[serde(with = "crate::bytes::base64")]
+        base64: Vec<u8>,
+    }
+
+    assert!(!serde::Serializer::is_human_readable(
+        &crate::ser::Serializer::default()
+    ));
+    assert!(!serde::Deserializer::is_human_readable(
+        &crate::de::Deserializer::new(DataItem::Null)
+    ));
+    for (bytes, hex, base64) in [
+        (&b""[..], "", ""),
+        (b"f", "66", "Zg=="),
+        (b"fo", "666f", "Zm8="),
+        (b"foo", "666f6f", "Zm9v"),
+        (b"foob", "666f6f62", "Zm9vYg=="),
+        (&[0xff, 0xfe, 0x00][..], "fffe00", "//4A"),
+    ] {
+        let encoded = Encoded {
+            hex: bytes.to_vec(),
+            base64: bytes.to_vec(),
+        };
+        let data_item = crate::to_data_item(&encoded).unwrap();
+        assert_eq!(
+            data_item,
+            DataItem::from(vec![("hex", hex), ("base64", base64)])
+        );
+        assert_eq!(
+            crate::from_data_item::<Encoded>(data_item).unwrap(),
+            encoded
+        );
+    }
+    assert_eq!(
+        crate::from_data_item::<Encoded>(DataItem::from(vec![
+            ("hex", DataItem::from("FFFE00")),
+            ("base64", DataItem::from("Zm9vYg")),
+        ]))
+        .unwrap(),
+        Encoded {
+            hex: vec![0xff, 0xfe, 0x00],
+            base64: b"foob".to_vec(),
+        }
+    );
+    assert_eq!(
+        crate::from_data_item::<Encoded>(DataItem::from(vec![
+            ("hex", DataItem::from([1u8, 2].as_slice())),
+            ("base64", DataItem::from([3u8].as_slice())),
+        ]))
+        .unwrap(),
+        Encoded {
+            hex: vec![1, 2],
+            base64: vec![3],
+        }
+    );
+    for (hex, base64) in [
+        ("abc", "Zm9v"),
+        ("zz", "Zm9v"),
+        ("00", "Z"),
+        ("00", "Zm9v*A=="),
+    ] {
+        assert!(
+            crate::from_data_item::<Encoded>(DataItem::from(vec![
+                ("hex", hex),
+                ("base64", base64)
+            ]))
+            .is_err()
+        );
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_reader_writer() {
+    use std::io::Read as _;
+
+    let value = std::collections::BTreeMap::from_iter(vec![
+        ("a".to_string(), vec![1u64, 2]),
+        ("b".to_string(), vec![]),
+    ]);
+    let mut writer = vec![];
+    crate::to_writer(&mut writer, &value).unwrap();
+    assert_eq!(writer, hex::decode("a26161820102616280").unwrap());
+    let reader = std::io::Cursor::new(writer);
+    assert_eq!(
+        crate::from_reader::<std::collections::BTreeMap<String, Vec<u64>>, _>(reader).unwrap(),
+        value
+    );
+    assert_eq!(
+        crate::from_reader::<u64, _>(std::io::Cursor::new(vec![0x1a, 0x01])),
+        Err(Error::NotWellFormed(Malformed::MissingBytes { missing: 3 }))
+    );
+    assert_eq!(
+        crate::to_writer([0u8; 2].as_mut_slice(), &value),
+        Err(Error::Io(std::io::ErrorKind::WriteZero))
+    );
+
+    let endless = [0x81, 0x01].chain(std::io::repeat(0));
+    assert!(crate::from_reader::<Vec<u64>, _>(endless).is_err());
+    let mut decode_options = DecodeOptions::default();
+    decode_options.set_max_size(16);
+    let mut options = crate::de::DeserializerOptions::default();
+    options.set_decode_options(decode_options);
+    let endless = [0x9f].chain(std::io::repeat(0x01));
+    assert!(matches!(
+        crate::de::from_reader_with_options::<Vec<u64>, _>(endless, &options),
+        Err(Error::LimitExceeded(Limit::Size { max_size: 16, .. }))
+    ));
+}
+
+#[cfg(all(feature = "serde", feature = "chrono", feature = "time"))]
+#[test]
+fn serde_datetime() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Event {
+        #[serde(with = "crate::datetime::rfc3339")]
+        created_at: chrono::DateTime<chrono::Utc>,
+        #[serde(with = "crate::datetime::epoch")]
+        updated_at: time::OffsetDateTime,
+    }
+
+    let created_at = chrono::DateTime::from_timestamp(1_363_896_240, 0).unwrap();
+    let updated_at = time::OffsetDateTime::from_unix_timestamp(1_363_896_240).unwrap()
+        + time::Duration::milliseconds(500);
+    let event = Event {
+        created_at,
+        updated_at,
+    };
+    let data_item = crate::to_data_item(&event).unwrap();
+    assert_eq!(
+        format!("{data_item:?}"),
+        "{\"created_at\": 0(\"2013-03-21T20:04:00Z\"), \"updated_at\": 1(1363896240.5)}"
+    );
+    assert_eq!(crate::from_data_item::<Event>(data_item).unwrap(), event);
+
+    let swapped = DataItem::from(vec![
+        (
+            "created_at",
+            DataItem::from(TagContent::from((1, 1_363_896_240))),
+        ),
+        (
+            "updated_at",
+            DataItem::from(TagContent::from((0, "2013-03-21T20:04:00.5Z"))),
+        ),
+    ]);
+    assert_eq!(crate::from_data_item::<Event>(swapped).unwrap(), event);
+
+    assert_eq!(
+        DataItem::from(created_at),
+        TagContent::from((0, "2013-03-21T20:04:00Z")).into()
+    );
+    assert_eq!(
+        DataItem::from(updated_at),
+        TagContent::from((0, "2013-03-21T20:04:00.5Z")).into()
+    );
+    let epoch = DataItem::from(TagContent::from((1, 1_363_896_240.5)));
+    assert_eq!(epoch.as_datetime(), Some(updated_at));
+    assert_eq!(
+        epoch.as_datetime(),
+        Some(created_at + chrono::Duration::milliseconds(500))
+    );
+    assert_eq!(
+        DataItem::from("2013-03-21T20:04:00Z").as_datetime::<time::OffsetDateTime>(),
+        None
+    );
+}
+
+#[cfg(all(feature = "serde", feature = "uuid"))]
+#[test]
+fn serde_uuid() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct User {
+        #[serde(with = "crate::uuid")]
+        id: uuid::Uuid,
+    }
+
+    let id = uuid::Uuid::from_u128(0x8d8a_c610_566d_4ef0_9c22_186b_2a5e_d793);
+    let user = User { id };
+    let bytes = crate::to_vec(&user).unwrap();
+    assert_eq!(
+        bytes,
+        hex::decode("a1626964d825508d8ac610566d4ef09c22186b2a5ed793").unwrap()
+    );
+    assert_eq!(crate::from_bytes::<User>(&bytes).unwrap(), user);
+    assert_eq!(
+        DataItem::decode(&bytes).unwrap()[DataItem::from("id")].as_uuid(),
+        Some(id)
+    );
+    let untagged = DataItem::from(vec![("id", DataItem::from(id.as_bytes().as_slice()))]);
+    assert_eq!(crate::from_data_item::<User>(untagged).unwrap(), user);
+    let wrong_tag = DataItem::from(vec![(
+        "id",
+        DataItem::from(TagContent::from((24, id.as_bytes().as_slice()))),
+    )]);
+    assert!(crate::from_data_item::<User>(wrong_tag).is_err());
+    assert_eq!(
+        DataItem::from(TagContent::from((37, [0u8; 4].as_slice()))).as_uuid(),
+        None
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_i128() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Balance {
+        total: u128,
+        delta: i128,
+        small: i128,
+    }
+
+    let balance = Balance {
+        total: u128::MAX,
+        delta: -18_446_744_073_709_551_617,
+        small: -10,
+    };
+    let data_item = crate::to_data_item(&balance).unwrap();
+    assert_eq!(
+        format!("{data_item:?}"),
+        "{\"total\": 2(h'ffffffffffffffffffffffffffffffff'), \"delta\": 3(h'010000000000000000'), \
+         \"small\": -10}"
+    );
+    assert_eq!(
+        crate::from_data_item::<Balance>(data_item.clone()).unwrap(),
+        balance
+    );
+    assert_eq!(
+        crate::from_data_item::<DataItem>(data_item.clone()).unwrap(),
+        data_item
+    );
+    assert!(crate::from_data_item::<u64>(DataItem::from(u128::MAX)).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_preserve_tag() {
+    use std::collections::BTreeMap;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Envelope {
+        kind: String,
+        payload: DataItem,
+        extra: BTreeMap<String, DataItem>,
+    }
+
+    let data_item = DataItem::from(vec![
+        ("kind", DataItem::from("uri")),
+        (
+            "payload",
+            TagContent::from((32, "http://www.example.com")).into(),
+        ),
+        (
+            "extra",
+            DataItem::from(vec![(
+                "a",
+                DataItem::from(vec![
+                    TagContent::from((1, 1_363_896_240)).into(),
+                    DataItem::from(u128::from(u64::MAX) + 1),
+                    DataItem::Undefined,
+                    TagContent::from((
+                        37,
+                        DataItem::from(vec![
+                            DataItem::from(16),
+                            TagContent::from((1, DataItem::Undefined)).into(),
+                        ]),
+                    ))
+                    .into(),
+                ]),
+            )]),
+        ),
+    ]);
+    assert_eq!(
+        format!("{data_item:?}"),
+        "{\"kind\": \"uri\", \"payload\": 32(\"http://www.example.com\"), \"extra\": {\"a\": \
+         [1(1363896240), 2(h'010000000000000000'), undefined, 37([16, 1(undefined)])]}}"
+    );
+    let envelope = crate::from_data_item::<Envelope>(data_item.clone()).unwrap();
+    assert_eq!(
+        envelope.payload,
+        DataItem::from(TagContent::from((32, "http://www.example.com")))
+    );
+    assert_eq!(crate::to_data_item(&envelope).unwrap(), data_item);
+    assert_eq!(
+        crate::from_data_item::<DataItem>(data_item.clone()).unwrap(),
+        data_item
+    );
+    assert_eq!(
+        crate::from_bytes::<DataItem>(&data_item.encode()).unwrap(),
+        data_item
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_json_conversion() {
+    let json = r#"{"name":"sketch","points":[1,-2,3.5],"visible":true,"parent":null}"#;
+    let data_item = serde_json::from_str::<DataItem>(json).unwrap();
+    assert_eq!(
+        format!("{data_item:?}"),
+        "{\"name\": \"sketch\", \"points\": [1, -2, 3.5], \"visible\": true, \"parent\": null}"
+    );
+    assert_eq!(serde_json::to_string(&data_item).unwrap(), json);
+
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    let bytes = crate::to_vec(&DataItem::deserialize(&mut deserializer).unwrap()).unwrap();
+    assert_eq!(bytes, data_item.encode());
+    assert_eq!(
+        crate::from_bytes::<serde_json::Value>(&bytes).unwrap(),
+        serde_json::from_str::<serde_json::Value>(json).unwrap()
+    );
+
+    let data_item = DataItem::from(vec![
+        TagContent::from((32, "http://www.example.com")).into(),
+        DataItem::from([1u8, 2].as_slice()),
+        DataItem::Undefined,
+    ]);
+    assert_eq!(
+        serde_json::to_string(&data_item).unwrap(),
+        r#"[[32,"http://www.example.com"],[1,2],null]"#
+    );
+    assert_eq!(
+        crate::from_data_item::<serde_json::Value>(DataItem::from(vec![
+            TagContent::from((32, "http://www.example.com")).into(),
+            DataItem::Undefined,
+        ]))
+        .unwrap()
+        .to_string(),
+        r#"["http://www.example.com",null]"#
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_bytes_decode_path() {
+    #[derive(Debug, Deserialize)]
+    struct Point {
+        _x: u64,
+    }
+
+    let repeated = hex::decode("a26278780162787802").unwrap();
+    assert_eq!(
+        DataItem::decode(&repeated),
+        Err(Error::NotWellFormed(Malformed::DuplicateKey { index: 1 }))
+    );
+    assert_eq!(
+        crate::from_bytes::<std::collections::BTreeMap<String, u64>>(&repeated).unwrap(),
+        std::collections::BTreeMap::from([("xx".to_string(), 2)])
+    );
+    assert!(crate::from_bytes::<Point>(&hex::decode("a2625f7801625f7802").unwrap()).is_err());
+
+    assert!(crate::from_bytes::<u64>(&[0x01, 0x02]).is_err());
+    assert_eq!(
+        crate::from_data_item::<u64>(DataItem::decode(&[0x01, 0x02]).unwrap()).unwrap(),
+        1
+    );
+
+    let value = DataItem::from(vec!["repeated", "repeated"]);
+    let compressed = value.compress_stringref().encode();
+    assert_eq!(
+        crate::from_data_item::<Vec<String>>(DataItem::decode(&compressed).unwrap()).unwrap(),
+        vec!["repeated", "repeated"]
+    );
+    assert!(crate::from_bytes::<Vec<String>>(&compressed).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_any_strips_tag() {
+    use crate::de::StreamDeserializer;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Link {
+        uri: String,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Page {
+        #[serde(flatten)]
+        link: Link,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(untagged)]
+    enum Stamp {
+        Num(u64),
+        Text(String),
+    }
+
+    let page = DataItem::from(MapContent::from_iter([(
+        "uri",
+        DataItem::from(TagContent::from((32, "http://www.example.com"))),
+    )]))
+    .encode();
+    let expected = Page {
+        link: Link {
+            uri: "http://www.example.com".to_string(),
+        },
+    };
+    assert_eq!(crate::from_bytes::<Page>(&page).unwrap(), expected);
+    assert_eq!(
+        Page::deserialize(&mut StreamDeserializer::new(page.as_slice())).unwrap(),
+        expected
+    );
+
+    let stamp = DataItem::from(TagContent::from((1, 1_363_896_240))).encode();
+    assert_eq!(
+        crate::from_bytes::<Stamp>(&stamp).unwrap(),
+        Stamp::Num(1_363_896_240)
+    );
+    assert_eq!(
+        Stamp::deserialize(&mut StreamDeserializer::new(stamp.as_slice())).unwrap(),
+        Stamp::Num(1_363_896_240)
+    );
+    assert_eq!(
+        crate::from_bytes::<serde_json::Value>(&stamp).unwrap(),
+        serde_json::Value::from(1_363_896_240)
+    );
+    assert_eq!(
+        serde_json::Value::deserialize(&mut StreamDeserializer::new(stamp.as_slice())).unwrap(),
+        serde_json::Value::from(1_363_896_240)
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_stream() {
+    use crate::de::StreamDeserializer;
+    use crate::read::IoReader;
+    use crate::ser::StreamSerializer;
+    use crate::tagged::Tagged;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Shape<'a> {
+        Point,
+        Circle(f64),
+        Label { text: &'a str },
+    }
+
+    let json = r#"{"name":"sketch","parent":null,"points":[1,-2,3.5],"tag":[32,"http://a"]}"#;
+    let mut bytes = vec![];
+    serde_json::Value::serialize(
+        &serde_json::from_str::<serde_json::Value>(json).unwrap(),
+        StreamSerializer::new(&mut bytes),
+    )
+    .unwrap();
+    assert_eq!(
+        bytes,
+        crate::to_data_item(&serde_json::from_str::<serde_json::Value>(json).unwrap())
+            .unwrap()
+            .encode()
+    );
+    let mut deserializer = StreamDeserializer::new(bytes.as_slice());
+    let value = serde_json::Value::deserialize(&mut deserializer).unwrap();
+    deserializer.end().unwrap();
+    assert_eq!(serde_json::to_string(&value).unwrap(), json);
+
+    let data_item = DataItem::from(vec![
+        TagContent::from((32, "http://www.example.com")).into(),
+        DataItem::Undefined,
+        SimpleValue::new(40).unwrap().into(),
+        DataItem::from(u64::MAX),
+        DataItem::Signed(u64::MAX),
+    ]);
+    let bytes = data_item.encode();
+    let value =
+        serde_json::Value::deserialize(&mut StreamDeserializer::new(bytes.as_slice())).unwrap();
+    assert_eq!(
+        value,
+        crate::from_data_item::<serde_json::Value>(data_item.clone()).unwrap()
+    );
+    assert_eq!(
+        DataItem::deserialize(&mut StreamDeserializer::new(bytes.as_slice())).unwrap(),
+        data_item
+    );
+    let reader = IoReader::new(std::io::Cursor::new(bytes.clone()));
+    assert_eq!(
+        DataItem::deserialize(&mut StreamDeserializer::new(reader)).unwrap(),
+        data_item
+    );
+    let mut streamed = vec![];
+    data_item
+        .serialize(StreamSerializer::new(&mut streamed))
+        .unwrap();
+    assert_eq!(streamed, bytes);
+
+    for shape in [
+        Shape::Point,
+        Shape::Circle(1.5),
+        Shape::Label { text: "label" },
+    ] {
+        let bytes = crate::to_vec(&shape).unwrap();
+        let mut deserializer = StreamDeserializer::new(bytes.as_slice());
+        assert_eq!(Shape::deserialize(&mut deserializer).unwrap(), shape);
+        deserializer.end().unwrap();
+    }
+
+    let bytes = DataItem::from(vec![
+        TagContent::from((2, [1u8, 0].as_slice())).into(),
+        TagContent::from((1, 10)).into(),
+        DataItem::from(vec![1, 2, 3]),
+        DataItem::from([1u8, 2].as_slice()),
+    ])
+    .encode();
+    let mut deserializer = StreamDeserializer::new(bytes.as_slice());
+    let value = <(u128, Tagged<u64>, (u8, u8), &[u8])>::deserialize(&mut deserializer).unwrap();
+    assert_eq!(
+        value,
+        (256, Tagged::new(Some(1), 10), (1, 2), [1, 2].as_slice())
+    );
+    deserializer.end().unwrap();
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_stream_limits() {
+    use crate::de::{DeserializerOptions, StreamDeserializer};
+
+    let mut decode_options = DecodeOptions::default();
+    decode_options.set_max_depth(2).set_max_size(64);
+    let mut options = DeserializerOptions::default();
+    options.set_decode_options(decode_options);
+    let nested = DataItem::from(vec![DataItem::from(vec![DataItem::from(vec![1])])]).encode();
+    assert!(matches!(
+        serde_json::Value::deserialize(&mut StreamDeserializer::with_options(
+            nested.as_slice(),
+            options
+        )),
+        Err(Error::LimitExceeded(_))
+    ));
+    assert!(matches!(
+        serde_json::Value::deserialize(&mut StreamDeserializer::with_options(
+            [0x5a, 0xff, 0xff, 0xff, 0xff].as_slice(),
+            options
+        )),
+        Err(Error::LimitExceeded(_))
+    ));
+    assert_eq!(
+        serde_json::Value::deserialize(&mut StreamDeserializer::new([0x9f, 0x01, 0x02].as_slice())),
+        Err(Error::IncompleteIndefinite)
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_seed() {
+    use serde::de::{DeserializeSeed, SeqAccess, Visitor};
+
+    struct Offset(u64);
+
+    impl<'de> DeserializeSeed<'de> for Offset {
+        type Value = Vec<u64>;
+
+        fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            deserializer.deserialize_seq(self)
+        }
+    }
+
+    impl<'de> Visitor<'de> for Offset {
+        type Value = Vec<u64>;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            formatter.write_str("array of integer")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut values = vec![];
+            while let Some(value) = seq.next_element::<u64>()? {
+                values.push(value + self.0);
+            }
+            Ok(values)
+        }
+    }
+
+    assert_eq!(
+        crate::de::from_bytes_seed(&[0x83, 0x01, 0x02, 0x03], Offset(10)).unwrap(),
+        vec![11, 12, 13]
+    );
+    assert_eq!(
+        crate::de::from_data_item_seed(DataItem::from(vec![5]), Offset(1)).unwrap(),
+        vec![6]
+    );
+    assert!(crate::de::from_data_item_seed(DataItem::from("a"), Offset(1)).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_limit() {
+    use crate::DecodeOptions;
+    use crate::de::DeserializerOptions;
+
+    let bytes = crate::to_vec(&vec![vec![1, 2], vec![3, 4]]).unwrap();
+    let mut decode_options = DecodeOptions::default();
+    decode_options.set_max_depth(2).set_max_length(2);
+    let mut options = DeserializerOptions::default();
+    options.set_decode_options(decode_options);
+    assert_eq!(
+        crate::de::from_bytes_with_options::<Vec<Vec<u8>>>(&bytes, &options).unwrap(),
+        vec![vec![1, 2], vec![3, 4]]
+    );
+    assert_eq!(
+        crate::de::from_reader_with_options::<Vec<Vec<u8>>, _>(bytes.as_slice(), &options).unwrap(),
+        vec![vec![1, 2], vec![3, 4]]
+    );
+
+    decode_options.set_max_depth(1);
+    options.set_decode_options(decode_options);
+    assert!(matches!(
+        crate::de::from_bytes_with_options::<Vec<Vec<u8>>>(&bytes, &options),
+        Err(Error::LimitExceeded(_))
+    ));
+
+    decode_options
+        .set_max_depth(2)
+        .set_max_size(bytes.len() - 1);
+    options.set_decode_options(decode_options);
+    assert!(matches!(
+        crate::de::from_bytes_with_options::<Vec<Vec<u8>>>(&bytes, &options),
+        Err(Error::LimitExceeded(_))
+    ));
+    assert!(matches!(
+        crate::de::from_reader_with_options::<Vec<Vec<u8>>, _>(bytes.as_slice(), &options),
+        Err(Error::LimitExceeded(_))
+    ));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_simple_value() {
+    let simple = DataItem::decode(&[0xf8, 0x20]).unwrap();
+    assert_eq!(
+        simple,
+        DataItem::GenericSimple(SimpleValue::new(32).unwrap())
+    );
+    assert_eq!(crate::to_data_item(&simple).unwrap(), simple);
+    assert_eq!(
+        crate::from_data_item::<DataItem>(simple.clone()).unwrap(),
+        simple
+    );
+    let array = DataItem::from(vec![simple.clone(), DataItem::Undefined]);
+    assert_eq!(crate::to_data_item(&array).unwrap(), array);
+    assert_eq!(
+        crate::from_data_item::<DataItem>(array.clone()).unwrap(),
+        array
+    );
+    assert_eq!(crate::from_data_item::<u8>(simple.clone()).unwrap(), 32);
+
+    let simple_value = SimpleValue::new(200).unwrap();
+    assert_eq!(
+        crate::to_data_item(&simple_value).unwrap(),
+        DataItem::GenericSimple(simple_value.clone())
+    );
+    for data_item in [
+        DataItem::GenericSimple(simple_value.clone()),
+        DataItem::Unsigned(200),
+    ] {
+        assert_eq!(
+            crate::from_data_item::<SimpleValue>(data_item).unwrap(),
+            simple_value
+        );
+    }
+    assert_eq!(
+        crate::from_data_item::<SimpleValue>(DataItem::Null).unwrap(),
+        SimpleValue::NULL
+    );
+    assert!(crate::from_data_item::<SimpleValue>(DataItem::Unsigned(24)).is_err());
+    assert!(crate::from_data_item::<SimpleValue>(DataItem::from("a")).is_err());
+
+    let json = serde_json::to_string(&simple).unwrap();
+    assert_eq!(json, "32");
+    assert_eq!(
+        serde_json::from_str::<SimpleValue>(&json).unwrap(),
+        SimpleValue::new(32).unwrap()
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_undefined() {
+    use crate::Maybe;
+    use crate::de::DeserializerOptions;
+    use crate::ser::SerializerOptions;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Patch {
+        #[serde(default, skip_serializing_if = "Maybe::is_absent")]
+        name: Maybe<String>,
+        #[serde(default, skip_serializing_if = "Maybe::is_absent")]
+        age: Maybe<u8>,
+        #[serde(default, skip_serializing_if = "Maybe::is_absent")]
+        email: Maybe<String>,
+        #[serde(default, skip_serializing_if = "Maybe::is_absent")]
+        phone: Maybe<String>,
+    }
+
+    let patch = Patch {
+        name: Maybe::Value("a".to_string()),
+        age: Maybe::Null,
+        email: Maybe::Undefined,
+        phone: Maybe::Absent,
+    };
+    let data_item = crate::to_data_item(&patch).unwrap();
+    assert_eq!(
+        format!("{data_item:?}"),
+        "{\"name\": \"a\", \"age\": null, \"email\": undefined}"
+    );
+    assert_eq!(crate::from_data_item::<Patch>(data_item).unwrap(), patch);
+
+    let mut serializer_options = SerializerOptions::default();
+    serializer_options.set_none_as_undefined(true);
+    assert_eq!(
+        crate::ser::to_data_item_with_options(&vec![Some(1), None], &serializer_options).unwrap(),
+        DataItem::from(vec![DataItem::from(1), DataItem::Undefined])
+    );
+    assert_eq!(
+        crate::to_data_item(&vec![Some(1), None]).unwrap(),
+        DataItem::from(vec![DataItem::from(1), DataItem::Null])
+    );
+
+    let data_item = DataItem::from(vec![DataItem::Null, DataItem::Undefined]);
+    assert_eq!(
+        crate::from_data_item::<Vec<Option<u8>>>(data_item.clone()).unwrap(),
+        vec![None, None]
+    );
+    let mut deserializer_options = DeserializerOptions::default();
+    deserializer_options.set_reject_undefined(true);
+    assert!(
+        crate::de::from_data_item_with_options::<Vec<Option<u8>>>(
+            data_item.clone(),
+            &deserializer_options
+        )
+        .is_err()
+    );
+    assert_eq!(
+        crate::de::from_data_item_with_options::<Vec<Maybe<u8>>>(data_item, &deserializer_options)
+            .unwrap(),
+        vec![Maybe::Null, Maybe::Undefined]
+    );
+}
+
+#[cfg(feature = "num-bigint")]
+#[test]
+fn num_bigint() {
+    use num_bigint::{BigInt, BigUint};
+
+    let big = BigUint::from(u128::MAX) + 1u8;
+    compare_cbor_value("c2510100000000000000000000000000000000", big.clone());
+    compare_cbor_value(
+        "c3510100000000000000000000000000000000",
+        -BigInt::from(big.clone()) - 1,
+    );
+    compare_cbor_value("1bffffffffffffffff", BigUint::from(u64::MAX));
+    compare_cbor_value("3bffffffffffffffff", -BigInt::from(u64::MAX) - 1);
+    compare_cbor_value("00", BigInt::from(0));
+    let decoded =
+        DataItem::decode(&hex::decode("c3510100000000000000000000000000000000").unwrap()).unwrap();
+    assert_eq!(decoded.as_bigint(), Some(-BigInt::from(big) - 1));
+    assert_eq!(DataItem::from(-10).as_bigint(), Some(BigInt::from(-10)));
+    assert_eq!(DataItem::from("10").as_bigint(), None);
+}
+
+#[test]
+fn decimal_fraction_and_bigfloat() {
+    compare_cbor_value("c48221196ab3", DataItem::decimal_fraction(-2, 27315));
+    compare_cbor_value("c5822003", DataItem::bigfloat(-1, 3));
+    compare_cbor_value(
+        "c48201c25001000000000000000000000000000000",
+        DataItem::decimal_fraction(1, 1 << 120),
+    );
+    let decoded = DataItem::decode(&hex::decode("c48221196ab3").unwrap()).unwrap();
+    assert_eq!(decoded.as_decimal_fraction(), Some((-2, 27315)));
+    assert_eq!(decoded.as_bigfloat(), None);
+    let decoded = DataItem::decode(&hex::decode("c5822003").unwrap()).unwrap();
+    assert_eq!(decoded.as_bigfloat(), Some((-1, 3)));
+    assert_eq!(
+        DataItem::from(TagContent::from((
+            4,
+            vec![DataItem::from(1.0), DataItem::from(2)]
+        )))
+        .as_decimal_fraction(),
+        None
+    );
+    assert_eq!(
+        DataItem::from(TagContent::from((4, vec![1, 2, 3]))).as_decimal_fraction(),
+        None
+    );
+}
+
+#[cfg(feature = "rust_decimal")]
+#[test]
+fn rust_decimal() {
+    use rust_decimal::Decimal;
+
+    compare_cbor_value("c48221196ab3", Decimal::new(27315, 2));
+    compare_cbor_value("c482003863", Decimal::new(-100, 0));
+    assert_eq!(
+        DataItem::decimal_fraction(2, -15).as_decimal(),
+        Some(Decimal::new(-1500, 0))
+    );
+    assert_eq!(
+        DataItem::decimal_fraction(-3, 1_500).as_decimal(),
+        Some(Decimal::new(15, 1))
+    );
+    assert_eq!(DataItem::decimal_fraction(-29, 1).as_decimal(), None);
+    assert_eq!(DataItem::decimal_fraction(40, 1).as_decimal(), None);
+    assert_eq!(DataItem::bigfloat(-2, 1).as_decimal(), None);
+}
+
+#[test]
+fn tag_registry() {
+    let registry = TagRegistry::standard();
+    for valid in [
+        "c074323031332d30332d32315432303a30343a30305a",
+        "c11a514b67b0",
+        "c1fb41d452d9ec200000",
+        "c249010000000000000000",
+        "c482213811aa",
+        "c5822003",
+        "d818456449455446",
+        "d82076687474703a2f2f7777772e6578616d706c652e636f6d",
+        "d8216441514944",
+        "d822644151493d",
+        "d82363612e63",
+    ] {
+        let value = DataItem::decode(&hex::decode(valid).unwrap()).unwrap();
+        assert!(value.validate(&registry).is_ok());
+    }
+    let value =
+        DataItem::decode(&hex::decode("82c11a514b67b0c06a323031332d30332d3231").unwrap()).unwrap();
+    assert!(matches!(
+        value.validate(&registry),
+        Err(Error::InvalidTagContent { .. })
+    ));
+    for invalid in [
+        "c06a323031332d30332d3231",
+        "c1f97e00",
+        "c201",
+        "c48221f4",
+        "d8184161",
+        "d8206161",
+        "d8216441513d3d",
+        "d8226141",
+        "d82301",
+    ] {
+        assert!(matches!(
+            DataItem::decode_with(&hex::decode(invalid).unwrap(), &registry),
+            Err(Error::InvalidTagContent { .. })
+        ));
+    }
+
+    let mut registry = TagRegistry::new();
+    assert!(!registry.is_registered(tag::EPOCH));
+    registry.register_converter(tag::EPOCH, |content| {
+        content
+            .as_unsigned()
+            .map(|seconds| DataItem::from(seconds * 1000))
+            .ok_or(Error::InvalidTagContent {
+                tag: tag::EPOCH,
+                expected: "unsigned integer",
+            })
+    });
+    assert!(registry.is_registered(tag::EPOCH));
+    assert_eq!(
+        DataItem::decode_with(&hex::decode("82c101a1c10263616263").unwrap(), &registry).unwrap(),
+        DataItem::from(vec![
+            DataItem::from(1000),
+            DataItem::from(vec![(2000, "abc")]),
+        ])
+    );
+    registry.unregister(tag::EPOCH);
+    assert!(!registry.is_registered(tag::EPOCH));
+    assert_eq!(
+        DataItem::decode_with(&hex::decode("c101").unwrap(), &registry).unwrap(),
+        TagContent::from((tag::EPOCH, 1)).into()
+    );
+}
+
+#[test]
+fn expect_tag() {
+    let bytes = hex::decode("d82076687474703a2f2f7777772e6578616d706c652e636f6d").unwrap();
+    assert_eq!(
+        DataItem::decode_expect_tag(tag::URI, &bytes).unwrap(),
+        DataItem::from("http://www.example.com")
+    );
+    assert_eq!(
+        DataItem::decode_expect_tag(tag::EPOCH, &bytes),
+        Err(Error::WrongTag {
+            expected: tag::EPOCH,
+            found: Some(tag::URI)
+        })
+    );
+    assert_eq!(
+        DataItem::decode_expect_tag(tag::URI, &hex::decode("6161").unwrap()),
+        Err(Error::WrongTag {
+            expected: tag::URI,
+            found: None
+        })
+    );
+    assert_eq!(
+        DataItem::decode_expect_tag(tag::URI, &hex::decode("d820").unwrap()),
+        Err(Error::Incomplete)
+    );
+
+    let nested = DataItem::from(TagContent::from((
+        tag::SELF_DESCRIBED,
+        TagContent::from((tag::EPOCH, 1)),
+    )));
+    assert_eq!(
+        nested
+            .require_tag(tag::SELF_DESCRIBED)
+            .and_then(|content| content.require_tag(tag::EPOCH)),
+        Ok(&DataItem::from(1))
+    );
+    assert_eq!(
+        nested.require_tag(tag::EPOCH).unwrap_err().to_string(),
+        "expected tag 1 but found tag 55799"
+    );
+    assert_eq!(
+        DataItem::from(1)
+            .require_tag(tag::EPOCH)
+            .unwrap_err()
+            .to_string(),
+        "expected tag 1 but found untagged data item"
+    );
+}
+
+#[test]
+fn diagnostic_pretty() {
+    let value =
+        DataItem::decode(&hex::decode("bf61610161629f0203ff6163d82080a0c1a1616482f5f6ff").unwrap())
+            .unwrap();
+    assert_eq!(
+        value.to_diagnostic_pretty(2),
+        "{_\n  \"a\": 1,\n  \"b\": [_\n    2,\n    3\n  ],\n  \"c\": 32([]),\n  {}: 1({\n    \
+         \"d\": [\n      true,\n      null\n    ]\n  })\n}"
+    );
+    assert_eq!(
+        value.to_diagnostic_pretty(0),
+        "{_\n\"a\": 1,\n\"b\": [_\n2,\n3\n],\n\"c\": 32([]),\n{}: 1({\n\"d\": \
+         [\ntrue,\nnull\n]\n})\n}"
+    );
+    assert_eq!(DataItem::from(1).to_diagnostic_pretty(4), "1");
+    assert_eq!(
+        DataItem::from(Vec::<u8>::new()).to_diagnostic_pretty(4),
+        "[]"
+    );
+}
+
+#[test]
+fn diagnostic_parse() {
+    for (diagnostic, hex_val) in [
+        ("0", "00"),
+        ("-1", "20"),
+        ("18446744073709551615", "1bffffffffffffffff"),
+        ("-18446744073709551616", "3bffffffffffffffff"),
+        ("1.5", "f93e00"),
+        ("-4.1", "fbc010666666666666"),
+        ("1.0e+300", "fb7e37e43c8800759c"),
+        ("5.960464477539063e-8", "f90001"),
+        ("-Infinity", "f9fc00"),
+        ("simple(255)", "f8ff"),
+        ("undefined", "f7"),
+        ("1(1363896240.5)", "c1fb41d452d9ec200000"),
+        ("23(h'01 02 03 04')", "d74401020304"),
+        ("'abc'", "43616263"),
+        ("b64'AQID'", "43010203"),
+        ("\"\\u00fc\"", "62c3bc"),
+        ("\"\\ud800\\udd51\"", "64f0908591"),
+        ("\"\\u{10151}\\n\"", "65f09085910a"),
+        ("[1, [2, 3], [4, 5]]", "8301820203820405"),
+        ("{_ \"a\": 1, \"b\": [_ 2, 3]}", "bf61610161629f0203ffff"),
+        ("(_ h'0102', h'030405')", "5f42010243030405ff"),
+        ("(_ \"strea\", \"ming\")", "7f657374726561646d696e67ff"),
+        ("[ _ ]", "9fff"),
+        ("{ / comment / 1 : 0x10 }", "a10110"),
+    ] {
+        assert_eq!(
+            diagnostic.parse::<DataItem>().unwrap(),
+            DataItem::decode(&hex::decode(hex_val).unwrap()).unwrap()
+        );
+    }
+    assert!(
+        DataItem::from_diagnostic("NaN")
+            .unwrap()
+            .as_floating()
+            .unwrap()
+            .is_nan()
+    );
+
+    let value =
+        DataItem::decode(&hex::decode("bf61610161629f0203ff6163d82080a0c1a1616482f5f6ff").unwrap())
+            .unwrap();
+    assert_eq!(
+        DataItem::from_diagnostic(&format!("{value:?}")).unwrap(),
+        value
+    );
+    assert_eq!(
+        DataItem::from_diagnostic(&value.to_diagnostic_pretty(4)).unwrap(),
+        value
+    );
+
+    for invalid in [
+        "",
+        "[1, 2",
+        "[1 2]",
+        "{1: 2, 1: 3}",
+        "h'0'",
+        "\"abc",
+        "simple(24)",
+        "18446744073709551616",
+        "-18446744073709551617",
+        "-1(2)",
+        "(_ 1)",
+        "tru",
+        "1 2",
+    ] {
+        assert!(matches!(
+            DataItem::from_diagnostic(invalid),
+            Err(Error::InvalidDiagnostic { .. })
+        ));
+    }
+
+    assert_eq!(
+        DataItem::from_diagnostic("{1: 2, 1: 3}"),
+        Err(Error::InvalidDiagnostic {
+            reason: Syntax::DuplicateKey { index: 1 },
+            position: 11
+        })
+    );
+    assert_eq!(
+        DataItem::from_diagnostic("[1 2]").unwrap_err().to_string(),
+        "invalid diagnostic notation : expected \",\" at position 3"
+    );
+    let nested = format!("{}1{}", "[".repeat(255), "]".repeat(255));
+    assert!(DataItem::from_diagnostic(&nested).is_ok());
+    for deep in [
+        format!("{}1{}", "[".repeat(256), "]".repeat(256)),
+        format!("{}1{}", "[".repeat(100_000), "]".repeat(100_000)),
+        format!("{}1{}", "1(".repeat(257), ")".repeat(257)),
+        format!("{}1{}", "<<".repeat(257), ">>".repeat(257)),
+    ] {
+        assert!(matches!(
+            DataItem::from_diagnostic(&deep),
+            Err(Error::InvalidDiagnostic { .. })
+        ));
+    }
+}
+
+#[test]
+fn diagnostic_extended() {
+    for (diagnostic, hex_val) in [
+        ("<<1, 2>>", "420102"),
+        ("<<>>", "40"),
+        ("24(<<{1: -7}>>)", "d81843a10126"),
+        ("'Hello ' h'776f726c6421'", "4c48656c6c6f20776f726c6421"),
+        ("\"Hello \" 'world' \"!\"", "6c48656c6c6f20776f726c6421"),
+        (
+            "[1, # comment till end of line\n 2 / inline comment / ]",
+            "820102",
+        ),
+        ("h'68 65 6c /doubled l!/ 6c 6f'", "4568656c6c6f"),
+    ] {
+        assert_eq!(
+            DataItem::from_diagnostic(diagnostic).unwrap(),
+            DataItem::decode(&hex::decode(hex_val).unwrap()).unwrap()
+        );
+    }
+    assert!(matches!(
+        DataItem::from_diagnostic("\"a\" h'ff'"),
+        Err(Error::InvalidDiagnostic { .. })
+    ));
+
+    let value =
+        DataItem::decode(&hex::decode("a2436b6579825f4161420a0bff4201025f42494f4142fff6").unwrap())
+            .unwrap();
+    assert_eq!(
+        format!("{value:?}"),
+        value.to_diagnostic(&DiagnosticOptions::default())
+    );
+    let mut options = DiagnosticOptions::default();
+    options.set_extended(true);
+    assert_eq!(
+        value.to_diagnostic(&options),
+        "{'key': [(_ 'a', h'0a0b'), h'0102'], (_ 'IO', 'B'): null}"
+    );
+    assert_eq!(
+        DataItem::from_diagnostic(&value.to_diagnostic(&options)).unwrap(),
+        value
+    );
+    options.set_indent(1);
+    assert_eq!(
+        value.to_diagnostic(&options),
+        "{\n 'key': [\n  (_ 'a', h'0a0b'),\n  h'0102'\n ],\n (_ 'IO', 'B'): null\n}"
+    );
+    assert_eq!(
+        DataItem::from_diagnostic(&value.to_diagnostic(&options)).unwrap(),
+        value
+    );
+}
+
+#[test]
+fn annotate() {
+    let bytes = hex::decode("bf6161d818439f010262625f420103ff").unwrap();
+    assert_eq!(
+        DataItem::annotate(&bytes).unwrap(),
+        "0000: bf                # map(*)\n\
+         0001:    61             #   text(1)\n\
+         0002:       61          #     \"a\"\n\
+         0003:    d8 18          #   tag(24)\n\
+         0005:       43          #     bytes(3)\n\
+         0006:          9f 01 02 #       h'9f0102'\n\
+         0009:    62             #   text(2)\n\
+         000a:       62 5f       #     \"b_\"\n\
+         000c:    42             #   bytes(2)\n\
+         000d:       01 03       #     h'0103'\n\
+         000f:    ff             #   break\n"
+    );
+    let bytes = hex::decode("9f5f42010241ffff3b0000000000000000f93e00f5f8ff40ff").unwrap();
+    assert_eq!(
+        DataItem::annotate(&bytes).unwrap(),
+        "0000: 9f                            # array(*)\n\
+         0001:    5f                         #   bytes(*)\n\
+         0002:       42                      #     bytes(2)\n\
+         0003:          01 02                #       h'0102'\n\
+         0005:       41                      #     bytes(1)\n\
+         0006:          ff                   #       h'ff'\n\
+         0007:       ff                      #     break\n\
+         0008:    3b 00 00 00 00 00 00 00 00 #   negative(-1)\n\
+         0011:    f9 3e 00                   #   1.5\n\
+         0014:    f5                         #   true\n\
+         0015:    f8 ff                      #   simple(255)\n\
+         0017:    40                         #   bytes(0)\n\
+         0018:    ff                         #   break\n"
+    );
+    assert_eq!(DataItem::annotate(&[0x82, 0x01]), Err(Error::Incomplete));
+}
+
+#[test]
+fn diagnostic_expand_embedded() {
+    let value = DataItem::decode(&hex::decode("83d81843a10126d8184201ffd818585a").unwrap());
+    assert!(value.is_err());
+    let value =
+        DataItem::decode(&hex::decode("84d81843a10126d8184201ffd818420101d8185f4101ff").unwrap())
+            .unwrap();
+    let mut options = DiagnosticOptions::default();
+    assert_eq!(
+        value.to_diagnostic(&options),
+        "[24(h'a10126'), 24(h'01ff'), 24(h'0101'), 24((_ h'01'))]"
+    );
+    options.set_expand_embedded(true);
+    assert_eq!(
+        value.to_diagnostic(&options),
+        "[24(<<{1: -7}>>), 24(h'01ff'), 24(h'0101'), 24((_ h'01'))]"
+    );
+    assert_eq!(
+        DataItem::from_diagnostic(&value.to_diagnostic(&options)).unwrap(),
+        value
+    );
+    options.set_indent(2);
+    let value = DataItem::from_diagnostic("24(<<[1, 24(<<\"a\">>)]>>)").unwrap();
+    assert_eq!(
+        value.to_diagnostic(&options),
+        "24(<<[\n  1,\n  24(<<\"a\">>)\n]>>)"
+    );
+}
+
+#[test]
+fn hex_conversion() {
+    for hex_val in [
+        "00",
+        "3bffffffffffffffff",
+        "f93e00",
+        "c074323031332d30332d32315432303a30343a30305a",
+        "5f42010243030405ff",
+        "bf61610161629f0203ffff",
+    ] {
+        let value = DataItem::decode_hex(hex_val).unwrap();
+        assert_eq!(
+            value,
+            DataItem::decode(&hex::decode(hex_val).unwrap()).unwrap()
+        );
+        assert_eq!(value.encode_hex(), hex_val);
+    }
+    assert_eq!(
+        DataItem::decode_hex("A2 01 02\n03 04").unwrap(),
+        DataItem::from(vec![(1, 2), (3, 4)])
+    );
+    assert_eq!(DataItem::decode_hex("8"), Err(Error::InvalidHex));
+    assert_eq!(DataItem::decode_hex("8g"), Err(Error::InvalidHex));
+    assert_eq!(DataItem::decode_hex("8201"), Err(Error::Incomplete));
+}
+
+#[test]
+fn debug_alternate() {
+    let value = DataItem::decode_hex("a26161820102616280").unwrap();
+    assert_eq!(format!("{value:?}"), "{\"a\": [1, 2], \"b\": []}");
+    assert_eq!(
+        format!("{value:#?}"),
+        "{\n    \"a\": [\n        1,\n        2\n    ],\n    \"b\": []\n}"
+    );
+    assert_eq!(
+        format!("{:#?}", Some(DataItem::from(vec![1]))),
+        "Some(\n    [\n        1\n    ],\n)"
+    );
+    assert_eq!(format!("{:#?}", DataItem::from("a")), "\"a\"");
+}
+
+#[test]
+fn diagnostic_expected_encoding() {
+    let value = DataItem::from_diagnostic(
+        "[21(h'fbff'), 22(h'fbff'), 23(h'fbff'), 21([h'00', {h'01': 23(h'02')}, 22((_ h'03', \
+         h'0405'))]), h'06']",
+    )
+    .unwrap();
+    let mut options = DiagnosticOptions::default();
+    assert_eq!(
+        value.to_diagnostic(&options),
+        "[21(b64'-_8'), 22(b64'+/8='), 23(h'fbff'), 21([b64'AA', {b64'AQ': 23(h'02')}, 22((_ \
+         b64'Aw==', b64'BAU='))]), h'06']"
+    );
+    assert_eq!(
+        DataItem::from_diagnostic(&value.to_diagnostic(&options)).unwrap(),
+        value
+    );
+    options.set_extended(true);
+    assert_eq!(
+        DataItem::from_diagnostic("[23('abc'), 'abc']")
+            .unwrap()
+            .to_diagnostic(&options),
+        "[23(h'616263'), 'abc']"
+    );
+}
+
+#[test]
+fn decode_span() {
+    let bytes = hex::decode("bf61610c6162d8209f5f4101ff1bffffffffffffffffffff").unwrap();
+    let (value, span) = DataItem::decode_with_span(&bytes).unwrap();
+    assert_eq!(value, DataItem::decode(&bytes).unwrap());
+    assert_eq!(span.range(), 0..bytes.len());
+    let children = span.children();
+    assert_eq!(
+        children.iter().map(SpanTree::range).collect::<Vec<_>>(),
+        vec![1..3, 3..4, 4..6, 6..23]
+    );
+    let tag_span = &children[3];
+    assert_eq!(tag_span.children().len(), 1);
+    let array_span = &tag_span.children()[0];
+    assert_eq!(array_span.range(), 8..23);
+    assert_eq!(
+        array_span
+            .children()
+            .iter()
+            .map(SpanTree::range)
+            .collect::<Vec<_>>(),
+        vec![9..13, 13..22]
+    );
+    assert_eq!(array_span.children()[0].children()[0].range(), 10..12);
+    assert!(array_span.children()[1].children().is_empty());
+    assert_eq!(
+        DataItem::decode(&bytes[array_span.children()[1].range()]).unwrap(),
+        DataItem::Unsigned(u64::MAX)
+    );
+    assert_eq!(
+        DataItem::decode_with_span(&bytes[..10]),
+        Err(Error::IncompleteIndefinite)
+    );
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_value() {
+    use serde_json::{Value, json};
+
+    use crate::error::Conversion;
+    use crate::json_options::JsonOptions;
+
+    let value = DataItem::from_diagnostic(
+        "{\"a\": 100, \"b\": -1, \"c\": -18446744073709551616, \"d\": 1.5, \"e\": Infinity, \
+         \"f\": h'fbff', \"g\": 22(h'fbff'), \"h\": 23([h'fbff', 21(h'fbff')]), \"i\": \
+         2(h'010000000000000000'), \"j\": 3(h'010000000000000000'), \"k\": 32(\"a\"), \"l\": \
+         [true, null, undefined, simple(16)]}",
+    )
+    .unwrap();
+    let json_value = json!({
+        "a": 100,
+        "b": -1,
+        "c": -18_446_744_073_709_551_616.0,
+        "d": 1.5,
+        "e": null,
+        "f": "-_8",
+        "g": "+/8=",
+        "h": ["fbff", "-_8"],
+        "i": "AQAAAAAAAAAA",
+        "j": "~AQAAAAAAAAAA",
+        "k": "a",
+        "l": [true, null, null, null]
+    });
+    #[cfg(feature = "json-arbitrary-precision")]
+    let json_value = {
+        let mut json_value = json_value;
+        for (key, number) in [
+            ("c", "-18446744073709551616"),
+            ("i", "18446744073709551616"),
+            ("j", "-18446744073709551617"),
+        ] {
+            json_value[key] = Value::Number(number.parse().unwrap());
+        }
+        json_value
+    };
+    assert_eq!(Value::try_from(value).unwrap(), json_value);
+
+    let value = DataItem::from_diagnostic("{1: [2], \"a\": {h'01': 3}}").unwrap();
+    assert_eq!(
+        Value::try_from(value.clone()),
+        Err(Error::JsonConversion(Conversion::NonTextKey {
+            found: ItemKind::Unsigned
+        }))
+    );
+    let mut options = JsonOptions::default();
+    options.set_stringify_keys(true);
+    assert_eq!(
+        value.to_json_value(&options).unwrap(),
+        json!({"1": [2], "a": {"h'01'": 3}})
+    );
+
+    let json_value = json!({"a": [1, -2, 1.5, "b", null, true], "c": {}});
+    assert_eq!(
+        DataItem::from(json_value.clone()),
+        DataItem::from_diagnostic("{\"a\": [1, -2, 1.5, \"b\", null, true], \"c\": {}}").unwrap()
+    );
+    assert_eq!(
+        Value::try_from(DataItem::from(json_value.clone())).unwrap(),
+        json_value
+    );
+}
+
+#[test]
+fn json_string() {
+    use crate::json_options::JsonOptions;
+
+    let value = DataItem::from_diagnostic(
+        "{\"a\": 100, \"b\": -18446744073709551616, \"c\": [1.5, 1.0e300, -0.0, NaN, -Infinity], \
+         \"d\": h'fbff', \"e\": 22(h'fbff'), \"f\": 23({\"g\": [h'fbff', 21(h'fbff')]}), \"h\": \
+         3(h'010000000000000000'), \"i\": \"q\\\"u\\\\o\\u0001te\\n\", \"j\": [true, false, null, \
+         undefined], \"k\": 0(\"2013-03-21T20:04:00Z\")}",
+    )
+    .unwrap();
+    let mut options = JsonOptions::default();
+    assert_eq!(
+        value.to_json_string(&options).unwrap(),
+        "{\"a\":100,\"b\":-18446744073709551616,\"c\":[1.5,1e300,-0.0,null,null],\"d\":\"-_8\",\"\
+         e\":\"+/8=\",\"f\":{\"g\":[\"fbff\",\"-_8\"]},\"h\":\"~AQAAAAAAAAAA\",\"i\":\"q\\\"u\\\\\
+         o\\u0001te\\n\",\"j\":[true,false,null,null],\"k\":\"2013-03-21T20:04:00Z\"}"
+    );
+    options.set_non_finite_as_string(true);
+    let json = value.to_json_string(&options).unwrap();
+    assert!(json.contains("\"c\":[1.5,1e300,-0.0,\"NaN\",\"-Infinity\"]"));
+    let parsed = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+    assert_eq!(parsed["i"], "q\"u\\o\u{1}te\n");
+    #[cfg(feature = "json")]
+    assert_eq!(parsed["f"], value.to_json_value(&options).unwrap()["f"]);
+
+    let value = DataItem::from_diagnostic("{1: 2}").unwrap();
+    assert!(matches!(
+        value.to_json_string(&JsonOptions::default()),
+        Err(Error::JsonConversion(_))
+    ));
+    options.set_stringify_keys(true);
+    assert_eq!(value.to_json_string(&options).unwrap(), "{\"1\":2}");
+}
+
+#[test]
+fn json_parse() {
+    use crate::json_options::JsonOptions;
+
+    for (json, diagnostic) in [
+        ("0", "0"),
+        ("-0", "0"),
+        ("18446744073709551615", "18446744073709551615"),
+        ("-18446744073709551616", "-18446744073709551616"),
+        ("18446744073709551616", "1.8446744073709552e19"),
+        ("1.0", "1.0"),
+        ("-1.5E+2", "-150.0"),
+        ("1e2", "100.0"),
+        (" [ ] ", "[]"),
+        ("{}", "{}"),
+        (
+            "\"\\ud800\\udd51\\u00fc\\/\\t\"",
+            "\"\\u{10151}\\u{fc}/\\t\"",
+        ),
+        (
+            "{\"a\": [true, false, null], \"b\": {\"c\": -1}}",
+            "{\"a\": [true, false, null], \"b\": {\"c\": -1}}",
+        ),
+    ] {
+        assert_eq!(
+            DataItem::from_json_str(json).unwrap(),
+            DataItem::from_diagnostic(diagnostic).unwrap()
+        );
+    }
+    assert_eq!(
+        DataItem::from_json_str("1.5").unwrap().encode_hex(),
+        "f93e00"
+    );
+
+    for invalid in [
+        "",
+        "01",
+        "1.",
+        ".1",
+        "-",
+        "1e",
+        "+1",
+        "[1,]",
+        "{\"a\" 1}",
+        "{\"a\": 1, \"a\": 2}",
+        "{1: 2}",
+        "\"\\x\"",
+        "\"\u{1}\"",
+        "\"\\ud800\"",
+        "tru",
+        "[1] 2",
+        "NaN",
+        "h'00'",
+    ] {
+        assert!(matches!(
+            DataItem::from_json_str(invalid),
+            Err(Error::InvalidJson { .. })
+        ));
+    }
+    assert_eq!(
+        DataItem::from_json_str("{\"a\": 1, \"a\": 2}"),
+        Err(Error::InvalidJson {
+            reason: Syntax::DuplicateKey { index: 1 },
+            position: 15
+        })
+    );
+
+    let json = "{\"a\":[1,-2,1.5,\"b\\n\",null,true],\"c\":{}}";
+    assert_eq!(
+        DataItem::from_json_str(json)
+            .unwrap()
+            .to_json_string(&JsonOptions::default())
+            .unwrap(),
+        json
+    );
+}
+
+#[test]
+fn json_lossless() {
+    use crate::json_options::JsonOptions;
+
+    let mut options = JsonOptions::default();
+    options.set_lossless(true);
+    let value = DataItem::from_diagnostic(
+        "{\"a\": [h'', h'fbff', -18446744073709551616, 1.0, NaN, -Infinity, undefined, null, \
+         simple(16), 1(\"b\")], \"$c\": {1: 2, [3]: {\"d\": 2(h'01')}}}",
+    )
+    .unwrap();
+    let json = value.to_json_string(&options).unwrap();
+    assert_eq!(
+        json,
+        "{\"$map\":[[\"a\",[{\"$bytes\":\"\"},{\"$bytes\":\"-_8\"},-18446744073709551616,1.0,{\"\
+         $float\":\"NaN\"},{\"$float\":\"-Infinity\"},{\"$undefined\":true},null,{\"$simple\":16},\
+         {\"$tag\":1,\"$value\":\"b\"}]],[\"$c\",{\"$map\":[[1,2],[[3],{\"d\":{\"$tag\":2,\"\
+         $value\":{\"$bytes\":\"AQ\"}}}]]}]]}"
+    );
+    let parsed = DataItem::from_json_str_with_options(&json, &options).unwrap();
+    assert_eq!(parsed.encode(), value.encode());
+    assert!(
+        DataItem::from_json_str(&json)
+            .unwrap()
+            .get(DataItem::from("$map"))
+            .is_some()
+    );
+
+    for invalid in [
+        "{\"$bytes\": 1}",
+        "{\"$bytes\": \"=\"}",
+        "{\"$tag\": -1, \"$value\": 1}",
+        "{\"$tag\": 1}",
+        "{\"$float\": \"1.0\"}",
+        "{\"$simple\": 24}",
+        "{\"$undefined\": false}",
+        "{\"$map\": [[1]]}",
+        "{\"$map\": [[1, 2], [1, 3]]}",
+        "{\"$unknown\": 1}",
+    ] {
+        assert!(matches!(
+            DataItem::from_json_str_with_options(invalid, &options),
+            Err(Error::InvalidJson { .. })
+        ));
+        assert!(DataItem::from_json_str(invalid).is_ok());
+    }
+}
+
+#[cfg(feature = "ciborium")]
+#[test]
+fn ciborium_value_conversion() {
+    use ciborium::Value;
+
+    use crate::error::Conversion;
+
+    let data_item = DataItem::from_diagnostic(
+        "{\"a\": [1, -1, -18446744073709551616, h'0102', 1.5, true, null], 1: 32(\"b\")}",
+    )
+    .unwrap();
+    let value = Value::try_from(data_item.clone()).unwrap();
+    assert_eq!(
+        value,
+        Value::Map(vec![
+            (
+                Value::Text("a".to_string()),
+                Value::Array(vec![
+                    Value::Integer(1.into()),
+                    Value::Integer((-1).into()),
+                    Value::Integer((-18_446_744_073_709_551_616_i128).try_into().unwrap()),
+                    Value::Bytes(vec![1, 2]),
+                    Value::Float(1.5),
+                    Value::Bool(true),
+                    Value::Null,
+                ])
+            ),
+            (
+                Value::Integer(1.into()),
+                Value::Tag(32, Box::new(Value::Text("b".to_string())))
+            ),
+        ])
+    );
+    assert_eq!(DataItem::try_from(value).unwrap(), data_item);
+
+    assert_eq!(
+        Value::try_from(DataItem::Undefined),
+        Err(Error::ValueConversion(Conversion::UnsupportedItem {
+            kind: ItemKind::Undefined
+        }))
+    );
+    assert_eq!(
+        DataItem::try_from(Value::Map(vec![
+            (Value::Null, Value::Null),
+            (Value::Null, Value::Bool(true)),
+        ])),
+        Err(Error::ValueConversion(Conversion::DuplicateKey))
+    );
+}
+
+#[cfg(feature = "serde_cbor")]
+#[test]
+fn serde_cbor_value_conversion() {
+    use std::collections::BTreeMap;
+
+    use serde_cbor::Value;
+
+    use crate::error::Conversion;
+
+    let data_item = DataItem::from_diagnostic(
+        "{\"aa\": [_ 1, -1, h'0102', 1.5, true, undefined], 10: 32(\"b\")}",
+    )
+    .unwrap();
+    let value = Value::try_from(data_item).unwrap();
+    let mut map = BTreeMap::new();
+    map.insert(
+        Value::Text("aa".to_string()),
+        Value::Array(vec![
+            Value::Integer(1),
+            Value::Integer(-1),
+            Value::Bytes(vec![1, 2]),
+            Value::Float(1.5),
+            Value::Bool(true),
+            Value::Null,
+        ]),
+    );
+    map.insert(
+        Value::Integer(10),
+        Value::Tag(32, Box::new(Value::Text("b".to_string()))),
+    );
+    assert_eq!(value, Value::Map(map));
+    assert_eq!(
+        DataItem::try_from(value).unwrap(),
+        DataItem::from_diagnostic("{10: 32(\"b\"), \"aa\": [1, -1, h'0102', 1.5, true, null]}")
+            .unwrap()
+    );
+
+    assert_eq!(
+        DataItem::try_from(Value::Integer(-18_446_744_073_709_551_617)).unwrap(),
+        DataItem::from_diagnostic("3(h'010000000000000000')").unwrap()
+    );
+    assert_eq!(
+        Value::try_from(DataItem::from_diagnostic("simple(16)").unwrap()),
+        Err(Error::ValueConversion(Conversion::UnsupportedItem {
+            kind: ItemKind::Simple
+        }))
+    );
+    assert_eq!(
+        Value::try_from(DataItem::from_diagnostic("{null: 1, undefined: 2}").unwrap()),
+        Err(Error::ValueConversion(Conversion::DuplicateKey))
+    );
+}
+
+#[cfg(feature = "json-arbitrary-precision")]
+#[test]
+fn json_arbitrary_precision() {
+    use serde_json::Value;
+
+    let json = "[18446744073709551615, -18446744073709551616, 18446744073709551616, \
+                -18446744073709551617, 340282366920938463463374607431768211456, 1.5]";
+    let value = serde_json::from_str::<Value>(json).unwrap();
+    let data_item = DataItem::from_diagnostic(
+        "[18446744073709551615, -18446744073709551616, 2(h'010000000000000000'), \
+         3(h'010000000000000000'), 2(h'0100000000000000000000000000000000'), 1.5]",
+    )
+    .unwrap();
+    assert_eq!(DataItem::from(value.clone()), data_item);
+    assert_eq!(Value::try_from(data_item.clone()).unwrap(), value);
+    #[cfg(feature = "serde")]
+    assert_eq!(serde_json::from_str::<DataItem>(json).unwrap(), data_item);
+    assert_eq!(
+        Value::try_from(DataItem::from_diagnostic("[2(h''), 3(h'00ff')]").unwrap()).unwrap(),
+        serde_json::from_str::<Value>("[0, -256]").unwrap()
+    );
+}
+
+#[test]
+fn merge_patch() {
+    use crate::merge_options::MergeOptions;
+
+    // test cases from RFC 7386 appendix A
+    for (target, patch, result) in [
+        (r#"{"a": "b"}"#, r#"{"a": "c"}"#, r#"{"a": "c"}"#),
+        (r#"{"a": "b"}"#, r#"{"b": "c"}"#, r#"{"a": "b", "b": "c"}"#),
+        (r#"{"a": "b"}"#, r#"{"a": null}"#, "{}"),
+        (r#"{"a": "b", "b": "c"}"#, r#"{"a": null}"#, r#"{"b": "c"}"#),
+        (r#"{"a": ["b"]}"#, r#"{"a": "c"}"#, r#"{"a": "c"}"#),
+        (r#"{"a": "c"}"#, r#"{"a": ["b"]}"#, r#"{"a": ["b"]}"#),
+        (
+            r#"{"a": {"b": "c"}}"#,
+            r#"{"a": {"b": "d", "c": null}}"#,
+            r#"{"a": {"b": "d"}}"#,
+        ),
+        (r#"{"a": [{"b": "c"}]}"#, r#"{"a": [1]}"#, r#"{"a": [1]}"#),
+        (r#"["a", "b"]"#, r#"["c", "d"]"#, r#"["c", "d"]"#),
+        (r#"{"a": "b"}"#, r#"["c"]"#, r#"["c"]"#),
+        (r#"{"a": "foo"}"#, "null", "null"),
+        (r#"{"a": "foo"}"#, r#""bar""#, r#""bar""#),
+        (r#"{"e": null}"#, r#"{"a": 1}"#, r#"{"e": null, "a": 1}"#),
+        ("[1, 2]", r#"{"a": "b", "c": null}"#, r#"{"a": "b"}"#),
+        (
+            "{}",
+            r#"{"a": {"bb": {"ccc": null}}}"#,
+            r#"{"a": {"bb": {}}}"#,
+        ),
+    ] {
+        let mut value = DataItem::from_diagnostic(target).unwrap();
+        value.merge(&DataItem::from_diagnostic(patch).unwrap());
+        assert_eq!(value, DataItem::from_diagnostic(result).unwrap());
+    }
+
+    let mut value = DataItem::from_diagnostic("{1: [_ 1], h'02': {3: [4]}}").unwrap();
+    let mut options = MergeOptions::default();
+    options.set_concat_array(true);
+    value.merge_with_options(
+        &DataItem::from_diagnostic("{1: [2, 3], h'02': {3: [5], 6: [7]}}").unwrap(),
+        &options,
+    );
+    assert_eq!(
+        format!("{value:?}"),
+        "{1: [_ 1, 2, 3], h'02': {3: [4, 5], 6: [7]}}"
+    );
+}
+
+#[test]
+fn diff_and_patch() {
+    use crate::patch::{Operation, Patch, diff};
+
+    for (source, target) in [
+        ("1", "1"),
+        ("1", "\"a\""),
+        ("[1, 2, 3]", "[1, 5]"),
+        ("[1]", "[1, [2], {3: 4}]"),
+        ("[_ 1, 2]", "[1, 2]"),
+        ("{1: {2: [3, 4]}, 5: 6}", "{1: {2: [3, 7, 8]}, 9: 10}"),
+        ("{\"a\": 1(2)}", "{\"a\": 1(3), h'00': null}"),
+        ("[{1: 2}, 3]", "{1: 2}"),
+    ] {
+        let source = DataItem::from_diagnostic(source).unwrap();
+        let target = DataItem::from_diagnostic(target).unwrap();
+        let patch = diff(&source, &target);
+        assert_eq!(patch.is_empty(), source == target);
+        let decoded =
+            Patch::try_from(DataItem::decode(&DataItem::from(patch.clone()).encode()).unwrap())
+                .unwrap();
+        assert_eq!(decoded, patch);
+        let mut value = source.clone();
+        patch.apply(&mut value).unwrap();
+        assert_eq!(value.encode(), target.encode());
+    }
+
+    let patch = diff(
+        &DataItem::from_diagnostic("{1: [2, 3], 4: 5}").unwrap(),
+        &DataItem::from_diagnostic("{1: [2], 6: 7}").unwrap(),
+    );
+    assert_eq!(
+        format!("{:?}", DataItem::from(patch)),
+        "[[1, [1, 1]], [1, [4]], [0, [6], 7]]"
+    );
+
+    let mut value = DataItem::from_diagnostic("{1: [2]}").unwrap();
+    for operation in [
+        Operation::Remove { path: vec![] },
+        Operation::Remove {
+            path: vec![DataItem::from(2)],
+        },
+        Operation::Replace {
+            path: vec![DataItem::from(1), DataItem::from(1)],
+            value: DataItem::Null,
+        },
+        Operation::Add {
+            path: vec![DataItem::from(1), DataItem::from(2)],
+            value: DataItem::Null,
+        },
+        Operation::Add {
+            path: vec![DataItem::from(1), DataItem::from(0), DataItem::from(0)],
+            value: DataItem::Null,
+        },
+    ] {
+        assert!(matches!(
+            Patch::from(vec![operation]).apply(&mut value),
+            Err(Error::InvalidPatch(_))
+        ));
+    }
+    assert_eq!(
+        Patch::from(vec![Operation::Remove {
+            path: vec![DataItem::from(1), DataItem::from(5)],
+        }])
+        .apply(&mut value),
+        Err(Error::InvalidPatch(PatchFailure::PathNotFound {
+            segment: 1
+        }))
+    );
+    for invalid in [
+        "{}",
+        "[1]",
+        "[[1]]",
+        "[[0, []]]",
+        "[[1, 2]]",
+        "[[3, [], 1]]",
+    ] {
+        assert!(matches!(
+            Patch::try_from(DataItem::from_diagnostic(invalid).unwrap()),
+            Err(Error::InvalidPatch(_))
+        ));
+    }
+    assert_eq!(
+        Patch::try_from(DataItem::from_diagnostic("[[0, [], 1], [1, 2]]").unwrap()),
+        Err(Error::InvalidPatch(PatchFailure::InvalidOperation {
+            index: 1
+        }))
+    );
+}
+
+#[test]
+fn try_from_data_item() {
+    assert_eq!(u8::try_from(DataItem::from(255)).unwrap(), 255);
+    assert_eq!(i64::try_from(&DataItem::from(-21)).unwrap(), -21);
+    assert!(matches!(
+        u8::try_from(DataItem::from(256)),
+        Err(Error::FromInt(_))
+    ));
+    assert!(matches!(
+        u64::try_from(DataItem::from(-1)),
+        Err(Error::FromInt(_))
+    ));
+    assert_eq!(
+        f64::try_from(DataItem::from(1.5)).unwrap().to_bits(),
+        1.5f64.to_bits()
+    );
+    assert!(bool::try_from(&DataItem::from(true)).unwrap());
+    assert_eq!(String::try_from(DataItem::from("abc")).unwrap(), "abc");
+    assert_eq!(
+        String::try_from(DataItem::from_diagnostic(r#"(_ "a", "bc")"#).unwrap()).unwrap(),
+        "abc"
+    );
+    assert_eq!(
+        Vec::<u8>::try_from(DataItem::from_diagnostic("h'0102'").unwrap()).unwrap(),
+        vec![1, 2]
+    );
+    assert_eq!(
+        Vec::<u8>::try_from(DataItem::from_diagnostic("[_ 1, 2]").unwrap()).unwrap(),
+        vec![1, 2]
+    );
+    assert_eq!(
+        Vec::<Vec<String>>::try_from(&DataItem::from_diagnostic("[[\"a\"], []]").unwrap()).unwrap(),
+        vec![vec!["a".to_string()], vec![]]
+    );
+    let map = IndexMap::<String, i32>::try_from(
+        DataItem::from_diagnostic("{\"b\": -1, \"a\": 2}").unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        map.into_iter().collect::<Vec<_>>(),
+        vec![("b".to_string(), -1), ("a".to_string(), 2)]
+    );
+
+    let error = u8::try_from(DataItem::from("1")).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "type mismatch : expected integer but found text string"
+    );
+    assert_eq!(
+        error,
+        Error::TypeMismatch {
+            expected: ItemKind::Integer,
+            found: ItemKind::Text
+        }
+    );
+    for (value, expected, found) in [
+        (
+            f64::try_from(DataItem::from(1)).map(|_| ()),
+            ItemKind::Floating,
+            ItemKind::Unsigned,
+        ),
+        (
+            bool::try_from(DataItem::Null).map(|_| ()),
+            ItemKind::Boolean,
+            ItemKind::Null,
+        ),
+        (
+            String::try_from(DataItem::from(b"a".as_slice())).map(|_| ()),
+            ItemKind::Text,
+            ItemKind::Byte,
+        ),
+        (
+            Vec::<u8>::try_from(DataItem::from("a")).map(|_| ()),
+            ItemKind::Array,
+            ItemKind::Text,
+        ),
+        (
+            IndexMap::<u8, u8>::try_from(DataItem::from(vec![1])).map(|_| ()),
+            ItemKind::Map,
+            ItemKind::Array,
+        ),
+    ] {
+        assert_eq!(value, Err(Error::TypeMismatch { expected, found }));
+    }
+    assert!(matches!(
+        Vec::<u8>::try_from(DataItem::from_diagnostic("[1, \"a\"]").unwrap()),
+        Err(Error::TypeMismatch { .. })
+    ));
+}
+
+#[test]
+fn mutable_accessor() {
+    let mut value = DataItem::from_diagnostic(
+        "{\"a\": [1], \"b\": (_ \"c\"), \"d\": h'01', \"e\": 1({\"f\": 2})}",
+    )
+    .unwrap();
+    value
+        .get_mut(DataItem::from("a"))
+        .and_then(DataItem::as_array_mut)
+        .unwrap()
+        .push_content(2);
+    value
+        .get_mut(DataItem::from("b"))
+        .and_then(DataItem::as_text_mut)
+        .unwrap()
+        .push_string("d");
+    value
+        .get_mut(DataItem::from("d"))
+        .and_then(DataItem::as_byte_mut)
+        .unwrap()
+        .push_bytes(&[2]);
+    let (number, content) = value
+        .get_mut(DataItem::from("e"))
+        .and_then(DataItem::as_tag_mut)
+        .unwrap();
+    assert_eq!(number, 1);
+    content.as_map_mut().unwrap().insert_content("g", 3);
+    value
+        .as_map_mut()
+        .unwrap()
+        .map_mut()
+        .shift_remove(&DataItem::from("d"));
+    assert_eq!(
+        format!("{value:?}"),
+        "{\"a\": [1, 2], \"b\": (_ \"c\", \"d\"), \"e\": 1({\"f\": 2, \"g\": 3})}"
+    );
+    assert!(value.as_array_mut().is_none());
+    assert!(value.as_text_mut().is_none());
+    assert!(value.as_byte_mut().is_none());
+    assert!(value.as_tag_mut().is_none());
+    assert!(DataItem::Null.as_map_mut().is_none());
+}
+
+#[test]
+fn take_and_replace() {
+    let mut value = DataItem::from_diagnostic("[{\"a\": [1, 2]}, 3]").unwrap();
+    let taken = value
+        .get_mut(0)
+        .and_then(|item| item.get_mut(DataItem::from("a")))
+        .unwrap()
+        .take();
+    assert_eq!(taken, DataItem::from(vec![1, 2]));
+    let old = value.get_mut(1).unwrap().replace(taken);
+    assert_eq!(old, DataItem::from(3));
+    assert_eq!(
+        value,
+        DataItem::from_diagnostic("[{\"a\": null}, [1, 2]]").unwrap()
+    );
+    assert!(value.take().is_array());
+    assert!(value.is_null());
+}
+
+#[test]
+fn from_std_types() {
+    use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+    assert_eq!(DataItem::from(Some(1)), DataItem::from(1));
+    assert_eq!(DataItem::from(None::<u8>), DataItem::Null);
+    assert_eq!(
+        DataItem::from([Some("a"), None]),
+        DataItem::from_diagnostic("[\"a\", null]").unwrap()
+    );
+    assert_eq!(
+        DataItem::from((1, "a", [true], (-1.5,), (None::<u8>, 2, 3))),
+        DataItem::from_diagnostic("[1, \"a\", [true], [-1.5], [null, 2, 3]]").unwrap()
+    );
+    assert_eq!(
+        DataItem::from(BTreeMap::from([("b", 1), ("a", 2)])),
+        DataItem::from_diagnostic("{\"a\": 2, \"b\": 1}").unwrap()
+    );
+    assert_eq!(
+        DataItem::from(HashMap::from([(1, vec![2]), (3, vec![])])),
+        DataItem::from_diagnostic("{3: [], 1: [2]}").unwrap()
+    );
+    assert_eq!(
+        DataItem::from(BTreeSet::from([3, 1, 2])),
+        DataItem::from(vec![1, 2, 3])
+    );
+    assert_eq!(DataItem::from(HashSet::from([1])), DataItem::from(vec![1]));
+    assert_eq!(
+        DataItem::from(TagContent::set(HashSet::from(["a"]))),
+        DataItem::from_diagnostic("258([\"a\"])").unwrap()
+    );
+}
+
+#[test]
+fn text_and_bytes_constructor() {
+    assert_eq!(DataItem::from('a'), DataItem::from("a"));
+    assert_eq!(DataItem::from('ü').encode(), vec![0x62, 0xc3, 0xbc]);
+    assert_eq!(DataItem::text(String::from("a")), DataItem::from("a"));
+    assert_eq!(DataItem::text('b'), DataItem::from("b"));
+    assert_eq!(
+        DataItem::bytes(b"ab".as_slice()),
+        DataItem::from_diagnostic("h'6162'").unwrap()
+    );
+    assert_eq!(
+        DataItem::bytes(vec![1]),
+        DataItem::from_diagnostic("h'01'").unwrap()
+    );
+}
+
+#[test]
+fn array_and_map_builder() {
+    use crate::builder::{ArrayBuilder, MapBuilder};
+
+    let value = DataItem::map_builder()
+        .insert("a", 1)
+        .insert_tagged(2, 1, 0)
+        .extend([("b", 3), ("a", 4)])
+        .insert(
+            "c",
+            DataItem::array_builder()
+                .indefinite(true)
+                .push(Some("x"))
+                .push_tagged(24, b"\x01".as_slice())
+                .extend(vec![5, 6])
+                .build(),
+        )
+        .build();
+    assert_eq!(
+        format!("{value:?}"),
+        "{\"a\": 4, 2: 1(0), \"b\": 3, \"c\": [_ \"x\", 24(h'01'), 5, 6]}"
+    );
+    assert_eq!(
+        MapBuilder::new().indefinite(true).build().encode(),
+        vec![0xbf, 0xff]
+    );
+    assert_eq!(
+        ArrayBuilder::new().build(),
+        DataItem::from(Vec::<u8>::new())
+    );
+}
+
+#[test]
+fn integer_constructor() {
+    for (number, diagnostic) in [
+        (0, "0"),
+        (-1, "-1"),
+        (i128::from(u64::MAX), "18446744073709551615"),
+        (-i128::from(u64::MAX) - 1, "-18446744073709551616"),
+        (i128::from(u64::MAX) + 1, "2(h'010000000000000000')"),
+        (-i128::from(u64::MAX) - 2, "3(h'010000000000000000')"),
+    ] {
+        let value = DataItem::integer(number);
+        assert_eq!(value, DataItem::from_diagnostic(diagnostic).unwrap());
+        assert_eq!(value.as_i128(), Some(number));
+    }
+    assert_eq!(DataItem::integer(7u8), DataItem::Unsigned(7));
+    assert_eq!(DataItem::integer(-7i64), DataItem::Signed(6));
+    assert_eq!(DataItem::integer(u128::MAX).as_u128(), Some(u128::MAX));
+    assert_eq!(DataItem::integer(u128::MAX).as_integer(), None);
+    assert_eq!(
+        DataItem::integer(i128::from(u64::MAX) + 1).as_integer(),
+        Some(i128::from(u64::MAX) + 1)
+    );
+
+    let smallest = DataItem::decode(&hex::decode("3bffffffffffffffff").unwrap()).unwrap();
+    assert_eq!(smallest, DataItem::Signed(u64::MAX));
+    assert_eq!(format!("{smallest:?}"), "-18446744073709551616");
+    assert_eq!(
+        smallest.to_diagnostic(&DiagnosticOptions::default()),
+        "-18446744073709551616"
+    );
+    assert_eq!(smallest.to_diagnostic_pretty(4), "-18446744073709551616");
+    assert_eq!(smallest.as_integer(), Some(-1 - i128::from(u64::MAX)));
+}
+
+#[test]
+fn checked_narrow_accessor() {
+    let value = DataItem::from(300);
+    assert_eq!(value.as_u8(), None);
+    assert_eq!(value.as_u16(), Some(300));
+    assert_eq!(value.as_u32(), Some(300));
+    assert_eq!(value.as_i32(), Some(300));
+    let value = DataItem::from(-300);
+    assert_eq!(value.as_u16(), None);
+    assert_eq!(value.as_i32(), Some(-300));
+    assert_eq!(DataItem::from("1").as_u8(), None);
+    assert_eq!(DataItem::from(1.0).as_u32(), None);
+    assert_eq!(
+        DataItem::from(i64::MIN).as_number(),
+        Some(i128::from(i64::MIN))
+    );
+
+    assert_eq!(DataItem::from(f64::INFINITY).as_f32(), Some(f32::INFINITY));
+    assert_eq!(
+        DataItem::from(-0.0).as_f32().map(f32::to_bits),
+        Some((-0.0f32).to_bits())
+    );
+    assert_eq!(DataItem::from(1e300).as_f32(), None);
+    assert_eq!(DataItem::from(1).as_f32(), None);
+}
+
+#[test]
+fn get_with_str() {
+    let mut value =
+        DataItem::from_diagnostic("{\"a\": 1, (_ \"b\", \"c\"): 2, h'64': 3, \"\": 4}").unwrap();
+    assert_eq!(value.get("a"), Some(&DataItem::from(1)));
+    assert_eq!(value.get("bc"), Some(&DataItem::from(2)));
+    assert_eq!(value.get("b"), None);
+    assert_eq!(value.get("bcd"), None);
+    assert_eq!(value.get("d"), None);
+    assert_eq!(value[""], DataItem::from(4));
+    *value.get_mut("bc").unwrap() = DataItem::from(5);
+    value["a"] = DataItem::Null;
+    assert_eq!(
+        format!("{value:?}"),
+        "{\"a\": null, (_ \"b\", \"c\"): 5, h'64': 3, \"\": 4}"
+    );
+    assert_eq!(DataItem::from(vec!["a"]).get("a"), None);
+}
+
+#[test]
+fn get_from_back() {
+    let mut value = DataItem::from_diagnostic("[_ 1, \"payload\", h'abcd']").unwrap();
+    assert_eq!(
+        value.get_back(0),
+        Some(&DataItem::from_diagnostic("h'abcd'").unwrap())
+    );
+    assert_eq!(value.get_back(1), value.get(1));
+    assert_eq!(value.get_back(3), None);
+    assert_eq!(value.get_back(usize::MAX), None);
+    *value.get_back_mut(2).unwrap() = DataItem::from(0);
+    assert_eq!(value.get(0), Some(&DataItem::from(0)));
+    assert!(value.get_back_mut(3).is_none());
+    assert!(DataItem::from(Vec::<u8>::new()).get_back(0).is_none());
+    assert!(DataItem::from(vec![("a", 1)]).get_back(0).is_none());
+}
+
+#[test]
+fn get_with_path() {
+    use crate::index::PathSegment;
+
+    let mut value =
+        DataItem::from_diagnostic("{\"a\": 1(2([{h'01': 3}, 4])), 5: {\"b\": [6]}}").unwrap();
+    assert_eq!(value.get_path(&[]), Ok(&value.clone()));
+    assert_eq!(
+        value.get_path(&[
+            PathSegment::from("a"),
+            PathSegment::from(0),
+            PathSegment::from(DataItem::from_diagnostic("h'01'").unwrap()),
+        ]),
+        Ok(&DataItem::from(3))
+    );
+    assert_eq!(
+        value.get_path(&[PathSegment::Key(DataItem::from(5)), "b".into(), 0.into()]),
+        Ok(&DataItem::from(6))
+    );
+    for (path, segment) in [
+        (vec![PathSegment::from("c")], 0),
+        (vec!["a".into(), 2.into()], 1),
+        (vec!["a".into(), 1.into(), 0.into()], 2),
+        (vec![5.into()], 0),
+    ] {
+        assert_eq!(value.get_path(&path), Err(Error::PathNotFound { segment }));
+        assert_eq!(
+            value.get_path_mut(&path),
+            Err(Error::PathNotFound { segment })
+        );
+    }
+    *value.get_path_mut(&["a".into(), 1.into()]).unwrap() = DataItem::Null;
+    assert_eq!(
+        format!("{value:?}"),
+        "{\"a\": 1(2([{h'01': 3}, null])), 5: {\"b\": [6]}}"
+    );
+    assert_eq!(
+        Error::PathNotFound { segment: 1 }.to_string(),
+        "path segment 1 not found"
+    );
+}
+
+#[test]
+fn get_many_mutable() {
+    let mut value = DataItem::from_diagnostic("[1, [2], {\"a\": 3}]").unwrap();
+    let [first, second, third] = value.get_many_mut([2.into(), 0.into(), 1.into()]).unwrap();
+    first
+        .as_map_mut()
+        .unwrap()
+        .insert_content("b", second.clone());
+    third.as_array_mut().unwrap().push_content(second.take());
+    assert_eq!(
+        value,
+        DataItem::from_diagnostic("[null, [2, 1], {\"a\": 3, \"b\": 1}]").unwrap()
+    );
+    assert!(value.get_many_mut([0.into(), 3.into()]).is_none());
+    assert!(value.get_many_mut([1.into(), 1.into()]).is_none());
+    assert!(value.get_many_mut(["a".into()]).is_none());
+    assert_eq!(
+        value.get_many_mut::<0>([]).map(|items| items.len()),
+        Some(0)
+    );
+
+    let mut value = DataItem::from_diagnostic("{1: \"x\", h'02': \"y\"}").unwrap();
+    let [one, two] = value
+        .get_many_mut([
+            DataItem::from(1).into(),
+            DataItem::from_diagnostic("h'02'").unwrap().into(),
+        ])
+        .unwrap();
+    std::mem::swap(one, two);
+    assert_eq!(
+        value,
+        DataItem::from_diagnostic("{1: \"y\", h'02': \"x\"}").unwrap()
+    );
+    assert!(value.get_many_mut([0.into()]).is_none());
+    assert!(DataItem::Null.get_many_mut::<0>([]).is_none());
+}
+
+#[test]
+fn get_or_insert_value() {
+    let mut value = DataItem::from_diagnostic("{}").unwrap();
+    for (name, port) in [("http", 80), ("https", 443)] {
+        value
+            .get_or_insert_with("server", || DataItem::from_diagnostic("{}").unwrap())
+            .and_then(|server| server.get_or_insert_with(name, || port))
+            .unwrap();
+    }
+    let mut called = false;
+    value
+        .get_or_insert_with("server", || {
+            called = true;
+            DataItem::Null
+        })
+        .unwrap();
+    assert!(!called);
+    assert_eq!(
+        value,
+        DataItem::from_diagnostic("{\"server\": {\"http\": 80, \"https\": 443}}").unwrap()
+    );
+    assert!(
+        DataItem::from(vec![1])
+            .get_or_insert_with(0, || 1)
+            .is_none()
+    );
+}
+
+#[test]
+fn remove_value() {
+    let mut value = DataItem::from_diagnostic("[_ 1, 2, 3, 4]").unwrap();
+    assert_eq!(value.remove(1), Some(DataItem::from(2)));
+    assert_eq!(value.remove(3), None);
+    assert_eq!(value.swap_remove(0), Some(DataItem::from(1)));
+    assert_eq!(value.swap_remove(2), None);
+    assert_eq!(format!("{value:?}"), "[_ 4, 3]");
+    assert_eq!(value.remove("a"), None);
+    assert_eq!(value.remove(DataItem::from(0)), None);
+
+    let mut value =
+        DataItem::from_diagnostic("{\"a\": 1, (_ \"b\", \"c\"): 2, 3: 4, \"d\": 5}").unwrap();
+    assert_eq!(value.remove("bc"), Some(DataItem::from(2)));
+    assert_eq!(value.remove(DataItem::from(3)), Some(DataItem::from(4)));
+    assert_eq!(value.remove("x"), None);
+    assert_eq!(value.remove(0), None);
+    assert_eq!(value.swap_remove(0), None);
+    assert_eq!(format!("{value:?}"), "{\"a\": 1, \"d\": 5}");
+}
+
+#[test]
+fn array_range() {
+    let value = DataItem::from_diagnostic("[_ 1, 2, 3, 4]").unwrap();
+    assert_eq!(value.get_range(..).map(<[DataItem]>::len), Some(4));
+    assert_eq!(value.get_range(4..), Some([].as_slice()));
+    assert_eq!(value.get_range(..=4), None);
+    assert_eq!(format!("{:?}", value.slice(1..=2).unwrap()), "[_ 2, 3]");
+    assert_eq!(value.slice(5..), None);
+    assert_eq!(DataItem::from("abc").get_range(..1), None);
+    assert_eq!(DataItem::from("abc").slice(..1), None);
+}
+
+#[test]
+fn find_all_item() {
+    use crate::index::PathSegment;
+
+    let value =
+        DataItem::from_diagnostic("[1(1363896240), {\"exp\": 1(1.5), 1: [1(0)]}, \"1\", 2(h'01')]")
+            .unwrap();
+    let found = value.find_all(|_, item| matches!(item.as_tag(), Some((1, _))));
+    assert_eq!(
+        found
+            .iter()
+            .map(|(path, _)| path.clone())
+            .collect::<Vec<_>>(),
+        vec![
+            vec![PathSegment::Index(0)],
+            vec![PathSegment::Index(1), "exp".into()],
+            vec![1.into(), DataItem::from(1).into(), 0.into()],
+        ]
+    );
+    for (path, item) in &found {
+        assert_eq!(value.get_path(path), Ok(*item));
+    }
+
+    let mut visited = vec![];
+    let found = value.find_all(|path, item| {
+        visited.push(path.len());
+        path.is_empty() || item.is_text()
+    });
+    assert_eq!(visited, vec![0, 1, 1, 1, 2, 2, 2, 3, 3, 1, 1, 1]);
+    assert_eq!(found.len(), 2);
+    assert_eq!(
+        found[1],
+        (vec![PathSegment::Index(2)], &DataItem::from("1"))
+    );
+}
+
+#[test]
+fn query_item() {
+    let value = DataItem::from_diagnostic(
+        "{\"a\": 258([{\"b\": 1}, {\"b\": 2, \"c\": 3}, 4]), \"d e\": {1: 5, \"x\": 6}, \"\\\"\": \
+         7}",
+    )
+    .unwrap();
+    for (query, expected) in [
+        (
+            "",
+            vec![
+                "{\"a\": 258([{\"b\": 1}, {\"b\": 2, \"c\": 3}, 4]), \"d e\": {1: 5, \"x\": 6}, \
+                 \"\\\"\": 7}",
+            ],
+        ),
+        ("$.a[*].b", vec!["1", "2"]),
+        (".a[1].*", vec!["2", "3"]),
+        (".a[-1]", vec!["4"]),
+        (".a[ 0 ]", vec!["{\"b\": 1}"]),
+        (".a[3]", vec![]),
+        (".a[-4]", vec![]),
+        ("[\"d e\"].*", vec!["5", "6"]),
+        ("[\"\\\"\"]", vec!["7"]),
+        (".*.x", vec!["6"]),
+        (".missing.b", vec![]),
+        (".a", vec!["258([{\"b\": 1}, {\"b\": 2, \"c\": 3}, 4])"]),
+    ] {
+        let found = value
+            .query(query)
+            .unwrap()
+            .into_iter()
+            .map(|item| format!("{item:?}"))
+            .collect::<Vec<_>>();
+        assert_eq!(found, expected, "{query}");
+    }
+    for invalid in ["a", ".", "$.a[", ".a[x]", "[\"a]", "[\"a\"", ".a]", "[]"] {
+        assert!(
+            matches!(value.query(invalid), Err(Error::InvalidQuery { .. })),
+            "{invalid}"
+        );
+    }
+    assert_eq!(
+        value.query("$.a[x]"),
+        Err(Error::InvalidQuery {
+            reason: Syntax::InvalidSelector,
+            position: 3
+        })
+    );
+}
+
+#[test]
+fn map_content_api() {
+    let mut map_content = MapContent::default();
+    assert!(map_content.is_empty());
+    assert_eq!(map_content.insert("a", 1), None);
+    assert_eq!(map_content.insert(2, "b"), None);
+    assert_eq!(map_content.insert("c", 3), None);
+    assert_eq!(map_content.insert("a", 4), Some(DataItem::from(1)));
+    assert_eq!(map_content.len(), 3);
+    assert!(map_content.contains_key(&DataItem::from(2)));
+    assert_eq!(
+        map_content.get(&DataItem::from("c")),
+        Some(&DataItem::from(3))
+    );
+    *map_content.get_mut(&DataItem::from("c")).unwrap() = DataItem::Null;
+    assert_eq!(
+        map_content.keys().cloned().collect::<Vec<_>>(),
+        vec![DataItem::from("a"), DataItem::from(2), DataItem::from("c")]
+    );
+    assert_eq!(
+        map_content.values().cloned().collect::<Vec<_>>(),
+        vec![DataItem::from(4), DataItem::from("b"), DataItem::Null]
+    );
+    assert_eq!(map_content.iter().len(), 3);
+
+    let mut swapped = map_content.clone();
+    assert_eq!(
+        swapped.remove(&DataItem::from("a")),
+        Some(DataItem::from(4))
+    );
+    assert_eq!(swapped.remove(&DataItem::from("a")), None);
+    assert_eq!(
+        format!("{:?}", DataItem::from(swapped)),
+        "{\"c\": null, 2: \"b\"}"
+    );
+    assert_eq!(
+        map_content.shift_remove(&DataItem::from("a")),
+        Some(DataItem::from(4))
+    );
+    assert_eq!(
+        format!("{:?}", DataItem::from(map_content)),
+        "{2: \"b\", \"c\": null}"
+    );
+}
+
+#[test]
+fn map_content_retain_sort() {
+    let Ok(DataItem::Map(mut map_content)) =
+        DataItem::from_diagnostic(r#"{"b": 1, "a": 2, "secret": 3, 1: 4}"#)
+    else {
+        panic!("diagnostic is not a map");
+    };
+    map_content.retain(|key, value| {
+        *value = DataItem::from(value.as_unsigned().unwrap() * 10);
+        key != &DataItem::from("secret")
+    });
+    assert_eq!(
+        format!("{:?}", DataItem::from(map_content.clone())),
+        "{\"b\": 10, \"a\": 20, 1: 40}"
+    );
+    map_content.sort_by(|key1, _, key2, _| key1.encode().cmp(&key2.encode()));
+    assert_eq!(
+        format!("{:?}", DataItem::from(map_content.clone())),
+        "{1: 40, \"a\": 20, \"b\": 10}"
+    );
+    map_content.sort_by(|_, value1, _, value2| value1.as_unsigned().cmp(&value2.as_unsigned()));
+    assert_eq!(
+        format!("{:?}", DataItem::from(map_content)),
+        "{\"b\": 10, \"a\": 20, 1: 40}"
+    );
+
+    let Ok(DataItem::Map(mut map_content)) =
+        DataItem::from_diagnostic(r#"{"ab": 1, (_ "a", "b"): 2, "c": 3}"#)
+    else {
+        panic!("diagnostic is not a map");
+    };
+    assert_eq!(map_content.len(), 3);
+    map_content.dedup_keys();
+    assert_eq!(
+        format!("{:?}", DataItem::from(map_content)),
+        "{\"ab\": 1, \"c\": 3}"
+    );
+}
+
+#[test]
+fn content_capacity() {
+    let mut byte_content = ByteContent::with_capacity(4);
+    assert!(byte_content.capacity() >= 4);
+    byte_content.push_bytes(&[1, 2]).reserve(10);
+    assert!(byte_content.capacity() >= 11);
+    byte_content.shrink_to_fit();
+    assert_eq!(byte_content.capacity(), 1);
+    assert_eq!(
+        DataItem::Byte(byte_content).encode(),
+        vec![0x42, 0x01, 0x02]
+    );
+
+    let mut array_content = ArrayContent::with_capacity(8);
+    assert!(array_content.capacity() >= 8);
+    array_content.push_content(1).push_content("a").reserve(20);
+    assert!(array_content.capacity() >= 22);
+    array_content.shrink_to_fit();
+    assert_eq!(array_content.capacity(), 2);
+    assert_eq!(
+        format!("{:?}", DataItem::Array(array_content)),
+        "[1, \"a\"]"
+    );
+
+    let mut map_content = MapContent::with_capacity(8);
+    assert!(map_content.capacity() >= 8);
+    map_content.insert("a", 1);
+    map_content.reserve(20).shrink_to_fit();
+    assert!(map_content.capacity() >= 1);
+    assert_eq!(format!("{:?}", DataItem::Map(map_content)), "{\"a\": 1}");
+}
+
+#[test]
+fn map_content_str_key() {
+    let Ok(DataItem::Map(mut map_content)) =
+        DataItem::from_diagnostic(r#"{"kid": 1, (_ "al", "g"): -7, 1: 2}"#)
+    else {
+        panic!("diagnostic is not a map");
+    };
+    assert_eq!(map_content.get_str("kid"), Some(&DataItem::from(1)));
+    assert_eq!(map_content.get_str("alg"), Some(&DataItem::from(-7)));
+    assert_eq!(map_content.get_str("al"), None);
+    assert_eq!(map_content.get_str("1"), None);
+    assert_eq!(map_content.insert_str("alg", -8), Some(DataItem::from(-7)));
+    assert_eq!(map_content.insert_str("x5t", "a"), None);
+    assert_eq!(map_content.len(), 4);
+    assert_eq!(
+        format!("{:?}", DataItem::Map(map_content)),
+        "{\"kid\": 1, (_ \"al\", \"g\"): -8, 1: 2, \"x5t\": \"a\"}"
+    );
+
+    let long_key = "k".repeat(150);
+    let mut value = DataItem::from_diagnostic(&format!(
+        r#"{{(_ "{}", "{}"): 1, "b": 2}}"#,
+        &long_key[..70],
+        &long_key[70..]
+    ))
+    .unwrap();
+    assert_eq!(value.get(long_key.as_str()), Some(&DataItem::from(1)));
+    *value.get_mut("b").unwrap() = DataItem::from(3);
+    assert_eq!(value.remove(long_key.as_str()), Some(DataItem::from(1)));
+    assert_eq!(value.remove(long_key.as_str()), None);
+    assert_eq!(value, DataItem::from(vec![("b", 3)]));
+}
+
+#[test]
+fn decode_preserve_duplicate_keys() {
+    let bytes = [0xa3, 0x61, 0x61, 0x01, 0x61, 0x61, 0x02, 0x61, 0x61, 0x03];
+    assert_eq!(
+        DataItem::decode(&bytes),
+        Err(Error::NotWellFormed(Malformed::DuplicateKey { index: 1 }))
+    );
+    assert_eq!(
+        DataItem::decode(&bytes).unwrap_err().to_string(),
+        "not well formed data : map key of entry 1 is repeated multiple times"
+    );
+    let mut options = DecodeOptions::default();
+    options.set_preserve_duplicate_keys(true);
+    assert!(options.preserve_duplicate_keys());
+    let data_item = DataItem::decode_with_options(&bytes, &options).unwrap();
+    let DataItem::Map(map_content) = &data_item else {
+        panic!("decoded item is not a map");
+    };
+    assert_eq!(map_content.len(), 1);
+    assert_eq!(
+        map_content.duplicates(),
+        &[
+            (1, DataItem::from("a"), DataItem::from(2)),
+            (2, DataItem::from("a"), DataItem::from(3))
+        ]
+    );
+    assert_eq!(format!("{data_item:?}"), "{\"a\": 1, \"a\": 2, \"a\": 3}");
+    assert_eq!(data_item.encode(), bytes);
+    assert_eq!(
+        data_item.encode_deterministic(&DeterministicMode::Core),
+        vec![0xa1, 0x61, 0x61, 0x01]
+    );
+
+    let indefinite_bytes = [0xbf, 0x01, 0x02, 0x01, 0x03, 0xff];
+    assert!(DataItem::decode(&indefinite_bytes).is_err());
+    let data_item = DataItem::decode_with_options(&indefinite_bytes, &options).unwrap();
+    let DataItem::Map(map_content) = &data_item else {
+        panic!("decoded item is not a map");
+    };
+    assert_eq!(
+        map_content.duplicates(),
+        &[(1, DataItem::from(1), DataItem::from(3))]
+    );
+    assert_eq!(data_item.encode(), indefinite_bytes);
+
+    let interleaved_bytes = [0xa3, 0x01, 0x02, 0x02, 0x00, 0x01, 0x03];
+    let mut data_item = DataItem::decode_with_options(&interleaved_bytes, &options).unwrap();
+    assert_eq!(data_item.encode(), interleaved_bytes);
+    assert_eq!(
+        data_item.to_diagnostic(&DiagnosticOptions::default()),
+        "{1: 2, 2: 0, 1: 3}"
+    );
+    assert_eq!(
+        data_item.to_diagnostic_pretty(2),
+        "{\n  1: 2,\n  2: 0,\n  1: 3\n}"
+    );
+    data_item.remove(DataItem::from(2));
+    assert_eq!(format!("{data_item:?}"), "{1: 2, 1: 3}");
+    let DataItem::Map(map_content) = &mut data_item else {
+        panic!("decoded item is not a map");
+    };
+    map_content.set_duplicates(vec![
+        (5, DataItem::from(1), DataItem::from(5)),
+        (0, DataItem::from(1), DataItem::from(4)),
+    ]);
+    assert_eq!(format!("{data_item:?}"), "{1: 4, 1: 2, 1: 5}");
+    options.set_max_length(1);
+    assert!(matches!(
+        DataItem::decode_with_options(&indefinite_bytes, &options),
+        Err(Error::LimitExceeded(_))
+    ));
+}
+
+#[test]
+fn map_content_iterator_traits() {
+    let mut map_content = vec![("a", 1), ("b", 2)].into_iter().collect::<MapContent>();
+    map_content.extend(vec![(DataItem::from(3), DataItem::from("c"))]);
+    map_content.extend([("a", 10)]);
+    assert_eq!(
+        format!("{:?}", DataItem::Map(map_content.clone())),
+        "{\"a\": 10, \"b\": 2, 3: \"c\"}"
+    );
+    for (key, value) in &mut map_content {
+        if key.is_text() {
+            *value = DataItem::Null;
+        }
+    }
+    let mut keys = vec![];
+    for (key, _) in &map_content {
+        keys.push(key.clone());
+    }
+    assert_eq!(
+        keys,
+        vec![DataItem::from("a"), DataItem::from("b"), DataItem::from(3)]
+    );
+    let entries = map_content.into_iter().collect::<Vec<_>>();
+    assert_eq!(
+        entries,
+        vec![
+            (DataItem::from("a"), DataItem::Null),
+            (DataItem::from("b"), DataItem::Null),
+            (DataItem::from(3), DataItem::from("c"))
+        ]
+    );
+}
+
+#[test]
+fn map_content_sort_keys() {
+    let Ok(DataItem::Map(mut map_content)) =
+        DataItem::from_diagnostic(r#"{"b": 1, (_ "a"): 2, 100: 3, -1: 4, 10: 5}"#)
+    else {
+        panic!("diagnostic is not a map");
+    };
+    map_content.sort_keys(&DeterministicMode::Core);
+    assert_eq!(
+        format!("{:?}", DataItem::Map(map_content.clone())),
+        "{10: 5, 100: 3, -1: 4, (_ \"a\"): 2, \"b\": 1}"
+    );
+    map_content.sort_keys(&DeterministicMode::LengthFirst);
+    assert_eq!(
+        format!("{:?}", DataItem::Map(map_content.clone())),
+        "{10: 5, -1: 4, 100: 3, (_ \"a\"): 2, \"b\": 1}"
+    );
+    assert!(!DataItem::Map(map_content).is_deterministic(&DeterministicMode::LengthFirst));
+}
+
+#[test]
+fn get_ignoring_tags() {
+    let Ok(DataItem::Map(map_content)) =
+        DataItem::from_diagnostic(r#"{42(1("id")): 1, 2: 24(h'01'), 3("x"): [3]}"#)
+    else {
+        panic!("diagnostic is not a map");
+    };
+    assert_eq!(
+        map_content.get_ignoring_tags(&DataItem::from("id")),
+        Some(&DataItem::from(1))
+    );
+    assert_eq!(
+        map_content.get_ignoring_tags(&DataItem::from_diagnostic("9(2)").unwrap()),
+        Some(&DataItem::from_diagnostic("24(h'01')").unwrap())
+    );
+    assert_eq!(map_content.get_ignoring_tags(&DataItem::from("y")), None);
+
+    let data_item = DataItem::from_diagnostic(r#"5(6(7({3("x"): [3]})))"#).unwrap();
+    assert_eq!(data_item.get("x"), None);
+    let array_item = data_item.get_ignoring_tags("x").unwrap();
+    assert_eq!(array_item.get_ignoring_tags(0), Some(&DataItem::from(3)));
+    assert_eq!(array_item.get_ignoring_tags(1), None);
+    assert_eq!(DataItem::from(1).get_ignoring_tags("x"), None);
+}
+
+#[test]
+fn map_content_sorted_index() {
+    let Ok(DataItem::Map(mut map_content)) =
+        DataItem::from_diagnostic(r#"{"b": 1, (_ "a"): 2, 100: 3, -1: 4}"#)
+    else {
+        panic!("diagnostic is not a map");
+    };
+    assert!(map_content.sorted_iter().is_none());
+    assert_eq!(map_content.sorted_position(&DataItem::from(100)), None);
+    map_content.set_sorted_index(Some(DeterministicMode::LengthFirst));
+    assert_eq!(
+        map_content.sorted_index_mode(),
+        Some(DeterministicMode::LengthFirst)
+    );
+    let sorted_keys = |map_content: &MapContent| {
+        map_content
+            .sorted_iter()
+            .unwrap()
+            .map(|(key, _)| format!("{key:?}"))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        sorted_keys(&map_content),
+        vec!["-1", "100", "(_ \"a\")", "\"b\""]
+    );
+    map_content.insert("a", 5);
+    map_content.insert(1, 6);
+    map_content.insert(100, 7);
+    assert_eq!(
+        sorted_keys(&map_content),
+        vec!["1", "-1", "100", "\"a\"", "(_ \"a\")", "\"b\""]
+    );
+    assert_eq!(map_content.sorted_position(&DataItem::from("a")), Some(3));
+    assert_eq!(map_content.sorted_position(&DataItem::from("c")), None);
+    assert_eq!(
+        map_content.get_sorted(2),
+        Some((&DataItem::from(100), &DataItem::from(7)))
+    );
+    assert_eq!(map_content.get_sorted(6), None);
+    map_content.remove(&DataItem::from(-1));
+    map_content.shift_remove(&DataItem::from("b"));
+    map_content.insert_str("c", 8);
+    map_content.retain(|key, _| key != &DataItem::from(1));
+    assert_eq!(
+        sorted_keys(&map_content),
+        vec!["100", "\"a\"", "(_ \"a\")", "\"c\""]
+    );
+    assert_eq!(
+        format!("{:?}", DataItem::Map(map_content.clone())),
+        "{(_ \"a\"): 2, 100: 7, \"a\": 5, \"c\": 8}"
+    );
+
+    let mut data_item = DataItem::Map(map_content.clone());
+    data_item.remove("c");
+    let DataItem::Map(removed_content) = &data_item else {
+        panic!("data item is not a map");
+    };
+    assert_eq!(
+        sorted_keys(removed_content),
+        vec!["100", "\"a\"", "(_ \"a\")"]
+    );
+    let encoded = data_item.encode_deterministic(&DeterministicMode::LengthFirst);
+    let deterministic = data_item
+        .clone()
+        .deterministic(&DeterministicMode::LengthFirst);
+    assert_eq!(format!("{deterministic:?}"), "{100: 7, \"a\": 5}");
+    assert_eq!(deterministic.encode(), encoded);
+    let mut plain_item = data_item.clone();
+    if let DataItem::Map(plain_content) = &mut plain_item {
+        plain_content.set_sorted_index(None);
+    }
+    assert_eq!(
+        plain_item.encode_deterministic(&DeterministicMode::LengthFirst),
+        encoded
+    );
+    assert_eq!(
+        plain_item.encode_deterministic(&DeterministicMode::Core),
+        data_item.encode_deterministic(&DeterministicMode::Core)
+    );
+
+    let mut plain_content = map_content.clone();
+    plain_content.set_sorted_index(None);
+    assert!(plain_content == map_content);
+    map_content
+        .map_mut()
+        .insert(DataItem::from(0), DataItem::from(9));
+    assert_eq!(
+        map_content.sorted_index_mode(),
+        Some(DeterministicMode::LengthFirst)
+    );
+}
+
+#[test]
+fn map_content_sorted_index_update() {
+    let Ok(DataItem::Map(mut map_content)) =
+        DataItem::from_diagnostic(r#"{(_ "a"): 2, 100: 7, "a": 5, "c": 8}"#)
+    else {
+        panic!("diagnostic is not a map");
+    };
+    map_content.set_sorted_index(Some(DeterministicMode::LengthFirst));
+    let sorted_keys = |map_content: &MapContent| {
+        map_content
+            .sorted_iter()
+            .unwrap()
+            .map(|(key, _)| format!("{key:?}"))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        sorted_keys(&map_content),
+        vec!["100", "\"a\"", "(_ \"a\")", "\"c\""]
+    );
+    map_content
+        .map_mut()
+        .insert(DataItem::from(0), DataItem::from(9));
+    assert_eq!(
+        sorted_keys(&map_content),
+        vec!["0", "100", "\"a\"", "(_ \"a\")", "\"c\""]
+    );
+
+    let mut rebuilt_content = map_content.clone();
+    for key in [-5, 24, 1000] {
+        map_content.insert(key, 0);
+    }
+    map_content.remove(&DataItem::from(0));
+    map_content.shift_remove(&DataItem::from(100));
+    map_content.insert_str("b", 1);
+    map_content.shift_remove(&DataItem::from("a"));
+    map_content.remove(&DataItem::from(1000));
+    for key in [-5, 24, 1000] {
+        rebuilt_content
+            .map_mut()
+            .insert(DataItem::from(key), DataItem::from(0));
+    }
+    rebuilt_content.map_mut().swap_remove(&DataItem::from(0));
+    rebuilt_content.map_mut().shift_remove(&DataItem::from(100));
+    rebuilt_content
+        .map_mut()
+        .insert(DataItem::from("b"), DataItem::from(1));
+    rebuilt_content.map_mut().shift_remove(&DataItem::from("a"));
+    rebuilt_content.map_mut().swap_remove(&DataItem::from(1000));
+    assert!(map_content == rebuilt_content);
+    assert_eq!(sorted_keys(&map_content), sorted_keys(&rebuilt_content));
+    assert_eq!(
+        sorted_keys(&map_content),
+        vec!["-5", "24", "(_ \"a\")", "\"b\"", "\"c\""]
+    );
+    for (position, (key, _)) in map_content.sorted_iter().unwrap().enumerate() {
+        assert_eq!(map_content.sorted_position(key), Some(position));
+    }
+}
+
+#[test]
+fn array_content_mutation() {
+    let mut array_content = ArrayContent::default();
+    assert!(array_content.is_empty());
+    array_content.push(1);
+    array_content.push("a");
+    array_content.insert(0, true);
+    array_content.insert(3, DataItem::Null);
+    assert_eq!(array_content.len(), 4);
+    assert_eq!(
+        format!("{:?}", DataItem::Array(array_content.clone())),
+        "[true, 1, \"a\", null]"
+    );
+    assert_eq!(array_content.pop(), Some(DataItem::Null));
+    assert_eq!(array_content.remove(1), Some(DataItem::from(1)));
+    assert_eq!(array_content.remove(2), None);
+    array_content.push(2);
+    array_content.truncate(2);
+    assert_eq!(
+        format!("{:?}", DataItem::Array(array_content.clone())),
+        "[true, \"a\"]"
+    );
+    array_content.clear();
+    assert!(array_content.is_empty());
+    assert_eq!(array_content.pop(), None);
+}
+
+#[test]
+fn array_content_iterator_traits() {
+    let mut array_content = (1..=3).collect::<ArrayContent>();
+    array_content.extend(vec!["a"]);
+    array_content.extend([DataItem::Null]);
+    for item in &mut array_content {
+        if let Some(number) = item.as_unsigned() {
+            *item = DataItem::from(number * 2);
+        }
+    }
+    let mut debug = vec![];
+    for item in &array_content {
+        debug.push(format!("{item:?}"));
+    }
+    assert_eq!(debug, vec!["2", "4", "6", "\"a\"", "null"]);
+    assert_eq!(array_content.iter().len(), 5);
+    let items = array_content.into_iter().collect::<Vec<_>>();
+    assert_eq!(items[3], DataItem::from("a"));
+}
+
+#[test]
+fn byte_content_borrowed_full() {
+    use std::borrow::Cow;
+
+    let content = ByteContent::from(vec![1, 2, 3]);
+    assert_eq!(content.as_slice(), Some([1, 2, 3].as_slice()));
+    assert!(matches!(content.full(), Cow::Borrowed(&[1, 2, 3])));
+    assert_eq!(ByteContent::default().as_slice(), Some([].as_slice()));
+
+    let Ok(DataItem::Byte(chunked)) = DataItem::from_diagnostic("(_ h'0102', h'03')") else {
+        panic!("diagnostic is not a byte string");
+    };
+    assert_eq!(chunked.as_slice(), None);
+    assert!(matches!(chunked.full(), Cow::Owned(bytes) if bytes == vec![1, 2, 3]));
+    let Ok(DataItem::Byte(single_chunk)) = DataItem::from_diagnostic("(_ h'0102')") else {
+        panic!("diagnostic is not a byte string");
+    };
+    assert_eq!(single_chunk.as_slice(), Some([1, 2].as_slice()));
+}
+
+#[test]
+fn byte_content_length() {
+    let mut content = ByteContent::default();
+    assert!(content.is_empty());
+    assert_eq!(content.chunk_count(), 0);
+    content.set_indefinite(true).push_chunk(vec![]);
+    assert!(content.is_empty());
+    content.push_chunk(vec![1, 2]).push_chunk(vec![3]);
+    assert!(!content.is_empty());
+    assert_eq!(content.len(), 3);
+    assert_eq!(content.chunk_count(), 3);
+    assert_eq!(
+        DataItem::Byte(content).encode(),
+        vec![0x5f, 0x40, 0x42, 0x01, 0x02, 0x41, 0x03, 0xff]
+    );
+}
+
+#[test]
+fn text_content_string_api() {
+    use std::borrow::Cow;
+
+    let content = TextContent::from("héllo");
+    assert!(matches!(content.full(), Cow::Borrowed("héllo")));
+    assert_eq!(content.len(), 6);
+    assert_eq!(content.chars().count(), 5);
+    assert!(TextContent::default().is_empty());
+    assert_eq!(TextContent::default().as_str(), Some(""));
+
+    let mut chunked = TextContent::default();
+    chunked
+        .set_indefinite(true)
+        .push_str("ab")
+        .push_str(String::from("cd"));
+    assert_eq!(chunked.as_str(), None);
+    assert!(matches!(chunked.full(), Cow::Owned(ref text) if text == "abcd"));
+    assert!(chunked.contains("bc"));
+    assert!(!chunked.contains("ac"));
+    assert_eq!(chunked.chars().collect::<String>(), "abcd");
+    assert_eq!(chunked.len(), 4);
+    assert!(!chunked.is_empty());
+}
+
+#[test]
+fn content_rechunk_coalesce() {
+    let mut byte_content = ByteContent::from(vec![1, 2, 3]);
+    byte_content.rechunk(0);
+    assert_eq!(byte_content.chunk_count(), 3);
+    assert_eq!(
+        DataItem::Byte(byte_content.clone()).encode(),
+        vec![0x5f, 0x41, 0x01, 0x41, 0x02, 0x41, 0x03, 0xff]
+    );
+    byte_content.coalesce();
+    assert_eq!(
+        DataItem::Byte(byte_content.clone()).encode(),
+        vec![0x5f, 0x43, 0x01, 0x02, 0x03, 0xff]
+    );
+    byte_content.set_indefinite(false).coalesce();
+    assert_eq!(
+        DataItem::Byte(byte_content).encode(),
+        vec![0x43, 0x01, 0x02, 0x03]
+    );
+    let mut empty = ByteContent::default();
+    empty.rechunk(4);
+    assert_eq!(DataItem::Byte(empty).encode(), vec![0x5f, 0xff]);
+
+    let Ok(DataItem::Text(mut text_content)) = DataItem::from_diagnostic(r#"(_ "a", "€b")"#)
+    else {
+        panic!("diagnostic is not a text string");
+    };
+    text_content.rechunk(1);
+    assert_eq!(text_content.chunk(), &["a", "€", "b"]);
+    text_content.coalesce();
+    assert_eq!(format!("{:?}", DataItem::Text(text_content)), "(_ \"a€b\")");
+}
+
+#[cfg(feature = "bytes")]
+#[test]
+fn bytes_conversion() {
+    use bytes::Bytes;
+
+    let data_item = DataItem::from(Bytes::from(vec![1, 2, 3]));
+    assert_eq!(data_item.encode(), vec![0x43, 0x01, 0x02, 0x03]);
+    let DataItem::Byte(byte_content) = data_item else {
+        panic!("data item is not a byte string");
+    };
+    assert_eq!(Bytes::from(byte_content), Bytes::from_static(&[1, 2, 3]));
+
+    let Ok(DataItem::Byte(chunked)) = DataItem::from_diagnostic("(_ h'01', h'0203')") else {
+        panic!("diagnostic is not a byte string");
+    };
+    assert_eq!(Bytes::from(chunked), Bytes::from_static(&[1, 2, 3]));
+    assert_eq!(Bytes::from(ByteContent::default()), Bytes::new());
+}
+
+#[test]
+fn static_text_content() {
+    use std::borrow::Cow;
+    use std::sync::Arc;
+
+    use crate::content::TextChunk;
+
+    let data_item = DataItem::from(Cow::Borrowed("alg"));
+    let DataItem::Text(text_content) = &data_item else {
+        panic!("data item is not a text string");
+    };
+    assert!(matches!(text_content.chunk(), [TextChunk::Static("alg")]));
+    assert_eq!(data_item, DataItem::from("alg"));
+    assert_eq!(
+        data_item.encode(),
+        DataItem::from("alg".to_string()).encode()
+    );
+
+    let mut chunked = TextContent::default();
+    chunked
+        .set_indefinite(true)
+        .push_static("a")
+        .push_string("b");
+    assert!(matches!(
+        chunked.chunk(),
+        [TextChunk::Static("a"), TextChunk::Owned(text)] if text == "b"
+    ));
+    assert_eq!(format!("{:?}", DataItem::Text(chunked)), "(_ \"a\", \"b\")");
+
+    let shared = Arc::<str>::from("kid");
+    let content = TextContent::from(Arc::clone(&shared));
+    assert!(matches!(content.chunk(), [TextChunk::Shared(chunk)] if Arc::ptr_eq(chunk, &shared)));
+    assert_eq!(content, TextContent::from("kid"));
+    assert_eq!(content.clone().into_full(), "kid");
+    assert_eq!(Arc::strong_count(&shared), 2);
+}
+
+#[test]
+fn owned_content_setters() {
+    let mut byte_content = ByteContent::default();
+    byte_content.set_bytes_owned(vec![1, 2]);
+    assert_eq!(byte_content.chunk(), &[vec![1, 2]]);
+
+    let mut text_content = TextContent::default();
+    text_content.set_string_owned(String::from("abc"));
+    assert_eq!(text_content.full(), "abc");
+
+    let mut array_content = ArrayContent::default();
+    array_content
+        .set_indefinite(true)
+        .set_content_owned(vec![DataItem::from(1), DataItem::Null]);
+    assert_eq!(
+        format!("{:?}", DataItem::Array(array_content)),
+        "[_ 1, null]"
+    );
+
+    let mut map_content = MapContent::default();
+    map_content
+        .set_sorted_index(Some(DeterministicMode::Core))
+        .set_content_owned(IndexMap::from_iter([
+            (DataItem::from("b"), DataItem::from(1)),
+            (DataItem::from("a"), DataItem::from(2)),
+        ]));
+    assert_eq!(
+        map_content.get_sorted(0),
+        Some((&DataItem::from("a"), &DataItem::from(2)))
+    );
+    assert_eq!(
+        format!("{:?}", DataItem::Map(map_content)),
+        "{\"b\": 1, \"a\": 2}"
+    );
+}
+
+#[test]
+fn tag_content_manipulation() {
+    let mut tag_content = TagContent::from((1, "a"));
+    tag_content.set_number(32).set_content("https://a.b");
+    assert_eq!(
+        format!("{:?}", DataItem::Tag(tag_content.clone())),
+        "32(\"https://a.b\")"
+    );
+    let tag_content = tag_content.map_content(|content| content.wrap_in(&[24]));
+    assert_eq!(
+        tag_content.into_parts(),
+        (32, DataItem::from("https://a.b").wrap_in(&[24]))
+    );
+
+    let nested = DataItem::from_diagnostic("55799(1(2(3)))").unwrap();
+    let (tags, content) = nested.clone().unwrap_all();
+    assert_eq!(tags, vec![55799, 1, 2]);
+    assert_eq!(content, DataItem::from(3));
+    assert_eq!(content.wrap_in(&tags), nested);
+    assert_eq!(DataItem::Null.wrap_in(&[]), DataItem::Null);
+}
+
+#[test]
+fn simple_value_registry() {
+    for (simple_value, data_item) in [
+        (SimpleValue::FALSE, DataItem::Boolean(false)),
+        (SimpleValue::TRUE, DataItem::Boolean(true)),
+        (SimpleValue::NULL, DataItem::Null),
+        (SimpleValue::UNDEFINED, DataItem::Undefined),
+    ] {
+        assert!(!simple_value.is_unassigned());
+        assert_eq!(data_item.as_simple_value(), Some(simple_value.clone()));
+        assert_eq!(DataItem::from(simple_value), data_item);
+    }
+    let unassigned = SimpleValue::try_from(16).unwrap();
+    assert!(unassigned.is_unassigned());
+    assert_eq!(
+        DataItem::from(unassigned.clone()).as_simple_value(),
+        Some(unassigned)
+    );
+    assert_eq!(
+        DataItem::from_diagnostic("simple(21)").unwrap(),
+        DataItem::Boolean(true)
+    );
+    assert_eq!(
+        DataItem::GenericSimple(SimpleValue::NULL).encode(),
+        DataItem::Null.encode()
+    );
+}
+
+#[test]
+fn data_item_total_order() {
+    let mut items = [
+        DataItem::from("b"),
+        DataItem::from_diagnostic(r#"(_ "a")"#).unwrap(),
+        DataItem::from("a"),
+        DataItem::from(vec![1]),
+        DataItem::from(-1),
+        DataItem::from(10),
+        DataItem::Null,
+    ];
+    items.sort();
+    assert_eq!(
+        items
+            .iter()
+            .map(|item| format!("{item:?}"))
+            .collect::<Vec<_>>(),
+        vec!["10", "-1", "\"a\"", "(_ \"a\")", "\"b\"", "[1]", "null"]
+    );
+
+    let first = DataItem::from_diagnostic(r#"{"a": 1, "b": [2]}"#).unwrap();
+    let second = DataItem::from_diagnostic(r#"{"b": [2], "a": 1}"#).unwrap();
+    let indefinite = DataItem::from_diagnostic(r#"{_ "b": [2], "a": 1}"#).unwrap();
+    assert_eq!(first, second);
+    assert_eq!(first.cmp(&second), std::cmp::Ordering::Equal);
+    assert_eq!(first.cmp(&indefinite), second.cmp(&indefinite));
+    assert_ne!(first.cmp(&indefinite), std::cmp::Ordering::Equal);
+
+    #[expect(
+        clippy::mutable_key_type,
+        reason = "cached digest of a map content doesn't affect its order"
+    )]
+    let tree = std::collections::BTreeMap::from([(first.clone(), 1), (second, 2)]);
+    assert_eq!(tree.len(), 1);
+    assert_eq!(tree.get(&first), Some(&2));
+
+    assert_ne!(DataItem::from(0.0), DataItem::from(-0.0));
+    assert_eq!(
+        DataItem::from(0.0).cmp(&DataItem::from(-0.0)),
+        std::cmp::Ordering::Less
+    );
+    assert_eq!(DataItem::from(f64::NAN), DataItem::from(f64::NAN));
+    assert_eq!(
+        DataItem::from(f64::NAN).cmp(&DataItem::from(f64::NAN)),
+        std::cmp::Ordering::Equal
+    );
+    assert_ne!(
+        DataItem::from(f64::NAN),
+        DataItem::from(f64::from_bits(f64::NAN.to_bits() | 1))
+    );
+}
+
+#[test]
+fn data_item_order_matches_deterministic_encoding() {
+    let items = [
+        "0",
+        "23",
+        "24",
+        "-1",
+        "-100",
+        "h'0102'",
+        "(_ h'01', h'02')",
+        "h'03'",
+        r#""a""#,
+        r#""ab""#,
+        r#"(_ "a", "b")"#,
+        "[1, 2]",
+        "[1, [2]]",
+        "[]",
+        r#"{"a": 1, 1: 2}"#,
+        r#"{1: 2, "a": 1}"#,
+        "{1: 3}",
+        "{_ 1: 3}",
+        "{}",
+        "1(2)",
+        "0(\"a\")",
+        "false",
+        "true",
+        "null",
+        "undefined",
+        "simple(16)",
+        "simple(255)",
+        "1.5",
+        "100000.0",
+        "1.1",
+        "-0.0",
+        "Infinity",
+    ]
+    .map(|diagnostic| DataItem::from_diagnostic(diagnostic).unwrap());
+    for first in &items {
+        for second in &items {
+            let first_bytes = first.encode_deterministic(&DeterministicMode::Core);
+            let second_bytes = second.encode_deterministic(&DeterministicMode::Core);
+            if first_bytes != second_bytes {
+                assert_eq!(
+                    first.cmp(second),
+                    first_bytes.cmp(&second_bytes),
+                    "{first:?} {second:?}"
+                );
+            }
+            assert_eq!(
+                first.cmp(second) == std::cmp::Ordering::Equal,
+                first == second
+            );
+            assert_eq!(first.cmp(second), second.cmp(first).reverse());
+        }
+    }
+}
+
+#[test]
+fn content_debug() {
+    #[derive(Debug)]
+    #[expect(dead_code, reason = "fields are only read by debug output")]
+    struct Holder {
+        byte: ByteContent,
+        text: TextContent,
+    }
+
+    let diagnostic = r#"[_ h'01', (_ h'02', h'03'), "a", (_ "b", "c"), {_ 1: 2}, 24({})]"#;
+    let Ok(DataItem::Array(array_content)) = DataItem::from_diagnostic(diagnostic) else {
+        panic!("diagnostic is not an array");
+    };
+    assert_eq!(format!("{array_content:?}"), diagnostic);
+    let debug = array_content
+        .iter()
+        .map(|item| {
+            match item {
+                DataItem::Byte(content) => format!("{content:?}"),
+                DataItem::Text(content) => format!("{content:?}"),
+                DataItem::Map(content) => format!("{content:?}"),
+                DataItem::Tag(content) => format!("{content:?}"),
+                _ => unreachable!("diagnostic only contains content"),
+            }
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        debug,
+        vec![
+            "h'01'",
+            "(_ h'02', h'03')",
+            "\"a\"",
+            "(_ \"b\", \"c\")",
+            "{_ 1: 2}",
+            "24({})"
+        ]
+    );
+    assert_eq!(
+        format!("{:#?}", ArrayContent::from(vec![1])),
+        format!("{:#?}", DataItem::from(vec![1]))
+    );
+    assert_eq!(
+        format!(
+            "{:?}",
+            Holder {
+                byte: ByteContent::from(vec![0xff]),
+                text: TextContent::from("x"),
+            }
+        ),
+        "Holder { byte: h'ff', text: \"x\" }"
+    );
+}
+
+#[test]
+fn empty_constructors() {
+    let mut document = DataItem::map();
+    document
+        .as_map_mut()
+        .unwrap()
+        .insert_content("list", DataItem::array())
+        .insert_content("blob", DataItem::bytes_empty())
+        .insert_content("name", DataItem::text_empty());
+    assert_eq!(
+        format!("{document:?}"),
+        "{\"list\": [], \"blob\": h'', \"name\": \"\"}"
+    );
+    assert!(std::mem::take(&mut document).is_map());
+    assert!(document.is_null());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_content_types() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Cache {
+        bytes: ByteContent,
+        text: TextContent,
+        array: ArrayContent,
+        map: MapContent,
+        tag: TagContent,
+        simple: SimpleValue,
+    }
+    let Ok(DataItem::Map(map)) = DataItem::from_diagnostic(r#"{"a": [1, -2], 3: h'ff'}"#) else {
+        panic!("valid map diagnostic");
+    };
+    let cache = Cache {
+        bytes: ByteContent::from(vec![1, 2]),
+        text: TextContent::from("hello"),
+        array: ArrayContent::from(vec![DataItem::from(1), DataItem::from("a")]),
+        map,
+        tag: TagContent::from((32, "http://example.com")),
+        simple: SimpleValue::new(99).unwrap(),
+    };
+    let data_item = crate::to_data_item(&cache).unwrap();
+    assert_eq!(
+        data_item.get("tag").unwrap(),
+        &DataItem::from(TagContent::from((32, "http://example.com")))
+    );
+    assert_eq!(crate::from_data_item::<Cache>(data_item).unwrap(), cache);
+
+    let json = serde_json::to_string(&cache.tag).unwrap();
+    assert_eq!(json, r#"[32,"http://example.com"]"#);
+    assert_eq!(
+        serde_json::from_str::<TagContent>(&json).unwrap(),
+        cache.tag
+    );
+    let json = serde_json::to_string(&cache.array).unwrap();
+    assert_eq!(
+        serde_json::from_str::<ArrayContent>(&json).unwrap(),
+        cache.array
+    );
+    assert!(serde_json::from_str::<MapContent>("[1]").is_err());
+    assert!(serde_json::from_str::<SimpleValue>("24").is_err());
+}
+
+#[test]
+fn map_digest_cache() {
+    use std::hash::{BuildHasher as _, RandomState};
+
+    let state = RandomState::new();
+    let Ok(DataItem::Map(mut first)) = DataItem::from_diagnostic(r#"{"a": 1, "b": {"c": 2}}"#)
+    else {
+        panic!("valid map diagnostic");
+    };
+    let Ok(DataItem::Map(second)) = DataItem::from_diagnostic(r#"{"b": {"c": 2}, "a": 1}"#) else {
+        panic!("valid map diagnostic");
+    };
+    assert_eq!(first, second);
+    assert_eq!(first.digest(), second.digest());
+    assert_eq!(
+        state.hash_one(DataItem::from(first.clone())),
+        state.hash_one(DataItem::from(second.clone()))
+    );
+
+    let digest = first.digest();
+    let nested = first.get_mut(&DataItem::from("b")).unwrap();
+    nested.as_map_mut().unwrap().insert("d", 3);
+    assert_ne!(first.digest(), digest);
+    first.shift_remove(&DataItem::from("b"));
+    first.insert("b", MapContent::from_iter([("c", 2)]));
+    assert_eq!(first.digest(), digest);
+
+    let swapped = MapContent::from_iter([(1, 2), (2, 1)]);
+    let same = MapContent::from_iter([(1, 1), (2, 2)]);
+    assert_ne!(swapped.digest(), same.digest());
+    assert_ne!(MapContent::default().digest(), same.digest());
+}
+
+#[test]
+fn encode_deeply_nested() {
+    const DEPTH: usize = 100_000;
+    let mut data_item = DataItem::from(0);
+    for level in 0..DEPTH {
+        data_item = match level % 3 {
+            0 => DataItem::from(vec![data_item]),
+            1 => MapContent::from_iter([(DataItem::from(1), data_item)]).into(),
+            _ => TagContent::from((7, data_item)).into(),
+        };
+    }
+    let bytes = data_item.encode();
+    assert_eq!(bytes.len(), DEPTH + DEPTH / 3 + 1);
+    assert!(bytes.starts_with(&[0x81, 0xc7, 0xa1, 0x01, 0x81]));
+    assert!(bytes.ends_with(&[0xc7, 0xa1, 0x01, 0x81, 0x00]));
+    assert_eq!(
+        data_item.encode_deterministic(&DeterministicMode::Core),
+        bytes
+    );
+    assert!(data_item.is_deterministic(&DeterministicMode::Core));
+    assert!(
+        data_item
+            .to_diagnostic(&DiagnosticOptions::default())
+            .starts_with("[7({1: [7({1: ")
+    );
+    assert!(matches!(
+        data_item.encode_deterministic_into(&mut vec![0; bytes.len()], &DeterministicMode::Core),
+        Err(Error::LimitExceeded(_))
+    ));
+    assert_eq!(
+        data_item.deterministic(&DeterministicMode::Core).encode(),
+        bytes
+    );
+}
+
+#[test]
+fn compact_data_item_size() {
+    assert!(size_of::<DataItem>() <= 48);
+    assert!(size_of::<MapContent>() <= 16);
+}
+
+#[cfg(not(feature = "smallvec"))]
+#[test]
+fn content_chunks_without_smallvec() {
+    let mut byte_content = ByteContent::with_capacity(4);
+    assert!(byte_content.capacity() >= 4);
+    byte_content
+        .set_indefinite(true)
+        .push_chunk(vec![1, 2])
+        .push_chunk(vec![3]);
+    let data_item = DataItem::Byte(byte_content.clone());
+    assert_eq!(data_item.encode(), hex::decode("5f4201024103ff").unwrap());
+    assert_eq!(DataItem::decode(&data_item.encode()).unwrap(), data_item);
+    byte_content.coalesce();
+    assert_eq!(byte_content.into_chunks(), vec![vec![1, 2, 3]]);
+
+    let mut text_content = TextContent::from("abc");
+    text_content.rechunk(1);
+    assert_eq!(text_content.chunk(), &["a", "b", "c"]);
+    let data_item = DataItem::Text(text_content.clone());
+    assert_eq!(DataItem::decode(&data_item.encode()).unwrap(), data_item);
+    text_content.coalesce();
+    assert_eq!(text_content.chunk(), &["abc"]);
+}
+
+#[cfg(feature = "bumpalo")]
+#[test]
+fn arena_decode_default_depth() {
+    use crate::arena::ArenaItem;
+
+    let bump = bumpalo::Bump::new();
+    let mut bytes = vec![0x81; 1_000_000];
+    bytes.push(0x01);
+    assert!(matches!(
+        ArenaItem::decode(&bytes, &bump),
+        Err(Error::LimitExceeded(Limit::Depth { max_depth: 128, .. }))
+    ));
+}
+
+#[cfg(feature = "bumpalo")]
+#[test]
+fn arena_decode() {
+    use crate::arena::ArenaItem;
+
+    let bump = bumpalo::Bump::new();
+    let data_item = DataItem::from_diagnostic(
+        r#"{"a": [1, -2, 1.5, true, null, undefined, simple(99)], 0("t"): h'0102', 3: {}}"#,
+    )
+    .unwrap();
+    let bytes = data_item.encode();
+    let arena_item = ArenaItem::decode(&bytes, &bump).unwrap();
+    assert_eq!(arena_item.to_data_item(), data_item);
+
+    let indefinite = DataItem::from_diagnostic(r#"[_ (_ "ab", "c"), (_ h'01', h'02')]"#).unwrap();
+    assert_eq!(
+        ArenaItem::decode(&indefinite.encode(), &bump).unwrap(),
+        ArenaItem::Array(&[ArenaItem::Text("abc"), ArenaItem::Byte(&[1, 2])])
+    );
+
+    assert_eq!(
+        ArenaItem::decode(&[0xff], &bump),
+        Err(Error::InvalidBreakStop)
+    );
+    assert_eq!(
+        ArenaItem::decode(&[0x82, 0x01], &bump),
+        Err(Error::Incomplete)
+    );
+    assert_eq!(
+        ArenaItem::decode(&[0x9f, 0x01], &bump),
+        Err(Error::IncompleteIndefinite)
+    );
+    assert_eq!(
+        ArenaItem::decode(&[0xf8, 0x10], &bump),
+        Err(Error::InvalidSimple)
+    );
+    assert!(ArenaItem::decode(&[0x62, 0xff, 0xfe], &bump).is_err());
+    assert!(ArenaItem::decode(&[0x7f, 0x41, 0x61, 0xff], &bump).is_err());
+}
+
+#[test]
+fn decode_preallocation() {
+    let data_item = DataItem::from((0..2000).collect::<Vec<u64>>());
+    let bytes = data_item.encode();
+    let mut options = DecodeOptions::default();
+    options.set_max_length(5000);
+    let Ok(DataItem::Array(array)) = DataItem::decode_with_options(&bytes, &options) else {
+        panic!("array should be decoded");
+    };
+    assert_eq!(array.len(), 2000);
+    assert!(array.capacity() >= 2000);
+    assert_eq!(DataItem::decode(&bytes).unwrap(), data_item);
+
+    let huge_array = [0x9b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01];
+    assert_eq!(DataItem::decode(&huge_array), Err(Error::Incomplete));
+    let huge_map = [0xbb, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01];
+    assert_eq!(DataItem::decode(&huge_map), Err(Error::Incomplete));
+    let huge_bytes = [0x5b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01];
+    assert!(DataItem::decode(&huge_bytes).is_err());
+}
+
+#[test]
+fn deterministic_encoding_shared() {
+    let value = DataItem::from_diagnostic(
+        r#"{_ "bb": {_ 100: 1, "a": [_ 2]}, 10: (_ h'01', h'02'), [1]: 0("x"), (_ "b", "b"): 3}"#,
+    )
+    .unwrap();
+    for mode in [DeterministicMode::Core, DeterministicMode::LengthFirst] {
+        let deterministic = value.clone().deterministic(&mode);
+        assert_eq!(value.encode_deterministic(&mode), deterministic.encode());
+        assert!(deterministic.is_deterministic(&mode));
+        assert!(!value.is_deterministic(&mode));
+    }
+
+    let nested = DataItem::from_diagnostic(r#"{1: {"b": 1, "a": 2}}"#).unwrap();
+    assert!(!nested.is_deterministic(&DeterministicMode::Core));
+    let nested_key = DataItem::from_diagnostic(r"{[_ 1]: 1}").unwrap();
+    assert!(!nested_key.is_deterministic(&DeterministicMode::Core));
+
+    let mut options = DecodeOptions::default();
+    options.set_preserve_duplicate_keys(true);
+    let duplicate =
+        DataItem::decode_with_options(&[0xa2, 0x01, 0x02, 0x01, 0x03], &options).unwrap();
+    assert!(!duplicate.is_deterministic(&DeterministicMode::Core));
+
+    for diagnostic in [
+        r#"{10: 1, "a": 2, [1]: 1.5}"#,
+        r#"{"a": 1, 10: 2}"#,
+        r#"{"b": 1, "aa": 2}"#,
+        r"[1, 100000.0, -0.0, {-1: 0, 1: 0}]",
+        r#"24(<<{"b": 1, "a": 2}>>)"#,
+    ] {
+        let value = DataItem::from_diagnostic(diagnostic).unwrap();
+        for mode in [DeterministicMode::Core, DeterministicMode::LengthFirst] {
+            assert_eq!(
+                value.is_deterministic(&mode),
+                value.encode() == value.encode_deterministic(&mode),
+                "{diagnostic}"
+            );
+        }
+    }
+}
+
+#[test]
+fn lazy_data_item() {
+    use crate::lazy::LazyDataItem;
+
+    let document = DataItem::from_diagnostic(
+        r#"{_ "list": [_ 1, (_ "a", "b"), 2(h'01')], (_ "ke", "y"): {1: -1}, 3: 32("u")}"#,
+    )
+    .unwrap();
+    let mut bytes = document.encode();
+    bytes.push(0x00);
+    let lazy = LazyDataItem::new(&bytes).unwrap();
+    assert_eq!(lazy.as_bytes(), &bytes[..bytes.len() - 1]);
+    assert_eq!(lazy.decode().unwrap(), document);
+    assert_eq!(lazy.entries().unwrap().count(), 3);
+
+    let list = lazy.get_str("list").unwrap();
+    assert_eq!(list.items().unwrap().count(), 3);
+    assert_eq!(
+        list.get_index(1).unwrap().decode().unwrap().as_text(),
+        Some("ab".to_string())
+    );
+    let (number, content) = list.get_index(2).unwrap().tag().unwrap();
+    assert_eq!(number, 2);
+    assert_eq!(
+        content.decode().unwrap(),
+        DataItem::from(vec![1u8].as_slice())
+    );
+    assert!(list.get_index(3).is_none());
+    assert!(list.get_str("list").is_none());
+
+    let nested = lazy.get_str("key").unwrap();
+    assert_eq!(
+        nested.get(&DataItem::from(1)).unwrap().decode().unwrap(),
+        DataItem::from(-1)
+    );
+    assert!(lazy.get(&DataItem::from(3)).unwrap().tag().is_some());
+
+    assert_eq!(LazyDataItem::new(&[0x82, 0x01]), Err(Error::Incomplete));
+    assert_eq!(
+        LazyDataItem::new(&[0x9f, 0x01]),
+        Err(Error::IncompleteIndefinite)
+    );
+    assert_eq!(
+        LazyDataItem::new(&[0x81, 0xff]),
+        Err(Error::InvalidBreakStop)
+    );
+    assert!(LazyDataItem::new(&[0x5f, 0x61, 0x61, 0xff]).is_err());
+    let mut deep = vec![0x81; 100_000];
+    deep.push(0x00);
+    assert!(LazyDataItem::new(&deep).is_ok());
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn encode_parallel() {
+    let items = (0..5000)
+        .map(|index| {
+            DataItem::from(vec![
+                DataItem::from(index),
+                DataItem::from(format!("item {index}")),
+            ])
+        })
+        .collect::<Vec<_>>();
+    let array = DataItem::from(items.clone());
+    assert_eq!(array.encode_parallel(), array.encode());
+
+    let mut indefinite = ArrayContent::from(items);
+    indefinite.set_indefinite(true);
+    let indefinite = DataItem::from(indefinite);
+    assert_eq!(indefinite.encode_parallel(), indefinite.encode());
+
+    let map = DataItem::from(
+        (0..5000)
+            .map(|index| (DataItem::from(index), DataItem::from(-index)))
+            .collect::<MapContent>(),
+    );
+    assert_eq!(map.encode_parallel(), map.encode());
+
+    let text = DataItem::from("text");
+    assert_eq!(text.encode_parallel(), text.encode());
+}
+
+#[test]
+fn decode_intern_keys() {
+    use std::sync::Arc;
+
+    use crate::content::TextChunk;
+
+    let long_key = "k".repeat(65);
+    let value = DataItem::from(vec![
+        DataItem::from(vec![
+            (DataItem::from("level"), DataItem::from("info")),
+            (DataItem::from(long_key.as_str()), DataItem::from(1)),
+        ]),
+        DataItem::from(vec![(DataItem::from("level"), DataItem::from("warn"))]),
+    ]);
+    let bytes = value.encode();
+    let mut options = DecodeOptions::default();
+    options.set_intern_keys(true);
+    let decoded = DataItem::decode_with_options(&bytes, &options).unwrap();
+    let other_decoded = DataItem::decode_with_options(&bytes, &options).unwrap();
+    assert_eq!(decoded, value);
+
+    let keys_of = |data_item: &DataItem| {
+        data_item
+            .as_array()
+            .unwrap()
+            .iter()
+            .flat_map(|entry| entry.as_map().unwrap().keys())
+            .map(|key| {
+                let DataItem::Text(text_content) = key else {
+                    panic!("key should be a text string");
+                };
+                text_content.chunk()[0].clone()
+            })
+            .collect::<Vec<_>>()
+    };
+    let keys = keys_of(&decoded);
+    let (TextChunk::Shared(first), TextChunk::Shared(second)) = (&keys[0], &keys[2]) else {
+        panic!("short key should be interned");
+    };
+    assert!(Arc::ptr_eq(first, second));
+    // table of a decode call is dropped so only keys share an interned string
+    assert_eq!(Arc::strong_count(first), 4);
+    assert!(matches!(keys[1], TextChunk::Owned(_)));
+    let TextChunk::Shared(other_first) = &keys_of(&other_decoded)[0] else {
+        panic!("short key should be interned");
+    };
+    assert!(!Arc::ptr_eq(first, other_first));
+    // value is never interned
+    let level = decoded.as_array().unwrap()[0].as_map().unwrap()[&DataItem::from("level")].clone();
+    let DataItem::Text(level) = level else {
+        panic!("value should be a text string");
+    };
+    assert!(matches!(level.chunk(), [TextChunk::Owned(_)]));
+}
+
+#[test]
+fn fixed_token_round_trip() {
+    use crate::fixed::{FixedDecoder, FixedEncoder, Token};
+
+    let value = DataItem::from_diagnostic(
+        r#"{"a": [1, -2, 1.1], "b": (_ h'01', h'02'), "c": 32("x"), "d": [_ true, simple(40)]}"#,
+    )
+    .unwrap();
+    let bytes = value.encode();
+    let tokens = FixedDecoder::<2>::new(&bytes)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(tokens[0], Token::Map(Some(4)));
+    assert_eq!(tokens[4], Token::Signed(1));
+    assert_eq!(tokens.last(), Some(&Token::Break));
+
+    let mut buffer = [0; 64];
+    let mut encoder = FixedEncoder::new(&mut buffer);
+    for token in &tokens {
+        encoder.push(token).unwrap();
+    }
+    assert_eq!(encoder.finish(), bytes.as_slice());
+
+    let mut small = [0; 4];
+    let mut encoder = FixedEncoder::new(&mut small);
+    encoder.push(&Token::Unsigned(1)).unwrap();
+    assert_eq!(
+        encoder.push(&Token::Unsigned(u64::MAX)).err(),
+        Some(Error::BufferFull)
+    );
+    assert_eq!(encoder.as_bytes(), &[0x01]);
+
+    assert!(matches!(
+        FixedDecoder::<1>::new(&bytes).find_map(Result::err),
+        Some(Error::LimitExceeded(_))
+    ));
+    let mut decoder = FixedDecoder::<1>::new(&[0x82, 0x01]);
+    assert_eq!(decoder.next(), Some(Ok(Token::Array(Some(2)))));
+    assert_eq!(decoder.next(), Some(Ok(Token::Unsigned(1))));
+    assert_eq!(decoder.next(), Some(Err(Error::Incomplete)));
+    assert_eq!(decoder.next(), None);
+    assert_eq!(
+        FixedDecoder::<1>::new(&[0x81, 0xff]).nth(1),
+        Some(Err(Error::InvalidBreakStop))
+    );
+    assert!(
+        FixedDecoder::<1>::new(&[0x5f, 0x61, 0x61, 0xff])
+            .nth(1)
+            .unwrap()
+            .is_err()
+    );
+    let sequence = FixedDecoder::<0>::new(&[0x01, 0x61, 0x61])
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(sequence, [Token::Unsigned(1), Token::Text("a")]);
+}
+
+#[test]
+fn half_precision_conversion() {
+    use crate::float16::{f16_bits_to_f64, f64_to_f16_bits};
+
+    for bits in 0..=u16::MAX {
+        let number = f16_bits_to_f64(bits);
+        #[cfg(feature = "half")]
+        {
+            let expected = f64::from(half::f16::from_bits(bits));
+            assert!(number.to_bits() == expected.to_bits() || number.is_nan() && expected.is_nan());
+        }
+        if number.is_nan() {
+            assert_eq!(f64_to_f16_bits(number), None);
+        } else {
+            assert_eq!(f64_to_f16_bits(number), Some(bits));
+        }
+    }
+    assert_eq!(f64_to_f16_bits(65504.0), Some(0x7bff));
+    assert_eq!(f64_to_f16_bits(65505.0), None);
+    assert_eq!(f64_to_f16_bits(1.0 + f64::EPSILON), None);
+    assert_eq!(f64_to_f16_bits(2f64.powi(-25)), None);
+    assert_eq!(f64_to_f16_bits(3.0 * 2f64.powi(-24)), Some(3));
+    assert_eq!(f64_to_f16_bits(f64::MIN_POSITIVE), None);
+    assert_eq!(
+        DataItem::decode(&[0xf9, 0x80, 0x01]).unwrap(),
+        DataItem::Floating(-(2f64.powi(-24)))
+    );
+    assert_eq!(DataItem::Floating(-0.0).encode(), vec![0xf9, 0x80, 0x00]);
+    assert_eq!(DataItem::Floating(f64::NAN).encode().len(), 9);
+}
+
+#[test]
+fn encode_deterministic_into_buffer() {
+    let value = DataItem::from_diagnostic(
+        r#"{"b": {3: 1, 1: [_ 2]}, 100: 1.5, "a": 1, "ab": 2, -1: (_ h'01', h'02'), 10: 0("t")}"#,
+    )
+    .unwrap();
+    for mode in [DeterministicMode::Core, DeterministicMode::LengthFirst] {
+        let expected = value.encode_deterministic(&mode);
+        let mut exact = vec![0; expected.len()];
+        assert_eq!(
+            value.encode_deterministic_into(&mut exact, &mode),
+            Ok(expected.len())
+        );
+        assert_eq!(exact, expected);
+        let mut short = vec![0; expected.len() - 1];
+        assert_eq!(
+            value.encode_deterministic_into(&mut short, &mode),
+            Err(Error::BufferFull)
+        );
+    }
+
+    // keys with a same deterministic encoding are merged and a shorter header
+    // is written
+    let mut repeated = DataItem::from_diagnostic(r#"{(_ "a", "b"): 1, "ab": 2}"#).unwrap();
+    for index in 0..22 {
+        repeated
+            .as_map_mut()
+            .unwrap()
+            .insert(DataItem::from(index), DataItem::Null);
+    }
+    let expected = repeated.encode_deterministic(&DeterministicMode::Core);
+    assert_eq!(expected[0], 0xb7);
+    let mut buffer = [0; 128];
+    let length = repeated
+        .encode_deterministic_into(&mut buffer, &DeterministicMode::Core)
+        .unwrap();
+    assert_eq!(&buffer[..length], expected.as_slice());
+}
+
+#[test]
+fn decode_scalar_without_tree() {
+    use crate::fixed::{Scalar, decode_scalar};
+
+    for diagnostic in [
+        "0",
+        "-500",
+        "18446744073709551615",
+        "h'0102'",
+        r#""héllo""#,
+        "true",
+        "null",
+        "undefined",
+        "1.1",
+        "-Infinity",
+        "simple(99)",
+    ] {
+        let value = DataItem::from_diagnostic(diagnostic).unwrap();
+        let bytes = value.encode();
+        assert_eq!(decode_scalar(&bytes).unwrap().to_data_item(), value);
+    }
+    assert_eq!(
+        decode_scalar(&[0x41, 0xaa, 0x01]),
+        Ok(Scalar::Byte(&[0xaa]))
+    );
+    assert_eq!(decode_scalar(&[]), Err(Error::Incomplete));
+    assert_eq!(decode_scalar(&[0x42, 0x01]), Err(Error::Incomplete));
+    assert_eq!(decode_scalar(&[0xff]), Err(Error::InvalidBreakStop));
+    for bytes in [[0x80, 0x00], [0xa0, 0x00], [0xc1, 0x00], [0x5f, 0xff]] {
+        assert!(matches!(
+            decode_scalar(&bytes),
+            Err(Error::TypeMismatch { .. })
+        ));
+    }
+}
+
+/// Core encoder and decoder of a minimal build with `default-features = false`
+#[cfg(not(any(
+    feature = "half",
+    feature = "serde",
+    feature = "chrono",
+    feature = "time",
+    feature = "uuid",
+    feature = "num-bigint",
+    feature = "rust_decimal",
+    feature = "json",
+    feature = "ciborium",
+    feature = "serde_cbor",
+    feature = "bytes",
+    feature = "bumpalo",
+    feature = "rayon",
+)))]
+#[test]
+fn minimal_profile() {
+    use crate::fixed::{FixedDecoder, Scalar, decode_scalar};
+    use crate::lazy::LazyDataItem;
+
+    let value = DataItem::from_diagnostic(r#"{"b": [1, -1, 1.5], "a": (_ h'01', h'02')}"#).unwrap();
+    let bytes = value.encode();
+    assert_eq!(DataItem::decode(&bytes).unwrap(), value);
+    let mut buffer = [0; 32];
+    let length = value
+        .encode_deterministic_into(&mut buffer, &DeterministicMode::Core)
+        .unwrap();
+    assert_eq!(
+        &buffer[..length],
+        value.encode_deterministic(&DeterministicMode::Core)
+    );
+    assert!(FixedDecoder::<2>::new(&bytes).all(|token| token.is_ok()));
+    assert_eq!(
+        decode_scalar(&[0xf9, 0x3c, 0x00]),
+        Ok(Scalar::Floating(1.0))
+    );
+    let lazy = LazyDataItem::new(&bytes).unwrap();
+    assert_eq!(
+        lazy.get_str("b").unwrap().get_index(2).unwrap().decode(),
+        Ok(DataItem::Floating(1.5))
+    );
+}
+
+#[test]
+fn encode_to_writer() {
+    use crate::write::{CborWrite as _, IoWriter};
+
+    struct FailingWriter;
+
+    impl std::io::Write for FailingWriter {
+        fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::ErrorKind::BrokenPipe.into())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let value =
+        DataItem::from_diagnostic(r#"{_ "b": [_ 1, -1], "a": (_ "x", "y"), 3: 1.5}"#).unwrap();
+    let mut bytes = vec![];
+    value.encode_to(&mut bytes).unwrap();
+    assert_eq!(bytes, value.encode());
+    let mut deterministic = vec![];
+    value
+        .encode_deterministic_to(&mut deterministic, &DeterministicMode::LengthFirst)
+        .unwrap();
+    assert_eq!(
+        deterministic,
+        value.encode_deterministic(&DeterministicMode::LengthFirst)
+    );
+
+    let mut buffer = vec![0; bytes.len() + 2];
+    let mut remaining = buffer.as_mut_slice();
+    value.encode_to(&mut remaining).unwrap();
+    assert_eq!(remaining.len(), 2);
+    assert_eq!(&buffer[..bytes.len()], bytes.as_slice());
+    let mut short = vec![0; bytes.len() - 1];
+    assert_eq!(
+        value.encode_to(&mut short.as_mut_slice()),
+        Err(Error::BufferFull)
+    );
+    let mut single = [0u8; 1];
+    let mut slice = single.as_mut_slice();
+    assert_eq!(slice.write_bytes(&[1, 2]), Err(Error::BufferFull));
+    assert_eq!(slice.len(), 1);
+
+    let mut writer = IoWriter::new(vec![]);
+    value.encode_to(&mut writer).unwrap();
+    assert_eq!(writer.into_inner(), bytes);
+    assert_eq!(
+        value.encode_to(&mut IoWriter::new(FailingWriter)),
+        Err(Error::Io(std::io::ErrorKind::BrokenPipe))
+    );
+}
+
+#[test]
+fn resumable_decoder_feed() {
+    use crate::fixed::{FixedDecoder, ResumableDecoder, Step, Token};
+
+    let bytes = DataItem::from_diagnostic(
+        r#"{"id": 1000000, "data": (_ h'0102', h'03'), "tags": [_ 1(2.5), -300]}"#,
+    )
+    .unwrap()
+    .encode();
+    let expected = FixedDecoder::<3>::new(&bytes)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    // bytes arrive one at a time and only unconsumed bytes are kept by a caller
+    let mut decoder = ResumableDecoder::<3>::new();
+    let mut tokens = vec![];
+    let mut start = 0;
+    let mut end = 0;
+    while end < bytes.len() {
+        end += 1;
+        match decoder.feed(&bytes[start..end]).unwrap() {
+            Step::Token(token, consumed) => {
+                tokens.push(token);
+                start += consumed;
+            }
+            Step::NeedMore(missing) => assert!(end + missing <= bytes.len()),
+        }
+    }
+    assert_eq!(start, bytes.len());
+    assert_eq!(tokens, expected);
+    assert_eq!(decoder.depth(), 0);
+
+    let mut decoder = ResumableDecoder::<1>::new();
+    assert_eq!(decoder.feed(&[]).unwrap(), Step::NeedMore(1));
+    assert_eq!(decoder.feed(&[0x1b, 0x00]).unwrap(), Step::NeedMore(7));
+    assert_eq!(decoder.feed(&[0x58, 0x10]).unwrap(), Step::NeedMore(16));
+    assert_eq!(decoder.depth(), 0);
+    assert_eq!(
+        decoder.feed(&[0x81, 0x81]).unwrap(),
+        Step::Token(Token::Array(Some(1)), 1)
+    );
+    assert!(matches!(
+        decoder.feed(&[0x81]),
+        Err(Error::LimitExceeded(_))
+    ));
+    assert_eq!(
+        ResumableDecoder::<1>::new().feed(&[0xff]),
+        Err(Error::InvalidBreakStop)
+    );
+    assert!(matches!(
+        ResumableDecoder::<1>::new().feed(&[0x62, 0xff, 0xfe]),
+        Err(Error::NotWellFormed(Malformed::InvalidUtf8(_)))
+    ));
+}
+
+#[test]
+fn decode_many_empty_chunks() {
+    use crate::DecodeOptions;
+
+    let mut bytes = vec![0x5f];
+    bytes.extend(std::iter::repeat_n(0x40, 1_000_000));
+    bytes.push(0xff);
+    let DataItem::Byte(content) = DataItem::decode(&bytes).unwrap() else {
+        panic!("indefinite byte string should be decoded as a byte string");
+    };
+    assert!(content.is_indefinite());
+    assert!(content.is_empty());
+
+    let mut options = DecodeOptions::default();
+    options
+        .set_max_depth(16)
+        .set_max_length(1024)
+        .set_max_size(4_000_000);
+    assert!(matches!(
+        DataItem::decode_with_options(&bytes, &options),
+        Err(Error::LimitExceeded(_))
+    ));
+}
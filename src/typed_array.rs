@@ -0,0 +1,95 @@
+/// Byte order of an element of typed array
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Endianness {
+    /// Big endian byte order
+    Big,
+    /// Little endian byte order
+    Little,
+}
+
+/// Trait implemented by number types which can be element of a typed array
+/// (tag 64 to tag 87)
+pub trait TypedArrayElement: Sized {
+    /// Tag number of a typed array in big endian and little endian byte order
+    const TAGS: (u64, u64);
+
+    /// Create an element from a bytes of element size in provided byte order
+    fn from_bytes(bytes: &[u8], endianness: Endianness) -> Option<Self>;
+
+    /// Convert an element into a bytes in provided byte order
+    fn to_bytes(&self, endianness: Endianness) -> Vec<u8>;
+
+    /// Get a byte order of a typed array with provided tag number. Return
+    /// `None` if tag number is not a typed array of this element
+    #[must_use]
+    fn endianness(tag: u64) -> Option<Endianness> {
+        if tag == Self::TAGS.0 {
+            Some(Endianness::Big)
+        } else if tag == Self::TAGS.1 {
+            Some(Endianness::Little)
+        } else {
+            None
+        }
+    }
+}
+
+macro_rules! impl_typed_array_element {
+    ($($ty:ty => ($big:expr, $little:expr)),+ $(,)?) => {
+        $(
+            impl TypedArrayElement for $ty {
+                const TAGS: (u64, u64) = ($big, $little);
+
+                fn from_bytes(bytes: &[u8], endianness: Endianness) -> Option<Self> {
+                    let bytes = bytes.try_into().ok()?;
+                    match endianness {
+                        Endianness::Big => Some(Self::from_be_bytes(bytes)),
+                        Endianness::Little => Some(Self::from_le_bytes(bytes)),
+                    }
+                }
+
+                fn to_bytes(&self, endianness: Endianness) -> Vec<u8> {
+                    match endianness {
+                        Endianness::Big => self.to_be_bytes().to_vec(),
+                        Endianness::Little => self.to_le_bytes().to_vec(),
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_typed_array_element!(
+    u16 => (65, 69),
+    u32 => (66, 70),
+    u64 => (67, 71),
+    i8 => (72, 72),
+    i16 => (73, 77),
+    i32 => (74, 78),
+    i64 => (75, 79),
+    f32 => (81, 85),
+    f64 => (82, 86),
+);
+
+#[cfg(feature = "half")]
+impl_typed_array_element!(half::f16 => (80, 84));
+
+impl TypedArrayElement for u8 {
+    const TAGS: (u64, u64) = (64, 64);
+
+    fn from_bytes(bytes: &[u8], _endianness: Endianness) -> Option<Self> {
+        match bytes {
+            [byte] => Some(*byte),
+            _ => None,
+        }
+    }
+
+    fn to_bytes(&self, _endianness: Endianness) -> Vec<u8> {
+        vec![*self]
+    }
+
+    fn endianness(tag: u64) -> Option<Endianness> {
+        // uint8 clamped array (tag 68) has same representation as uint8 array
+        matches!(tag, 64 | 68).then_some(Endianness::Big)
+    }
+}
@@ -0,0 +1,59 @@
+use crate::content::TagContent;
+use crate::data_item::DataItem;
+use crate::tag;
+
+impl From<::uuid::Uuid> for DataItem {
+    fn from(value: ::uuid::Uuid) -> Self {
+        TagContent::from((tag::UUID, value.as_bytes().as_slice())).into()
+    }
+}
+
+/// Parse a UUID from a content of a tag 37
+pub(crate) fn from_tag_content(content: &DataItem) -> Option<::uuid::Uuid> {
+    match content {
+        DataItem::Byte(byte_content) => ::uuid::Uuid::from_slice(&byte_content.full()).ok(),
+        _ => None,
+    }
+}
+
+/// Serialize a UUID as a tag 37 with a 16 byte string
+///
+/// # Example
+/// ```rust
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// struct User {
+///     #[serde(with = "cbor_next::uuid")]
+///     id: uuid::Uuid,
+/// }
+/// ```
+///
+/// # Errors
+/// If serializer fails to serialize a UUID
+#[cfg(feature = "serde")]
+pub fn serialize<S>(value: &::uuid::Uuid, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    use serde::Serialize as _;
+
+    DataItem::from(*value).serialize(serializer)
+}
+
+/// Deserialize a UUID from a tag 37 or from an untagged 16 byte string
+///
+/// # Errors
+/// If deserializer fails to deserialize a UUID
+#[cfg(feature = "serde")]
+pub fn deserialize<'de, D>(deserializer: D) -> Result<::uuid::Uuid, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::Deserialize as _;
+    use serde::de::Error as _;
+
+    match crate::tagged::Tagged::<DataItem>::deserialize(deserializer)?.into_parts() {
+        (Some(tag::UUID) | None, content) => from_tag_content(&content),
+        _ => None,
+    }
+    .ok_or_else(|| D::Error::custom("invalid UUID value"))
+}
@@ -0,0 +1,88 @@
+use crate::error::Error;
+
+/// Destination of a CBOR encoded bytes
+///
+/// Encoding writes through this trait so a data item can be encoded into a
+/// growable vector, a fixed caller provided buffer or any `std::io::Write`
+/// wrapped in an [`IoWriter`]
+///
+/// # Example
+/// ```rust
+/// use cbor_next::DataItem;
+///
+/// let value = DataItem::from(vec![1, 2]);
+/// let mut buffer = [0; 3];
+/// let mut remaining = buffer.as_mut_slice();
+/// value.encode_to(&mut remaining).unwrap();
+/// assert!(remaining.is_empty());
+/// assert_eq!(buffer, [0x82, 0x01, 0x02]);
+/// assert!(value.encode_to(&mut [0; 2].as_mut_slice()).is_err());
+/// ```
+pub trait CborWrite {
+    /// Write all provided bytes
+    ///
+    /// # Errors
+    /// If all bytes cannot be written
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error>;
+}
+
+impl CborWrite for Vec<u8> {
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.extend_from_slice(bytes);
+        Ok(())
+    }
+}
+
+impl CborWrite for &mut [u8] {
+    /// Write bytes at a start of a slice and move a slice past written bytes.
+    /// Nothing is written if a slice is shorter than provided bytes
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        if bytes.len() > self.len() {
+            return Err(Error::BufferFull);
+        }
+        let (written, remaining) = std::mem::take(self).split_at_mut(bytes.len());
+        written.copy_from_slice(bytes);
+        *self = remaining;
+        Ok(())
+    }
+}
+
+/// Adapter which writes a CBOR encoded bytes to a `std::io::Write`
+///
+/// # Example
+/// ```rust
+/// use std::io::Cursor;
+///
+/// use cbor_next::DataItem;
+/// use cbor_next::write::IoWriter;
+///
+/// let mut writer = IoWriter::new(Cursor::new(vec![]));
+/// DataItem::from("a").encode_to(&mut writer).unwrap();
+/// assert_eq!(writer.into_inner().into_inner(), vec![0x61, 0x61]);
+/// ```
+#[derive(Debug)]
+pub struct IoWriter<W> {
+    writer: W,
+}
+
+impl<W> IoWriter<W> {
+    /// Create an adapter around provided writer
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Get back an underlying writer
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W> CborWrite for IoWriter<W>
+where
+    W: std::io::Write,
+{
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.writer.write_all(bytes)?;
+        Ok(())
+    }
+}